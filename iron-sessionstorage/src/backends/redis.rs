@@ -1,9 +1,9 @@
 use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
 
 use redis;
-use redis::Commands;
-use r2d2;
-use r2d2_redis::RedisConnectionManager;
+use redis::{Commands, PipelineCommands};
 use iron;
 use rand;
 use rand::Rng;
@@ -12,34 +12,131 @@ use RawSession;
 use SessionBackend;
 use get_default_cookie;
 
-use errors::*;
 use iron::prelude::*;
 use cookie;
 
 const COOKIE_NAME: &'static str = "iron_session_id";
 
-type RedisPool = r2d2::Pool<RedisConnectionManager>;
+/// Number of unused connections kept open for later requests
+const MAX_IDLE_CONNECTIONS: usize = 16;
+
+fn session_key(session_id: &str) -> String {
+    format!("iron_session:{}", session_id)
+}
+
+fn index_key(key: &str, value: &str) -> String {
+    format!("iron_session_index:{}:{}", key, value)
+}
+
+fn make_session_id() -> String {
+    let mut rng = rand::OsRng::new().unwrap();
+    String::from_iter(rng.gen_ascii_chars().take(40))
+}
+
+/// Keeps connections to the Redis server open after use, so that every access does not have to open a new one.
+struct ConnectionPool {
+    client: redis::Client,
+    idle: Mutex<Vec<redis::Connection>>,
+}
+
+impl ConnectionPool {
+    fn get(pool: &Arc<ConnectionPool>) -> redis::RedisResult<PooledConnection> {
+        let idle = pool.idle.lock().unwrap().pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => pool.client.get_connection()?,
+        };
+        Ok(PooledConnection { pool: pool.clone(), conn: Some(conn) })
+    }
+}
+
+/// A connection that is returned to the pool when dropped, unless it has been closed because of an error.
+struct PooledConnection {
+    pool: Arc<ConnectionPool>,
+    conn: Option<redis::Connection>,
+}
+
+impl Deref for PooledConnection {
+    type Target = redis::Connection;
+
+    fn deref(&self) -> &redis::Connection {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut redis::Connection {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            let mut idle = self.pool.idle.lock().unwrap();
+            if conn.is_open() && idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(conn);
+            }
+        }
+    }
+}
 
 pub struct RedisSession {
     session_id: String,
-    pool: RedisPool,
+    pool: Arc<ConnectionPool>,
+    indexed_keys: Arc<Vec<String>>,
+    expiry: Option<usize>,
 }
 
 impl RawSession for RedisSession {
     fn get_raw(&self, key: &str) -> IronResult<Option<String>> {
-        let conn = itry!(self.pool.get());
-        Ok(itry!(conn.hget(&self.session_id, key)))
+        let mut conn = itry!(ConnectionPool::get(&self.pool));
+        match self.expiry {
+            Some(expiry) => {
+                let (value,): (Option<String>,) = itry!(redis::pipe()
+                    .hget(session_key(&self.session_id), key)
+                    .expire(session_key(&self.session_id), expiry).ignore()
+                    .query(&mut *conn));
+                Ok(value)
+            }
+            None => Ok(itry!(conn.hget(session_key(&self.session_id), key))),
+        }
     }
 
     fn set_raw(&mut self, key: &str, value: String) -> IronResult<()> {
-        let conn = itry!(self.pool.get());
-        itry!(conn.hset::<&str, &str, String, ()>(&self.session_id, key, value));
+        let mut conn = itry!(ConnectionPool::get(&self.pool));
+        if self.indexed_keys.iter().any(|k| k == key) {
+            // Changing an indexed value (e.g. a login) moves the session to a new id, so that a session id known
+            // before can not be used to take over the session
+            let old_value: Option<String> = itry!(conn.hget(session_key(&self.session_id), key));
+            if old_value.as_deref() != Some(value.as_str()) {
+                let new_session_id = make_session_id();
+                // A new session without any values has nothing to be moved yet
+                let exists: bool = itry!(conn.exists(session_key(&self.session_id)));
+                if exists {
+                    itry!(conn.rename::<_, ()>(session_key(&self.session_id), session_key(&new_session_id)));
+                }
+                self.session_id = new_session_id;
+            }
+        }
+        let mut pipe = redis::pipe();
+        pipe.hset(session_key(&self.session_id), key, &value).ignore();
+        if let Some(expiry) = self.expiry {
+            pipe.expire(session_key(&self.session_id), expiry).ignore();
+        }
+        if self.indexed_keys.iter().any(|k| k == key) {
+            pipe.sadd(index_key(key, &value), &self.session_id).ignore();
+            if let Some(expiry) = self.expiry {
+                pipe.expire(index_key(key, &value), expiry).ignore();
+            }
+        }
+        itry!(pipe.query::<()>(&mut *conn));
         Ok(())
     }
 
     fn clear(&mut self) -> IronResult<()> {
-        let conn = itry!(self.pool.get());
-        itry!(conn.del::<&str, ()>(&self.session_id));
+        let mut conn = itry!(ConnectionPool::get(&self.pool));
+        itry!(conn.del::<_, ()>(session_key(&self.session_id)));
         self.session_id = "".to_owned();
         Ok(())
     }
@@ -47,31 +144,64 @@ impl RawSession for RedisSession {
     fn write(&self, res: &mut Response) -> IronResult<()> {
         let cookie = get_default_cookie(COOKIE_NAME.to_owned(), self.session_id.clone());
         if let Some(cookies) = res.headers.get_mut::<iron::headers::SetCookie>() {
-            debug_assert!(cookies.iter().all(|cookie| cookie != COOKIE_NAME));
-            cookies.push(format!("{}", cookie.pair()));
+            debug_assert!(cookies.iter().all(|cookie| !cookie.starts_with(COOKIE_NAME)));
+            cookies.push(format!("{}", cookie));
             return Ok(());
         }
         res.headers
-            .set(iron::headers::SetCookie(vec![format!("{}", cookie.pair())]));
+            .set(iron::headers::SetCookie(vec![format!("{}", cookie)]));
         Ok(())
     }
 }
 
+/// Store the session data in Redis. Only a random session id is sent to the client.
+///
+/// In contrast to `SignedCookieBackend`, sessions can be revoked on the server: Register the keys whose values you
+/// want to look up sessions by with `index_key` and call `revoke` to delete all sessions with a given value.
+#[derive(Clone)]
 pub struct RedisBackend {
-    pool: RedisPool,
+    pool: Arc<ConnectionPool>,
+    indexed_keys: Arc<Vec<String>>,
+    expiry: Option<usize>,
 }
 
 impl RedisBackend {
-    pub fn new<T: redis::IntoConnectionInfo>(params: T) -> Result<Self> {
-        let manager =
-            RedisConnectionManager::new(params)
-                .chain_err(|| "Couldn't create redis connection manager")?;
-        let pool = 
-            r2d2::Pool::builder()
-                .build(manager)
-                .chain_err(|| "Couldn't create redis connection pool")?;
-
-        Ok(RedisBackend { pool: pool })
+    pub fn new<T: redis::IntoConnectionInfo>(params: T) -> redis::RedisResult<Self> {
+        let client = redis::Client::open(params)?;
+        // Fail early if the server cannot be reached
+        let conn = client.get_connection()?;
+
+        let pool = ConnectionPool { client: client, idle: Mutex::new(vec![conn]) };
+        Ok(RedisBackend { pool: Arc::new(pool), indexed_keys: Arc::new(Vec::new()), expiry: None })
+    }
+
+    /// Remember which sessions contain which value for `key`, so that they can be deleted with `revoke`.
+    pub fn index_key(&mut self, key: &str) {
+        Arc::make_mut(&mut self.indexed_keys).push(key.to_owned());
+    }
+
+    /// Delete sessions that have not been used for `seconds` seconds.
+    pub fn set_expiry(&mut self, seconds: usize) {
+        self.expiry = Some(seconds);
+    }
+
+    /// Whether the session with the id `session_id` has been stored. Connection errors count as unknown sessions.
+    fn session_exists(&self, session_id: &str) -> bool {
+        ConnectionPool::get(&self.pool)
+            .and_then(|mut conn| conn.exists(session_key(session_id)))
+            .unwrap_or(false)
+    }
+
+    /// Delete all sessions in which the indexed `key` is set to `value`.
+    pub fn revoke(&self, key: &str, value: &str) -> redis::RedisResult<()> {
+        let mut conn = ConnectionPool::get(&self.pool)?;
+        let session_ids: Vec<String> = conn.smembers(index_key(key, value))?;
+        let mut pipe = redis::pipe();
+        for session_id in session_ids {
+            pipe.del(session_key(&session_id)).ignore();
+        }
+        pipe.del(index_key(key, value)).ignore();
+        pipe.query(&mut *conn)
     }
 }
 
@@ -81,26 +211,23 @@ impl SessionBackend for RedisBackend {
     fn from_request(&self, req: &mut Request) -> Self::S {
         let session_id = req.headers
             .get::<iron::headers::Cookie>()
-            .map(|cookies| {
-                // FIXME: Our cookies are unsigned. Why do I need to specify a key?
-                let mut jar = cookie::CookieJar::new(b"");
-                for cookie in cookies.iter() {
-                    if let Ok(cookie) = cookie::Cookie::parse(&cookie) {
-                        jar.add_original(cookie);
-                    }
-                }
-                jar
+            .and_then(|cookies| {
+                cookies.iter()
+                    .filter_map(|cookie| cookie::Cookie::parse(cookie.clone()).ok())
+                    .find(|cookie| cookie.name() == COOKIE_NAME)
+                    .map(|cookie| cookie.value().to_owned())
             })
-            .and_then(|jar| jar.find(COOKIE_NAME))
-            .map(|cookie| cookie.value)
-            .unwrap_or_else(|| {
-                let mut rng = rand::OsRng::new().unwrap();
-                String::from_iter(rng.gen_ascii_chars().take(40))
-            });
+            .filter(|session_id| !session_id.is_empty())
+            // Only continue sessions that exist, so that a client can not choose the id of its next session (session
+            // fixation)
+            .filter(|session_id| self.session_exists(session_id))
+            .unwrap_or_else(make_session_id);
 
         RedisSession {
             session_id: session_id,
             pool: self.pool.clone(),
+            indexed_keys: self.indexed_keys.clone(),
+            expiry: self.expiry,
         }
     }
 }
@@ -3,8 +3,6 @@ extern crate cookie as _cookie;
 #[cfg(not(feature = "redis-backend"))] extern crate iron;
 extern crate rand;
 #[cfg(feature = "redis-backend")] extern crate redis;
-#[cfg(feature = "redis-backend")] extern crate r2d2;
-#[cfg(feature = "redis-backend")] extern crate r2d2_redis;
 
 use iron::prelude::*;
 use iron::middleware::{AroundMiddleware,Handler};
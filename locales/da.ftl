@@ -0,0 +1,769 @@
+# Danske tekster til brugerfladen
+
+language-name = Dansk
+
+## Klassetrin
+
+grade-0 = Går endnu ikke i skole
+grade-11 = 11 (G8)
+grade-12 = 12 (G8)
+grade-111 = 11 (G9)
+grade-112 = 12 (G9)
+grade-113 = 13 (G9)
+grade-114 = Erhvervsskole
+grade-255 = Går ikke længere i skole
+
+sex-not-stated = Ikke oplyst
+sex-female = pige
+sex-male = dreng
+sex-diverse = divers
+sex-other = andet
+
+## Navigation
+
+slogan = Programmering – lettere end du tror!
+logged-in-as = Logget ind som
+role-teacher = Lærer
+logout = Log ud
+login = Log ind
+log-in = log ind
+nav-contests = Konkurrence
+nav-profile = Profil
+nav-groups = Gruppeadministration
+nav-teacher-material = Materiale til lærere
+nav-admin = Administration
+
+## Log ind
+
+username = Brugernavn
+password = Adgangskode
+password-forgotten = Glemt adgangskode?
+login-code = Gruppekode / deltagerkode
+login-code-placeholder = Gruppekode eller login-kode
+login-hint = Bemærk: Hvis du ikke længere kan huske dine loginoplysninger, kan din lærer se dem for dig.
+login-with-password = Log ind med brugernavn og adgangskode (ikke PMS-konto)
+login-with-code = Log ind med gruppekode eller login-kode
+login-with-oauth = Log ind med en anden konto
+login-failed = Login mislykkedes. Prøv igen.
+login-code-invalid = Ugyldig kode. Prøv igen.
+login-link-invalid = Login-linket er udløbet eller spærret. Log ind med din login-kode.
+oauth-login-failed = OAuth-login mislykkedes.
+
+## Profil
+
+profile-language = Sprog
+profile-language-browser = Browserens sprog
+profile-language-save = Skift sprog
+
+## Stjerner i profilen
+
+stars-none = Kom i gang, din første stjerne venter på dig!
+stars-few = En fremragende start!
+stars-many = Det er ret godt!
+stars-master = En sand mester!
+stars-wow = Wow! Simpelthen wow!
+
+## Opgaver
+
+task-title = Opgave „{ $taskgroup }“ i { $contest }
+
+## Sletning og oprydning
+
+delete-user-protected-participations = Brugeren har deltagelser i beskyttede konkurrencer.
+delete-user-group-admin = Brugeren er administrator af grupper.
+delete-user-not-found = Brugeren findes ikke.
+delete-group-not-found = Gruppen findes ikke.
+delete-group-protected-participations = Gruppen har medlemmer med beskyttede deltagelser.
+cleanup-failed = Fejl.
+
+## Import af grupper
+
+import-column = Kolonne { $number }
+import-column-group = Gruppe
+import-column-grade = Klassetrin
+import-column-firstname = Fornavn
+import-column-lastname = Efternavn
+import-column-sex = Køn
+import-no-column = Ingen kolonne valgt for "{ $field }"
+import-value-missing = "{ $field }" mangler
+import-name-missing = For- og efternavn mangler
+import-too-long = "{ $field }" er længere end { $max } tegn
+import-unknown-grade = Ukendt klassetrin "{ $value }"
+import-unknown-sex = Ukendt køn "{ $value }"
+import-sex-not-allowed = Køn "{ $value }" er ikke tilladt
+import-invalid-encoding = Filen er ikke gemt i den valgte tegnkodning.
+import-invalid-csv = Filen kunne ikke læses: { $error }
+import-empty = Filen indeholder ingen deltagere.
+
+## Loginark (PDF)
+
+login-sheet-title = Dine loginoplysninger
+login-sheet-group = Gruppe { $group }, klassetrin { $grade }
+login-sheet-logincode = Login-kode
+login-sheet-groupcode = Gruppekode: { $groupcode }
+login-sheet-instructions = Åbn { $login_url } og indtast din login-kode, eller scan QR-koden. Pas godt på din login-kode, du skal bruge den hver gang du logger ind.
+login-sheet-summary-title = Loginoplysninger for gruppen { $group }
+login-sheet-summary = De følgende sider indeholder loginoplysningerne for gruppens { $count } deltagere til at klippe ud og dele ud. Deltagerne logger ind på { $login_url } med deres login-kode. Denne oversigt er kun til dig.
+login-sheet-name = Navn
+login-sheet-grade = Klassetrin
+
+## Sidefod
+
+footer-source-code = Kildekode
+footer-task-license = Alle opgaver under
+# Ingen artikel på dansk
+footer-jwinf-article =
+footer-jwinf-is-one-of = er en af de
+footer-bwinf = tyske informatikkonkurrencer
+footer-bwinf-funding = De tyske informatikkonkurrencer er elevkonkurrencer støttet af de tyske undervisningsministres konference og står under forbundspræsidentens protektion.
+footer-organizers = Arrangører:
+
+## Kvalifikation
+
+qualification-unknown-contest = Konkurrencen { $contest } er ukendt.
+qualification-not-participated = Du har ikke deltaget i konkurrencen „{ $contest }“.
+qualification-participated = Du har deltaget i konkurrencen „{ $contest }“.
+qualification-percentage-reached = Du har opnået { $percentage } % af point, der kræves { $required } %.
+qualification-percentage-missed = Du har opnået { $percentage } % af point, men der kræves { $required } %.
+qualification-rank-reached = Du er nummer { $rank } på dit klassetrin, de bedste { $top } er kvalificeret.
+qualification-rank-missed = Du er nummer { $rank } på dit klassetrin, men kun de bedste { $top } er kvalificeret.
+qualification-not-ranked = Du er ikke placeret på dit klassetrin.
+qualification-admitted = Din optagelse er registreret.
+qualification-not-admitted = Der er ikke registreret nogen optagelse for dig.
+qualification-invalid-rules = Kvalifikationskravene for denne konkurrence er fejlbehæftede.
+
+## E-mails
+
+mail-greeting = Hej { $name },
+mail-confirm-email-subject = Bekræftelse af din e-mailadresse
+mail-confirm-email-text = bekræft venligst din e-mailadresse ved at åbne følgende link:
+mail-confirm-email-ignore = Hvis du ikke har angivet denne e-mailadresse, kan du ignorere denne e-mail.
+mail-password-reset-subject = Nulstil adgangskode
+mail-password-reset-text = der er anmodet om en ny adgangskode til din konto. Med følgende link kan du vælge en ny adgangskode inden for 24 timer:
+mail-password-reset-ignore = Hvis du ikke har anmodet om en ny adgangskode, kan du ignorere denne e-mail.
+
+## Roller i administrationen
+
+role-contest_manager = Konkurrenceadministration
+role-school_coordinator = Skolekoordinering
+role-support = Support
+role-auditor = Revision (kun læsning)
+
+## Statistik
+
+report-without-oauth = Uden OAuth
+
+## Konkurrenceoversigt
+
+contests-training = Træningsopgaver
+contests-contest = Konkurrencer
+contests-challenge = Udfordringer
+contests-standalone-tasks = Enkelte opgaver uden point
+
+## Generelt
+
+back-to-start = Til forsiden
+back-to-contests = Til konkurrenceoversigten
+contests-filter-by-tag = Filtrér efter tag:
+contests-view-results = Se resultater
+contests-and-tasks = Konkurrencer / opgaver
+contests-none-available = Der er i øjeblikket ingen konkurrencer / opgaver tilgængelige her.
+
+## Konkurrence
+
+contest-title = Konkurrence:
+contest-training-title = Træningsopgaver:
+contest-duration = Varighed: { $minutes } minutter
+contest-show-group-results = Vis resultaterne for mine grupper.
+contest-show-scoreboard = Vis ranglisten.
+contest-group-results = Grupperesultater
+contest-group-results-text = Se resultaterne for mine grupper i denne konkurrence
+contest-scoreboard = Rangliste
+contest-scoreboard-text = Se den anonymiserede live-rangliste for denne konkurrence
+contest-not-logged-in = Bemærk: Du er ikke logget ind. Log ind for at gemme dine fremskridt.
+contest-started-ago = Startet for { $seconds } sekunder siden.
+contest-time-left = Resterende tid:
+contest-extra-time = (inklusive { $minutes } minutters ekstra tid)
+contest-teachers-exempt = (Lærere er undtaget fra tidsgrænsen.)
+contest-paused = Deltagelsen er sat på pause.
+contest-paused-by-teacher = Din deltagelse er sat på pause af din lærer. Tiden løber ikke i øjeblikket.
+contest-review-mode = Gennemsynstilstand: Du kan åbne og arbejde med opgaverne. Løsningerne bliver dog ikke gemt og giver ingen point.
+contest-review-ends-in = Gennemsynstilstanden slutter om { $days } dage, { $hours } timer og { $minutes } minutter.
+contest-time-over = Tiden er udløbet. Du kan ikke længere åbne opgaverne.
+contest-review-starts-in = Gennemsynstilstanden begynder om { $days } dage, { $hours } timer og { $minutes } minutter.
+contest-score = Pointstilling:
+contest-qualified = Du er kvalificeret til denne konkurrence:
+contest-not-qualified = Du er ikke kvalificeret til denne konkurrence.
+contest-qualification-requirements = For at deltage skal et af følgende krav være opfyldt:
+contest-password = Konkurrenceadgangskode
+contest-password-wrong = Den indtastede adgangskode er ikke korrekt.
+contest-start-now = Start nu!
+contest-runs-for = Efter start varer konkurrencen { $minutes } minutter.
+contest-cannot-pause = Konkurrencen kan ikke sættes på pause efter start.
+contest-cannot-start = Du kan ikke starte denne konkurrence.
+contest-over = Konkurrencen er allerede slut.
+contest-not-begun = Konkurrencen er ikke begyndt endnu.
+contest-wrong-grade = Det klassetrin, du har angivet, passer ikke til de aldersgrupper, der er tiltænkt denne konkurrence.
+contest-login-needed = Du skal muligvis logge ind først:
+contest-begins-in = Konkurrencen begynder om: { $days } dage, { $hours } timer, { $minutes } minutter.
+contest-ends-in = Konkurrencen kører stadig: { $days } dage, { $hours } timer, { $minutes } minutter.
+
+## Opgave
+
+task-review-mode = Gennemsynstilstand
+task-time-left = Resterende tid
+task-fullscreen = Fuld skærm
+task-overview = Oversigt
+task-next = Næste opgave
+task-previous = Forrige opgave
+task-no-frames = Din browser understøtter desværre ikke frames. Du kan også åbne opgaven manuelt:
+task-open-manually = Arbejd med opgaven manuelt
+task-load-failed = Indlæsning mislykkedes
+task-save-failed = Lagring mislykkedes
+
+## Resultater
+
+yes = ja
+no = nej
+groups = Grupper
+back-to-contest = Til konkurrencen
+back-to-results = Til resultaterne
+back-to-group = Til gruppen
+results = Resultater
+results-in = Resultater i { $contest }
+results-download = Download resultater:
+results-show-ranking = Vis rangliste
+results-group = Gruppe
+results-groupcode = Gruppekode
+results-participant = Deltager
+results-rank = Placering
+results-sum = Sum
+results-total = Samlede point
+results-admission = Adgang til 3. runde
+results-admission-short = Adgang
+results-statistics = Statistik
+results-none = Der er endnu ingen resultater fra dine grupper i denne konkurrence.
+scoreboard-in = Rangliste i { $contest }
+scoreboard-empty = Der er endnu ikke opnået nogen point.
+ranking = Rangliste
+ranking-in = Rangliste i { $contest }
+ranking-school = Skole
+ranking-filter = Filtrer
+ranking-public-scoreboard = Offentlig rangliste
+ranking-score-reached = Pointtal opnået
+ranking-attempts = Afleveringer
+ranking-empty = Der er ingen matchende deltagelser i denne konkurrence.
+
+## Gruppestatistik
+
+statistics-title = { $contest }: Statistik for gruppen { $group }
+statistics-title-long = Statistik for gruppen „{ $group }“ i { $contest }
+statistics-participants = Deltagere med resultater:
+statistics-average-time = Gennemsnitlig tid fra start til første aflevering:
+statistics-hardest-task = Sværeste opgave:
+statistics-task = Opgave
+statistics-average-stars = Gennemsnitlige stjerner
+statistics-stars-of = { $stars } af { $max }
+statistics-reached = Opnået
+statistics-share-reached = Andel opnået
+statistics-not-attempted = Uden aflevering
+statistics-time-to-first-submission = Tid til første aflevering
+statistics-hardest = sværeste
+statistics-distribution = Pointfordeling
+statistics-with = med
+
+## Afleveringer
+
+submissions-back-to-participation = Tilbage til deltagelsen
+submissions-of = Afleveringer fra { $firstname } { $lastname }
+submissions-history = Historik
+submissions-history-title = Afleveringshistorik
+submissions-number = Nr.
+submissions-time = Tidspunkt
+submissions-time-review = Tidspunkt / gennemsyn
+submissions-points = Point
+submissions-unvalidated-points = Ukontrollerede point
+submissions-validated = Kontrolleret
+submissions-pending = afventer
+submissions-left = Venstre
+submissions-right = Højre
+submissions-compare = Sammenlign
+submissions-none = Ingen afleveringer.
+submissions-comparison = Sammenligning af aflevering { $left } og { $right }
+submissions-too-large = Afleveringerne er for store til at blive sammenlignet linje for linje.
+
+## Fejlsider
+
+page-locked = 423: Siden er spærret
+page-locked-text = Denne side er midlertidigt deaktiveret for at mindske serverbelastningen og sikre en gnidningsløs konkurrence. Prøv venligst igen senere!
+action-forbidden = Denne handling er ikke tilladt!
+
+## Grupper
+
+back = Tilbage
+back-to-groups = Til gruppeoversigten
+edit = rediger …
+optional = valgfri
+group-members = Medlemmer
+group-tag = Mærke
+group-login-sheets-print = Loginoplysninger til udskrivning (PDF)
+groups-create = Opret gruppe
+groups-create-plural = Opret grupper
+groups-create-new = Opret ny gruppe
+groups-name = Gruppenavn
+groups-tag-hint = Mærket kan f.eks. være navnet på klassen eller holdet. Det bliver trykt på diplomerne og hjælper dig med at sortere dem.
+groups-class = Klasse-/holdbetegnelse
+groups-class-hint = Klasse- og holdbetegnelserne bliver trykt på diplomerne. Diplomerne sorteres efter dem.
+groups-csv-upload-before = Du kan også oprette grupper med en
+groups-csv-upload = CSV-upload
+groups-invitations = Invitationer til at administrere grupper sammen
+groups-admin = Gruppeadministrator
+groups-accept = Accepter
+groups-decline = Afvis
+groups-mine = Mine eksisterende grupper
+groups-managed = Grupper, som jeg administrerer
+groups-results-hint = For at se konkurrenceresultaterne for mine grupper skal du først
+groups-choose-contest = vælge en konkurrence
+groups-contest-results = Konkurrenceresultater
+groups-contest-results-link = Se konkurrenceresultaterne for mine grupper
+groups-download = Download
+groups-view = Vis …
+groups-login-sheets = Loginoplysninger (PDF)
+groups-none = Der er endnu ingen grupper. Opret en ny gruppe ovenfor, så dine elever kan deltage i konkurrencer
+
+## CSV-upload
+
+import-title = Opret grupper med CSV-upload
+import-intro = Her kan du oprette grupper og konti fra en CSV-fil, f.eks. en eksport fra skolens administrationssystem.
+import-columns-intro = Filen skal indeholde en linje med følgende kolonner for hver deltager. Yderligere kolonner ignoreres.
+import-columns-group = Gruppens navn. (En fil kan indeholde flere grupper.)
+import-columns-grade = Deltagerens klassetrin, som tal eller som i profilen (f.eks. „12 (G9)“).
+import-columns-firstname = Deltagerens fornavn.
+import-columns-lastname = Deltagerens efternavn.
+import-columns-sex = Deltagerens køn (m, f, d eller tom).
+import-columns-biber = Disse fem kolonner svarer til de første fem kolonner i CSV-filerne til upload til Informatik-Biber (uden brugernavne og adgangskoder).
+import-columns-header = Hvis filen har en overskriftslinje med kolonnenavne som „Group“, „Grade“, „First name“, „Last name“ og „Sex“, genkendes kolonnerne ud fra dem.
+import-example = Et eksempel kunne se sådan ud:
+import-example-header = Group,Grade,First name,Last name,Sex
+import-file = CSV-fil
+import-encoding = Tegnkodning
+import-encoding-utf16 = UTF-16 (Excel „Unicode-tekst“)
+import-detect = Genkend automatisk
+import-delimiter = Skilletegn
+import-comma = Komma
+import-semicolon = Semikolon
+import-tab = Tabulator
+import-header = Overskriftslinje
+import-header-yes = Ja
+import-header-no = Nej
+import-paste = I stedet for at vælge en fil kan du også indsætte dens indhold her:
+import-check = Kontroller fil
+import-preview = Forhåndsvisning
+import-no-column-selected = ingen
+import-correct = Du kan også rette dataene direkte her:
+import-update-preview = Opdater forhåndsvisning
+import-create = Opret grupper
+import-will-create = Følgende grupper bliver oprettet. Oprettede grupper kan ikke slettes.
+import-error-count = Filen indeholder { $count } fejl. Ret venligst fejlene, før du opretter grupperne. Grupperne oprettes først, når alle linjer er korrekte.
+import-participants = { $count } deltagere
+import-line = Linje
+import-grade-short = Klassetrin
+import-errors = Fejl
+
+## Administration
+
+admin-search = Administratorsøgning
+admin-search-wildcard = % er et jokertegn i navnesøgningen.
+admin-search-limit = Søgningen viser kun op til 30 resultater for ikke at belaste serveren for meget. Lav evt. en mere præcis søgning!
+admin-firstname = Fornavn:
+admin-lastname = Efternavn:
+admin-search-by-name = Søg efter navn
+admin-logincode = Loginkode:
+admin-search-by-logincode = Søg efter loginkode
+admin-groupcode = Gruppekode:
+admin-search-by-groupcode = Søg efter gruppekode
+admin-search-by-id = Søg efter ID
+admin-search-by-pmsid = Søg efter PMS-ID
+admin-contest-export = Konkurrenceeksport
+admin-contest-export-link = Konkurrenceoversigt og -eksport
+admin-dbstatus = Databasestatus
+admin-reports-link = Deltagelser, registreringer og afleveringer
+admin-audit-log = Revisionslog
+admin-audit-log-link = Ændringer foretaget af administratorer og lærere
+admin-cleanup = Ryd op i gamle brugerkonti
+admin-cleanup-link = Databaseoprydning
+admin-user-id = Bruger-ID:
+admin-group-id = Gruppe-ID:
+admin-from = Fra:
+admin-until = Til:
+admin-search-submit = Søg
+admin-audit-no-entries = Ingen poster fundet.
+admin-audit-actor = Udført af
+admin-audit-action = Handling
+admin-user = Bruger
+admin-contest = Konkurrence
+admin-audit-before = Før
+admin-audit-after = Efter
+admin-cleanup-hard = Slet gamle data
+admin-cleanup-hard-text = Dette sletter alle administrerede elevkonti, der ikke er blevet brugt i mere end 180 dage, alle lærerkonti, der ikke er brugt i mere end 3 år, og alle andre konti, der ikke er brugt i mere end 10 år.
+admin-cleanup-hard-warning = Bør ikke udføres under en konkurrence eller efter tilmeldingens start, hvis en lærer stadig planlægger at bruge ældre konti. Udføres helst før tilmeldingens start og efter afslutningen af anden runde.
+admin-cleanup-hard-submit = Slet gamle data!
+admin-cleanup-session = Slet midlertidige sessioner
+admin-cleanup-session-text = Sletter sessioner for brugere, der ikke har logget ind, hvis de er ældre end 30 dage.
+admin-cleanup-session-safe = Kan til enhver tid udføres uden risiko.
+admin-cleanup-session-submit = Slet midlertidige sessioner!
+admin-export-title = Eksport: { $contestname }
+admin-export-format = Format
+admin-export-csv-excel = CSV til Excel (med BOM, semikolonsepareret)
+admin-export-columns = Kolonner
+admin-export-subtasks = Point for hver delopgave
+admin-export-submissions = Antal afleveringer pr. opgave
+admin-export-download = Download
+admin-scan = Genindlæs konkurrencer
+admin-scan-text = Indlæser alle konkurrence- og opgavefiler, der er ændret siden sidste indlæsning. Konkurrencer, hvis fil er fjernet, skjules. Filer med fejl indlæses ikke, den tidligere version bevares.
+admin-scan-safe = Kan også udføres under en igangværende konkurrence.
+admin-scan-result = Resultat
+admin-scan-added = Tilføjet
+admin-scan-changed = Ændret
+admin-scan-hidden = Skjult
+admin-results-export = Eksport af resultatdata
+admin-search-no-results = Ingen resultater fundet.
+admin-search-too-many = Mere end { $max_results } resultater fundet. Kun de første { $max_results } resultater vises. Forfin venligst søgningen!
+admin-search-count = { $num_results } resultater:
+admin-cleanup-deleted = Data blev slettet:
+admin-cleanup-students = elever
+admin-cleanup-teachers = lærere
+admin-cleanup-others = andre
+admin-cleanup-sessions = sessioner
+admin-cleanup-failed = Kan ikke slette data:
+admin-new-search = Ny søgning
+admin-role-admin = Administrator
+admin-role-student = Elev
+admin-user-id-value = Id: { $user_id }
+admin-user-firstname = Fornavn: { $user_firstname }
+admin-user-lastname = Efternavn: { $user_lastname }
+admin-user-grade = Klassetrin: { $user_grade }
+admin-user-username = Brugernavn: { $user_username }
+admin-user-logincode = Loginkode: { $user_logincode }
+admin-user-oauth = OAuth-login: { $user_oauthprovider } ({ $user_oauthprovider }-id: { $user_oauthid })
+admin-user-logged-in = Er logget ind
+admin-user-admin-of = Administrator for
+admin-id = Id
+admin-class-short = Klasse/hold
+admin-user-member-of = Medlem af
+admin-participations = Deltagelser
+admin-roles = Roller
+admin-roles-save = Gem roller
+admin-sessions = Login
+admin-sessions-revoke = Log ud på alle enheder
+admin-user-delete = Slet bruger
+admin-user-delete-submit = Slet bruger!
+admin-group-id-value = Id: { $group_id }
+admin-group-name = Navn: { $group_name }
+admin-group-code = Gruppekode: { $group_code }
+admin-group-tag = Klasse/hold: { $group_tag }
+admin-group-admin = Gruppeadministrator:
+admin-group-links-revoked = Loginlinkene er blevet spærret. Download loginoplysningerne igen for at få nye loginlinks.
+admin-group-revoke-link = Spær loginlink
+admin-group-revoke-links = Spær gruppens loginlinks
+admin-group-teachers = Lærere
+admin-group-invited = Læreren er blevet inviteret.
+admin-group-already-invited = Denne lærer administrerer allerede gruppen eller er allerede inviteret.
+admin-group-invitee-not-found = Der blev ikke fundet nogen lærer med denne loginkode eller e-mailadresse.
+admin-group-removed = Læreren er blevet fjernet.
+admin-group-transferred = Gruppen er blevet overdraget.
+admin-group-accepted = Du administrerer nu denne gruppe sammen med andre.
+admin-group-status = Status
+admin-group-co-managing = administrerer med
+admin-group-invitation-pending = inviteret
+admin-group-transfer = Overdrag gruppe
+admin-group-remove = Fjern
+admin-group-leave = Stop med at administrere
+admin-group-invitee = Loginkode eller e-mailadresse:
+admin-group-invite = Inviter lærer
+admin-group-delete = Slet gruppe
+admin-group-delete-submit = Slet gruppe!
+admin-participation = Deltagelse
+admin-participation-data = Deltagelsesdata
+admin-participation-start = Start
+admin-participation-expired = udløbet
+admin-participation-extra-time = (inkl. { $extra_time } minutters ekstra tid)
+admin-participation-paused = , sat på pause
+admin-participation-extra-time-minutes = Ekstra tid i minutter:
+admin-participation-set = Fastsæt
+admin-participation-resume = Fortsæt
+admin-participation-pause = Sæt på pause
+admin-participation-restart = Genstart tid
+admin-submissions = Afleveringer
+admin-participation-history = Historik og sammenligning
+admin-participation-delete = Slet deltagelse
+admin-participation-delete-submit = Slet deltagelse!
+admin-reports-show = Vis
+admin-reports-export = Eksporter
+admin-reports-overview = Overblik
+admin-reports-teachers = Lærere med deltagende grupper
+admin-reports-schools = Skoler med deltagende grupper
+admin-reports-per-contest = Deltagelser pr. konkurrence
+admin-reports-by-login = Deltagelser efter login
+admin-reports-by-grade = Deltagelser efter klassetrin
+admin-reports-by-sex = Deltagelser efter køn
+admin-reports-accounts-per-day = Nye brugerkonti pr. dag (UTC)
+admin-reports-submissions-per-hour = Afleveringer pr. time (UTC)
+admin-user-deleted = Bruger slettet.
+admin-user-delete-failed = Kan ikke slette bruger:
+admin-group-deleted = Gruppe slettet.
+admin-group-delete-failed = Kan ikke slette gruppe:
+admin-participation-deleted = Deltagelse slettet.
+admin-participation-delete-failed = Kan ikke slette deltagelse:
+admin-participation-delete-confirm = Vil du virkelig slette deltagelsen?
+admin-search-for = Administratorsøgning efter …
+admin-search-for-name = … navn
+admin-search-for-logincode = … loginkode
+admin-search-for-groupcode = … gruppekode
+admin-search-for-id = … ID
+admin-search-user-id = Bruger-ID
+admin-search-for-pmsid = … PMS-ID
+admin-cleanup-title = Sletning af gamle brugerkonti
+admin-dbstatus-show = Vis databasestatus
+admin-export = Eksport
+admin-scan-short = Genindlæs
+admin-contest-list = Konkurrenceliste
+admin-contest-all = Alle konkurrencer
+admin-results-export-link = Eksporter resultater
+admin-results-upload = ⇧ Upload resultater
+admin-search-results = Søgeresultater
+
+## Administration (JWINF)
+
+admin-user-id-short = Bruger-ID
+admin-oauth-login = OAuth-login
+admin-oauth-id = { $user_oauthprovider }-ID
+admin-user-delete-named = Slet brugeren „{ $user_firstname } { $user_lastname }“:
+admin-cannot-be-undone = Denne handling kan ikke fortrydes!
+admin-user-protected-warning = Bemærk: Denne bruger har beskyttede deltagelser i konkurrencer.
+admin-user-protected = Brugeren kan ikke slettes, da brugeren har beskyttede deltagelser i konkurrencer.
+admin-user-protected-hint = Brugeren kan først slettes, når evalueringen af de pågældende konkurrencer er afsluttet, og beskyttelsen af deltagelserne er ophævet.
+admin-user-has-groups = Brugeren kan kun slettes, hvis brugeren ikke ejer nogen grupper. Slet venligst brugerens grupper først.
+admin-user-move-named = Flyt brugeren „{ $user_firstname } { $user_lastname }“ til en anden gruppe:
+admin-user-move = Flyt bruger
+admin-user-edit-profile = Rediger profil
+admin-roles-label = Roller:
+admin-sessions-label = Login:
+admin-user-member-in = Medlem af
+admin-user-logincode-hint = Dette er brugerens loginkode. Brugeren kan logge ind med denne kode.
+admin-tasks-contest = Opgaver/konkurrence
+admin-start-time = Starttidspunkt
+admin-user-no-participations = Ingen deltagelser endnu. Så snart brugeren starter deltagelser, vises de her.
+admin-user-delete-hint = Her kan du slette brugeren med alle deltagelser. Sletningen kan ikke fortrydes.
+admin-user-delete-all = Slet bruger med alle deltagelser
+admin-user-timed-warning = Bemærk: Denne bruger har deltagelser i tidsbegrænsede konkurrencer.
+admin-back-to-group = Tilbage til gruppen
+admin-user-has-groups-short = Brugeren kan kun slettes, hvis brugeren ikke ejer nogen grupper.
+admin-user-delete-groups-first = Slet venligst brugerens grupper først.
+admin-group-tag-paren = (Klasse/hold: { $group_tag })
+admin-group-id-short = Gruppe-ID
+admin-group-more-teachers = Yderligere lærere
+admin-group-invited-paren = (inviteret)
+admin-group-delete-named = Slet gruppen „{ $group_name }“:
+admin-group-protected-warning = Bemærk: Denne gruppe har medlemmer med beskyttede deltagelser! Hvis gruppen slettes, slettes deltagelser i JWINF!
+admin-group-code-hint = Dette er gruppekoden. Giv den videre til deltagerne. Gruppekoden opretter en ny konto, hver gang den bruges.
+admin-sex-short = Køn
+admin-login-link = Loginlink
+admin-revoke = Spær
+admin-group-download-members = Download medlemsliste:
+admin-group-login-sheets-paren = (loginoplysninger til udskrivning)
+admin-group-login-links-hint = QR-koderne på loginoplysningerne indeholder loginlinks, som deltagerne kan logge ind med uden at indtaste loginkoden. Hvis loginoplysninger er gået tabt, kan du spærre loginlinkene for enkelte deltagere eller hele gruppen og downloade loginoplysningerne igen. Loginkoderne forbliver gyldige.
+admin-group-no-members = Ingen medlemmer endnu. Så snart deltagere logger ind med gruppekoden ovenfor, vises de her.
+admin-group-invited-hint = Læreren er blevet inviteret. Læreren kan administrere gruppen med, så snart invitationen er accepteret.
+admin-group-transferred-hint = Gruppen er blevet overdraget. Du kan fortsat administrere gruppen med.
+admin-group-admin-named = Gruppeadministrator: { $group_admin_firstname } { $group_admin_lastname }
+admin-group-invite-hint = Du kan invitere flere lærere til at administrere gruppen med, f.eks. for at tage sig af klassens deltagelse i konkurrencen ved sygdom. Kun gruppeadministratoren kan slette gruppen og invitere eller fjerne lærere.
+admin-group-invitee-label = Loginkode eller e-mailadresse
+admin-group-delete-hint = Her kan du slette gruppen med alle medlemmer og alle deres deltagelser. Dette trin kan ikke fortrydes.
+admin-group-protected = Gruppen kan ikke slettes, da den har medlemmer med beskyttede deltagelser.
+admin-group-protected-hint = Gruppen kan først slettes, når evalueringen af de pågældende konkurrencer er afsluttet, og beskyttelsen af deltagelserne er ophævet.
+admin-group-only-admin-deletes = Kun gruppeadministratoren kan slette gruppen.
+admin-participation-extra-time-short = (inkl. { $extra_time } min. ekstra tid)
+admin-participation-delete-named = Slet deltagelsen i „{ $contestname }“:
+admin-back-to-user = Tilbage til brugeren
+admin-participation-protected = Deltagelsen kan ikke slettes, da det er en beskyttet deltagelse.
+admin-participation-reset = Nulstil deltagelse
+admin-participation-reset-hint = Her kan du nulstille deltagelsen. Nulstillingen kan ikke fortrydes.
+admin-participation-was-reset = Deltagelse nulstillet.
+admin-participation-reset-protected = Deltagelsen kan ikke nulstilles, da det er en beskyttet deltagelse.
+admin-participation-time = Arbejdstid
+admin-participation-time-left = Resterende tid: { $time_left_formatted }
+admin-participation-time-over = Arbejdstiden er udløbet.
+admin-participation-extra-time-included = Heri er { $extra_time } minutters ekstra tid inkluderet.
+admin-participation-set-extra-time = Fastsæt ekstra tid i minutter
+admin-participation-time-hint = Ekstra tid kan f.eks. gives som kompensation for et handicap. Under en pause, f.eks. efter en teknisk fejl, løber tiden ikke, og der kan ikke gemmes løsninger. Ved genstart begynder arbejdstiden forfra, de hidtidige afleveringer bevares.
+admin-user-move-failed = Kan ikke flytte bruger:
+admin-user-delete-confirm = Vil du virkelig slette brugeren?
+admin-group-delete-confirm = Vil du virkelig slette gruppen og alle medlemmer?
+admin-participation-restart-confirm = Vil du virkelig genstarte arbejdstiden?
+
+## Adgange
+
+admission-title = Opret konkurrenceresultater med CSV-upload
+admission-intro = Her kan du uploade deltagernes adgange med en CSV-fil. Træk CSV-filen hen på det grønne felt.
+admission-requirements = CSV-filen, der skal uploades, skal opfylde følgende kriterier:
+admission-delimiter = CSV-filen skal være komma- eller tabulatorsepareret
+admission-encoding = Filen skal være kodet i UTF-8 (Unicode UTF-8)
+admission-columns = Filen skal indeholde mindst to kolonner. Alle yderligere kolonner ignoreres.
+admission-column-user = Deltagerens bruger-ID.
+admission-column-admission = Deltagerens adgangsstatus til konkurrencens næste runde (fri tekst).
+admission-after-upload = Efter upload kan du slette enkelte linjer (f.eks. overskriftslinjer), før du opretter dataene. Uploadede resultater kan overskrives af en ny upload.
+admission-drop = Træk CSV-filen herhen …
+admission-create = Opret data
+admission-clear = Slet alle
+admission-only-one-file = Slip venligst kun én fil!
+admission-delete = Slet
+admission-skipped-before = Der blev sprunget over
+admission-skipped-after = linjer, der ikke opfyldte kravene!
+
+## Profil og adgangskode
+
+profile-own-named = Min profil: { $profile_firstname } { $profile_lastname }
+profile-edit-named = Rediger brugerdata: { $profile_firstname } { $profile_lastname }
+profile-signed-up = Du har oprettet en konto.
+profile-signed-up-hint = På denne side kan du angive flere oplysninger om dig selv og ændre din adgangskode.
+profile-username = Brugernavn: { $profile_username }
+profile-logincode = Loginkode: { $profile_logincode }
+profile-street = Gade og husnummer:
+profile-zip = Postnummer:
+profile-city = By:
+profile-grade = Klassetrin:
+profile-sex-optional = Køn (valgfrit):
+profile-new-password = Ny adgangskode:
+profile-new-password-repeat = Ny adgangskode (gentag):
+profile-save = Gem
+profile-nothing-changed = Ingen ændringer.
+profile-data-changed = Dataene er blevet ændret.
+profile-password-changed = Adgangskoden er blevet ændret.
+profile-password-mismatch = De indtastede adgangskoder stemmer ikke overens. Adgangskoden blev ikke ændret.
+profile-email-confirmation-sent = Vi har sendt dig en e-mail med et bekræftelseslink. Den nye e-mailadresse bruges først efter bekræftelsen.
+profile-email-taken = Denne e-mailadresse bruges allerede af en anden konto.
+profile-email-empty = Indtast venligst en e-mailadresse.
+profile-email = E-mailadresse
+profile-email-value = E-mailadresse: { $profile_email }
+profile-email-unconfirmed-value = Endnu ikke bekræftet: { $profile_email_unconfirmed }
+profile-email-change = Skift e-mailadresse
+password-forgotten-title = Glemt adgangskode
+password-reset-sent = Hvis der findes en konto med adgangskode og e-mailadresse til disse oplysninger, har vi sendt en e-mail med et link til at nulstille adgangskoden.
+password-reset-hint = Indtast dit brugernavn eller din e-mailadresse. Vi sender dig derefter en e-mail med et link, hvor du kan vælge en ny adgangskode.
+password-reset-name = Brugernavn eller e-mailadresse:
+password-reset-submit = Nulstil adgangskode
+back-to-login = Til login
+password-reset-form-title = Vælg en ny adgangskode
+password-reset-done = Din adgangskode er blevet ændret. Du kan nu logge ind med den nye adgangskode.
+password-reset-mismatch = De indtastede adgangskoder stemmer ikke overens.
+password-reset-empty = Indtast venligst en ny adgangskode.
+password-reset-save = Gem adgangskode
+password-reset-invalid = Dette link er ugyldigt eller udløbet. Du kan
+password-reset-request-new = anmode om et nyt link
+email-confirm-title = Bekræft e-mailadresse
+email-confirm-done = E-mailadressen { $email } er blevet bekræftet.
+email-confirm-taken = E-mailadressen { $email } er allerede bekræftet for en anden konto.
+email-confirm-invalid = Dette bekræftelseslink er ugyldigt eller er allerede blevet brugt.
+profile-own = Min profil:
+profile-edit = Rediger brugerdata for
+profile-logincode-label = Loginkode:
+profile-logincode-only = Kun denne loginkode giver dig mulighed for at logge ind igen
+profile-logincode-instead = (i stedet for et brugernavn og en adgangskode).
+profile-logincode-write-down = Skriv den venligst ned!
+profile-logincode-forgotten = Hvis du har glemt din loginkode, kan din lærer fortælle dig den.
+profile-logincode-written-down = Jeg har skrevet loginkoden ned
+profile-first-login-hint = Angiv venligst dit navn og dit klassetrin, og tryk derefter på „Gem“:
+optional-paren = (valgfrit)
+profile-email-label = E-mailadresse:
+profile-email-unconfirmed = Endnu ikke bekræftet:
+profile-email-new = Ny e-mailadresse
+back-to-start-button = Tilbage til forsiden!
+profile-to-training = Til træningen!
+profile-to-contests = Til konkurrencerne!
+profile-my-stars = Mine stjerner
+profile-stars-before = Du har indtil nu samlet
+profile-stars = Stjerner
+profile-stars-after = .
+profile-my-participations = Mine deltagelser
+profile-active = (aktiv)
+profile-finished = (afsluttet)
+profile-training-and-challenges = Træningsopgaver og udfordringer
+password-reset-name-placeholder = Brugernavn eller e-mailadresse
+password-reset-new = Ny adgangskode
+password-reset-new-repeat = Ny adgangskode (gentag)
+
+## Cookies, tilmelding, startside
+
+cookie-title = Ingen cookies
+cookie-not-set = Desværre kunne denne side ikke gemme en cookie i din browser.
+cookie-disabled = Måske har du deaktiveret cookies i din browser generelt eller for denne side. I så fald skal du aktivere cookies for denne side. Måske understøtter din browser slet ikke cookies. Så kan du desværre ikke bruge dele af denne side.
+cookie-retry-hint = Hvis du nu har aktiveret cookies i din browser, kan du trykke på denne knap for at indlæse siden igen.
+cookie-retry = Jeg har aktiveret cookies, prøv igen
+cookie-back-hint = Ellers kommer du tilbage eller til startsiden med disse knapper.
+cookie-why-title = Hvorfor har denne side brug for cookies
+cookie-why = Denne side har brug for cookies for at oprette en session mellem din browser og serveren. Ellers ville serveren ved næste sidevisning ikke vide, hvilken computer der anmoder om siden. Det har vi brug for, dels så du kan logge ind (ellers ville vi ikke vide, hvem der er logget ind), dels for at gemme dine opgaveløsninger.
+signup-title = Tilmeld dig
+signup-username-taken = Dette brugernavn findes allerede. Vælg venligst et andet.
+signup-email-taken = Denne e-mailadresse bruges allerede af en anden konto. Vælg venligst en anden.
+signup-logged-in = Du er allerede logget ind. Hvis du vil oprette en ny konto, skal du først logge ud.
+signup-empty-fields = Udfyld venligst alle felter for at oprette en konto.
+signup-submit = Tilmeld dig
+oauth-select-school = Vælg venligst en skole for at logge ind
+oauth-school-missing = Hvis den ønskede skole ikke er anført her, skal du registrere skolen på
+oauth-school-missing-upload = og uploade et dokument, der bekræfter dit tilhørsforhold til din skole.
+oauth-without-school = Log ind uden skole
+oauth-without-school-hint = Du kan logge ind uden skole. Dine deltageres data bliver så ikke knyttet til din skole.
+oauth-without-school-button = Log ind uden skole! Ingen deltagelse i Jugendwettbewerb Informatik 2021 mulig.
+oauth-no-school = Du er i øjeblikket registreret på
+oauth-no-school-end = for ingen skole.
+oauth-without-school-2020 = Du kan logge ind uden skole for at se data fra konkurrencen 2020.
+oauth-without-school-2020-end = Dette er kun muligt indtil udgangen af 2020! Det giver ingen mulighed for at deltage i Jugendwettbewerb Informatik 2021.
+index-admin = Administration
+index-admin-hint = Åbn administrationssiden og administrer brugere, grupper og deltagelser
+index-groups-hint = Opret og administrer grupper til konkurrencedeltagelse
+index-training-hint = Til at prøve og øve
+index-to-training = Til træningsopgaverne …
+index-current = Aktuelle konkurrencer
+index-to-current = Til konkurrencerne …
+index-information = Information
+index-information-hint = Information om tilmelding og deltagelse i konkurrencen, datoer, materialer og mere …
+index-challenge-hint = Opgaver med og uden tidsbegrænsning
+index-to-challenge = Til udfordringerne …
+teacher-information = Lærerinformation
+teacher-no-information = Ingen information tilgængelig endnu
+
+## Konkurrenceliste
+
+contests-image = Billede
+contests-blockly = Blockly
+
+## Felter i revisionsloggen
+
+audit-field-firstname = Fornavn
+audit-field-lastname = Efternavn
+audit-field-logincode = Login-kode
+audit-field-username = Brugernavn
+audit-field-group = Gruppe
+audit-field-name = Navn
+audit-field-groupcode = Gruppekode
+audit-field-members = Medlemmer
+audit-field-owner = Ejer
+audit-field-contest = Konkurrence
+audit-field-start = Start
+audit-field-extra-time = Ekstra tid (minutter)
+audit-field-paused-secs = Sat på pause (sekunder)
+audit-field-paused-since = Sat på pause siden
+audit-field-students = Elever
+audit-field-groups = Grupper
+audit-field-teachers = Lærere
+audit-field-others = Andre
+
+## Konkurrencescanning
+
+admin-scan-summary = { $added } tilføjet, { $changed } ændret, { $hidden } skjult, { $errors } fejl, { $unchanged } filer uændrede
+audit-field-added = Tilføjet
+audit-field-changed = Ændret
+audit-field-hidden = Skjult
+audit-field-errors = Fejl
+audit-field-unchanged = Uændrede filer
@@ -0,0 +1,768 @@
+# Deutsche Texte der Benutzeroberfläche. Fehlt ein Text in einer anderen Sprache, wird der deutsche Text verwendet.
+
+language-name = Deutsch
+
+## Jahrgangsstufen
+
+grade-0 = Noch kein Schüler
+grade-11 = 11 (G8)
+grade-12 = 12 (G8)
+grade-111 = 11 (G9)
+grade-112 = 12 (G9)
+grade-113 = 13 (G9)
+grade-114 = Berufsschule
+grade-255 = Kein Schüler mehr
+
+sex-not-stated = Keine Angabe
+sex-female = weiblich
+sex-male = männlich
+sex-diverse = divers
+sex-other = anderes
+
+## Navigation
+
+slogan = Programmieren – leichter, als du denkst!
+logged-in-as = Eingeloggt als
+role-teacher = Lehrer
+logout = Logout
+login = Login
+log-in = log in
+nav-contests = Wettbewerb
+nav-profile = Profil
+nav-groups = Gruppenverwaltung
+nav-teacher-material = Lehrkräfte-Material
+nav-admin = Administration
+
+## Login
+
+username = Benutzername
+password = Passwort
+password-forgotten = Passwort vergessen?
+login-code = Gruppencode / Teilnahmecode
+login-code-placeholder = Gruppencode oder Logincode
+login-hint = Hinweis: Wenn du deine Zugangsdaten nicht mehr kennst, kann deine Lehrerin oder dein Lehrer sie für dich einsehen.
+login-with-password = Login mit Benutzername und Passwort (nicht PMS-Account)
+login-with-code = Login mit Gruppencode oder Logincode
+login-with-oauth = Login mit anderem Account
+login-failed = Login fehlgeschlagen. Bitte erneut versuchen.
+login-code-invalid = Kein gültiger Code. Bitte erneut versuchen.
+login-link-invalid = Der Login-Link ist abgelaufen oder wurde gesperrt. Bitte mit dem Logincode anmelden.
+oauth-login-failed = OAuth-Login fehlgeschlagen.
+
+## Profil
+
+profile-language = Sprache
+profile-language-browser = Sprache des Browsers
+profile-language-save = Sprache ändern
+
+## Sterne im Profil
+
+stars-none = Auf gehts, dein erster Stern wartet auf dich!
+stars-few = Ein hervorragender Anfang!
+stars-many = Das ist ziemlich gut!
+stars-master = Ein wahrer Meister!
+stars-wow = Wow! Einfach wow!
+
+## Aufgaben
+
+task-title = Aufgabe „{ $taskgroup }“ in { $contest }
+
+## Löschen und Aufräumen
+
+delete-user-protected-participations = Benutzer hat Teilnahmen an geschützten Wettbewerben.
+delete-user-group-admin = Benutzer ist Administrator von Gruppen.
+delete-user-not-found = Benutzer existiert nicht.
+delete-group-not-found = Gruppe existiert nicht.
+delete-group-protected-participations = Gruppe hat Mitglieder mit geschützten Teilnahmen.
+cleanup-failed = Fehler.
+
+## Gruppenimport
+
+import-column = Spalte { $number }
+import-column-group = Gruppe
+import-column-grade = Jahrgangsstufe
+import-column-firstname = Vorname
+import-column-lastname = Nachname
+import-column-sex = Geschlecht
+import-no-column = Keine Spalte für „{ $field }“ ausgewählt
+import-value-missing = „{ $field }“ fehlt
+import-name-missing = Vor- und Nachname fehlen
+import-too-long = „{ $field }“ ist länger als { $max } Zeichen
+import-unknown-grade = Unbekannte Jahrgangsstufe „{ $value }“
+import-unknown-sex = Unbekanntes Geschlecht „{ $value }“
+import-sex-not-allowed = Geschlecht „{ $value }“ ist nicht erlaubt
+import-invalid-encoding = Die Datei ist nicht in der gewählten Zeichenkodierung gespeichert.
+import-invalid-csv = Die Datei konnte nicht gelesen werden: { $error }
+import-empty = Die Datei enthält keine Teilnehmer.
+
+## Zugangsdaten (PDF)
+
+login-sheet-title = Deine Zugangsdaten
+login-sheet-group = Gruppe { $group }, Jahrgangsstufe { $grade }
+login-sheet-logincode = Logincode
+login-sheet-groupcode = Gruppencode: { $groupcode }
+login-sheet-instructions = Öffne { $login_url } und gib deinen Logincode ein oder scanne den QR-Code. Bewahre den Logincode gut auf, du brauchst ihn bei jeder Anmeldung.
+login-sheet-summary-title = Zugangsdaten der Gruppe { $group }
+login-sheet-summary = Die folgenden Seiten enthalten die Zugangsdaten der { $count } Teilnehmer dieser Gruppe zum Ausschneiden und Verteilen. Die Teilnehmer melden sich unter { $login_url } mit ihrem Logincode an. Diese Übersicht ist nur für Sie bestimmt.
+login-sheet-name = Name
+login-sheet-grade = Jahrgangsstufe
+
+## Fußzeile
+
+footer-source-code = Quellcode
+footer-task-license = Alle Aufgaben unter
+footer-jwinf-article = Der
+footer-jwinf-is-one-of = ist einer der
+footer-bwinf = Bundesweiten Informatikwettbewerbe
+footer-bwinf-funding = Die Bundesweiten Informatikwettbewerbe sind von der Kultusministerkonferenz geförderte Schülerwettbewerbe und stehen unter der Schirmherrschaft des Bundespräsidenten.
+footer-organizers = Träger:
+
+## Zulassung
+
+qualification-unknown-contest = Der Wettbewerb { $contest } ist nicht bekannt.
+qualification-not-participated = Du hast nicht am Wettbewerb „{ $contest }“ teilgenommen.
+qualification-participated = Du hast am Wettbewerb „{ $contest }“ teilgenommen.
+qualification-percentage-reached = Du hast { $percentage } % der Punkte erreicht, benötigt werden { $required } %.
+qualification-percentage-missed = Du hast { $percentage } % der Punkte erreicht, benötigt werden aber { $required } %.
+qualification-rank-reached = Du bist in deiner Jahrgangsstufe auf Platz { $rank }, die besten { $top } sind qualifiziert.
+qualification-rank-missed = Du bist in deiner Jahrgangsstufe auf Platz { $rank }, qualifiziert sind aber nur die besten { $top }.
+qualification-not-ranked = Du bist in deiner Jahrgangsstufe nicht platziert.
+qualification-admitted = Deine Zulassung ist vermerkt.
+qualification-not-admitted = Für dich ist keine Zulassung vermerkt.
+qualification-invalid-rules = Die Zulassungsvoraussetzungen dieses Wettbewerbs sind fehlerhaft.
+
+## E-Mails
+
+mail-greeting = Hallo { $name },
+mail-confirm-email-subject = Bestätigung deiner E-Mail-Adresse
+mail-confirm-email-text = bitte bestätige deine E-Mail-Adresse, indem du den folgenden Link öffnest:
+mail-confirm-email-ignore = Falls du diese E-Mail-Adresse nicht angegeben hast, kannst du diese E-Mail ignorieren.
+mail-password-reset-subject = Passwort zurücksetzen
+mail-password-reset-text = für dein Konto wurde ein neues Passwort angefordert. Über den folgenden Link kannst du innerhalb von 24 Stunden ein neues Passwort festlegen:
+mail-password-reset-ignore = Falls du kein neues Passwort angefordert hast, kannst du diese E-Mail ignorieren.
+
+## Rollen in der Administration
+
+role-contest_manager = Wettbewerbsverwaltung
+role-school_coordinator = Schulkoordination
+role-support = Support
+role-auditor = Prüfung (nur lesend)
+
+## Statistik
+
+report-without-oauth = Ohne OAuth
+
+## Wettbewerbsübersicht
+
+contests-training = Trainingsaufgaben
+contests-contest = Wettbewerbe
+contests-challenge = Herausforderungen
+contests-standalone-tasks = Einzelne Aufgaben ohne Wertung
+
+## Allgemein
+
+back-to-start = Zur Startseite
+back-to-contests = Zur Wettbewerbsübersicht
+contests-filter-by-tag = Nach Tag filtern:
+contests-view-results = Ergebnisse einsehen
+contests-and-tasks = Wettbewerbe / Aufgaben
+contests-none-available = Es sind zur Zeit hier keine Wettbewerbe / Aufgaben verfügbar.
+
+## Wettbewerb
+
+contest-title = Wettbewerb:
+contest-training-title = Trainingsaufgaben:
+contest-duration = Dauer: { $minutes } Minuten
+contest-show-group-results = Ergebnisse meiner Gruppen anzeigen.
+contest-show-scoreboard = Bestenliste anzeigen.
+contest-group-results = Gruppenergebnisse
+contest-group-results-text = Ergebnisse meiner Gruppen für diesen Wettbewerb einsehen
+contest-scoreboard = Bestenliste
+contest-scoreboard-text = Anonymisierte Live-Bestenliste dieses Wettbewerbs ansehen
+contest-not-logged-in = Hinweis: Du bist nicht eingeloggt. Logge dich ein um deinen Fortschritt zu speichern.
+contest-started-ago = Gestartet vor { $seconds } Sekunden.
+contest-time-left = Verbleibende Zeit:
+contest-extra-time = (inklusive { $minutes } Minuten Zusatzzeit)
+contest-teachers-exempt = (Lehrkräfte sind vom Zeitlimit ausgenommen.)
+contest-paused = Die Teilnahme ist pausiert.
+contest-paused-by-teacher = Deine Teilnahme wurde von deiner Lehrkraft pausiert. Die Zeit läuft gerade nicht weiter.
+contest-review-mode = Review-Modus: Du kannst die Aufgaben öffnen und bearbeiten. Die Lösungen werden aber nicht gespeichert und geben keine Punkte.
+contest-review-ends-in = Der Review-Modus endet in { $days } Tagen, { $hours } Stunden und { $minutes } Minuten.
+contest-time-over = Die Zeit ist abgelaufen. Du kannst nun die Aufgaben nicht mehr öffnen.
+contest-review-starts-in = Der Review-Modus beginnt in { $days } Tagen, { $hours } Stunden und { $minutes } Minuten.
+contest-score = Punktestand:
+contest-qualified = Du bist für diesen Wettbewerb qualifiziert:
+contest-not-qualified = Du bist für diesen Wettbewerb nicht qualifiziert.
+contest-qualification-requirements = Für die Teilnahme muss eine der folgenden Voraussetzungen erfüllt sein:
+contest-password = Wettbewerbspasswort
+contest-password-wrong = Das eingegeben Passwort ist nicht korrekt.
+contest-start-now = Jetzt starten!
+contest-runs-for = Der Wettbewerb läuft nach dem Starten für { $minutes } Minuten.
+contest-cannot-pause = Der Wettbewerb kann nach dem Starten nicht mehr pausiert werden.
+contest-cannot-start = Du kannst diesen Wettbewerb nicht starten.
+contest-over = Der Wettbewerb ist bereits beendet.
+contest-not-begun = Der Wettbewerb hat noch nicht begonnen.
+contest-wrong-grade = Deine angegebene Jahrgangsstufe entspricht nicht den für diesen Wettbewerb vorgesehenen Altersgruppen.
+contest-login-needed = Möglicherweise musst du dich zunächst einloggen:
+contest-begins-in = Der Wettbewerb beginnt in: { $days } Tagen, { $hours } Stunden, { $minutes } Minuten.
+contest-ends-in = Der Wettbewerb läuft noch: { $days } Tage, { $hours } Stunden, { $minutes } Minuten.
+
+## Aufgabe
+
+task-review-mode = Review-Modus
+task-time-left = Verbleibende Zeit
+task-fullscreen = Vollbild
+task-overview = Übersicht
+task-next = Nächste Aufgabe
+task-previous = Vorherige Aufgabe
+task-no-frames = Leider unterstützt ihr Browser keine Frames. Sie können die Aufgabe auch manuell aufrufen:
+task-open-manually = Aufgabe manuell bearbeiten
+task-load-failed = Laden fehlgeschlagen
+task-save-failed = Speichern fehlgeschlagen
+
+## Ergebnisse
+
+yes = ja
+no = nein
+groups = Gruppen
+back-to-contest = Zum Wettbewerb
+back-to-results = Zu den Ergebnissen
+back-to-group = Zur Gruppe
+results = Ergebnisse
+results-in = Ergebnisse in { $contest }
+results-download = Ergebnisse herunterladen:
+results-show-ranking = Rangliste anzeigen
+results-group = Gruppe
+results-groupcode = Gruppencode
+results-participant = Teilnehmer
+results-rank = Platz
+results-sum = Summe
+results-total = Gesamtpunkte
+results-admission = Zulassung zur 3. Runde
+results-admission-short = Zulassung
+results-statistics = Statistik
+results-none = Für diesen Wettbewerb sind noch keine Ergebnisse Ihrer Gruppen verzeichnet.
+scoreboard-in = Bestenliste in { $contest }
+scoreboard-empty = Es wurden noch keine Punkte erzielt.
+ranking = Rangliste
+ranking-in = Rangliste in { $contest }
+ranking-school = Schule
+ranking-filter = Filtern
+ranking-public-scoreboard = Öffentliche Bestenliste
+ranking-score-reached = Punktzahl erreicht
+ranking-attempts = Abgaben
+ranking-empty = Für diesen Wettbewerb gibt es keine passenden Teilnahmen.
+
+## Gruppenstatistik
+
+statistics-title = { $contest }: Statistik der Gruppe { $group }
+statistics-title-long = Statistik der Gruppe „{ $group }“ in { $contest }
+statistics-participants = Teilnehmer mit Ergebnissen:
+statistics-average-time = Durchschnittliche Zeit vom Start bis zur ersten Einsendung:
+statistics-hardest-task = Schwierigste Aufgabe:
+statistics-task = Aufgabe
+statistics-average-stars = Sterne im Schnitt
+statistics-stars-of = { $stars } von { $max }
+statistics-reached = Erreicht
+statistics-share-reached = Anteil erreicht
+statistics-not-attempted = Ohne Einsendung
+statistics-time-to-first-submission = Zeit bis zur ersten Einsendung
+statistics-hardest = schwierigste
+statistics-distribution = Punkteverteilung
+statistics-with = mit
+
+## Einsendungen
+
+submissions-back-to-participation = Zurück zur Teilnahme
+submissions-of = Einsendungen von { $firstname } { $lastname }
+submissions-history = Verlauf
+submissions-history-title = Verlauf der Einsendungen
+submissions-number = Nr.
+submissions-time = Zeitpunkt
+submissions-time-review = Zeitpunkt / Review
+submissions-points = Punkte
+submissions-unvalidated-points = Ungeprüfte Punkte
+submissions-validated = Geprüft
+submissions-pending = ausstehend
+submissions-left = Links
+submissions-right = Rechts
+submissions-compare = Vergleichen
+submissions-none = Keine Einsendungen.
+submissions-comparison = Vergleich von Einsendung { $left } und { $right }
+submissions-too-large = Die Einsendungen sind zu groß, um sie zeilenweise zu vergleichen.
+
+## Fehlerseiten
+
+page-locked = 423: Seite gesperrt
+page-locked-text = Diese Seite ist zur Zeit deaktiviert um die Serverlast zu minimieren und einen reibungslosen Wettbewerb zu ermöglichen. Bitte probieren Sie es später noch einmal!
+action-forbidden = Diese Aktion ist nicht erlaubt!
+
+## Gruppen
+
+back = Zurück
+back-to-groups = Zur Gruppenübersicht
+edit = bearbeiten …
+optional = optional
+group-members = Mitglieder
+group-tag = Marker
+group-login-sheets-print = Zugangsdaten zum Ausdrucken (PDF)
+groups-create = Gruppe anlegen
+groups-create-plural = Gruppen anlegen
+groups-create-new = Neue Gruppe anlegen
+groups-name = Gruppenname
+groups-tag-hint = Der Marker kann z. B. der Name der Klasse oder des Kurses sein. Er wird beim Druck von Urkunden mitgedruckt und dient für Sie zur Zuordnung.
+groups-class = Klassen-/Kursbezeichnung
+groups-class-hint = Die Klassen-/Kursbezeichnungen werden auf die Urkunden gedruckt. Die Urkunden werden danach sortiert.
+groups-csv-upload-before = Sie können Gruppen auch anlegen mit einem
+groups-csv-upload = CSV-Upload
+groups-invitations = Einladungen zur Mitverwaltung
+groups-admin = Gruppen-Admin
+groups-accept = Annehmen
+groups-decline = Ablehnen
+groups-mine = Meine bestehenden Gruppen
+groups-managed = Meine verwalteten Gruppen
+groups-results-hint = Für die Wettbewerbsergebnisse meiner Gruppen bitte zunächst
+groups-choose-contest = einen Wettbewerb auswählen
+groups-contest-results = Wettbewerbsergebnisse
+groups-contest-results-link = Wettbewerbsergebnisse meiner Gruppen einsehen
+groups-download = Download
+groups-view = Ansehen …
+groups-login-sheets = Zugangsdaten (PDF)
+groups-none = Noch keine Gruppe vorhanden. Legen Sie oben eine neue Gruppe an, um Ihren Schülerinnen und Schülern die Teilnahme an Wettbewerben zu ermöglichen
+
+## CSV-Upload
+
+import-title = Gruppen per CSV-Upload anlegen
+import-intro = Hier können Sie Gruppen und Accounts über eine CSV-Datei anlegen, z. B. über einen Export aus der Schulverwaltung.
+import-columns-intro = Die Datei muss für jeden Teilnehmer eine Zeile mit den folgenden Spalten enthalten. Weitere Spalten werden ignoriert.
+import-columns-group = Der Name der Gruppe. (Es können mehrere Gruppen in einer Datei definert sein.)
+import-columns-grade = Die Jahrgangsstufe des Teilnehmers, als Zahl oder wie in der Auswahl im Profil (z. B. „12 (G9)“).
+import-columns-firstname = Der Vorname des Teilnehmers.
+import-columns-lastname = Der Nachname des Teilnehmers.
+import-columns-sex = Das Geschlecht des Teilnehmers (m, w, d oder leer).
+import-columns-biber = Diese fünf Spalten entsprechen den ersten fünf Spalten der CSV-Dateien für den Upload zum Informatik-Biber (ohne Benutzernamen und Passwörter).
+import-columns-header = Hat die Datei eine Kopfzeile mit Spaltennamen wie „Klasse“, „Stufe“, „Vorname“, „Nachname“ und „Geschlecht“, werden die Spalten daran erkannt.
+import-example = Ein Beispiel könnte so aussehen:
+import-example-header = Gruppenname,Stufe,Vorname,Nachname,Geschlecht
+import-file = CSV-Datei
+import-encoding = Zeichenkodierung
+import-encoding-utf16 = UTF-16 (Excel „Unicode-Text“)
+import-detect = Automatisch erkennen
+import-delimiter = Trennzeichen
+import-comma = Komma
+import-semicolon = Semikolon
+import-tab = Tabulator
+import-header = Kopfzeile
+import-header-yes = Ja
+import-header-no = Nein
+import-paste = Statt eine Datei auszuwählen, können Sie den Inhalt der Datei auch hier einfügen:
+import-check = Datei prüfen
+import-preview = Vorschau
+import-no-column-selected = keine Angabe
+import-correct = Sie können die Daten hier auch direkt korrigieren:
+import-update-preview = Vorschau aktualisieren
+import-create = Gruppen erstellen
+import-will-create = Es werden die folgenden Gruppen angelegt. Angelegte Gruppen lassen sich nicht mehr löschen.
+import-error-count = Die Datei enthält { $count } Fehler. Bitte korrigieren Sie die Fehler, bevor Sie die Gruppen anlegen. Es werden erst dann Gruppen angelegt, wenn alle Zeilen korrekt sind.
+import-participants = { $count } Teilnehmer
+import-line = Zeile
+import-grade-short = Jgst.
+import-errors = Fehler
+
+## Administration
+
+admin-search = Admin-Suche
+admin-search-wildcard = % ist ein Wildcart in der Namenssuche.
+admin-search-limit = Die Suche gibt nur bis zu 30 Ergebnisse aus, um den Server nicht übermäßig zu belasten. Ggf. eine genauere Suche durchführen!
+admin-firstname = Vorname:
+admin-lastname = Nachname:
+admin-search-by-name = Nach Namen suchen
+admin-logincode = Logincode:
+admin-search-by-logincode = Nach Logincode suchen
+admin-groupcode = Gruppencode:
+admin-search-by-groupcode = Nach Gruppencode suchen
+admin-search-by-id = Nach ID suchen
+admin-search-by-pmsid = Nach PMS-ID suchen
+admin-contest-export = Wettbewerbs-Export
+admin-contest-export-link = Wettbewerbsübersicht und -export
+admin-dbstatus = Datenbankstatus
+admin-reports-link = Teilnahmen, Registrierungen und Einsendungen
+admin-audit-log = Audit-Log
+admin-audit-log-link = Änderungen durch Administratoren und Lehrkräfte
+admin-cleanup = Alte Benutzeraccounts aufräumen
+admin-cleanup-link = Datenbank-Cleanup
+admin-user-id = Benutzer-ID:
+admin-group-id = Gruppen-ID:
+admin-from = Von:
+admin-until = Bis:
+admin-search-submit = Suchen
+admin-audit-no-entries = Keine Einträge gefunden.
+admin-audit-actor = Durchgeführt von
+admin-audit-action = Aktion
+admin-user = Benutzer
+admin-contest = Wettbewerb
+admin-audit-before = Vorher
+admin-audit-after = Nachher
+admin-cleanup-hard = Alte Daten löschen
+admin-cleanup-hard-text = Dies Löscht alle verwalteten Schüleraccounts, die länger als 180 Tage nicht genutzt wurden, sowie alle Lehreraccounts länger als 3 Jahre und alle anderen Accounts die länger als 10 Jahre nicht genutzt wurden.
+admin-cleanup-hard-warning = Sollte nicht während eines Wettbewerbes oder nach Beginn der Anmeldephase ausgeführt werden, falls ein Lehrer noch plant ältere Accounts zu verwenden. Bestenfalls vor Anmeldebeginn und nach Ende der zweiten Runde ausführen.
+admin-cleanup-hard-submit = Alte Daten löschen!
+admin-cleanup-session = Temporäre Sitzungen löschen
+admin-cleanup-session-text = Löscht Sitzungen von Benutzern, die sich nicht eingeloggt haben, wenn diese älter als 30 Tage sind.
+admin-cleanup-session-safe = Kann jederzeit gefahrlos ausgeführt werden.
+admin-cleanup-session-submit = Temporäre Sitzungen löschen!
+admin-export-title = Export: { $contestname }
+admin-export-format = Format
+admin-export-csv-excel = CSV für Excel (mit BOM, Semikolon-getrennt)
+admin-export-columns = Spalten
+admin-export-subtasks = Punkte für jede Teilaufgabe
+admin-export-submissions = Anzahl der Abgaben je Aufgabe
+admin-export-download = Herunterladen
+admin-scan = Wettbewerbe neu einlesen
+admin-scan-text = Liest alle Wettbewerbs- und Aufgabendateien ein, die sich seit dem letzten Einlesen geändert haben. Wettbewerbe, deren Datei entfernt wurde, werden ausgeblendet. Dateien mit Fehlern werden nicht eingelesen, die bisherige Version bleibt erhalten.
+admin-scan-safe = Kann auch während eines laufenden Wettbewerbs ausgeführt werden.
+admin-scan-result = Ergebnis
+admin-scan-added = Hinzugefügt
+admin-scan-changed = Geändert
+admin-scan-hidden = Ausgeblendet
+admin-results-export = Ergebnis-Daten-Export
+admin-search-no-results = Keine Ergebnisse gefunden.
+admin-search-too-many = Mehr als { $max_results } Ergebnisse gefunden. Nur die ersten { $max_results } Ergebnisse werden angezeigt. Bitte Suche verfeinern!
+admin-search-count = { $num_results } Ergebnisse:
+admin-cleanup-deleted = Daten erfolgreich gelöscht:
+admin-cleanup-students = Schüler
+admin-cleanup-teachers = Lehrer
+admin-cleanup-others = Andere
+admin-cleanup-sessions = Sitzungen
+admin-cleanup-failed = Kann Daten nicht löschen:
+admin-new-search = Neue Suche
+admin-role-admin = Administrator
+admin-role-student = Schüler
+admin-user-id-value = Id: { $user_id }
+admin-user-firstname = Vorname: { $user_firstname }
+admin-user-lastname = Nachname: { $user_lastname }
+admin-user-grade = Jahrgangsstufe: { $user_grade }
+admin-user-username = Benutzername: { $user_username }
+admin-user-logincode = Logincode: { $user_logincode }
+admin-user-oauth = OAuth-Login: { $user_oauthprovider } ({ $user_oauthprovider }-id: { $user_oauthid })
+admin-user-logged-in = Ist eingeloggt
+admin-user-admin-of = Admin von
+admin-id = Id
+admin-class-short = Klassen-/Kursbez.
+admin-user-member-of = Mitglied von
+admin-participations = Teilnahmen
+admin-roles = Rollen
+admin-roles-save = Rollen speichern
+admin-sessions = Anmeldung
+admin-sessions-revoke = Auf allen Geräten abmelden
+admin-user-delete = Benutzer löschen
+admin-user-delete-submit = Benutzer löschen!
+admin-group-id-value = Id: { $group_id }
+admin-group-name = Name: { $group_name }
+admin-group-code = Gruppencode: { $group_code }
+admin-group-tag = Klassen-/Kursbez.: { $group_tag }
+admin-group-admin = Gruppen-Administrator:
+admin-group-links-revoked = Die Login-Links wurden gesperrt. Laden Sie die Zugangsdaten erneut herunter, um neue Login-Links zu erhalten.
+admin-group-revoke-link = Login-Link sperren
+admin-group-revoke-links = Login-Links der Gruppe sperren
+admin-group-teachers = Lehrkräfte
+admin-group-invited = Die Lehrkraft wurde eingeladen.
+admin-group-already-invited = Diese Lehrkraft verwaltet die Gruppe bereits oder wurde bereits eingeladen.
+admin-group-invitee-not-found = Es wurde keine Lehrkraft mit diesem Logincode oder dieser E-Mail-Adresse gefunden.
+admin-group-removed = Die Lehrkraft wurde entfernt.
+admin-group-transferred = Die Gruppe wurde übergeben.
+admin-group-accepted = Sie verwalten diese Gruppe jetzt mit.
+admin-group-status = Status
+admin-group-co-managing = verwaltet mit
+admin-group-invitation-pending = eingeladen
+admin-group-transfer = Gruppe übergeben
+admin-group-remove = Entfernen
+admin-group-leave = Mitverwaltung beenden
+admin-group-invitee = Logincode oder E-Mail-Adresse:
+admin-group-invite = Lehrkraft einladen
+admin-group-delete = Gruppe löschen
+admin-group-delete-submit = Gruppe Löschen!
+admin-participation = Teilnahme
+admin-participation-data = Teilnahmedaten
+admin-participation-start = Start
+admin-participation-expired = abgelaufen
+admin-participation-extra-time = (inkl. { $extra_time } Minuten Zusatzzeit)
+admin-participation-paused = , pausiert
+admin-participation-extra-time-minutes = Zusatzzeit in Minuten:
+admin-participation-set = Festlegen
+admin-participation-resume = Fortsetzen
+admin-participation-pause = Pausieren
+admin-participation-restart = Zeit neu starten
+admin-submissions = Einsendungen
+admin-participation-history = Verlauf und Vergleich
+admin-participation-delete = Teilnahme löschen
+admin-participation-delete-submit = Teilnahme löschen!
+admin-reports-show = Anzeigen
+admin-reports-export = Exportieren
+admin-reports-overview = Überblick
+admin-reports-teachers = Lehrkräfte mit teilnehmenden Gruppen
+admin-reports-schools = Schulen mit teilnehmenden Gruppen
+admin-reports-per-contest = Teilnahmen pro Wettbewerb
+admin-reports-by-login = Teilnahmen nach Anmeldung
+admin-reports-by-grade = Teilnahmen nach Klassenstufe
+admin-reports-by-sex = Teilnahmen nach Geschlecht
+admin-reports-accounts-per-day = Neue Benutzeraccounts pro Tag (UTC)
+admin-reports-submissions-per-hour = Einsendungen pro Stunde (UTC)
+admin-user-deleted = Benutzer gelöscht.
+admin-user-delete-failed = Kann Benutzer nicht löschen:
+admin-group-deleted = Gruppe gelöscht.
+admin-group-delete-failed = Kann Gruppe nicht löschen:
+admin-participation-deleted = Teilnahme gelöscht.
+admin-participation-delete-failed = Kann Teilnahme nicht löschen:
+admin-participation-delete-confirm = Teilnahme wirklich löschen?
+admin-search-for = Admin-Suche nach …
+admin-search-for-name = … Namen
+admin-search-for-logincode = … Logincode
+admin-search-for-groupcode = … Gruppencode
+admin-search-for-id = … ID
+admin-search-user-id = User-ID
+admin-search-for-pmsid = … PMS-ID
+admin-cleanup-title = Löschen von alten Benutzeraccounts
+admin-dbstatus-show = Datenbankstatus anzeigen
+admin-export = Export
+admin-scan-short = Neu einlesen
+admin-contest-list = Wettbewerbsliste
+admin-contest-all = Alle Wettbewerbe
+admin-results-export-link = Ergebnisse exportieren
+admin-results-upload = ⇧ Ergebnisse hochladen
+admin-search-results = Suchergebnisse
+
+## Administration (JWINF)
+
+admin-user-id-short = Benutzer-ID
+admin-oauth-login = OAuth-Login
+admin-oauth-id = { $user_oauthprovider }-ID
+admin-user-delete-named = Benutzer „{ $user_firstname } { $user_lastname }“ löschen:
+admin-cannot-be-undone = Diese Aktion kann nicht rückgängig gemacht werden!
+admin-user-protected-warning = Achtung: Dieser Nutzer hat geschützte Teilnahmen an Wettbewerben.
+admin-user-protected = Benutzer kann nicht gelöscht werden, da er geschützte Teilnahmen an Wettbewerben hat.
+admin-user-protected-hint = Der Benutzer kann erst gelöscht werden, wenn die Auswertung der entsprechenden Wettbewerbe abgeschlossen ist und der Schutz der Teilnahmen aufgehoben wurde.
+admin-user-has-groups = Benutzer kann nur gelöscht werden, wenn er keine Gruppen besitzt. Bitte löschen Sie zuerst die Gruppen des Benutzers.
+admin-user-move-named = Benutzer „{ $user_firstname } { $user_lastname }“ in andere Gruppe verschieben:
+admin-user-move = Benutzer verschieben
+admin-user-edit-profile = Profil bearbeiten
+admin-roles-label = Rollen:
+admin-sessions-label = Anmeldung:
+admin-user-member-in = Mitglied in
+admin-user-logincode-hint = Dies ist der Logincode des Benutzers. Mit diesem Code kann sich der Benutzer einloggen.
+admin-tasks-contest = Aufgaben/Wettbewerb
+admin-start-time = Startzeitpunkt
+admin-user-no-participations = Noch keine Teilnahmen vorhanden. Sobald der Benutzer Teilnahmen startet, tauchen sie an dieser Stelle auf.
+admin-user-delete-hint = Hier können Sie den Benutzer mit allen Teilnahmen löschen. Das Löschen lässt sich nicht rückgängig machen.
+admin-user-delete-all = Benutzer mit allen Teilnahmen löschen
+admin-user-timed-warning = Achtung: Dieser Nutzer hat Teilnahmen an zeitbeschränkten Wettbewerben.
+admin-back-to-group = Zurück zur Gruppe
+admin-user-has-groups-short = Benutzer kann nur gelöscht werden, wenn er keine Gruppen besitzt.
+admin-user-delete-groups-first = Bitte löschen Sie zuerst die Gruppen des Benutzers.
+admin-group-tag-paren = (Klassen-/Kursbez.: { $group_tag })
+admin-group-id-short = Gruppen-ID
+admin-group-more-teachers = Weitere Lehrkräfte
+admin-group-invited-paren = (eingeladen)
+admin-group-delete-named = Gruppe „{ $group_name }“ löschen:
+admin-group-protected-warning = Achtung: Diese Gruppe hat Mitglieder mit geschützten Teilnahmen! Bei Löschen dieser Gruppe werden Wettbewerbsteilnahmen am JWINF gelöscht!
+admin-group-code-hint = Dies ist der Gruppencode. Geben Sie diesen an die Teilnehmer weiter. Der Gruppencode erzeugt bei jeder Benutzung einen neuen Account.
+admin-sex-short = Geschl.
+admin-login-link = Login-Link
+admin-revoke = Sperren
+admin-group-download-members = Mitgliederliste herunterladen:
+admin-group-login-sheets-paren = (Zugangsdaten zum Ausdrucken)
+admin-group-login-links-hint = Die QR-Codes auf den Zugangsdaten enthalten Login-Links, mit denen sich die Teilnehmer ohne Eingabe des Logincodes anmelden können. Falls Zugangsdaten verloren gegangen sind, können Sie die Login-Links einzelner Teilnehmer oder der ganzen Gruppe sperren und die Zugangsdaten erneut herunterladen. Die Logincodes bleiben dabei gültig.
+admin-group-no-members = Noch keine Mitglieder vorhanden. Sobald sich Teilnehmer mit dem Gruppencode oben einloggen, tauchen sie an dieser Stelle auf.
+admin-group-invited-hint = Die Lehrkraft wurde eingeladen. Sie kann die Gruppe mitverwalten, sobald sie die Einladung angenommen hat.
+admin-group-transferred-hint = Die Gruppe wurde übergeben. Sie können die Gruppe weiterhin mitverwalten.
+admin-group-admin-named = Gruppen-Admin: { $group_admin_firstname } { $group_admin_lastname }
+admin-group-invite-hint = Sie können weitere Lehrkräfte einladen, die Gruppe mitzuverwalten, z. B. um bei Krankheit die Wettbewerbsteilnahme der Klasse zu betreuen. Nur der Gruppen-Admin kann die Gruppe löschen und Lehrkräfte einladen oder entfernen.
+admin-group-invitee-label = Logincode oder E-Mail-Adresse
+admin-group-delete-hint = Hier können Sie die Gruppe mit allen Mitgliedern und allen ihren Teilnahmen löschen. Dieser Schritt lässt sich nicht rückgängig machen.
+admin-group-protected = Gruppe kann nicht gelöscht werden, da sie Mitglieder mit geschützten Teilnahmen besitzt.
+admin-group-protected-hint = Die Gruppe kann erst gelöscht werden, wenn die Auswertung der entsprechenden Wettbewerbe abgeschlossen ist und der Schutz der Teilnahmen aufgehoben wurde.
+admin-group-only-admin-deletes = Nur der Gruppen-Admin kann die Gruppe löschen.
+admin-participation-extra-time-short = (inkl. { $extra_time } Min. Zusatzzeit)
+admin-participation-delete-named = Wettbewerbsteilnahme „{ $contestname }“ löschen:
+admin-back-to-user = Zurück zum Benutzer
+admin-participation-protected = Teilnahme kann nicht gelöscht werden, da es sich um eine geschützte Teilnahme handelt.
+admin-participation-reset = Teilnahme zurücksetzen
+admin-participation-reset-hint = Hier können Sie die Teilnahme zurücksetzen. Das Zurücksetzen lässt sich nicht rückgängig machen.
+admin-participation-was-reset = Teilnahme zurückgesetzt.
+admin-participation-reset-protected = Teilnahme kann nicht zurückgesetzt werden, da es sich um eine geschützte Teilnahme handelt.
+admin-participation-time = Bearbeitungszeit
+admin-participation-time-left = Verbleibende Zeit: { $time_left_formatted }
+admin-participation-time-over = Die Bearbeitungszeit ist abgelaufen.
+admin-participation-extra-time-included = Darin sind { $extra_time } Minuten Zusatzzeit enthalten.
+admin-participation-set-extra-time = Zusatzzeit in Minuten festlegen
+admin-participation-time-hint = Zusatzzeit kann z. B. als Nachteilsausgleich gewährt werden. Während einer Pause, etwa nach einer technischen Störung, läuft die Zeit nicht weiter und es können keine Lösungen gespeichert werden. Beim Neustart beginnt die Bearbeitungszeit von vorne, die bisherigen Einsendungen bleiben erhalten.
+admin-user-move-failed = Kann Benutzer nicht verschieben:
+admin-user-delete-confirm = Benutzer wirklich löschen?
+admin-group-delete-confirm = Gruppe und alle Mitglieder wirklich löschen?
+admin-participation-restart-confirm = Bearbeitungszeit wirklich neu starten?
+
+## Zulassungen
+
+admission-title = Wettbewerbsergebnisse per CSV-Upload anlegen
+admission-intro = Hier können Sie die Zulassungen der Teilnehmer über eine CSV-Datei hochladen. Schieben Sie dazu die CSV-Datei auf das grüne Feld.
+admission-requirements = Die hochzuladende CSV-Datei muss den folgenden Kriterien genügen:
+admission-delimiter = Die CSV-Datei muss Komma- oder Tab-getrennt sein
+admission-encoding = Die Datei muss in UTF-8 (Unicode UTF-8) kodiert sein
+admission-columns = Die Datei muss mindestens zwei Spalten enthalten. Alle weiteren Spalten werden ignoriert.
+admission-column-user = Die User-ID des Teilnehmers.
+admission-column-admission = Der Zulassungszustand des Teilnehmers für die nächste Runde des Wettbewerbs (Freitext).
+admission-after-upload = Im Anschluss an den Upload haben Sie hier noch die Möglichkeit, einzelne Zeilen zu löschen (z. B. Kopfzeilen), bevor Sie die Daten anlegen. Hochgeladene Ergebnisse können durch einen neuen Upload überschrieben werden.
+admission-drop = CSV-Datei hier hineinschieben …
+admission-create = Daten anlegen
+admission-clear = Alle löschen
+admission-only-one-file = Bitte nur eine Datei ablegen!
+admission-delete = Löschen
+admission-skipped-before = Es wurden
+admission-skipped-after = Zeilen ausgelassen, die nicht den Vorgaben entsprachen!
+
+## Profil und Passwort
+
+profile-own-named = Mein Profil: { $profile_firstname } { $profile_lastname }
+profile-edit-named = Benutzerdaten bearbeiten: { $profile_firstname } { $profile_lastname }
+profile-signed-up = Du hast dir erfolgreich einen Account angelegt.
+profile-signed-up-hint = Auf dieser Seite kannst du weitere Angaben zu dir eintragen und dein Passwort ändern.
+profile-username = Benutzername: { $profile_username }
+profile-logincode = Login-Code: { $profile_logincode }
+profile-street = Straße und Hausnummer:
+profile-zip = Postleitzahl:
+profile-city = Stadt:
+profile-grade = Jahrgangsstufe:
+profile-sex-optional = Geschlecht (optional):
+profile-new-password = Neues Passwort:
+profile-new-password-repeat = Neues Passwort (wdh):
+profile-save = Speichern
+profile-nothing-changed = Keine Änderungen.
+profile-data-changed = Daten wurden erfolgreich geändert.
+profile-password-changed = Passwort wurde erfolgreich geändert.
+profile-password-mismatch = Die eingegebenen Passwörter stimmen nicht überein. Passwort wurde nicht geändert.
+profile-email-confirmation-sent = Wir haben dir eine E-Mail mit einem Bestätigungslink geschickt. Die neue E-Mail-Adresse wird erst nach der Bestätigung verwendet.
+profile-email-taken = Diese E-Mail-Adresse wird bereits von einem anderen Account verwendet.
+profile-email-empty = Bitte gib eine E-Mail-Adresse ein.
+profile-email = E-Mail-Adresse
+profile-email-value = E-Mail-Adresse: { $profile_email }
+profile-email-unconfirmed-value = Noch nicht bestätigt: { $profile_email_unconfirmed }
+profile-email-change = E-Mail-Adresse ändern
+password-forgotten-title = Passwort vergessen
+password-reset-sent = Falls zu diesen Angaben ein Account mit Passwort und E-Mail-Adresse existiert, haben wir eine E-Mail mit einem Link zum Zurücksetzen des Passworts verschickt.
+password-reset-hint = Gib deinen Benutzernamen oder deine E-Mail-Adresse ein. Wir schicken dir dann eine E-Mail mit einem Link, über den du ein neues Passwort festlegen kannst.
+password-reset-name = Benutzername oder E-Mail-Adresse:
+password-reset-submit = Passwort zurücksetzen
+back-to-login = Zum Login
+password-reset-form-title = Neues Passwort festlegen
+password-reset-done = Dein Passwort wurde erfolgreich geändert. Du kannst dich jetzt mit dem neuen Passwort anmelden.
+password-reset-mismatch = Die eingegebenen Passwörter stimmen nicht überein.
+password-reset-empty = Bitte gib ein neues Passwort ein.
+password-reset-save = Passwort speichern
+password-reset-invalid = Dieser Link ist ungültig oder abgelaufen. Du kannst
+password-reset-request-new = einen neuen Link anfordern
+email-confirm-title = E-Mail-Adresse bestätigen
+email-confirm-done = Die E-Mail-Adresse { $email } wurde erfolgreich bestätigt.
+email-confirm-taken = Die E-Mail-Adresse { $email } wurde bereits für ein anderes Konto bestätigt.
+email-confirm-invalid = Dieser Bestätigungslink ist ungültig oder wurde bereits verwendet.
+profile-own = Mein Profil:
+profile-edit = Benutzerdaten bearbeiten von
+profile-logincode-label = Login-Code:
+profile-logincode-only = Nur dieser Logincode erlaubt dir, dich erneut einzuloggen
+profile-logincode-instead = (anstelle eines Benutzernamen und eines Passworts).
+profile-logincode-write-down = Bitte schreibe ihn dir auf!
+profile-logincode-forgotten = Wenn du deinen Logincode vergessen hast, kann deine Lehrerin oder dein Lehrer ihn dir sagen.
+profile-logincode-written-down = Ich habe mir den Logincode aufgeschrieben
+profile-first-login-hint = Bitte gib noch deinen Namen und deine Jahrgangsstufe an und drücke dann auf „Speichern“:
+optional-paren = (optional)
+profile-email-label = E-Mail-Adresse:
+profile-email-unconfirmed = Noch nicht bestätigt:
+profile-email-new = Neue E-Mail-Adresse
+back-to-start-button = Zurück zur Startseite!
+profile-to-training = Zum Training!
+profile-to-contests = Zu den Wettbewerben!
+profile-my-stars = Meine Sterne
+profile-stars-before = Du hast bis jetzt
+profile-stars = Sterne
+profile-stars-after = gesammelt.
+profile-my-participations = Meine Teilnahmen
+profile-active = (aktiv)
+profile-finished = (beendet)
+profile-training-and-challenges = Trainingsaufgaben und Herausforderungen
+password-reset-name-placeholder = Benutzername oder E-Mail-Adresse
+password-reset-new = Neues Passwort
+password-reset-new-repeat = Neues Passwort (wdh)
+
+## Cookies, Registrierung, Startseite
+
+cookie-title = Keine Cookies
+cookie-not-set = Leider konnte diese Seite kein Cookie in deinem Browser setzen.
+cookie-disabled = Möglicherweise hast du Cookies in deinem Browser allgemein oder für diese Seite deaktiviert. In diesem Fall aktiviere bitte Cookies für diese Seite. Vielleicht unterstützt dein Browser auch keine Cookies. Dann kannst du Teile dieser Seite leider nicht nutzen.
+cookie-retry-hint = Wenn du Cookies jetzt in deinem Browser aktiviert hast, kannst du auf diesen Button drücken, um die angeforderte Seite noch einmal zu laden.
+cookie-retry = Ich habe Cookies aktiviert, noch einmal probieren
+cookie-back-hint = Ansonsten kommst du mit diesen Buttons zurück oder zur Startseite.
+cookie-why-title = Wozu benötigt diese Seite Cookies
+cookie-why = Die Cookies werden von dieser Seite benötigt, um eine Session zwischen deinem Browser und dem Server aufzubauen. Ansonsten könnte der Server beim nächsten Seitenaufruf nicht mehr wissen, welcher Computer die Seite anfragt. Das brauchen wir einerseits, damit du dich auf der Seite einloggen kannst (sonst wüssten wir nicht, wer sich eingeloggt hat) und andererseits, um deine Aufgabenlösungen zu speichern.
+signup-title = Registrieren
+signup-username-taken = Dieser Benutzername existiert bereits. Bitte wähle einen anderen.
+signup-email-taken = Diese E-Mail-Adresse wird bereits von einem anderen Account verwendet. Bitte wähle eine andere.
+signup-logged-in = Du bist bereits angemeldet. Wenn du einen neuen Account erstellen möchtest, dann melde dich zunächst ab.
+signup-empty-fields = Bitte fülle alle Felder aus, um dir einen Account anzulegen.
+signup-submit = Registrieren
+oauth-select-school = Bitte zum Einloggen Schule auswählen
+oauth-school-missing = Wenn die gewünschte Schule hier nicht aufgeführt ist, bitte die Schule unter
+oauth-school-missing-upload = eintragen und ein Dokument hochladen, das die Zugehörigkeit zu Ihrer Schule bestätigt.
+oauth-without-school = Ohne Schule einloggen
+oauth-without-school-hint = Sie können sich ohne Schule einloggen. Damit werden die Daten Ihrer Teilnehmer nicht Ihrer Schule zugeordnet.
+oauth-without-school-button = Ohne Schule einloggen! Keine Teilnahmen am Jugendwettbewerb Informatik 2021 möglich.
+oauth-no-school = Sie sind zur Zeit unter
+oauth-no-school-end = für keine Schule eingetragen.
+oauth-without-school-2020 = Sie können sich ohne Schule einloggen, um Daten aus dem Wettbewerb 2020 einzusehen.
+oauth-without-school-2020-end = Dies ist nur bis Ende 2020 möglich! Damit ist keine Teilnahme am Jugendwettbewerb Informatik 2021 möglich.
+index-admin = Administration
+index-admin-hint = Administrationsseite öffnen und Benutzer, Gruppen und Teilnahmen verwalten
+index-groups-hint = Gruppen für die Wettbewerbsteilnahme anlegen und verwalten
+index-training-hint = Zum Ausprobieren und Üben
+index-to-training = Zu den Trainingsaufgaben …
+index-current = Aktuelle Wettbewerbe
+index-to-current = Zu den Wettbewerben …
+index-information = Informationen
+index-information-hint = Informationen zur Anmeldung und Teilnahme am Wettbewerb, Termine, Materialien und mehr …
+index-challenge-hint = Aufgaben mit und ohne Zeitbeschränkung
+index-to-challenge = Zu den Herausforderungen …
+teacher-information = Lehrer-Informationen
+teacher-no-information = Noch keine Informationen vorhanden
+
+## Wettbewerbsliste
+
+contests-image = Bild
+contests-blockly = Blockly
+
+## Audit-Log-Felder
+
+audit-field-firstname = Vorname
+audit-field-lastname = Nachname
+audit-field-logincode = Logincode
+audit-field-username = Benutzername
+audit-field-group = Gruppe
+audit-field-name = Name
+audit-field-groupcode = Gruppencode
+audit-field-members = Mitglieder
+audit-field-owner = Besitzer
+audit-field-contest = Wettbewerb
+audit-field-start = Beginn
+audit-field-extra-time = Zusatzzeit (Minuten)
+audit-field-paused-secs = Pausiert (Sekunden)
+audit-field-paused-since = Pausiert seit
+audit-field-students = Schüler
+audit-field-groups = Gruppen
+audit-field-teachers = Lehrkräfte
+audit-field-others = Andere
+
+## Contest-Scan
+
+admin-scan-summary = { $added } hinzugefügt, { $changed } geändert, { $hidden } ausgeblendet, { $errors } Fehler, { $unchanged } Dateien unverändert
+audit-field-added = Hinzugefügt
+audit-field-changed = Geändert
+audit-field-hidden = Ausgeblendet
+audit-field-errors = Fehler
+audit-field-unchanged = Unveränderte Dateien
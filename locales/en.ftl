@@ -0,0 +1,768 @@
+# English texts of the user interface
+
+language-name = English
+
+## School grades
+
+grade-0 = Not yet in school
+grade-11 = 11 (G8)
+grade-12 = 12 (G8)
+grade-111 = 11 (G9)
+grade-112 = 12 (G9)
+grade-113 = 13 (G9)
+grade-114 = Vocational school
+grade-255 = No longer in school
+
+sex-not-stated = Not stated
+sex-female = female
+sex-male = male
+sex-diverse = diverse
+sex-other = other
+
+## Navigation
+
+slogan = Programming – easier than you think!
+logged-in-as = Logged in as
+role-teacher = Teacher
+logout = Logout
+login = Login
+log-in = log in
+nav-contests = Contest
+nav-profile = Profile
+nav-groups = Group management
+nav-teacher-material = Teaching material
+nav-admin = Administration
+
+## Login
+
+username = Username
+password = Password
+password-forgotten = Forgot your password?
+login-code = Group code / participation code
+login-code-placeholder = Group code or login code
+login-hint = Note: If you do not remember your login details, your teacher can look them up for you.
+login-with-password = Login with username and password (not a PMS account)
+login-with-code = Login with group code or login code
+login-with-oauth = Login with another account
+login-failed = Login failed. Please try again.
+login-code-invalid = Invalid code. Please try again.
+login-link-invalid = The login link has expired or has been revoked. Please log in with your login code.
+oauth-login-failed = OAuth login failed.
+
+## Profile
+
+profile-language = Language
+profile-language-browser = Language of the browser
+profile-language-save = Change language
+
+## Stars in the profile
+
+stars-none = Let's go, your first star is waiting for you!
+stars-few = An excellent start!
+stars-many = That is pretty good!
+stars-master = A true master!
+stars-wow = Wow! Simply wow!
+
+## Tasks
+
+task-title = Task “{ $taskgroup }” in { $contest }
+
+## Deletion and cleanup
+
+delete-user-protected-participations = User has participations in protected contests.
+delete-user-group-admin = User is administrator of groups.
+delete-user-not-found = User does not exist.
+delete-group-not-found = Group does not exist.
+delete-group-protected-participations = Group has members with protected participations.
+cleanup-failed = Error.
+
+## Group import
+
+import-column = Column { $number }
+import-column-group = Group
+import-column-grade = Grade
+import-column-firstname = First name
+import-column-lastname = Last name
+import-column-sex = Sex
+import-no-column = No column selected for "{ $field }"
+import-value-missing = "{ $field }" is missing
+import-name-missing = First and last name are missing
+import-too-long = "{ $field }" is longer than { $max } characters
+import-unknown-grade = Unknown grade "{ $value }"
+import-unknown-sex = Unknown sex "{ $value }"
+import-sex-not-allowed = Sex "{ $value }" is not allowed
+import-invalid-encoding = The file is not saved in the selected character encoding.
+import-invalid-csv = The file could not be read: { $error }
+import-empty = The file does not contain any participants.
+
+## Login sheets (PDF)
+
+login-sheet-title = Your login details
+login-sheet-group = Group { $group }, grade { $grade }
+login-sheet-logincode = Login code
+login-sheet-groupcode = Group code: { $groupcode }
+login-sheet-instructions = Open { $login_url } and enter your login code or scan the QR code. Keep your login code safe, you need it every time you log in.
+login-sheet-summary-title = Login details of group { $group }
+login-sheet-summary = The following pages contain the login details of the { $count } participants of this group to cut out and hand out. The participants log in at { $login_url } with their login code. This summary is for you only.
+login-sheet-name = Name
+login-sheet-grade = Grade
+
+## Footer
+
+footer-source-code = Source code
+footer-task-license = All tasks under
+footer-jwinf-article = The
+footer-jwinf-is-one-of = is one of the
+footer-bwinf = German Informatics Competitions
+footer-bwinf-funding = The German Informatics Competitions are student competitions funded by the Standing Conference of the Ministers of Education and are under the patronage of the Federal President.
+footer-organizers = Organizers:
+
+## Qualification
+
+qualification-unknown-contest = The contest { $contest } is not known.
+qualification-not-participated = You have not participated in the contest “{ $contest }”.
+qualification-participated = You have participated in the contest “{ $contest }”.
+qualification-percentage-reached = You have reached { $percentage } % of the points, { $required } % are required.
+qualification-percentage-missed = You have reached { $percentage } % of the points, but { $required } % are required.
+qualification-rank-reached = You are ranked { $rank } in your grade, the best { $top } are qualified.
+qualification-rank-missed = You are ranked { $rank } in your grade, but only the best { $top } are qualified.
+qualification-not-ranked = You are not ranked in your grade.
+qualification-admitted = Your admission has been recorded.
+qualification-not-admitted = No admission has been recorded for you.
+qualification-invalid-rules = The qualification requirements of this contest are invalid.
+
+## Emails
+
+mail-greeting = Hello { $name },
+mail-confirm-email-subject = Confirmation of your email address
+mail-confirm-email-text = please confirm your email address by opening the following link:
+mail-confirm-email-ignore = If you did not enter this email address, you can ignore this email.
+mail-password-reset-subject = Reset password
+mail-password-reset-text = a new password has been requested for your account. You can set a new password within 24 hours with the following link:
+mail-password-reset-ignore = If you did not request a new password, you can ignore this email.
+
+## Roles in the admin area
+
+role-contest_manager = Contest management
+role-school_coordinator = School coordination
+role-support = Support
+role-auditor = Audit (read only)
+
+## Reports
+
+report-without-oauth = Without OAuth
+
+## Contest overview
+
+contests-training = Training tasks
+contests-contest = Contests
+contests-challenge = Challenges
+contests-standalone-tasks = Single tasks without scoring
+
+## General
+
+back-to-start = Back to the start page
+back-to-contests = Back to the contest overview
+contests-filter-by-tag = Filter by tag:
+contests-view-results = View results
+contests-and-tasks = Contests / tasks
+contests-none-available = There are currently no contests / tasks available here.
+
+## Contest
+
+contest-title = Contest:
+contest-training-title = Training tasks:
+contest-duration = Duration: { $minutes } minutes
+contest-show-group-results = Show the results of my groups.
+contest-show-scoreboard = Show the scoreboard.
+contest-group-results = Group results
+contest-group-results-text = View the results of my groups for this contest
+contest-scoreboard = Scoreboard
+contest-scoreboard-text = View the anonymized live scoreboard of this contest
+contest-not-logged-in = Note: You are not logged in. Log in to save your progress.
+contest-started-ago = Started { $seconds } seconds ago.
+contest-time-left = Time left:
+contest-extra-time = (including { $minutes } minutes of extra time)
+contest-teachers-exempt = (Teachers are exempt from the time limit.)
+contest-paused = The participation is paused.
+contest-paused-by-teacher = Your participation has been paused by your teacher. The time is currently not running.
+contest-review-mode = Review mode: You can open and work on the tasks. The solutions are not saved, however, and do not give any points.
+contest-review-ends-in = The review mode ends in { $days } days, { $hours } hours and { $minutes } minutes.
+contest-time-over = The time is over. You can no longer open the tasks.
+contest-review-starts-in = The review mode starts in { $days } days, { $hours } hours and { $minutes } minutes.
+contest-score = Score:
+contest-qualified = You are qualified for this contest:
+contest-not-qualified = You are not qualified for this contest.
+contest-qualification-requirements = To participate, one of the following requirements has to be met:
+contest-password = Contest password
+contest-password-wrong = The password you entered is not correct.
+contest-start-now = Start now!
+contest-runs-for = After starting, the contest runs for { $minutes } minutes.
+contest-cannot-pause = The contest cannot be paused after starting.
+contest-cannot-start = You cannot start this contest.
+contest-over = The contest is already over.
+contest-not-begun = The contest has not begun yet.
+contest-wrong-grade = The grade you entered does not match the age groups intended for this contest.
+contest-login-needed = You may have to log in first:
+contest-begins-in = The contest begins in: { $days } days, { $hours } hours, { $minutes } minutes.
+contest-ends-in = The contest is still running for: { $days } days, { $hours } hours, { $minutes } minutes.
+
+## Task
+
+task-review-mode = Review mode
+task-time-left = Time left
+task-fullscreen = Full screen
+task-overview = Overview
+task-next = Next task
+task-previous = Previous task
+task-no-frames = Unfortunately, your browser does not support frames. You can also open the task manually:
+task-open-manually = Work on the task manually
+task-load-failed = Loading failed
+task-save-failed = Saving failed
+
+## Results
+
+yes = yes
+no = no
+groups = Groups
+back-to-contest = Back to the contest
+back-to-results = Back to the results
+back-to-group = Back to the group
+results = Results
+results-in = Results in { $contest }
+results-download = Download results:
+results-show-ranking = Show ranking
+results-group = Group
+results-groupcode = Group code
+results-participant = Participant
+results-rank = Rank
+results-sum = Total
+results-total = Total points
+results-admission = Admission to the 3rd round
+results-admission-short = Admission
+results-statistics = Statistics
+results-none = There are no results of your groups for this contest yet.
+scoreboard-in = Scoreboard of { $contest }
+scoreboard-empty = No points have been scored yet.
+ranking = Ranking
+ranking-in = Ranking of { $contest }
+ranking-school = School
+ranking-filter = Filter
+ranking-public-scoreboard = Public scoreboard
+ranking-score-reached = Score reached
+ranking-attempts = Submissions
+ranking-empty = There are no matching participations for this contest.
+
+## Group statistics
+
+statistics-title = { $contest }: Statistics of group { $group }
+statistics-title-long = Statistics of group “{ $group }” in { $contest }
+statistics-participants = Participants with results:
+statistics-average-time = Average time from the start to the first submission:
+statistics-hardest-task = Hardest task:
+statistics-task = Task
+statistics-average-stars = Average stars
+statistics-stars-of = { $stars } of { $max }
+statistics-reached = Reached
+statistics-share-reached = Share reached
+statistics-not-attempted = Without submission
+statistics-time-to-first-submission = Time to the first submission
+statistics-hardest = hardest
+statistics-distribution = Score distribution
+statistics-with = with
+
+## Submissions
+
+submissions-back-to-participation = Back to the participation
+submissions-of = Submissions of { $firstname } { $lastname }
+submissions-history = History
+submissions-history-title = Submission history
+submissions-number = No.
+submissions-time = Time
+submissions-time-review = Time / review
+submissions-points = Points
+submissions-unvalidated-points = Unvalidated points
+submissions-validated = Validated
+submissions-pending = pending
+submissions-left = Left
+submissions-right = Right
+submissions-compare = Compare
+submissions-none = No submissions.
+submissions-comparison = Comparison of submission { $left } and { $right }
+submissions-too-large = The submissions are too large to be compared line by line.
+
+## Error pages
+
+page-locked = 423: Page locked
+page-locked-text = This page is currently disabled to reduce the server load and allow a smooth contest. Please try again later!
+action-forbidden = This action is not allowed!
+
+## Groups
+
+back = Back
+back-to-groups = Back to the groups
+edit = edit …
+optional = optional
+group-members = Members
+group-tag = Tag
+group-login-sheets-print = Login data for printing (PDF)
+groups-create = Create group
+groups-create-plural = Create groups
+groups-create-new = Create new group
+groups-name = Group name
+groups-tag-hint = The tag can be the name of the class or course, for example. It is printed on the certificates and helps you to assign them.
+groups-class = Class / course
+groups-class-hint = The class and course names are printed on the certificates. The certificates are sorted by them.
+groups-csv-upload-before = You can also create groups with a
+groups-csv-upload = CSV upload
+groups-invitations = Invitations to co-manage groups
+groups-admin = Group admin
+groups-accept = Accept
+groups-decline = Decline
+groups-mine = My existing groups
+groups-managed = Groups managed by me
+groups-results-hint = To see the contest results of my groups, please first
+groups-choose-contest = choose a contest
+groups-contest-results = Contest results
+groups-contest-results-link = View the contest results of my groups
+groups-download = Download
+groups-view = View …
+groups-login-sheets = Login data (PDF)
+groups-none = There are no groups yet. Create a new group above to let your students take part in contests
+
+## CSV upload
+
+import-title = Create groups with a CSV upload
+import-intro = Here you can create groups and accounts from a CSV file, e.g. an export from the school administration software.
+import-columns-intro = The file must contain a line with the following columns for each participant. Further columns are ignored.
+import-columns-group = The name of the group. (A file can contain several groups.)
+import-columns-grade = The grade of the participant, as a number or as in the profile (e.g. “12 (G9)”).
+import-columns-firstname = The first name of the participant.
+import-columns-lastname = The last name of the participant.
+import-columns-sex = The sex of the participant (m, f, d or empty).
+import-columns-biber = These five columns are the first five columns of the CSV files for the upload to the Informatik-Biber (without user names and passwords).
+import-columns-header = If the file has a header line with column names like “Group”, “Grade”, “First name”, “Last name” and “Sex”, the columns are recognized by them.
+import-example = An example could look like this:
+import-example-header = Group,Grade,First name,Last name,Sex
+import-file = CSV file
+import-encoding = Character encoding
+import-encoding-utf16 = UTF-16 (Excel “Unicode Text”)
+import-detect = Detect automatically
+import-delimiter = Delimiter
+import-comma = Comma
+import-semicolon = Semicolon
+import-tab = Tab
+import-header = Header line
+import-header-yes = Yes
+import-header-no = No
+import-paste = Instead of choosing a file, you can also paste its content here:
+import-check = Check file
+import-preview = Preview
+import-no-column-selected = none
+import-correct = You can also correct the data directly here:
+import-update-preview = Update preview
+import-create = Create groups
+import-will-create = The following groups will be created. Created groups cannot be deleted.
+import-error-count = The file contains { $count } errors. Please correct them before creating the groups. Groups are only created when all lines are correct.
+import-participants = { $count } participants
+import-line = Line
+import-grade-short = Grade
+import-errors = Errors
+
+## Administration
+
+admin-search = Admin search
+admin-search-wildcard = % is a wildcard in the name search.
+admin-search-limit = The search only returns up to 30 results to avoid overloading the server. Refine the search if necessary!
+admin-firstname = First name:
+admin-lastname = Last name:
+admin-search-by-name = Search by name
+admin-logincode = Login code:
+admin-search-by-logincode = Search by login code
+admin-groupcode = Group code:
+admin-search-by-groupcode = Search by group code
+admin-search-by-id = Search by ID
+admin-search-by-pmsid = Search by PMS ID
+admin-contest-export = Contest export
+admin-contest-export-link = Contest overview and export
+admin-dbstatus = Database status
+admin-reports-link = Participations, registrations and submissions
+admin-audit-log = Audit log
+admin-audit-log-link = Changes by administrators and teachers
+admin-cleanup = Clean up old user accounts
+admin-cleanup-link = Database cleanup
+admin-user-id = User ID:
+admin-group-id = Group ID:
+admin-from = From:
+admin-until = Until:
+admin-search-submit = Search
+admin-audit-no-entries = No entries found.
+admin-audit-actor = Performed by
+admin-audit-action = Action
+admin-user = User
+admin-contest = Contest
+admin-audit-before = Before
+admin-audit-after = After
+admin-cleanup-hard = Delete old data
+admin-cleanup-hard-text = This deletes all managed student accounts that have not been used for more than 180 days, all teacher accounts unused for more than 3 years and all other accounts unused for more than 10 years.
+admin-cleanup-hard-warning = Should not be run during a contest or after the registration has started, in case a teacher still plans to use older accounts. Ideally run it before the registration starts and after the end of the second round.
+admin-cleanup-hard-submit = Delete old data!
+admin-cleanup-session = Delete temporary sessions
+admin-cleanup-session-text = Deletes sessions of users who have not logged in if they are older than 30 days.
+admin-cleanup-session-safe = Can be run safely at any time.
+admin-cleanup-session-submit = Delete temporary sessions!
+admin-export-title = Export: { $contestname }
+admin-export-format = Format
+admin-export-csv-excel = CSV for Excel (with BOM, separated by semicolons)
+admin-export-columns = Columns
+admin-export-subtasks = Points for each subtask
+admin-export-submissions = Number of submissions per task
+admin-export-download = Download
+admin-scan = Rescan contests
+admin-scan-text = Reads all contest and task files that have changed since the last scan. Contests whose file was removed are hidden. Files with errors are not read, the previous version is kept.
+admin-scan-safe = Can also be run during a running contest.
+admin-scan-result = Result
+admin-scan-added = Added
+admin-scan-changed = Changed
+admin-scan-hidden = Hidden
+admin-results-export = Result data export
+admin-search-no-results = No results found.
+admin-search-too-many = More than { $max_results } results found. Only the first { $max_results } results are shown. Please refine the search!
+admin-search-count = { $num_results } results:
+admin-cleanup-deleted = Data deleted successfully:
+admin-cleanup-students = students
+admin-cleanup-teachers = teachers
+admin-cleanup-others = others
+admin-cleanup-sessions = sessions
+admin-cleanup-failed = Cannot delete data:
+admin-new-search = New search
+admin-role-admin = Administrator
+admin-role-student = Student
+admin-user-id-value = Id: { $user_id }
+admin-user-firstname = First name: { $user_firstname }
+admin-user-lastname = Last name: { $user_lastname }
+admin-user-grade = Grade: { $user_grade }
+admin-user-username = User name: { $user_username }
+admin-user-logincode = Login code: { $user_logincode }
+admin-user-oauth = OAuth login: { $user_oauthprovider } ({ $user_oauthprovider } id: { $user_oauthid })
+admin-user-logged-in = Is logged in
+admin-user-admin-of = Admin of
+admin-id = Id
+admin-class-short = Class/course
+admin-user-member-of = Member of
+admin-participations = Participations
+admin-roles = Roles
+admin-roles-save = Save roles
+admin-sessions = Login
+admin-sessions-revoke = Log out on all devices
+admin-user-delete = Delete user
+admin-user-delete-submit = Delete user!
+admin-group-id-value = Id: { $group_id }
+admin-group-name = Name: { $group_name }
+admin-group-code = Group code: { $group_code }
+admin-group-tag = Class/course: { $group_tag }
+admin-group-admin = Group administrator:
+admin-group-links-revoked = The login links have been revoked. Download the login data again to get new login links.
+admin-group-revoke-link = Revoke login link
+admin-group-revoke-links = Revoke the login links of the group
+admin-group-teachers = Teachers
+admin-group-invited = The teacher has been invited.
+admin-group-already-invited = This teacher already manages the group or has already been invited.
+admin-group-invitee-not-found = No teacher with this login code or email address was found.
+admin-group-removed = The teacher has been removed.
+admin-group-transferred = The group has been transferred.
+admin-group-accepted = You now co-manage this group.
+admin-group-status = Status
+admin-group-co-managing = co-managing
+admin-group-invitation-pending = invited
+admin-group-transfer = Transfer group
+admin-group-remove = Remove
+admin-group-leave = Stop co-managing
+admin-group-invitee = Login code or email address:
+admin-group-invite = Invite teacher
+admin-group-delete = Delete group
+admin-group-delete-submit = Delete group!
+admin-participation = Participation
+admin-participation-data = Participation data
+admin-participation-start = Start
+admin-participation-expired = expired
+admin-participation-extra-time = (incl. { $extra_time } minutes of extra time)
+admin-participation-paused = , paused
+admin-participation-extra-time-minutes = Extra time in minutes:
+admin-participation-set = Set
+admin-participation-resume = Resume
+admin-participation-pause = Pause
+admin-participation-restart = Restart time
+admin-submissions = Submissions
+admin-participation-history = History and comparison
+admin-participation-delete = Delete participation
+admin-participation-delete-submit = Delete participation!
+admin-reports-show = Show
+admin-reports-export = Export
+admin-reports-overview = Overview
+admin-reports-teachers = Teachers with participating groups
+admin-reports-schools = Schools with participating groups
+admin-reports-per-contest = Participations per contest
+admin-reports-by-login = Participations by login
+admin-reports-by-grade = Participations by grade
+admin-reports-by-sex = Participations by sex
+admin-reports-accounts-per-day = New user accounts per day (UTC)
+admin-reports-submissions-per-hour = Submissions per hour (UTC)
+admin-user-deleted = User deleted.
+admin-user-delete-failed = Cannot delete user:
+admin-group-deleted = Group deleted.
+admin-group-delete-failed = Cannot delete group:
+admin-participation-deleted = Participation deleted.
+admin-participation-delete-failed = Cannot delete participation:
+admin-participation-delete-confirm = Really delete the participation?
+admin-search-for = Admin search by …
+admin-search-for-name = … name
+admin-search-for-logincode = … login code
+admin-search-for-groupcode = … group code
+admin-search-for-id = … ID
+admin-search-user-id = User ID
+admin-search-for-pmsid = … PMS ID
+admin-cleanup-title = Deleting old user accounts
+admin-dbstatus-show = Show database status
+admin-export = Export
+admin-scan-short = Rescan
+admin-contest-list = Contest list
+admin-contest-all = All contests
+admin-results-export-link = Export results
+admin-results-upload = ⇧ Upload results
+admin-search-results = Search results
+
+## Administration (JWINF)
+
+admin-user-id-short = User ID
+admin-oauth-login = OAuth login
+admin-oauth-id = { $user_oauthprovider } ID
+admin-user-delete-named = Delete user “{ $user_firstname } { $user_lastname }”:
+admin-cannot-be-undone = This action cannot be undone!
+admin-user-protected-warning = Attention: This user has protected participations in contests.
+admin-user-protected = The user cannot be deleted because they have protected participations in contests.
+admin-user-protected-hint = The user can only be deleted once the evaluation of the respective contests is finished and the protection of the participations has been lifted.
+admin-user-has-groups = The user can only be deleted if they do not own any groups. Please delete the groups of the user first.
+admin-user-move-named = Move user “{ $user_firstname } { $user_lastname }” to another group:
+admin-user-move = Move user
+admin-user-edit-profile = Edit profile
+admin-roles-label = Roles:
+admin-sessions-label = Login:
+admin-user-member-in = Member of
+admin-user-logincode-hint = This is the login code of the user. The user can log in with this code.
+admin-tasks-contest = Tasks/contest
+admin-start-time = Start time
+admin-user-no-participations = No participations yet. As soon as the user starts participations, they appear here.
+admin-user-delete-hint = Here you can delete the user with all participations. The deletion cannot be undone.
+admin-user-delete-all = Delete user with all participations
+admin-user-timed-warning = Attention: This user has participations in time-limited contests.
+admin-back-to-group = Back to the group
+admin-user-has-groups-short = The user can only be deleted if they do not own any groups.
+admin-user-delete-groups-first = Please delete the groups of the user first.
+admin-group-tag-paren = (Class/course: { $group_tag })
+admin-group-id-short = Group ID
+admin-group-more-teachers = Further teachers
+admin-group-invited-paren = (invited)
+admin-group-delete-named = Delete group “{ $group_name }”:
+admin-group-protected-warning = Attention: This group has members with protected participations! Deleting this group deletes participations in the JWINF!
+admin-group-code-hint = This is the group code. Pass it on to the participants. The group code creates a new account each time it is used.
+admin-sex-short = Sex
+admin-login-link = Login link
+admin-revoke = Revoke
+admin-group-download-members = Download member list:
+admin-group-login-sheets-paren = (login data for printing)
+admin-group-login-links-hint = The QR codes on the login data contain login links that let the participants log in without entering the login code. If login data has been lost, you can revoke the login links of single participants or of the whole group and download the login data again. The login codes remain valid.
+admin-group-no-members = No members yet. As soon as participants log in with the group code above, they appear here.
+admin-group-invited-hint = The teacher has been invited. They can co-manage the group as soon as they accept the invitation.
+admin-group-transferred-hint = The group has been transferred. You can continue to co-manage the group.
+admin-group-admin-named = Group admin: { $group_admin_firstname } { $group_admin_lastname }
+admin-group-invite-hint = You can invite further teachers to co-manage the group, e.g. to look after the contest participation of the class in case of illness. Only the group admin can delete the group and invite or remove teachers.
+admin-group-invitee-label = Login code or email address
+admin-group-delete-hint = Here you can delete the group with all members and all their participations. This step cannot be undone.
+admin-group-protected = The group cannot be deleted because it has members with protected participations.
+admin-group-protected-hint = The group can only be deleted once the evaluation of the respective contests is finished and the protection of the participations has been lifted.
+admin-group-only-admin-deletes = Only the group admin can delete the group.
+admin-participation-extra-time-short = (incl. { $extra_time } min. extra time)
+admin-participation-delete-named = Delete participation in “{ $contestname }”:
+admin-back-to-user = Back to the user
+admin-participation-protected = The participation cannot be deleted because it is a protected participation.
+admin-participation-reset = Reset participation
+admin-participation-reset-hint = Here you can reset the participation. The reset cannot be undone.
+admin-participation-was-reset = Participation reset.
+admin-participation-reset-protected = The participation cannot be reset because it is a protected participation.
+admin-participation-time = Working time
+admin-participation-time-left = Time left: { $time_left_formatted }
+admin-participation-time-over = The working time is over.
+admin-participation-extra-time-included = This includes { $extra_time } minutes of extra time.
+admin-participation-set-extra-time = Set extra time in minutes
+admin-participation-time-hint = Extra time can be granted e.g. to compensate for disadvantages. During a pause, e.g. after a technical problem, the time does not run and no solutions can be saved. On a restart, the working time starts again from the beginning, the previous submissions are kept.
+admin-user-move-failed = Cannot move user:
+admin-user-delete-confirm = Really delete the user?
+admin-group-delete-confirm = Really delete the group and all members?
+admin-participation-restart-confirm = Really restart the working time?
+
+## Admissions
+
+admission-title = Create contest results with a CSV upload
+admission-intro = Here you can upload the admissions of the participants with a CSV file. Drag the CSV file onto the green box.
+admission-requirements = The CSV file to be uploaded must meet the following criteria:
+admission-delimiter = The CSV file must be separated by commas or tabs
+admission-encoding = The file must be encoded in UTF-8 (Unicode UTF-8)
+admission-columns = The file must contain at least two columns. All further columns are ignored.
+admission-column-user = The user ID of the participant.
+admission-column-admission = The admission state of the participant for the next round of the contest (free text).
+admission-after-upload = After the upload you can delete single lines (e.g. header lines) before you create the data. Uploaded results can be overwritten by a new upload.
+admission-drop = Drag the CSV file here …
+admission-create = Create data
+admission-clear = Delete all
+admission-only-one-file = Please drop only one file!
+admission-delete = Delete
+admission-skipped-before = Skipped
+admission-skipped-after = lines that did not meet the requirements!
+
+## Profile and password
+
+profile-own-named = My profile: { $profile_firstname } { $profile_lastname }
+profile-edit-named = Edit user data: { $profile_firstname } { $profile_lastname }
+profile-signed-up = You have successfully created an account.
+profile-signed-up-hint = On this page you can enter further details about yourself and change your password.
+profile-username = User name: { $profile_username }
+profile-logincode = Login code: { $profile_logincode }
+profile-street = Street and house number:
+profile-zip = Postal code:
+profile-city = City:
+profile-grade = Grade:
+profile-sex-optional = Sex (optional):
+profile-new-password = New password:
+profile-new-password-repeat = New password (repeat):
+profile-save = Save
+profile-nothing-changed = No changes.
+profile-data-changed = Data changed successfully.
+profile-password-changed = Password changed successfully.
+profile-password-mismatch = The passwords entered do not match. The password was not changed.
+profile-email-confirmation-sent = We have sent you an email with a confirmation link. The new email address is only used after the confirmation.
+profile-email-taken = This email address is already used by another account.
+profile-email-empty = Please enter an email address.
+profile-email = Email address
+profile-email-value = Email address: { $profile_email }
+profile-email-unconfirmed-value = Not yet confirmed: { $profile_email_unconfirmed }
+profile-email-change = Change email address
+password-forgotten-title = Forgot password
+password-reset-sent = If an account with a password and email address exists for these details, we have sent an email with a link to reset the password.
+password-reset-hint = Enter your user name or email address. We will then send you an email with a link to set a new password.
+password-reset-name = User name or email address:
+password-reset-submit = Reset password
+back-to-login = Back to login
+password-reset-form-title = Set a new password
+password-reset-done = Your password has been changed successfully. You can now log in with the new password.
+password-reset-mismatch = The passwords entered do not match.
+password-reset-empty = Please enter a new password.
+password-reset-save = Save password
+password-reset-invalid = This link is invalid or has expired. You can
+password-reset-request-new = request a new link
+email-confirm-title = Confirm email address
+email-confirm-done = The email address { $email } has been confirmed successfully.
+email-confirm-taken = The email address { $email } has already been confirmed for another account.
+email-confirm-invalid = This confirmation link is invalid or has already been used.
+profile-own = My profile:
+profile-edit = Edit user data of
+profile-logincode-label = Login code:
+profile-logincode-only = Only this login code allows you to log in again
+profile-logincode-instead = (instead of a user name and a password).
+profile-logincode-write-down = Please write it down!
+profile-logincode-forgotten = If you have forgotten your login code, your teacher can tell you.
+profile-logincode-written-down = I have written down the login code
+profile-first-login-hint = Please enter your name and your grade and then press “Save”:
+optional-paren = (optional)
+profile-email-label = Email address:
+profile-email-unconfirmed = Not yet confirmed:
+profile-email-new = New email address
+back-to-start-button = Back to the start page!
+profile-to-training = To the training!
+profile-to-contests = To the contests!
+profile-my-stars = My stars
+profile-stars-before = You have collected
+profile-stars = Stars
+profile-stars-after = so far.
+profile-my-participations = My participations
+profile-active = (active)
+profile-finished = (finished)
+profile-training-and-challenges = Training tasks and challenges
+password-reset-name-placeholder = User name or email address
+password-reset-new = New password
+password-reset-new-repeat = New password (repeat)
+
+## Cookies, sign-up, start page
+
+cookie-title = No cookies
+cookie-not-set = Unfortunately this page could not set a cookie in your browser.
+cookie-disabled = Maybe you have disabled cookies in your browser in general or for this page. In that case, please enable cookies for this page. Maybe your browser does not support cookies at all. Then you unfortunately cannot use parts of this page.
+cookie-retry-hint = If you have now enabled cookies in your browser, you can press this button to load the requested page again.
+cookie-retry = I have enabled cookies, try again
+cookie-back-hint = Otherwise these buttons take you back or to the start page.
+cookie-why-title = Why does this page need cookies
+cookie-why = This page needs cookies to establish a session between your browser and the server. Otherwise the server could not know which computer is requesting the page on the next page load. We need this on the one hand so that you can log in (otherwise we would not know who logged in) and on the other hand to save your task solutions.
+signup-title = Sign up
+signup-username-taken = This username already exists. Please choose another one.
+signup-email-taken = This email address is already used by another account. Please choose another one.
+signup-logged-in = You are already logged in. If you want to create a new account, please log out first.
+signup-empty-fields = Please fill in all fields to create an account.
+signup-submit = Sign up
+oauth-select-school = Please select a school to log in
+oauth-school-missing = If the desired school is not listed here, please register the school at
+oauth-school-missing-upload = and upload a document confirming your affiliation with your school.
+oauth-without-school = Log in without school
+oauth-without-school-hint = You can log in without a school. The data of your participants will then not be assigned to your school.
+oauth-without-school-button = Log in without school! No participation in the Jugendwettbewerb Informatik 2021 possible.
+oauth-no-school = You are currently registered at
+oauth-no-school-end = for no school.
+oauth-without-school-2020 = You can log in without a school to view data from the 2020 contest.
+oauth-without-school-2020-end = This is only possible until the end of 2020! No participation in the Jugendwettbewerb Informatik 2021 is possible this way.
+index-admin = Administration
+index-admin-hint = Open the administration page and manage users, groups and participations
+index-groups-hint = Create and manage groups for contest participation
+index-training-hint = For trying out and practising
+index-to-training = To the training tasks …
+index-current = Current contests
+index-to-current = To the contests …
+index-information = Information
+index-information-hint = Information on registration and participation in the contest, dates, materials and more …
+index-challenge-hint = Tasks with and without time limit
+index-to-challenge = To the challenges …
+teacher-information = Teacher information
+teacher-no-information = No information available yet
+
+## Contest list
+
+contests-image = Image
+contests-blockly = Blockly
+
+## Audit log fields
+
+audit-field-firstname = First name
+audit-field-lastname = Last name
+audit-field-logincode = Login code
+audit-field-username = Username
+audit-field-group = Group
+audit-field-name = Name
+audit-field-groupcode = Group code
+audit-field-members = Members
+audit-field-owner = Owner
+audit-field-contest = Contest
+audit-field-start = Start
+audit-field-extra-time = Extra time (minutes)
+audit-field-paused-secs = Paused (seconds)
+audit-field-paused-since = Paused since
+audit-field-students = Students
+audit-field-groups = Groups
+audit-field-teachers = Teachers
+audit-field-others = Others
+
+## Contest scan
+
+admin-scan-summary = { $added } added, { $changed } changed, { $hidden } hidden, { $errors } errors, { $unchanged } files unchanged
+audit-field-added = Added
+audit-field-changed = Changed
+audit-field-hidden = Hidden
+audit-field-errors = Errors
+audit-field-unchanged = Unchanged files
@@ -0,0 +1,7 @@
+CREATE TABLE contest_file (
+       location TEXT NOT NULL,
+       filename TEXT NOT NULL,
+       hash TEXT NOT NULL,
+       scanned TIMESTAMP,
+       PRIMARY KEY (location, filename)
+)
@@ -0,0 +1,7 @@
+CREATE TABLE usergroup_admin (
+       usergroup INTEGER REFERENCES usergroup (id) ON DELETE CASCADE,
+       session INTEGER REFERENCES session (id) ON DELETE CASCADE,
+       accepted BOOL NOT NULL,
+       invited TIMESTAMP,
+       PRIMARY KEY (usergroup, session)
+)
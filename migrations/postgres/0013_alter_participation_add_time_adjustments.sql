@@ -0,0 +1,3 @@
+ALTER TABLE participation ADD COLUMN extra_time INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE participation ADD COLUMN paused_secs INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE participation ADD COLUMN paused_since TIMESTAMP;
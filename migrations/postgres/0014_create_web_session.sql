@@ -0,0 +1,7 @@
+CREATE TABLE web_session (
+       id TEXT NOT NULL,
+       key TEXT NOT NULL,
+       value TEXT NOT NULL,
+       last_change TIMESTAMP NOT NULL,
+       PRIMARY KEY (id, key)
+)
@@ -0,0 +1 @@
+ALTER TABLE session ADD COLUMN language TEXT;
@@ -0,0 +1 @@
+ALTER TABLE contest ADD COLUMN public_scoreboard INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE contest ADD COLUMN qualification TEXT;
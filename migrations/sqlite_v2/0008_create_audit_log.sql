@@ -0,0 +1,12 @@
+CREATE TABLE audit_log (
+       id INTEGER PRIMARY KEY,
+       actor INTEGER,
+       actor_name TEXT,
+       action TEXT NOT NULL,
+       target_user INTEGER,
+       target_group INTEGER,
+       target_contest INTEGER,
+       before TEXT,
+       after TEXT,
+       date TEXT
+)
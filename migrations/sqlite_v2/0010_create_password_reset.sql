@@ -0,0 +1,5 @@
+CREATE TABLE password_reset (
+       token_hash TEXT PRIMARY KEY,
+       session INTEGER REFERENCES session (id) ON DELETE CASCADE,
+       created TEXT
+)
@@ -0,0 +1,5 @@
+CREATE TABLE session_role (
+       session INTEGER REFERENCES session (id) ON DELETE CASCADE,
+       role TEXT NOT NULL,
+       PRIMARY KEY (session, role)
+)
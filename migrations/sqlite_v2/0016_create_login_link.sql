@@ -0,0 +1,4 @@
+CREATE TABLE login_link (
+       session INTEGER PRIMARY KEY REFERENCES session (id) ON DELETE CASCADE,
+       generation INTEGER NOT NULL
+)
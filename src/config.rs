@@ -29,6 +29,20 @@ pub struct OauthProvider {
     pub school_data_secret: Option<String>,
     pub allow_teacher_login_without_school: Option<bool>,
     pub login_link_text: String,
+    pub discovery_url: Option<String>,
+    pub scope: Option<String>,
+    pub claim_mapping: Option<OauthClaimMapping>,
+    pub role_mapping: Option<::std::collections::BTreeMap<String, String>>,
+}
+
+/// Names of the claims of an OpenID Connect provider (`medal_oauth_type: oidc`) that contain the user data.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct OauthClaimMapping {
+    pub foreign_id: Option<String>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub gender: Option<String>,
+    pub role: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
@@ -39,10 +53,19 @@ pub struct Config {
     pub oauth_providers: Option<Vec<OauthProvider>>,
     pub database_file: Option<PathBuf>,
     pub database_url: Option<String>,
+    pub database_pool_size: Option<usize>,
+    pub sqlite_readers: Option<usize>,
     pub template: Option<String>,
+    pub default_language: Option<String>,
+    pub grades: Option<Vec<::grades::GradeDefinition>>,
+    pub login_sheets: Option<::login_sheets::LoginSheetConfig>,
+    pub login_link_secret: Option<String>,
+    pub login_link_validity_days: Option<i64>,
     pub no_contest_scan: Option<bool>,
     pub open_browser: Option<bool>,
     pub cookie_signing_secret: Option<String>,
+    pub session_backend: Option<String>,
+    pub redis_url: Option<String>,
     pub disable_results_page: Option<bool>,
     pub enable_password_login: Option<bool>,
     pub require_sex: Option<bool>,
@@ -53,8 +76,19 @@ pub struct Config {
     pub template_params: Option<::std::collections::BTreeMap<String, serde_json::Value>>,
     pub only_contest_scan: Option<bool>,
     pub reset_admin_pw: Option<bool>,
+    pub check_tasks: Option<bool>,
     pub log_timing: Option<bool>,
     pub auto_save_interval: Option<u64>,
+    pub validation_interval: Option<u64>,
+    pub validation_timeout: Option<u64>,
+    pub contest_rescan_interval: Option<u64>,
+    pub mail_from: Option<String>,
+    pub mail_maildir: Option<String>,
+    pub smtp_server: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub smtp_security: Option<String>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
 }
 
 #[derive(StructOpt, Debug)]
@@ -92,6 +126,10 @@ struct Opt {
     #[structopt(short = "s", long = "only-contest-scan")]
     pub onlycontestscan: bool,
 
+    /// Check contest and task definitions for problems without starting medal or touching the database
+    #[structopt(long = "check-tasks")]
+    pub checktasks: bool,
+
     /// Automatically open medal in the default browser
     #[structopt(short = "b", long = "browser")]
     pub openbrowser: bool,
@@ -178,6 +216,12 @@ pub fn read_config_from_file(file: &Path) -> Config {
     if config.auto_save_interval.is_none() {
         config.auto_save_interval = Some(10)
     }
+    if config.validation_interval.is_none() {
+        config.validation_interval = Some(5)
+    }
+    if config.validation_timeout.is_none() {
+        config.validation_timeout = Some(10)
+    }
 
     println!("OAuth providers will be told to redirect to {}", config.self_url.as_ref().unwrap());
 
@@ -216,6 +260,7 @@ pub fn get_config() -> Config {
     merge_flag(&mut config.enable_password_login, opt.enablepasswordlogin);
     merge_flag(&mut config.only_contest_scan, opt.onlycontestscan);
     merge_flag(&mut config.reset_admin_pw, opt.resetadminpw);
+    merge_flag(&mut config.check_tasks, opt.checktasks);
     merge_flag(&mut config.log_timing, opt.logtiming);
 
     if let Some(template_params) = &mut config.template_params {
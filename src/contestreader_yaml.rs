@@ -13,7 +13,11 @@
 \*  <http://www.gnu.org/licenses/>.                                                                                  */
 
 use db_objects::{Contest, Task, Taskgroup};
+use qualification::QualificationRule;
+use ranking::TieBreak;
 
+use base64;
+use serde_json;
 use serde_yaml;
 use std::path::Path;
 
@@ -44,6 +48,10 @@ struct ContestYaml {
 
     tags: Option<Vec<String>>,
 
+    tie_breaking: Option<Vec<String>>,
+    public_scoreboard: Option<bool>,
+    qualification: Option<Vec<QualificationRule>>,
+
     tasks: Option<serde_yaml::Mapping>,
 }
 
@@ -58,6 +66,8 @@ struct TaskYaml {
     image: Option<String>,
     tags: Option<Vec<String>>,
     languages: Option<Vec<String>>,
+
+    checker: Option<String>,
 }
 
 use self::time::{strptime, Timespec};
@@ -90,6 +100,14 @@ fn parse_contest_yaml(content: &str, filename: &str, directory: &str) -> Option<
         config.review_start.map(|x| parse_timespec(x, "review_start", directory, filename));
     let review_end: Option<Timespec> = config.review_end.map(|x| parse_timespec(x, "review_end", directory, filename));
 
+    if let Some(ref tie_breaking) = config.tie_breaking {
+        for name in tie_breaking {
+            if TieBreak::from_name(name).is_none() {
+                panic!("Unknown tie breaking rule '{}' in {}{}", name, directory, filename)
+            }
+        }
+    }
+
     let review_start = if review_end.is_none() {
         review_start
     } else if let Some(end) = end {
@@ -116,7 +134,7 @@ fn parse_contest_yaml(content: &str, filename: &str, directory: &str) -> Option<
                   positionalnumber: config.position,
                   protected: config.protected.unwrap_or(false),
                   requires_login: config.requires_login,
-                  // Consumed by `let required_contests = contest.requires_contest.as_ref()?.split(',');` in core.rs
+                  // Consumed by `let required_contests = contest.requires_contest.as_ref()?.split(',');` in qualification.rs
                   requires_contest: config.requires_contest.map(|list| list.join(",")),
                   secret: config.secret,
                   message: config.message,
@@ -124,6 +142,10 @@ fn parse_contest_yaml(content: &str, filename: &str, directory: &str) -> Option<
                   language: config.language.clone(),
                   category: config.category,
                   standalone_task: None,
+                  tie_breaking: config.tie_breaking.map(|list| list.join(",")),
+                  public_scoreboard: config.public_scoreboard,
+                  // Consumed by `serde_json::from_str` in qualification.rs
+                  qualification: config.qualification.map(|rules| serde_json::to_string(&rules).unwrap()),
                   tags: config.tags.unwrap_or_else(Vec::new),
                   taskgroups: Vec::new() };
     // TODO: Timeparsing should fail more pleasantly (-> Panic, thus shows message)
@@ -160,7 +182,12 @@ fn parse_contest_yaml(content: &str, filename: &str, directory: &str) -> Option<
                             {
                                 stars = cstars.as_u64().unwrap() as i32;
                             }
-                            let task = Task::new(taskdir, config.language.clone(), stars);
+                            let mut task = Task::new(taskdir, config.language.clone(), stars);
+                            if let Some(serde_yaml::Value::String(checker)) =
+                                taskinfo.get(&serde_yaml::Value::String("checker".to_string()))
+                            {
+                                task.checker = Some(checker.clone());
+                            }
                             taskgroup.tasks.push(task);
                             stars += 1;
                         } else {
@@ -227,13 +254,17 @@ fn parse_task_yaml(content: &str, filename: &str, directory: &str) -> Result<Vec
                                     language: Some(language.clone()),
                                     category: None,
                                     standalone_task: Some(true),
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: config.tags.clone().unwrap_or_else(Vec::new),
                                     taskgroups: Vec::new() };
 
         let mut taskgroup = Taskgroup::new(name, None);
         let stars = 0;
         let taskdir = ".".to_string();
-        let task = Task::new(taskdir, Some(language), stars);
+        let mut task = Task::new(taskdir, Some(language), stars);
+        task.checker = config.checker.clone();
         taskgroup.tasks.push(task);
         contest.taskgroups.push(taskgroup);
 
@@ -243,25 +274,9 @@ fn parse_task_yaml(content: &str, filename: &str, directory: &str) -> Result<Vec
     Ok(contests)
 }
 
-fn read_task_or_contest(p: &Path) -> Option<Vec<Contest>> {
-    use std::fs::File;
-    use std::io::Read;
-
-    let mut file = File::open(p).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).ok()?;
-
-    let filename: &str = p.file_name().to_owned()?.to_str()?;
-
-    if filename == "task.yaml" {
-        parse_task_yaml(&contents, filename, &format!("{}/", p.parent().unwrap().to_str()?)).ok()
-    } else {
-        parse_contest_yaml(&contents, filename, &format!("{}/", p.parent().unwrap().to_str()?))
-    }
-}
-
-pub fn get_all_contest_info(task_dir: &str) -> Vec<Contest> {
-    fn walk_me_recursively(p: &Path, contests: &mut Vec<Contest>) {
+/// Call `f` for every YAML file below `task_dir`, in alphabetical order
+fn walk_yaml_files(task_dir: &str, f: &mut dyn FnMut(&Path)) {
+    fn walk_me_recursively(p: &Path, f: &mut dyn FnMut(&Path)) {
         if let Ok(paths) = std::fs::read_dir(p) {
             {
                 use std::io::Write;
@@ -272,27 +287,421 @@ pub fn get_all_contest_info(task_dir: &str) -> Vec<Contest> {
             paths.sort_by_key(|dir| dir.path());
             for path in paths {
                 let p = path.path();
-                walk_me_recursively(&p, contests);
+                walk_me_recursively(&p, f);
             }
         }
 
         let filename = p.file_name().unwrap().to_string_lossy().to_string();
         if filename.ends_with(".yaml") {
-            read_task_or_contest(p).as_mut().map(|cs| contests.append(cs));
+            f(p);
         };
     }
 
-    let mut contests = Vec::new();
     match std::fs::read_dir(task_dir) {
         Err(why) => eprintln!("Error opening tasks directory! {:?}", why.kind()),
         Ok(paths) => {
             for path in paths {
-                walk_me_recursively(&path.unwrap().path(), &mut contests);
+                walk_me_recursively(&path.unwrap().path(), f);
             }
         }
     };
+}
+
+/// A contest or task file found in the task directory
+pub struct ContestFile {
+    /// Directory of the file, ending with '/'
+    pub location: String,
+    pub filename: String,
+    /// Hash of the file content to detect changes
+    pub hash: String,
+    content: String,
+}
 
-    contests
+impl ContestFile {
+    fn read(p: &Path) -> Option<ContestFile> {
+        use sha2::{Digest, Sha256};
+
+        let content = match std::fs::read_to_string(p) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Could not read {}: {}", p.display(), e);
+                return None;
+            }
+        };
+        let mut hasher = Sha256::default();
+        hasher.input(content.as_bytes());
+
+        Some(ContestFile { location: format!("{}/", p.parent()?.to_str()?),
+                           filename: p.file_name()?.to_str()?.to_string(),
+                           hash: base64::encode(hasher.result()),
+                           content })
+    }
+
+    pub fn path(&self) -> String { format!("{}{}", self.location, self.filename) }
+
+    /// Whether `contest` has been read from this file
+    pub fn contains(&self, contest: &Contest) -> bool { is_defined_in(contest, &self.location, &self.filename) }
+
+    /// Read all contests defined in this file. If the file contains errors, nothing is read and the errors are
+    /// returned instead.
+    pub fn read_contests(&self) -> Result<Vec<Contest>, Vec<Problem>> {
+        let (checker, _) = check_file(self.path(), &self.filename, &self.location, &self.content);
+        let errors: Vec<Problem> = checker.problems.into_iter().filter(|p| p.severity == Severity::Error).collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        if self.filename == "task.yaml" {
+            Ok(parse_task_yaml(&self.content, &self.filename, &self.location).unwrap_or_default())
+        } else {
+            Ok(parse_contest_yaml(&self.content, &self.filename, &self.location).unwrap_or_default())
+        }
+    }
+}
+
+/// Whether `contest` has been read from the file `filename` in `location`. A `task.yaml` defines one contest per
+/// language.
+pub fn is_defined_in(contest: &Contest, location: &str, filename: &str) -> bool {
+    contest.location == location
+    && (contest.filename == filename || filename == "task.yaml" && contest.filename.ends_with("_task.yaml"))
+}
+
+/// Read and hash all contest and task files below `task_dir`
+pub fn get_all_contest_files(task_dir: &str) -> Vec<ContestFile> {
+    let mut files = Vec::new();
+    walk_yaml_files(task_dir, &mut |p| files.extend(ContestFile::read(p)));
+
+    files
+}
+
+const CONTEST_KEYS: &[&str] = &["name",
+                                "participation_start",
+                                "participation_end",
+                                "review_start",
+                                "review_end",
+                                "duration_minutes",
+                                "public_listing",
+                                "protected",
+                                "requires_login",
+                                "requires_contest",
+                                "secret",
+                                "message",
+                                "image",
+                                "language",
+                                "category",
+                                "min_grade",
+                                "max_grade",
+                                "position",
+                                "tags",
+                                "tie_breaking",
+                                "public_scoreboard",
+                                "qualification",
+                                "tasks"];
+const CONTEST_TASK_KEYS: &[&str] = &["stars", "checker"];
+const TASK_KEYS: &[&str] =
+    &["name", "standalone", "public_listing", "position", "image", "tags", "languages", "checker"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    /// The file can not be loaded or is loaded differently than intended
+    Error,
+    /// The file can be loaded, but probably contains a mistake
+    Warning,
+}
+
+/// A problem in a contest or task definition found by `check_all_contest_info`
+#[derive(Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub file: String,
+    /// Line in `file`, starting at 1, if the problem can be attributed to a line
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.file, line, severity, self.message),
+            None => write!(f, "{}: {}: {}", self.file, severity, self.message),
+        }
+    }
+}
+
+/// Collects the problems of a single file
+struct FileChecker<'a> {
+    file: String,
+    content: &'a str,
+    problems: Vec<Problem>,
+}
+
+impl<'a> FileChecker<'a> {
+    fn report(&mut self, severity: Severity, line: Option<usize>, message: String) {
+        self.problems.push(Problem { severity, file: self.file.clone(), line, message });
+    }
+
+    fn error(&mut self, line: Option<usize>, message: String) { self.report(Severity::Error, line, message) }
+
+    fn warning(&mut self, line: Option<usize>, message: String) { self.report(Severity::Warning, line, message) }
+
+    /// Line of the first mapping entry with key `key`
+    fn line_of_key(&self, key: &str) -> Option<usize> {
+        let key = format!("{}:", key);
+        self.content.lines().position(|line| line.trim_start().starts_with(&key)).map(|n| n + 1)
+    }
+
+    /// Line of the first occurrence of `text` as a (possibly quoted) YAML scalar
+    fn line_of(&self, text: &str) -> Option<usize> {
+        let is_separator = |c: char| c.is_whitespace() || ":\"',[]{}".contains(c);
+        self.content.lines().position(|line| line.split(is_separator).any(|token| token == text)).map(|n| n + 1)
+    }
+
+    /// Parse the file and check that all keys of the top level mapping are in `known_keys`
+    fn check_keys(&mut self, known_keys: &[&str]) -> Option<serde_yaml::Mapping> {
+        let mapping = match serde_yaml::from_str::<serde_yaml::Value>(self.content) {
+            Ok(serde_yaml::Value::Mapping(mapping)) => mapping,
+            Ok(_) => {
+                self.error(None, "file does not contain a YAML mapping".to_string());
+                return None;
+            }
+            Err(e) => {
+                self.error(e.location().map(|l| l.line()), format!("invalid YAML: {}", e));
+                return None;
+            }
+        };
+
+        for (key, _) in mapping.iter() {
+            match key {
+                serde_yaml::Value::String(key) if known_keys.contains(&key.as_str()) => (),
+                serde_yaml::Value::String(key) => {
+                    let line = self.line_of_key(key);
+                    self.warning(line, format!("unknown key '{}'", key))
+                }
+                _ => self.error(None, format!("invalid key {:?}", key)),
+            }
+        }
+        Some(mapping)
+    }
+
+    fn check_timespec(&mut self, time: &Option<String>, key: &str) -> Option<Timespec> {
+        let time = time.as_ref()?;
+        match strptime(time, "%FT%T%z") {
+            Ok(t) => Some(t.to_timespec()),
+            Err(e) => {
+                let line = self.line_of_key(key);
+                self.error(line, format!("'{}' could not be parsed as date ({}): {}", key, time, e));
+                None
+            }
+        }
+    }
+
+    /// Check that the date in `end_key` is not before the date in `start_key`
+    fn check_window(&mut self, start: Option<Timespec>, end: Option<Timespec>, start_key: &str, end_key: &str) {
+        if let (Some(start), Some(end)) = (start, end) {
+            if end < start {
+                let line = self.line_of_key(end_key);
+                self.error(line, format!("'{}' is before '{}'", end_key, start_key));
+            }
+        }
+    }
+
+    fn check_contest_reference(&mut self, directory: &str, filename: &str, key: &str) {
+        if !Path::new(directory).join(filename).is_file() {
+            let line = self.line_of(filename).or_else(|| self.line_of_key(key));
+            self.error(line, format!("'{}' references the nonexistent contest file '{}'", key, filename));
+        }
+    }
+
+    fn check_task_directory(&mut self, directory: &str, taskdir: &str) {
+        if !Path::new(directory).join(taskdir).is_dir() {
+            let line = self.line_of(taskdir);
+            self.error(line, format!("task directory '{}' does not exist", taskdir));
+        }
+    }
+}
+
+/// Check a contest file like `parse_contest_yaml` would load it. Returns the position of the contest, if any.
+fn check_contest_yaml(checker: &mut FileChecker, directory: &str) -> Option<i32> {
+    checker.check_keys(CONTEST_KEYS)?;
+
+    let config: ContestYaml = match serde_yaml::from_str(checker.content) {
+        Ok(config) => config,
+        Err(e) => {
+            checker.error(e.location().map(|l| l.line()), format!("invalid contest definition: {}", e));
+            return None;
+        }
+    };
+
+    if config.name.is_none() {
+        checker.error(None, "'name' missing".to_string());
+    }
+    if config.duration_minutes.is_none() {
+        checker.error(None, "'duration_minutes' missing".to_string());
+    }
+
+    let start = checker.check_timespec(&config.participation_start, "participation_start");
+    let end = checker.check_timespec(&config.participation_end, "participation_end");
+    let review_start = checker.check_timespec(&config.review_start, "review_start");
+    let review_end = checker.check_timespec(&config.review_end, "review_end");
+
+    checker.check_window(start, end, "participation_start", "participation_end");
+    // Same default as in `parse_contest_yaml`
+    let review_start_key = if review_start.is_none() && end.is_some() { "participation_end" } else { "review_start" };
+    checker.check_window(review_start.or(end), review_end, review_start_key, "review_end");
+
+    for name in config.tie_breaking.iter().flatten() {
+        if TieBreak::from_name(name).is_none() {
+            let line = checker.line_of(name);
+            checker.error(line, format!("unknown tie breaking rule '{}'", name));
+        }
+    }
+
+    for filename in config.requires_contest.iter().flatten() {
+        checker.check_contest_reference(directory, filename, "requires_contest");
+    }
+    for rule in config.qualification.iter().flatten() {
+        checker.check_contest_reference(directory, &rule.contest, "qualification");
+    }
+
+    let tasks = match config.tasks {
+        Some(tasks) => tasks,
+        None => {
+            checker.warning(None, "'tasks' missing, the file is ignored".to_string());
+            return None;
+        }
+    };
+
+    for (name, info) in tasks {
+        let name = match name {
+            serde_yaml::Value::String(name) => name,
+            name => {
+                checker.error(None, format!("invalid taskgroup name {:?}", name));
+                continue;
+            }
+        };
+        match info {
+            serde_yaml::Value::String(taskdir) => checker.check_task_directory(directory, &taskdir),
+            serde_yaml::Value::Sequence(taskdirs) => {
+                for taskdir in taskdirs {
+                    if let serde_yaml::Value::String(taskdir) = taskdir {
+                        checker.check_task_directory(directory, &taskdir);
+                    } else {
+                        let line = checker.line_of_key(&name);
+                        checker.error(line, format!("invalid task directory {:?} in taskgroup '{}'", taskdir, name));
+                    }
+                }
+            }
+            serde_yaml::Value::Mapping(taskdirs) => {
+                for (taskdir, taskinfo) in taskdirs {
+                    if let (serde_yaml::Value::String(taskdir), serde_yaml::Value::Mapping(taskinfo)) =
+                        (&taskdir, &taskinfo)
+                    {
+                        checker.check_task_directory(directory, taskdir);
+                        for (key, _) in taskinfo.iter() {
+                            if let serde_yaml::Value::String(key) = key {
+                                if !CONTEST_TASK_KEYS.contains(&key.as_str()) {
+                                    let line = checker.line_of(taskdir);
+                                    checker.warning(line, format!("unknown key '{}' for task '{}'", key, taskdir));
+                                }
+                            }
+                        }
+                    } else {
+                        let line = checker.line_of_key(&name);
+                        checker.error(line, format!("invalid task {:?} in taskgroup '{}'", taskdir, name));
+                    }
+                }
+            }
+            _ => {
+                let line = checker.line_of_key(&name);
+                checker.error(line, format!("invalid tasks in taskgroup '{}'", name));
+            }
+        }
+    }
+
+    config.position
+}
+
+/// Check a task file like `parse_task_yaml` would load it. Returns the position of the task, if any.
+fn check_task_yaml(checker: &mut FileChecker) -> Option<i32> {
+    checker.check_keys(TASK_KEYS)?;
+
+    let config: TaskYaml = match serde_yaml::from_str(checker.content) {
+        Ok(config) => config,
+        Err(e) => {
+            checker.error(e.location().map(|l| l.line()), format!("invalid task definition: {}", e));
+            return None;
+        }
+    };
+
+    if config.standalone != Some(true) {
+        checker.warning(None, "'standalone' is not set to true, the file is ignored".to_string());
+        return None;
+    }
+    if config.languages.map(|languages| languages.is_empty()).unwrap_or(true) {
+        let line = checker.line_of_key("languages");
+        checker.error(line, "'languages' missing or empty, the file is ignored".to_string());
+        return None;
+    }
+    if config.name.is_none() {
+        checker.error(None, "'name' missing".to_string());
+    }
+
+    config.position
+}
+
+/// Check a contest or task file. Returns the position of the contest or task, if any.
+fn check_file<'a>(file: String, filename: &str, directory: &str, content: &'a str) -> (FileChecker<'a>, Option<i32>) {
+    let mut checker = FileChecker { file, content, problems: Vec::new() };
+    let position = if filename == "task.yaml" {
+        check_task_yaml(&mut checker)
+    } else {
+        check_contest_yaml(&mut checker, directory)
+    };
+
+    (checker, position)
+}
+
+/// Check all contest and task definitions below `task_dir` without loading them.
+///
+/// In contrast to loading the files this does not stop at the first problem, but reports every problem found.
+pub fn check_all_contest_info(task_dir: &str) -> Vec<Problem> {
+    use std::collections::BTreeMap;
+
+    let mut problems = Vec::new();
+    // Position → first file with this position
+    let mut positions: BTreeMap<i32, String> = BTreeMap::new();
+
+    walk_yaml_files(task_dir, &mut |p| {
+        let file = p.to_string_lossy().to_string();
+        let content = match std::fs::read_to_string(p) {
+            Ok(content) => content,
+            Err(e) => {
+                problems.push(Problem { severity: Severity::Error, file, line: None, message: e.to_string() });
+                return;
+            }
+        };
+        let directory = format!("{}/", p.parent().unwrap().to_string_lossy());
+        let filename = p.file_name().unwrap().to_string_lossy();
+
+        let (mut checker, position) = check_file(file, &filename, &directory, &content);
+        if let Some(position) = position {
+            if let Some(other) = positions.get(&position) {
+                let line = checker.line_of_key("position");
+                let message = format!("position {} is also used in {}", position, other);
+                checker.warning(line, message);
+            } else {
+                positions.insert(position, checker.file.clone());
+            }
+        }
+
+        problems.append(&mut checker.problems);
+    });
+
+    problems
 }
 
 #[test]
@@ -336,3 +745,98 @@ tasks: {}
     // GMT 	Thu Mar 31 2022 22:00:00 GMT+0000
     // Your Time Zone 	Fri Apr 01 2022 00:00:00 GMT+0200 (Mitteleuropäische Sommerzeit)
 }
+
+#[test]
+fn parse_contest_yaml_ranking() {
+    let contest_file_contents = r#"
+name: "JwInf 2020 Runde 1: Jgst. 3 – 6"
+duration_minutes: 60
+public_scoreboard: true
+tie_breaking:
+  - earliest_submission
+  - fewest_attempts
+
+tasks: {}
+"#;
+
+    let contest = parse_contest_yaml(contest_file_contents, "", "").unwrap();
+    assert_eq!(contest[0].tie_breaking, Some("earliest_submission,fewest_attempts".to_string()));
+    assert_eq!(contest[0].public_scoreboard, Some(true));
+}
+
+#[test]
+fn parse_contest_yaml_qualification() {
+    let contest_file_contents = r#"
+name: "JwInf 2020 Runde 2"
+duration_minutes: 60
+qualification:
+  - contest: runde1.yaml
+    min_percentage: 60
+  - contest: runde1.yaml
+    top_per_grade: 10
+  - contest: runde1.yaml
+    annotation: "ja"
+
+tasks: {}
+"#;
+
+    let contest = parse_contest_yaml(contest_file_contents, "", "").unwrap();
+    let rules: Vec<QualificationRule> = serde_json::from_str(contest[0].qualification.as_ref().unwrap()).unwrap();
+    assert_eq!(rules.len(), 3);
+    assert_eq!(rules[0].min_percentage, Some(60));
+    assert_eq!(rules[1].top_per_grade, Some(10));
+    assert_eq!(rules[2].annotation, Some("ja".to_string()));
+    assert_eq!(rules[2].min_percentage, None);
+}
+
+#[test]
+fn check_all_contest_info_reports_problems() {
+    let task_dir = std::env::temp_dir().join(format!("medal-check-tasks-{}", std::process::id()));
+    let contest_dir = task_dir.join("jwinf");
+    std::fs::create_dir_all(contest_dir.join("task1")).unwrap();
+    std::fs::create_dir_all(contest_dir.join("standalone")).unwrap();
+
+    let round1_contents = r#"name: "Runde 1"
+duration_minutes: 60
+participation_start: "2022-01-01T00:00:00+01:00"
+participation_end: "2021-12-01T00:00:00+01:00"
+review_end: "2021-11-01T00:00:00+01:00"
+requires_contest: ["round0.yaml"]
+position: 3
+colour: blue
+tasks:
+  "Aufgabe 1": "task1"
+  "Aufgabe 2":
+    "task2":
+      stars: 2
+"#;
+    let round2_contents = r#"name: "Runde 2"
+participation_start: "2022-13-01T00:00:00+01:00"
+position: 3
+tasks:
+  "Aufgabe 1": "task1"
+"#;
+    let task_contents = r#"name: "Einzelaufgabe"
+standalone: true
+"#;
+    std::fs::write(contest_dir.join("round1.yaml"), round1_contents).unwrap();
+    std::fs::write(contest_dir.join("round2.yaml"), round2_contents).unwrap();
+    std::fs::write(contest_dir.join("standalone/task.yaml"), task_contents).unwrap();
+
+    let problems = check_all_contest_info(task_dir.to_str().unwrap());
+    std::fs::remove_dir_all(&task_dir).unwrap();
+
+    let problems: Vec<String> =
+        problems.iter().map(|p| p.to_string().replace(&format!("{}/", contest_dir.display()), "")).collect();
+    assert_eq!(problems,
+               vec!["round1.yaml:8: warning: unknown key 'colour'",
+                    "round1.yaml:4: error: 'participation_end' is before 'participation_start'",
+                    "round1.yaml:5: error: 'review_end' is before 'participation_end'",
+                    "round1.yaml:6: error: 'requires_contest' references the nonexistent contest file 'round0.yaml'",
+                    "round1.yaml:12: error: task directory 'task2' does not exist",
+                    "round2.yaml: error: 'duration_minutes' missing",
+                    "round2.yaml:2: error: 'participation_start' could not be parsed as date \
+                     (2022-13-01T00:00:00+01:00): Invalid month.",
+                    "round2.yaml:3: warning: position 3 is also used in round1.yaml",
+                    "standalone/task.yaml: error: 'languages' missing or empty, the file is ignored"]);
+}
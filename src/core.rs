@@ -15,13 +15,30 @@
 use time;
 
 use config::OauthProvider;
+use contestreader_yaml;
 use db_conn::MedalConnection;
 #[cfg(feature = "signup")]
 use db_conn::SignupResult;
 use db_objects::OptionSession;
 use db_objects::SessionUser;
-use db_objects::{Contest, Grade, Group, Participation, Submission, Taskgroup};
+use db_objects::{AuditLogEntry, Contest, Grade, Group, Participation, Submission, Taskgroup};
+use diff;
+use export;
+use grades;
+use group_import;
 use helpers;
+use i18n;
+use login_links::LoginLinkSigner;
+use login_sheets;
+use login_sheets::{LoginSheetConfig, LoginSheetMember, LoginSheets};
+use mail::Mailer;
+use metrics;
+use permissions::{authorize, is_authorized, Action, Resource, Role};
+use qualification;
+use ranking;
+use ranking::{RankingEntry, RankingFilter, TieBreak};
+use reporting;
+use statistics;
 use webfw_iron::{json_val, to_json};
 
 #[derive(Serialize, Deserialize)]
@@ -69,6 +86,7 @@ pub enum MedalError {
 
 pub struct LoginInfo {
     pub password_login: bool,
+    pub password_reset: bool,
     pub self_url: Option<String>,
     pub oauth_providers: Option<Vec<OauthProvider>>,
 }
@@ -101,6 +119,7 @@ fn fill_user_data_prefix(session: &SessionUser, data: &mut json_val::Map<String,
 fn fill_user_data(session: &SessionUser, data: &mut json_val::Map<String, serde_json::Value>) {
     fill_user_data_prefix(session, data, "");
 
+    data.insert("admin_area".to_string(), to_json(&is_authorized(session, Action::ViewAdminArea, &Resource::Site)));
     data.insert("parent".to_string(), to_json(&"base"));
     data.insert("medal_version".to_string(), to_json(&env!("CARGO_PKG_VERSION")));
 }
@@ -109,9 +128,12 @@ fn fill_oauth_data(login_info: LoginInfo, data: &mut json_val::Map<String, serde
     let mut oauth_links: Vec<(String, String, String)> = Vec::new();
     if let Some(oauth_providers) = login_info.oauth_providers {
         for oauth_provider in oauth_providers {
+            // OpenID Connect logins are started by medal itself, since the state and PKCE challenge are generated
+            // per login. Therefore, the link points directly to the redirect url.
+            let url = if oauth_provider.medal_oauth_type == "oidc" { "" } else { &oauth_provider.url };
             oauth_links.push((oauth_provider.provider_id.to_owned(),
                               oauth_provider.login_link_text.to_owned(),
-                              oauth_provider.url.to_owned()));
+                              url.to_owned()));
         }
     }
 
@@ -119,21 +141,7 @@ fn fill_oauth_data(login_info: LoginInfo, data: &mut json_val::Map<String, serde
     data.insert("oauth_links".to_string(), to_json(&oauth_links));
 
     data.insert("password_login".to_string(), to_json(&login_info.password_login));
-}
-
-fn grade_to_string(grade: i32) -> String {
-    match grade {
-        0 => "Noch kein Schüler".to_string(),
-        n @ 1..=10 => format!("{}", n),
-        11 => "11 (G8)".to_string(),
-        12 => "12 (G8)".to_string(),
-        111 => "11 (G9)".to_string(),
-        112 => "12 (G9)".to_string(),
-        113 => "13 (G9)".to_string(),
-        114 => "Berufsschule".to_string(),
-        255 => "Kein Schüler mehr".to_string(),
-        _ => "?".to_string(),
-    }
+    data.insert("password_reset".to_string(), to_json(&login_info.password_reset));
 }
 
 pub fn index<T: MedalConnection>(conn: &T, session_token: Option<String>, login_info: LoginInfo) -> MedalValueResult {
@@ -181,6 +189,18 @@ pub fn status<T: MedalConnection>(conn: &T, config_secret: Option<String>, given
     }
 }
 
+/// Metrics of the server in the Prometheus text format, protected by the same secret as `status`. Without a
+/// configured secret, the metrics are not available at all.
+pub fn metrics<T: MedalConnection>(conn: &T, config_secret: Option<String>, given_secret: Option<String>)
+                                   -> MedalResult<String> {
+    if config_secret.is_none() || config_secret != given_secret {
+        return Err(MedalError::AccessDenied);
+    }
+
+    let active_sessions = conn.get_active_session_count(time::get_time() - time::Duration::hours(1));
+    Ok(metrics::render(active_sessions))
+}
+
 pub fn debug<T: MedalConnection>(conn: &T, session_token: Option<String>)
                                  -> (String, json_val::Map<String, json_val::Value>) {
     let mut data = json_val::Map::new();
@@ -282,12 +302,12 @@ pub fn show_contests<T: MedalConnection>(conn: &T, session_token: &str, login_in
     data.insert("contests_contest".to_string(), to_json(&contests_contest));
     data.insert("contests_challenge".to_string(), to_json(&contests_challenge));
 
-    data.insert("contests_training_header".to_string(), to_json(&"Trainingsaufgaben"));
-    data.insert("contests_contest_header".to_string(), to_json(&"Wettbewerbe"));
-    data.insert("contests_challenge_header".to_string(), to_json(&"Herausforderungen"));
+    data.insert("contests_training_header".to_string(), to_json(&i18n::translate("contests-training")));
+    data.insert("contests_contest_header".to_string(), to_json(&i18n::translate("contests-contest")));
+    data.insert("contests_challenge_header".to_string(), to_json(&i18n::translate("contests-challenge")));
 
     if visibility == ContestVisibility::StandaloneTask {
-        data.insert("contests_training_header".to_string(), to_json(&"Einzelne Aufgaben ohne Wertung"));
+        data.insert("contests_training_header".to_string(), to_json(&i18n::translate("contests-standalone-tasks")));
     }
 
     Ok(("contests".to_owned(), data))
@@ -325,29 +345,18 @@ pub struct ContestStartConstraints {
     pub grade_matching: bool,
 }
 
-fn check_contest_qualification<T: MedalConnection>(conn: &T, session: &SessionUser, contest: &Contest) -> Option<bool> {
-    // Produced by `config.requires_contest.map(|list| list.join(",")),` in contestreader_yaml.rs
-    let required_contests = contest.requires_contest.as_ref()?.split(',');
-
-    for req_contest in required_contests {
-        if conn.has_participation_by_contest_file(session.id, &contest.location, req_contest) {
-            return Some(true);
-        }
-    }
-
-    Some(false)
-}
-
 fn check_contest_constraints(session: &SessionUser, contest: &Contest) -> ContestStartConstraints {
     let now = time::get_time();
-    let student_grade = session.grade % 100 - if session.grade / 100 == 1 { 1 } else { 0 };
+    let grades = grades::scale();
 
     let contest_not_begun = contest.start.map(|start| now < start).unwrap_or(false);
     let contest_over = contest.end.map(|end| now > end).unwrap_or(false);
-    let grade_too_low =
-        contest.min_grade.map(|min_grade| student_grade < min_grade && !session.is_teacher).unwrap_or(false);
-    let grade_too_high =
-        contest.max_grade.map(|max_grade| student_grade > max_grade && !session.is_teacher).unwrap_or(false);
+    let grade_too_low = contest.min_grade
+                               .map(|min_grade| grades.is_below(session.grade, min_grade) && !session.is_teacher)
+                               .unwrap_or(false);
+    let grade_too_high = contest.max_grade
+                                .map(|max_grade| grades.is_above(session.grade, max_grade) && !session.is_teacher)
+                                .unwrap_or(false);
 
     let contest_running = !contest_not_begun && !contest_over;
     let grade_matching = !grade_too_low && !grade_too_high;
@@ -370,6 +379,8 @@ pub struct ContestTimeInfo {
     pub left_sec: i64,
     pub has_timelimit: bool,
     pub is_time_left: bool,
+    pub is_paused: bool,
+    pub extra_time: i32,
     pub exempt_from_timelimit: bool,
     pub can_still_compete: bool,
     pub review_has_timelimit: bool,
@@ -387,24 +398,41 @@ pub struct ContestTimeInfo {
     pub until_review_end_min: i64,
 }
 
+/// Submissions arriving this many seconds after the time ran out are still accepted, since the timer of the
+/// browser and the server are never exactly in sync
+const SUBMISSION_GRACE_SECS: i64 = 10;
+
+/// Seconds of the contest time that have been used up in `participation` at `now`. Pauses do not count.
+fn participation_passed_secs(participation: &Participation, now: time::Timespec) -> i64 {
+    let until = participation.paused_since.unwrap_or(now);
+    until.sec - participation.start.sec - i64::from(participation.paused_secs)
+}
+
+/// Seconds left in `participation` at `now` for a contest lasting `duration` minutes, including extra time
+fn participation_left_secs(duration: i32, participation: &Participation, now: time::Timespec) -> i64 {
+    (i64::from(duration) + i64::from(participation.extra_time)) * 60 - participation_passed_secs(participation, now)
+}
+
 fn check_contest_time_left(session: &SessionUser, contest: &Contest, participation: &Participation) -> ContestTimeInfo {
     let now = time::get_time();
-    let passed_secs_total = now.sec - participation.start.sec;
+    let passed_secs_total = participation_passed_secs(participation, now);
     if passed_secs_total < 0 {
         // Handle inconsistent server time
     }
-    let left_secs_total = i64::from(contest.duration) * 60 - passed_secs_total;
+    let left_secs_total = participation_left_secs(contest.duration, participation, now);
 
     let is_time_left = contest.duration == 0 || left_secs_total >= 0;
+    let is_paused = participation.paused_since.is_some();
     let exempt_from_timelimit = session.is_teacher() || session.is_admin();
 
-    let can_still_compete = is_time_left || exempt_from_timelimit;
+    let can_still_compete = (is_time_left && !is_paused) || exempt_from_timelimit;
 
     let review_has_timelimit = contest.review_end.is_none() && contest.review_start.is_some();
     let has_future_review = (contest.review_start.is_some() || contest.review_end.is_some())
                             && contest.review_end.map(|end| end > now).unwrap_or(true);
     let has_review_end = contest.review_end.is_some();
     let is_review = !can_still_compete
+                    && !is_paused
                     && (contest.review_start.is_some() || contest.review_end.is_some())
                     && contest.review_start.map(|start| now >= start).unwrap_or(true)
                     && contest.review_end.map(|end| now <= end).unwrap_or(true);
@@ -420,6 +448,8 @@ fn check_contest_time_left(session: &SessionUser, contest: &Contest, participati
                       left_sec: left_secs_total % 60,
                       has_timelimit: contest.duration != 0,
                       is_time_left,
+                      is_paused,
+                      extra_time: participation.extra_time,
                       exempt_from_timelimit,
                       can_still_compete,
                       review_has_timelimit,
@@ -466,6 +496,7 @@ pub fn show_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_token
     data.insert("contest".to_string(), to_json(&ci));
     data.insert("title".to_string(), to_json(&ci.name));
     data.insert("message".to_string(), to_json(&contest.message));
+    data.insert("public_scoreboard".to_string(), to_json(&contest.public_scoreboard.unwrap_or(false)));
     fill_oauth_data(login_info, &mut data);
 
     if secret.is_some() && secret != contest.secret {
@@ -485,7 +516,8 @@ pub fn show_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_token
     }
 
     let constraints = check_contest_constraints(&session, &contest);
-    let is_qualified = check_contest_qualification(conn, &session, &contest).unwrap_or(true);
+    let qualification = qualification::check_qualification(conn, &session, &contest);
+    let is_qualified = qualification.as_ref().map(|q| q.is_qualified).unwrap_or(true);
 
     let has_tasks = contest.taskgroups.len() > 0;
     let can_start = constraints.contest_running
@@ -498,6 +530,7 @@ pub fn show_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_token
 
     data.insert("constraints".to_string(), to_json(&constraints));
     data.insert("is_qualified".to_string(), to_json(&is_qualified));
+    data.insert("qualification".to_string(), to_json(&qualification));
     data.insert("has_duration".to_string(), to_json(&has_duration));
     data.insert("can_start".to_string(), to_json(&can_start));
     data.insert("has_tasks".to_string(), to_json(&has_tasks));
@@ -515,7 +548,12 @@ pub fn show_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_token
        && contest.requires_login != Some(true)
     {
         conn.new_participation(&session_token, contest_id).map_err(|_| MedalError::AccessDenied)?;
-        opt_part = Some(Participation { contest: contest_id, user: session.id, start: time::get_time() });
+        opt_part = Some(Participation { contest: contest_id,
+                                        user: session.id,
+                                        start: time::get_time(),
+                                        extra_time: 0,
+                                        paused_secs: 0,
+                                        paused_since: None });
     }
 
     let now = time::get_time();
@@ -646,7 +684,7 @@ pub fn show_contest_results<T: MedalConnection>(conn: &T, contest_id: i32, sessi
             groupresults.push(UserResults { firstname: user.firstname.unwrap_or_else(|| "–".to_string()),
                                             lastname: user.lastname.unwrap_or_else(|| "–".to_string()),
                                             user_id: user.id,
-                                            grade: grade_to_string(user.grade),
+                                            grade: grades::scale().name(user.grade),
                                             logincode: user.logincode.unwrap_or_else(|| "".to_string()),
                                             annotation: user.annotation.unwrap_or_else(|| "".to_string()),
                                             results: userresults });
@@ -678,6 +716,253 @@ pub fn show_contest_results<T: MedalConnection>(conn: &T, contest_id: i32, sessi
     Ok(("contestresults".to_owned(), data))
 }
 
+fn format_secs(secs: i64) -> String { format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60) }
+
+pub fn show_contest_group_statistics<T: MedalConnection>(conn: &T, contest_id: i32, group_id: i32,
+                                                         session_token: &str)
+                                                         -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    let group = conn.get_group_complete(group_id).ok_or(MedalError::UnknownId)?;
+    authorize(&session, Action::ViewParticipation, &group_resource(conn, &group))?;
+
+    let contest = conn.get_contest_by_id_complete(contest_id).ok_or(MedalError::UnknownId)?;
+
+    // The grades are loaded for the groups of the owner, so that co-teachers and staff can see them as well
+    let (_, groups_grades) = conn.get_contest_groups_grades(group.admin, contest_id);
+    let grades: Vec<Vec<Option<i32>>> = groups_grades.into_iter()
+                                                     .filter(|(g, _)| g.id == group.id)
+                                                     .flat_map(|(_, users)| users)
+                                                     .map(|(_, grades)| grades.iter().map(|g| g.grade).collect())
+                                                     .collect();
+
+    let taskgroups: Vec<(String, i32)> =
+        contest.taskgroups
+               .iter()
+               .map(|tg| (tg.name.clone(), tg.tasks.iter().map(|t| t.stars).max().unwrap_or(0)))
+               .collect();
+    let taskgroup_index: ::std::collections::BTreeMap<i32, usize> =
+        contest.taskgroups.iter().enumerate().filter_map(|(index, tg)| Some((tg.id?, index))).collect();
+    let first_submissions: Vec<(i32, usize, i64)> =
+        conn.get_first_submission_dates(group_id, contest_id)
+            .into_iter()
+            .filter_map(|(student, taskgroup, start, first)| {
+                Some((student, *taskgroup_index.get(&taskgroup)?, (first - start).num_seconds().max(0)))
+            })
+            .collect();
+
+    let statistics = statistics::group_statistics(&taskgroups, &grades, &first_submissions);
+
+    #[derive(Serialize)]
+    struct ReachedInfo {
+        stars: usize,
+        percent: usize,
+    }
+
+    #[derive(Serialize)]
+    struct TaskgroupStatisticsInfo {
+        name: String,
+        max_stars: i32,
+        average_stars: String,
+        not_attempted: usize,
+        reached: Vec<ReachedInfo>,
+        average_time: String,
+        is_hardest: bool,
+    }
+
+    let mut rows: Vec<TaskgroupStatisticsInfo> = Vec::new();
+    for (index, tg) in statistics.taskgroups.iter().enumerate() {
+        let reached = tg.reached_percent.iter().enumerate().map(|(i, &percent)| ReachedInfo { stars: i + 1, percent });
+        let average_time = tg.average_secs_to_first_submission.map(format_secs).unwrap_or_else(|| "–".to_string());
+        rows.push(TaskgroupStatisticsInfo { name: tg.name.clone(),
+                                            max_stars: tg.max_stars,
+                                            average_stars: format!("{:.1}", tg.average_stars),
+                                            not_attempted: tg.not_attempted,
+                                            reached: reached.collect(),
+                                            average_time,
+                                            is_hardest: statistics.hardest_taskgroup == Some(index) });
+    }
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    data.insert("contestname".to_string(), to_json(&contest.name));
+    data.insert("contest_id".to_string(), to_json(&contest_id));
+    data.insert("groupname".to_string(), to_json(&group.name));
+    data.insert("group_id".to_string(), to_json(&group_id));
+    data.insert("n_students".to_string(), to_json(&statistics.n_students));
+    data.insert("taskgroups".to_string(), to_json(&rows));
+    data.insert("average_time".to_string(), to_json(&statistics.average_secs_to_first_submission.map(format_secs)));
+    if let Some(index) = statistics.hardest_taskgroup {
+        data.insert("hardest_taskgroup".to_string(), to_json(&statistics.taskgroups[index].name));
+    }
+    // The charts are drawn from this JSON inside a <script> element, so `<` must not appear literally
+    let statistics_json = serde_json::to_string(&statistics).unwrap_or_default().replace('<', "\\u003c");
+    data.insert("statistics_json".to_string(), to_json(&statistics_json));
+
+    Ok(("contestresults_statistics".to_owned(), data))
+}
+
+#[derive(Serialize, Deserialize)]
+struct RankingRow {
+    rank: usize,
+    total: i32,
+    results: Vec<String>,
+    firstname: String,
+    lastname: String,
+    user_id: i32,
+    grade: String,
+    groupname: String,
+    group_id: Option<i32>,
+    school_id: String,
+    attempts: i32,
+    final_score_reached: String,
+}
+
+fn ranking_row(entry: RankingEntry) -> RankingRow {
+    let participant = entry.participant;
+    RankingRow { rank: entry.rank,
+                 total: entry.total,
+                 results: participant.grades
+                                     .iter()
+                                     .map(|grade| grade.map(|g| format!("{}", g)).unwrap_or_else(|| "–".to_string()))
+                                     .collect(),
+                 firstname: participant.user.firstname.unwrap_or_else(|| "–".to_string()),
+                 lastname: participant.user.lastname.unwrap_or_else(|| "–".to_string()),
+                 user_id: participant.user.id,
+                 grade: grades::scale().name(participant.user.grade),
+                 groupname: participant.group_name.unwrap_or_else(|| "–".to_string()),
+                 group_id: participant.group_id,
+                 school_id: participant.school_id.unwrap_or_default(),
+                 attempts: participant.attempts,
+                 final_score_reached:
+                     participant.final_score_reached
+                                .map(|t| self::time::strftime("%e. %b %Y, %H:%M:%S", &self::time::at(t)).unwrap())
+                                .unwrap_or_else(|| "–".to_string()) }
+}
+
+pub fn show_contest_ranking<T: MedalConnection>(conn: &T, contest_id: i32, session_token: &str,
+                                                filter: RankingFilter)
+                                                -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ViewRanking, &Resource::Site)?;
+
+    let contest = conn.get_contest_by_id(contest_id).ok_or(MedalError::UnknownId)?;
+    let (tasknames, participants) = conn.get_contest_ranking_data(contest_id);
+
+    // Teachers only get to see the ranking among the students of their own groups
+    let mut co_admins: ::std::collections::BTreeMap<i32, Vec<i32>> = ::std::collections::BTreeMap::new();
+    let participants: Vec<_> =
+        participants.into_iter()
+                    .filter(|participant| {
+                        let resource = match (participant.group_id, participant.group_admin) {
+                            (Some(group_id), Some(owner)) => {
+                                let co_admins = co_admins.entry(group_id)
+                                                         .or_insert_with(|| accepted_group_admins(conn, group_id))
+                                                         .clone();
+                                Resource::Group { owner, co_admins, school_id: participant.school_id.clone() }
+                            }
+                            _ => Resource::User { id: participant.user.id, school_id: None },
+                        };
+                        is_authorized(&session, Action::ViewParticipation, &resource)
+                    })
+                    .collect();
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    struct GroupOption {
+        id: i32,
+        name: String,
+        selected: bool,
+    }
+
+    let mut groups: Vec<GroupOption> = participants.iter()
+                                                   .filter_map(|participant| {
+                                                       Some(GroupOption { id: participant.group_id?,
+                                                                          name: participant.group_name.clone()?,
+                                                                          selected: participant.group_id
+                                                                                    == filter.group_id })
+                                                   })
+                                                   .collect();
+    groups.sort();
+    groups.dedup();
+
+    let tie_breaks = TieBreak::of_contest(&contest);
+    let ranking: Vec<RankingRow> =
+        ranking::compute_ranking(participants, &tie_breaks, &filter).into_iter().map(ranking_row).collect();
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    data.insert("taskname".to_string(), to_json(&tasknames));
+    data.insert("ranking".to_string(), to_json(&ranking));
+    data.insert("groups".to_string(), to_json(&groups));
+    data.insert("tie_breaking".to_string(), to_json(&contest.tie_breaking));
+    data.insert("has_earliest_submission".to_string(), to_json(&tie_breaks.contains(&TieBreak::EarliestSubmission)));
+    data.insert("has_fewest_attempts".to_string(), to_json(&tie_breaks.contains(&TieBreak::FewestAttempts)));
+    data.insert("public_scoreboard".to_string(), to_json(&contest.public_scoreboard.unwrap_or(false)));
+    data.insert("filter_grade".to_string(), to_json(&filter.grade));
+    data.insert("filter_school".to_string(), to_json(&filter.school_id));
+    data.insert("grades".to_string(), to_json(&grades::scale().options(filter.grade)));
+    if let Some(sex) = filter.sex {
+        data.insert(format!("sex_{}", sex), to_json(&"selected"));
+    }
+
+    data.insert("contestid".to_string(), to_json(&contest_id));
+    data.insert("contestname".to_string(), to_json(&contest.name));
+
+    Ok(("contestranking".to_owned(), data))
+}
+
+pub fn show_contest_scoreboard<T: MedalConnection>(conn: &T, contest_id: i32, session_token: Option<String>)
+                                                   -> MedalValueResult {
+    let contest = conn.get_contest_by_id(contest_id).ok_or(MedalError::UnknownId)?;
+    if !contest.public_scoreboard.unwrap_or(false) {
+        return Err(MedalError::AccessDenied);
+    }
+
+    let mut data = json_val::Map::new();
+    let own_id = match session_token.and_then(|token| conn.get_session(&token)) {
+        Some(session) => {
+            fill_user_data(&session, &mut data);
+            Some(session.id)
+        }
+        None => {
+            data.insert("parent".to_string(), to_json(&"base"));
+            None
+        }
+    };
+
+    #[derive(Serialize, Deserialize)]
+    struct ScoreboardRow {
+        rank: usize,
+        total: i32,
+        results: Vec<String>,
+        own: bool,
+    }
+
+    let (tasknames, participants) = conn.get_contest_ranking_data(contest_id);
+
+    // The public scoreboard is anonymized: Only the rank and the points are shown, participants can only recognize
+    // their own row
+    let scoreboard: Vec<ScoreboardRow> =
+        ranking::compute_ranking(participants, &TieBreak::of_contest(&contest), &RankingFilter::default())
+            .into_iter()
+            .filter(|entry| entry.total > 0)
+            .map(|entry| {
+                let own = Some(entry.participant.user.id) == own_id;
+                let row = ranking_row(entry);
+                ScoreboardRow { rank: row.rank, total: row.total, results: row.results, own }
+            })
+            .collect();
+
+    data.insert("taskname".to_string(), to_json(&tasknames));
+    data.insert("scoreboard".to_string(), to_json(&scoreboard));
+    data.insert("contestid".to_string(), to_json(&contest_id));
+    data.insert("contestname".to_string(), to_json(&contest.name));
+
+    Ok(("contestscoreboard".to_owned(), data))
+}
+
 pub fn start_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_token: &str, csrf_token: &str,
                                          secret: Option<String>)
                                          -> MedalResult<()> {
@@ -705,9 +990,9 @@ pub fn start_contest<T: MedalConnection>(conn: &T, contest_id: i32, session_toke
         return Err(MedalError::AccessDenied);
     }
 
-    let is_qualified = check_contest_qualification(conn, &session, &contest);
+    let qualification = qualification::check_qualification(conn, &session, &contest);
 
-    if is_qualified == Some(false) {
+    if qualification.map(|q| q.is_qualified) == Some(false) {
         return Err(MedalError::AccessDenied);
     }
 
@@ -730,7 +1015,7 @@ pub fn login<T: MedalConnection>(conn: &T, login_data: (String, String), login_i
         Ok(session_token) => Ok(session_token),
         Err(()) => {
             let mut data = json_val::Map::new();
-            data.insert("reason".to_string(), to_json(&"Login fehlgeschlagen. Bitte erneut versuchen.".to_string()));
+            data.insert("reason".to_string(), to_json(&i18n::translate("login-failed")));
             data.insert("username".to_string(), to_json(&username));
             data.insert("parent".to_string(), to_json(&"base"));
 
@@ -750,7 +1035,7 @@ pub fn login_with_code<T: MedalConnection>(
             Ok(session_token) => Ok(Err(session_token)),
             Err(()) => {
                 let mut data = json_val::Map::new();
-                data.insert("reason".to_string(), to_json(&"Kein gültiger Code. Bitte erneut versuchen.".to_string()));
+                data.insert("reason".to_string(), to_json(&i18n::translate("login-code-invalid")));
                 data.insert("code".to_string(), to_json(&code));
                 data.insert("parent".to_string(), to_json(&"base"));
 
@@ -762,12 +1047,70 @@ pub fn login_with_code<T: MedalConnection>(
     }
 }
 
+/// Log in with a signed login link (see `login_links`)
+pub fn login_with_link<T: MedalConnection>(conn: &T, user_id: i32, expires: i64, signature: &str,
+                                           signer: Option<&LoginLinkSigner>, login_info: LoginInfo)
+                                           -> Result<String, (String, json_val::Map<String, json_val::Value>)> {
+    let logincode = conn.get_user_by_id(user_id).and_then(|user| user.logincode);
+    let session_token = match (signer, logincode) {
+        (Some(signer), Some(logincode))
+            if signer.verify(user_id,
+                             expires,
+                             signature,
+                             &logincode,
+                             conn.get_login_link_generation(user_id),
+                             time::get_time()) =>
+        {
+            conn.login_with_code(None, &logincode).ok()
+        }
+        _ => None,
+    };
+
+    session_token.ok_or_else(|| {
+                     let mut data = json_val::Map::new();
+                     data.insert("reason".to_string(), to_json(&i18n::translate("login-link-invalid")));
+                     data.insert("parent".to_string(), to_json(&"base"));
+
+                     fill_oauth_data(login_info, &mut data);
+
+                     ("login".to_owned(), data)
+                 })
+}
+
 pub fn logout<T: MedalConnection>(conn: &T, session_token: Option<String>) {
     session_token.map(|token| conn.logout(&token));
 }
 
+/// Logs the session out like `logout`, but only if the CSRF token matches, as required for modifying API requests.
+pub fn logout_with_csrf_check<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str) -> MedalResult<()> {
+    let session = conn.get_session(session_token).ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    conn.logout(session_token);
+
+    Ok(())
+}
+
+/// Logs the user out on all devices. Returns the revoked session token, so that server-side session stores can
+/// forget the sessions containing it.
+pub fn logout_everywhere<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str) -> MedalResult<String> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    conn.logout(session_token);
+
+    Ok(session_token.to_string())
+}
+
 #[cfg(feature = "signup")]
-pub fn signup<T: MedalConnection>(conn: &T, session_token: Option<String>, signup_data: (String, String, String))
+pub fn signup<T: MedalConnection>(conn: &T, session_token: Option<String>, signup_data: (String, String, String),
+                                  mailer: Option<&Mailer>)
                                   -> MedalResult<SignupResult> {
     let (username, email, password) = signup_data;
 
@@ -778,7 +1121,17 @@ pub fn signup<T: MedalConnection>(conn: &T, session_token: Option<String>, signu
     let salt = helpers::make_salt();
     let hash = helpers::hash_password(&password, &salt)?;
 
-    let result = conn.signup(&session_token.unwrap(), &username, &email, hash, &salt);
+    let session_token = session_token.unwrap();
+    let result = conn.signup(&session_token, &username, &email, hash, &salt);
+
+    if let (SignupResult::SignedUp, Some(mailer)) = (&result, mailer) {
+        if let Some(mut session) = conn.get_session(&session_token) {
+            session.email_confirmationcode = Some(helpers::make_email_confirmationcode());
+            send_email_confirmation(mailer, &session);
+            conn.save_session(session);
+        }
+    }
+
     Ok(result)
 }
 
@@ -795,6 +1148,239 @@ pub fn signupdata(query_string: Option<String>) -> json_val::Map<String, json_va
     data
 }
 
+/// Send a mail with a confirmation link to the unconfirmed email address of `user`.
+fn send_email_confirmation(mailer: &Mailer, user: &SessionUser) {
+    if let (Some(email), Some(code)) = (&user.email_unconfirmed, &user.email_confirmationcode) {
+        let body = format!("{}\n\n{}\n\n{}/email/confirm/{}\n\n{}\n",
+                           i18n::translate_with("mail-greeting", &[("name", user.username.as_deref().unwrap_or(""))]),
+                           i18n::translate("mail-confirm-email-text"),
+                           mailer.self_url,
+                           code,
+                           i18n::translate("mail-confirm-email-ignore"));
+        if let Err(e) = mailer.send(email, &i18n::translate("mail-confirm-email-subject"), &body) {
+            eprintln!("Could not send confirmation mail to user {}: {:?}", user.id, e);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EmailChangeStatus {
+    EmailConfirmationSent,
+    EmailTaken,
+    EmailEmpty,
+}
+
+/// Set a new email address for the logged in user. The address is only used after it has been confirmed with the
+/// link in the confirmation mail.
+pub fn change_email<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, email: &str,
+                                        mailer: Option<&Mailer>)
+                                        -> MedalResult<EmailChangeStatus> {
+    let mut session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+    let mailer = mailer.ok_or(MedalError::NotFound)?;
+    if session.password.is_none() {
+        return Err(MedalError::AccessDenied);
+    }
+
+    let email = email.trim();
+    if email.is_empty() {
+        return Ok(EmailChangeStatus::EmailEmpty);
+    }
+    if conn.get_user_id_by_email(email).map(|id| id != session.id).unwrap_or(false) {
+        return Ok(EmailChangeStatus::EmailTaken);
+    }
+
+    session.email_unconfirmed = Some(email.to_string());
+    session.email_confirmationcode = Some(helpers::make_email_confirmationcode());
+    send_email_confirmation(mailer, &session);
+    conn.save_session(session);
+
+    Ok(EmailChangeStatus::EmailConfirmationSent)
+}
+
+/// Set the language of the user interface for the logged in user. An empty `language` uses the language of the browser.
+pub fn set_language<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, language: &str)
+                                        -> MedalResult<()> {
+    let mut session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    session.language = match language {
+        "" => None,
+        language if i18n::catalogs().has_language(language) => Some(language.to_string()),
+        _ => return Err(MedalError::NotFound),
+    };
+    conn.save_session(session);
+
+    Ok(())
+}
+
+pub fn confirm_email<T: MedalConnection>(conn: &T, code: &str) -> MedalValueResult {
+    let mut data = json_val::Map::new();
+    data.insert("parent".to_string(), to_json(&"base"));
+
+    let user = conn.get_user_id_by_email_confirmationcode(code).and_then(|id| conn.get_user_by_id(id));
+    match user {
+        // Several accounts can enter the same address, but only one of them can confirm it
+        Some(user)
+            if user.email_unconfirmed
+                   .as_ref()
+                   .and_then(|email| conn.get_user_id_by_email(email))
+                   .map(|id| id != user.id)
+                   .unwrap_or(false) =>
+        {
+            data.insert("email".to_string(), to_json(&user.email_unconfirmed));
+            data.insert("confirmed".to_string(), to_json(&false));
+            data.insert("EmailTaken".to_string(), to_json(&true));
+        }
+        Some(mut user) if user.email_unconfirmed.is_some() => {
+            data.insert("email".to_string(), to_json(&user.email_unconfirmed));
+            data.insert("confirmed".to_string(), to_json(&true));
+
+            user.email = user.email_unconfirmed.take();
+            user.email_confirmationcode = None;
+            conn.save_session(user);
+        }
+        _ => {
+            data.insert("confirmed".to_string(), to_json(&false));
+        }
+    }
+
+    Ok(("email_confirm".to_string(), data))
+}
+
+/// Password reset links are valid for one day
+fn password_reset_min_created() -> time::Timespec { time::get_time() - time::Duration::days(1) }
+
+pub fn show_password_reset_request(mailer: Option<&Mailer>) -> MedalValueResult {
+    mailer.ok_or(MedalError::NotFound)?;
+
+    let mut data = json_val::Map::new();
+    data.insert("parent".to_string(), to_json(&"base"));
+    Ok(("password_reset".to_string(), data))
+}
+
+/// Send a password reset link to the user with username or email address `name`, if the user can log in with a
+/// password. To not reveal which accounts exist, the result is always the same.
+pub fn request_password_reset<T: MedalConnection>(conn: &T, name: &str, mailer: Option<&Mailer>) -> MedalValueResult {
+    let mailer = mailer.ok_or(MedalError::NotFound)?;
+    let name = name.trim();
+
+    let user = conn.get_user_id_by_username(name)
+                   .or_else(|| conn.get_user_id_by_email(name))
+                   .and_then(|id| conn.get_user_by_id(id));
+    if let Some(user) = user {
+        // Only confirmed addresses are used. Unconfirmed addresses might belong to someone else.
+        if let (Some(email), Some(username), true) = (&user.email, &user.username, user.password.is_some()) {
+            let token = helpers::make_password_reset_token();
+            conn.add_password_reset_token(user.id, &helpers::hash_token(&token));
+
+            let body = format!("{}\n\n{}\n\n{}/password/reset/{}\n\n{}\n",
+                               i18n::translate_with("mail-greeting", &[("name", username)]),
+                               i18n::translate("mail-password-reset-text"),
+                               mailer.self_url,
+                               token,
+                               i18n::translate("mail-password-reset-ignore"));
+            if let Err(e) = mailer.send(email, &i18n::translate("mail-password-reset-subject"), &body) {
+                eprintln!("Could not send password reset mail to user {}: {:?}", user.id, e);
+            }
+        }
+    }
+
+    let mut data = json_val::Map::new();
+    data.insert("parent".to_string(), to_json(&"base"));
+    data.insert("sent".to_string(), to_json(&true));
+    Ok(("password_reset".to_string(), data))
+}
+
+pub fn show_password_reset<T: MedalConnection>(conn: &T, token: &str) -> MedalValueResult {
+    let mut data = json_val::Map::new();
+    data.insert("parent".to_string(), to_json(&"base"));
+    let valid =
+        conn.get_user_id_by_password_reset_token(&helpers::hash_token(token), password_reset_min_created()).is_some();
+    data.insert("valid".to_string(), to_json(&valid));
+
+    Ok(("password_reset_form".to_string(), data))
+}
+
+pub fn reset_password<T: MedalConnection>(conn: &T, token: &str, password: &str, password_repeat: &str)
+                                          -> MedalValueResult {
+    let mut data = json_val::Map::new();
+    data.insert("parent".to_string(), to_json(&"base"));
+
+    let user = conn.get_user_id_by_password_reset_token(&helpers::hash_token(token), password_reset_min_created())
+                   .and_then(|id| conn.get_user_by_id(id));
+    let mut user = match user {
+        Some(user) => user,
+        None => {
+            data.insert("valid".to_string(), to_json(&false));
+            return Ok(("password_reset_form".to_string(), data));
+        }
+    };
+    data.insert("valid".to_string(), to_json(&true));
+
+    if password.is_empty() {
+        data.insert("PasswordEmpty".to_string(), to_json(&true));
+        return Ok(("password_reset_form".to_string(), data));
+    }
+    if password != password_repeat {
+        data.insert("PasswordMissmatch".to_string(), to_json(&true));
+        return Ok(("password_reset_form".to_string(), data));
+    }
+
+    let salt = helpers::make_salt();
+    user.password = Some(helpers::hash_password(password, &salt)?);
+    user.salt = Some(salt);
+    // The token must only be used up together with the password change
+    conn.transaction(|| {
+            conn.remove_password_reset_tokens(user.id);
+            // Whoever knew the old password might still be logged in
+            if let Some(ref session_token) = user.session_token {
+                conn.logout(session_token);
+            }
+            conn.save_session(user);
+        });
+
+    data.insert("PasswordChanged".to_string(), to_json(&true));
+    Ok(("password_reset_form".to_string(), data))
+}
+
+/// The ids of the teachers that accepted the invitation to manage the group with id `group_id`
+fn accepted_group_admins<T: MedalConnection>(conn: &T, group_id: i32) -> Vec<i32> {
+    conn.get_group_admins(group_id).into_iter().filter(|admin| admin.accepted).map(|admin| admin.id).collect()
+}
+
+/// The resource access checks for `group` refer to
+fn group_resource<T: MedalConnection>(conn: &T, group: &Group) -> Resource {
+    Resource::Group { owner: group.admin,
+                      co_admins: group.id.map(|id| accepted_group_admins(conn, id)).unwrap_or_default(),
+                      school_id: conn.get_user_by_id(group.admin).and_then(|owner| owner.school_id()) }
+}
+
+/// The resource access checks for `user` refer to: The group managing the user or the user themself
+fn user_resource<T: MedalConnection>(conn: &T, user: &SessionUser, group: Option<&Group>) -> Resource {
+    match group {
+        Some(group) => group_resource(conn, group),
+        None => Resource::User { id: user.id, school_id: user.school_id() },
+    }
+}
+
+/// Check that the submissions of the user with id `user_id` may be read in `session`: Users can read their own
+/// submissions, everyone else needs the permission to view the user's participations.
+fn check_submission_access<T: MedalConnection>(conn: &T, session: &SessionUser, user_id: i32) -> MedalResult<()> {
+    // Is it not our own submission?
+    if user_id != session.id {
+        let (user, group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+        authorize(session, Action::ViewParticipation, &user_resource(conn, &user, group.as_ref()))?;
+    }
+    Ok(())
+}
+
 pub fn load_submission<T: MedalConnection>(conn: &T, task_id: i32, session_token: &str, subtask: Option<String>,
                                            submission_id: Option<i32>)
                                            -> MedalResult<String> {
@@ -809,18 +1395,7 @@ pub fn load_submission<T: MedalConnection>(conn: &T, task_id: i32, session_token
             let (submission, _, _, _) =
                 conn.get_submission_by_id_complete_shallow_contest(submission_id).ok_or(MedalError::UnknownId)?;
 
-            // Is it not our own submission?
-            if submission.user != session.id && !session.is_admin.unwrap_or(false) {
-                if let Some((_, Some(group))) = conn.get_user_and_group_by_id(submission.user) {
-                    if group.admin != session.id {
-                        // We are not admin of the user's group
-                        return Err(MedalError::AccessDenied);
-                    }
-                } else {
-                    // The user has no group
-                    return Err(MedalError::AccessDenied);
-                }
-            }
+            check_submission_access(conn, &session, submission.user)?;
             Ok(submission.value)
         }
     }
@@ -841,7 +1416,9 @@ pub fn save_submission<T: MedalConnection>(conn: &T, task_id: i32, session_token
         None => return Err(MedalError::AccessDenied),
         Some(participation) => {
             let time_info = check_contest_time_left(&session, &contest, &participation);
-            if !time_info.can_still_compete && time_info.left_secs_total < -10 {
+            if !time_info.can_still_compete
+               && (time_info.is_paused || time_info.left_secs_total < -SUBMISSION_GRACE_SECS)
+            {
                 return Err(MedalError::AccessDenied);
                 // Contest over
                 // TODO: Nicer message!
@@ -895,10 +1472,16 @@ pub fn save_submission<T: MedalConnection>(conn: &T, task_id: i32, session_token
                                   date: time::get_time() };
 
     conn.submit_submission(submission);
+    qualification::invalidate_grade_ranks(contest.id.unwrap());
+    metrics::count_submission();
 
     Ok("{}".to_string())
 }
 
+fn task_title(taskgroup_name: &str, contest_name: &str) -> String {
+    i18n::translate_with("task-title", &[("taskgroup", taskgroup_name), ("contest", contest_name)])
+}
+
 pub fn show_task<T: MedalConnection>(conn: &T, task_id: i32, session_token: &str, autosaveinterval: u64)
                                      -> MedalResult<Result<MedalValue, i32>> {
     let session = conn.get_session_or_new(&session_token).unwrap();
@@ -952,7 +1535,7 @@ pub fn show_task<T: MedalConnection>(conn: &T, task_id: i32, session_token: &str
             if time_info.can_still_compete || time_info.is_review {
                 data.insert("contestname".to_string(), to_json(&contest.name));
                 data.insert("name".to_string(), to_json(&tg.name));
-                data.insert("title".to_string(), to_json(&format!("Aufgabe „{}“ in {}", &tg.name, &contest.name)));
+                data.insert("title".to_string(), to_json(&task_title(&tg.name, &contest.name)));
                 data.insert("taskid".to_string(), to_json(&task_id));
                 data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
                 data.insert("contestid".to_string(), to_json(&contest.id));
@@ -1004,18 +1587,7 @@ pub fn review_task<T: MedalConnection>(conn: &T, task_id: i32, session_token: &s
                         grade: Some(submission.grade),
                         validated: submission.validated };
 
-    // Is it not our own submission?
-    if submission.user != session.id && !session.is_admin.unwrap_or(false) {
-        if let Some((_, Some(group))) = conn.get_user_and_group_by_id(submission.user) {
-            if group.admin != session.id {
-                // We are not admin of the user's group
-                return Err(MedalError::AccessDenied);
-            }
-        } else {
-            // The user has no group
-            return Err(MedalError::AccessDenied);
-        }
-    }
+    check_submission_access(conn, &session, submission.user)?;
 
     let subtaskstars = generate_subtaskstars(&tg, &grade, Some(task_id)); // TODO does this work in general?
 
@@ -1030,6 +1602,8 @@ pub fn review_task<T: MedalConnection>(conn: &T, task_id: i32, session_token: &s
                                       left_sec: 0,
                                       has_timelimit: contest.duration != 0,
                                       is_time_left: false,
+                                      is_paused: false,
+                                      extra_time: 0,
                                       exempt_from_timelimit: true,
                                       can_still_compete: false,
                                       review_has_timelimit: false,
@@ -1056,7 +1630,7 @@ pub fn review_task<T: MedalConnection>(conn: &T, task_id: i32, session_token: &s
 
     //data.insert("contestname".to_string(), to_json(&contest.name));
     data.insert("name".to_string(), to_json(&tg.name));
-    data.insert("title".to_string(), to_json(&format!("Aufgabe „{}“ in {}", &tg.name, &contest.name)));
+    data.insert("title".to_string(), to_json(&task_title(&tg.name, &contest.name)));
     data.insert("taskid".to_string(), to_json(&task_id));
     data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
     //data.insert("contestid".to_string(), to_json(&contest.id));
@@ -1104,52 +1678,225 @@ pub fn show_groups<T: MedalConnection>(conn: &T, session_token: &str) -> MedalVa
                                  code: g.groupcode.clone() })
             .collect();
     data.insert("group".to_string(), to_json(&v));
+
+    let invitations: Vec<GroupInvitationInfo> =
+        conn.get_group_invitations(session.id)
+            .into_iter()
+            .map(|g| {
+                let owner = conn.get_user_by_id(g.admin);
+                GroupInvitationInfo { id: g.id.unwrap(),
+                                      name: g.name,
+                                      tag: g.tag,
+                                      owner_firstname: owner.as_ref().and_then(|o| o.firstname.clone()),
+                                      owner_lastname: owner.and_then(|o| o.lastname) }
+            })
+            .collect();
+    data.insert("invitation".to_string(), to_json(&invitations));
     data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
 
     Ok(("groups".to_string(), data))
 }
 
 #[derive(Serialize, Deserialize)]
-pub struct MemberInfo {
+pub struct GroupInvitationInfo {
     pub id: i32,
-    pub firstname: String,
-    pub lastname: String,
-    pub sex: String,
-    pub grade: String,
-    pub logincode: String,
+    pub name: String,
+    pub tag: String,
+    pub owner_firstname: Option<String>,
+    pub owner_lastname: Option<String>,
 }
 
-pub fn show_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
-    let group = conn.get_group_complete(group_id).unwrap(); // TODO handle error
+#[derive(Serialize, Deserialize)]
+pub struct GroupAdminInfo {
+    pub id: i32,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub accepted: bool,
+    pub is_self: bool,
+}
 
-    let mut data = json_val::Map::new();
-    fill_user_data(&session, &mut data);
+#[derive(Debug, PartialEq, Eq)]
+pub enum GroupAdminStatus {
+    Invited,
+    AlreadyInvited,
+    InviteeNotFound,
+    Removed,
+    Left,
+    Transferred,
+    Accepted,
+    Declined,
+}
+
+/// Invite the teacher with the login code or confirmed email address `invitee` to manage the group with id
+/// `group_id` together with its owner. The teacher gets access to the group after accepting the invitation.
+pub fn invite_group_admin<T: MedalConnection>(conn: &T, group_id: i32, invitee: &str, session_token: &str,
+                                              csrf_token: &str)
+                                              -> MedalResult<GroupAdminStatus> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
 
-    if group.admin != session.id {
-        return Err(MedalError::AccessDenied);
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
     }
 
-    let gi = GroupInfo { id: group.id.unwrap(),
-                         name: group.name.clone(),
-                         tag: group.tag.clone(),
-                         code: group.groupcode.clone() };
+    let group = conn.get_group(group_id).ok_or(MedalError::UnknownId)?;
+    authorize(&session, Action::ManageGroupAdmins, &group_resource(conn, &group))?;
 
-    let v: Vec<MemberInfo> = group.members
-                                  .iter()
-                                  .filter_map(|m| {
-                                      Some(MemberInfo { id: m.id,
-                                                        firstname: m.firstname.clone()?,
-                                                        lastname: m.lastname.clone()?,
-                                                        sex: (match m.sex {
-                                                                 Some(0) | None => "/",
-                                                                 Some(1) => "m",
-                                                                 Some(2) => "w",
-                                                                 Some(3) => "d",
-                                                                 Some(4) => "…",
-                                                                 _ => "?",
-                                                             }).to_string(),
-                                                        grade: grade_to_string(m.grade),
+    let invitee = invitee.trim();
+    if invitee.is_empty() {
+        return Ok(GroupAdminStatus::InviteeNotFound);
+    }
+    let teacher = match conn.get_user_id_by_logincode(invitee)
+                            .or_else(|| conn.get_user_id_by_email(invitee))
+                            .and_then(|id| conn.get_user_by_id(id))
+    {
+        Some(teacher) if teacher.is_teacher() => teacher,
+        _ => return Ok(GroupAdminStatus::InviteeNotFound),
+    };
+
+    if teacher.id == group.admin || conn.get_group_admins(group_id).iter().any(|admin| admin.id == teacher.id) {
+        return Ok(GroupAdminStatus::AlreadyInvited);
+    }
+
+    conn.add_group_admin(group_id, teacher.id);
+    conn.add_audit_log_entry(AuditLogEntry { target_user: Some(teacher.id),
+                                             target_group: Some(group_id),
+                                             ..AuditLogEntry::new(&session, "invite_group_admin") });
+
+    Ok(GroupAdminStatus::Invited)
+}
+
+/// Remove the teacher with id `user_id` from the admins of the group with id `group_id`. Teachers can always leave
+/// a group they have been invited to.
+pub fn remove_group_admin<T: MedalConnection>(conn: &T, group_id: i32, user_id: i32, session_token: &str,
+                                              csrf_token: &str)
+                                              -> MedalResult<GroupAdminStatus> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let group = conn.get_group(group_id).ok_or(MedalError::UnknownId)?;
+    if user_id != session.id {
+        authorize(&session, Action::ManageGroupAdmins, &group_resource(conn, &group))?;
+    }
+
+    if !conn.get_group_admins(group_id).iter().any(|admin| admin.id == user_id) {
+        return Err(MedalError::UnknownId);
+    }
+
+    conn.remove_group_admin(group_id, user_id);
+    conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                             target_group: Some(group_id),
+                                             ..AuditLogEntry::new(&session, "remove_group_admin") });
+
+    if user_id == session.id {
+        Ok(GroupAdminStatus::Left)
+    } else {
+        Ok(GroupAdminStatus::Removed)
+    }
+}
+
+/// Make the teacher with id `user_id`, who must have accepted the invitation to manage the group with id
+/// `group_id`, the new owner of the group
+pub fn transfer_group_ownership<T: MedalConnection>(conn: &T, group_id: i32, user_id: i32, session_token: &str,
+                                                    csrf_token: &str)
+                                                    -> MedalResult<GroupAdminStatus> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let group = conn.get_group(group_id).ok_or(MedalError::UnknownId)?;
+    authorize(&session, Action::ManageGroupAdmins, &group_resource(conn, &group))?;
+
+    if !accepted_group_admins(conn, group_id).contains(&user_id) {
+        return Err(MedalError::UnknownId);
+    }
+
+    conn.transfer_group_ownership(group_id, user_id);
+    conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                             target_group: Some(group_id),
+                                             before: Some(audit_value(&AuditGroupOwner { owner: group.admin })),
+                                             after: Some(audit_value(&AuditGroupOwner { owner: user_id })),
+                                             ..AuditLogEntry::new(&session, "transfer_group") });
+
+    Ok(GroupAdminStatus::Transferred)
+}
+
+/// The owner of a group in the audit log
+#[derive(Serialize)]
+struct AuditGroupOwner {
+    owner: i32,
+}
+
+/// Accept or decline the invitation to manage the group with id `group_id`
+pub fn answer_group_invitation<T: MedalConnection>(conn: &T, group_id: i32, accept: bool, session_token: &str,
+                                                   csrf_token: &str)
+                                                   -> MedalResult<GroupAdminStatus> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    if !conn.get_group_invitations(session.id).iter().any(|group| group.id == Some(group_id)) {
+        return Err(MedalError::UnknownId);
+    }
+
+    if accept {
+        conn.accept_group_admin(group_id, session.id);
+        conn.add_audit_log_entry(AuditLogEntry { target_group: Some(group_id),
+                                                 ..AuditLogEntry::new(&session, "accept_group_admin") });
+        Ok(GroupAdminStatus::Accepted)
+    } else {
+        conn.remove_group_admin(group_id, session.id);
+        conn.add_audit_log_entry(AuditLogEntry { target_group: Some(group_id),
+                                                 ..AuditLogEntry::new(&session, "decline_group_admin") });
+        Ok(GroupAdminStatus::Declined)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MemberInfo {
+    pub id: i32,
+    pub firstname: String,
+    pub lastname: String,
+    pub sex: String,
+    pub grade: String,
+    pub logincode: String,
+}
+
+pub fn show_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str) -> MedalValueResult {
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    let group = conn.get_group_complete(group_id).ok_or(MedalError::UnknownId)?;
+
+    authorize(&session, Action::ViewGroup, &group_resource(conn, &group))?;
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    let gi = GroupInfo { id: group.id.unwrap(),
+                         name: group.name.clone(),
+                         tag: group.tag.clone(),
+                         code: group.groupcode.clone() };
+
+    let v: Vec<MemberInfo> = group.members
+                                  .iter()
+                                  .filter_map(|m| {
+                                      Some(MemberInfo { id: m.id,
+                                                        firstname: m.firstname.clone()?,
+                                                        lastname: m.lastname.clone()?,
+                                                        sex: (match m.sex {
+                                                                 Some(0) | None => "/",
+                                                                 Some(1) => "m",
+                                                                 Some(2) => "w",
+                                                                 Some(3) => "d",
+                                                                 Some(4) => "…",
+                                                                 _ => "?",
+                                                             }).to_string(),
+                                                        grade: grades::scale().name(m.grade),
                                                         logincode: m.logincode.clone()? })
                                   })
                                   .collect();
@@ -1161,17 +1908,79 @@ pub fn show_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &s
     Ok(("group".to_string(), data))
 }
 
+/// Login sheets of all members of the group as PDF, returns the name of the group and the PDF. Without `signer`, the
+/// QR codes only lead to the login page with the login code filled in.
+pub fn group_login_sheets<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str, self_url: &str,
+                                              config: &LoginSheetConfig, signer: Option<&LoginLinkSigner>)
+                                              -> MedalResult<(String, Vec<u8>)> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    let group = conn.get_group_complete(group_id).ok_or(MedalError::UnknownId)?;
+
+    authorize(&session, Action::ViewGroup, &group_resource(conn, &group))?;
+
+    let login_url = format!("{}/login", self_url);
+    let now = time::get_time();
+    let login_link = |user_id: i32, logincode: &str| match signer {
+        Some(signer) => signer.link(self_url, user_id, logincode, conn.get_login_link_generation(user_id), now),
+        None => format!("{}?code={}", login_url, logincode),
+    };
+    let members = group.members
+                       .iter()
+                       .filter_map(|m| {
+                           let logincode = m.logincode.clone()?;
+                           Some(LoginSheetMember { name: format!("{} {}",
+                                                                 m.firstname.as_deref().unwrap_or_default(),
+                                                                 m.lastname.as_deref().unwrap_or_default()),
+                                                   grade: grades::scale().name(m.grade),
+                                                   login_link: login_link(m.id, &logincode),
+                                                   logincode })
+                       })
+                       .collect();
+    let sheets = LoginSheets { group: group.name.clone(), groupcode: group.groupcode.clone(), login_url, members };
+
+    Ok((group.name, login_sheets::render(&sheets, config)))
+}
+
+/// Revoke the login links of the member `user_id` of the group or, without `user_id`, of all members of the group.
+/// New links are created with the next login sheets.
+pub fn revoke_login_links<T: MedalConnection>(conn: &T, group_id: i32, user_id: Option<i32>, session_token: &str,
+                                              csrf_token: &str)
+                                              -> MedalResult<()> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    let group = conn.get_group_complete(group_id).ok_or(MedalError::UnknownId)?;
+
+    authorize(&session, Action::EditUser, &group_resource(conn, &group))?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let members: Vec<i32> =
+        group.members.iter().map(|member| member.id).filter(|&id| user_id.is_none() || user_id == Some(id)).collect();
+    if user_id.is_some() && members.is_empty() {
+        return Err(MedalError::UnknownId);
+    }
+
+    conn.transaction(|| {
+            for &member in &members {
+                conn.revoke_login_links(member);
+            }
+            conn.add_audit_log_entry(AuditLogEntry { target_group: Some(group_id),
+                                                     target_user: user_id,
+                                                     ..AuditLogEntry::new(&session, "revoke_login_links") });
+        });
+
+    Ok(())
+}
+
 pub fn modify_group<T: MedalConnection>(_conn: &T, _group_id: i32, _session_token: &str) -> MedalResult<()> {
     unimplemented!()
 }
 
 pub fn add_group<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, name: String, tag: String)
                                      -> MedalResult<i32> {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::AccessDenied)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::AccessDenied)?;
+    authorize(&session, Action::CreateGroup, &Resource::Site)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
@@ -1196,86 +2005,171 @@ pub fn add_group<T: MedalConnection>(conn: &T, session_token: &str, csrf_token:
     Ok(group.id.unwrap())
 }
 
-pub fn group_csv<T: MedalConnection>(conn: &T, session_token: &str, sex_infos: SexInformation) -> MedalValueResult {
+pub fn group_csv<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
     let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::CreateGroup, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
 
-    data.insert("require_sex".to_string(), to_json(&sex_infos.require_sex));
-    data.insert("allow_sex_na".to_string(), to_json(&sex_infos.allow_sex_na));
-    data.insert("allow_sex_diverse".to_string(), to_json(&sex_infos.allow_sex_diverse));
-    data.insert("allow_sex_other".to_string(), to_json(&sex_infos.allow_sex_other));
+    Ok(("groupcsv".to_string(), data))
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ColumnOption {
+    pub column: usize,
+    pub name: String,
+    pub selected: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ColumnSelection {
+    pub field: String,
+    pub label: String,
+    pub optional: bool,
+    pub columns: Vec<ColumnOption>,
+}
+
+/// Decode and parse an uploaded CSV file of groups
+fn read_group_import(file: &[u8], encoding: group_import::Encoding, options: &group_import::ImportOptions,
+                     sex_infos: &SexInformation)
+                     -> Result<(String, group_import::GroupImport), String> {
+    let text = group_import::decode(file, encoding)?;
+    let import = group_import::parse(&text, options, sex_infos);
+    Ok((text, import))
+}
+
+/// Show the groups and participants of an uploaded CSV file with all errors before creating them
+pub fn preview_groups<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, file: &[u8],
+                                          encoding: group_import::Encoding, options: group_import::ImportOptions,
+                                          sex_infos: SexInformation)
+                                          -> MedalValueResult {
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::CreateGroup, &Resource::Site)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let mut data = json_val::Map::new();
+    data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
+
+    let (text, import) = match read_group_import(file, encoding, &options, &sex_infos) {
+        Ok(result) => result,
+        Err(message) => {
+            data.insert("file_error".to_string(), to_json(&message));
+            return Ok(("groupcsv".to_string(), data));
+        }
+    };
+
+    let column_options = |selected: Option<usize>| -> Vec<ColumnOption> {
+        import.column_names
+              .iter()
+              .enumerate()
+              .map(|(column, name)| ColumnOption { column, name: name.clone(), selected: selected == Some(column) })
+              .collect()
+    };
+    let columns: Vec<ColumnSelection> =
+        group_import::FIELDS.iter()
+                            .map(|&field| ColumnSelection { field: field.id().to_string(),
+                                                            label: field.label(),
+                                                            optional: field == group_import::Field::Sex,
+                                                            columns: column_options(import.columns.get(field)) })
+                            .collect();
+    let groups: Vec<(String, usize)> =
+        import.groups().into_iter().map(|(name, rows)| (name.to_string(), rows.len())).collect();
+    let delimiter = match import.delimiter {
+        ';' => "semicolon",
+        '\t' => "tab",
+        _ => "comma",
+    };
+
+    data.insert("csv_data".to_string(), to_json(&text));
+    data.insert(format!("delimiter_{}", delimiter), to_json(&"selected"));
+    data.insert(format!("header_{}", if import.header { "yes" } else { "no" }), to_json(&"selected"));
+    data.insert("columns".to_string(), to_json(&columns));
+    data.insert("groups".to_string(), to_json(&groups));
+    data.insert("error_count".to_string(), to_json(&import.error_count()));
+    data.insert("can_create".to_string(), to_json(&(import.error_count() == 0)));
+    data.insert("import".to_string(), to_json(&import));
 
     Ok(("groupcsv".to_string(), data))
 }
 
-// TODO: Should creating the users and groups happen in a batch operation to speed things up?
-pub fn upload_groups<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, group_data: &str)
-                                         -> MedalResult<()> {
+/// Create the groups of an uploaded CSV file. Nothing is created if the file contains errors, in which case `false`
+/// is returned.
+pub fn upload_groups<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, file: &[u8],
+                                         encoding: group_import::Encoding, options: group_import::ImportOptions,
+                                         sex_infos: SexInformation)
+                                         -> MedalResult<bool> {
     let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::CreateGroup, &Resource::Site)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
     }
 
-    let mut v: Vec<Vec<String>> = serde_json::from_str(group_data).or(Err(MedalError::AccessDenied))?; // TODO: Change error type
-    v.sort_unstable_by(|a, b| a[0].partial_cmp(&b[0]).unwrap());
+    let import = match read_group_import(file, encoding, &options, &sex_infos) {
+        Ok((_, import)) if import.error_count() == 0 => import,
+        _ => return Ok(false),
+    };
 
-    let mut groupcode = String::new();
-    let mut name = String::new();
-    let mut group =
-        Group { id: None, name: name.clone(), groupcode, tag: String::new(), admin: session.id, members: Vec::new() };
-
-    for line in v {
-        if name != line[0] {
-            if name != "" {
-                conn.create_group_with_users(group);
+    let mut groups: Vec<Group> = Vec::new();
+    for (name, rows) in import.groups() {
+        let mut groupcode = String::new();
+        for i in 0..10 {
+            if i == 9 {
+                panic!("ERROR: Too many groupcode collisions! Give up ...");
             }
-            name = line[0].clone();
-
-            groupcode = String::new();
-            for i in 0..10 {
-                if i == 9 {
-                    panic!("ERROR: Too many groupcode collisions! Give up ...");
-                }
-                groupcode = helpers::make_groupcode();
-                if !conn.code_exists(&groupcode) {
-                    break;
-                }
-                println!("WARNING: Groupcode collision! Retrying ...");
+            groupcode = helpers::make_groupcode();
+            if !conn.code_exists(&groupcode) && groups.iter().all(|group| group.groupcode != groupcode) {
+                break;
             }
+            println!("WARNING: Groupcode collision! Retrying ...");
+        }
 
-            group = Group { id: None,
-                            name: name.clone(),
+        let members = rows.into_iter()
+                          .map(|row| {
+                              let mut user = SessionUser::group_user_stub();
+                              user.grade = row.grade;
+                              user.firstname = Some(row.firstname.clone());
+                              user.lastname = Some(row.lastname.clone());
+                              user.sex = row.sex;
+                              user
+                          })
+                          .collect();
+
+        groups.push(Group { id: None,
+                            name: name.to_string(),
                             groupcode,
-                            tag: name.clone(),
+                            tag: name.to_string(),
                             admin: session.id,
-                            members: Vec::new() };
-        }
-
-        let mut user = SessionUser::group_user_stub();
-        user.grade = line[1].parse::<i32>().unwrap_or(0);
-        user.firstname = Some(line[2].clone());
-        user.lastname = Some(line[3].clone());
-
-        use db_objects::Sex;
-        match line[4].as_str() {
-            "m" => user.sex = Some(Sex::Male as i32),
-            "f" => user.sex = Some(Sex::Female as i32),
-            "d" => user.sex = Some(Sex::Diverse as i32),
-            _ => user.sex = None,
-        }
-
-        group.members.push(user);
-    }
-    conn.create_group_with_users(group);
+                            members });
+    }
+
+    let afters: Vec<String> = groups.iter()
+                                    .map(|group| {
+                                        audit_value(&AuditGroup { name: &group.name,
+                                                                  groupcode: &group.groupcode,
+                                                                  members: group.members.len() })
+                                    })
+                                    .collect();
+    // Either all groups are created or none, so that a failed upload can simply be repeated
+    conn.transaction(|| {
+            let group_ids = conn.create_groups_with_users(groups);
+            for (group_id, after) in group_ids.into_iter().zip(afters) {
+                conn.add_audit_log_entry(AuditLogEntry { target_group: Some(group_id),
+                                                         after: Some(after),
+                                                         ..AuditLogEntry::new(&session, "upload_group") });
+            }
+        });
 
-    Ok(())
+    Ok(true)
 }
 
 pub fn contest_admission_csv<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
     let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ManageContests, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     data.insert("csrf_token".to_string(), to_json(&session.csrf_token));
@@ -1287,6 +2181,7 @@ pub fn upload_contest_admission_csv<T: MedalConnection>(conn: &T, session_token:
                                                         contest_id: i32, admission_data: &str)
                                                         -> MedalResult<()> {
     let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ManageContests, &Resource::Site)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
@@ -1315,6 +2210,7 @@ pub fn show_groups_results<T: MedalConnection>(conn: &T, contest_id: i32, sessio
     Ok(("groupresults".into(), data))
 }
 
+#[derive(Clone)]
 pub struct SexInformation {
     pub require_sex: bool,
     pub allow_sex_na: bool,
@@ -1342,7 +2238,7 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
             data.insert("profile_street".to_string(), to_json(&session.street));
             data.insert("profile_zip".to_string(), to_json(&session.zip));
             data.insert("profile_city".to_string(), to_json(&session.city));
-            data.insert(format!("sel{}", session.grade), to_json(&"selected"));
+            data.insert("grades".to_string(), to_json(&grades::scale().options(Some(session.grade))));
             if let Some(sex) = session.sex {
                 data.insert(format!("sex_{}", sex), to_json(&"selected"));
             } else {
@@ -1352,6 +2248,8 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
             data.insert("profile_logincode".to_string(), to_json(&session.logincode));
             if session.password.is_some() {
                 data.insert("profile_username".to_string(), to_json(&session.username));
+                data.insert("profile_email".to_string(), to_json(&session.email));
+                data.insert("profile_email_unconfirmed".to_string(), to_json(&session.email_unconfirmed));
             }
             if session.managed_by.is_none() {
                 data.insert("profile_not_in_group".into(), to_json(&true));
@@ -1363,6 +2261,11 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
             }
             data.insert("ownprofile".into(), to_json(&true));
 
+            let is_selected = |language: &String| session.language.as_ref() == Some(language);
+            let languages: Vec<(String, String, bool)> =
+                i18n::catalogs().languages().into_iter().map(|(l, name)| (l.clone(), name, is_selected(&l))).collect();
+            data.insert("languages".into(), to_json(&languages));
+
             if let Some(query) = query_string {
                 if let Some(status) = query.strip_prefix("status=") {
                     if ["NothingChanged",
@@ -1370,7 +2273,10 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
                         "PasswordChanged",
                         "PasswordMissmatch",
                         "firstlogin",
-                        "SignedUp"].contains(&status)
+                        "SignedUp",
+                        "EmailConfirmationSent",
+                        "EmailTaken",
+                        "EmailEmpty"].contains(&status)
                     {
                         data.insert((status).to_string(), to_json(&true));
                     }
@@ -1385,8 +2291,7 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
                     .into_iter()
                     .rev()
                     .map(|(participation, contest)| {
-                        let passed_secs = now.sec - participation.start.sec;
-                        let left_secs = i64::from(contest.duration) * 60 - passed_secs;
+                        let left_secs = participation_left_secs(contest.duration, &participation, now);
                         let is_time_left = contest.duration == 0 || left_secs >= 0;
                         let has_timelimit = contest.duration != 0;
                         let requires_login = contest.requires_login == Some(true);
@@ -1397,13 +2302,13 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
 
             let stars_count = conn.count_all_stars(session.id);
             data.insert("stars_count".into(), to_json(&stars_count));
-            let stars_message = match stars_count {
-                                    0 => "Auf gehts, dein erster Stern wartet auf dich!",
-                                    1..=9 => "Ein hervorragender Anfang!",
-                                    10..=99 => "Das ist ziemlich gut!",
-                                    100..=999 => "Ein wahrer Meister!",
-                                    _ => "Wow! Einfach wow!",
-                                }.to_string();
+            let stars_message = i18n::translate(match stars_count {
+                                                    0 => "stars-none",
+                                                    1..=9 => "stars-few",
+                                                    10..=99 => "stars-many",
+                                                    100..=999 => "stars-master",
+                                                    _ => "stars-wow",
+                                                });
 
             data.insert("stars_message".into(), to_json(&stars_message));
         }
@@ -1412,16 +2317,14 @@ pub fn show_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
             // TODO: Add test to check if this access restriction works
             let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
             let group = opt_group.ok_or(MedalError::AccessDenied)?;
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
+            authorize(&session, Action::EditUser, &group_resource(conn, &group))?;
 
             data.insert("profile_firstname".to_string(), to_json(&user.firstname));
             data.insert("profile_lastname".to_string(), to_json(&user.lastname));
             data.insert("profile_street".to_string(), to_json(&session.street));
             data.insert("profile_zip".to_string(), to_json(&session.zip));
             data.insert("profile_city".to_string(), to_json(&session.city));
-            data.insert(format!("sel{}", user.grade), to_json(&"selected"));
+            data.insert("grades".to_string(), to_json(&grades::scale().options(Some(user.grade))));
             if let Some(sex) = user.sex {
                 data.insert(format!("sex_{}", sex), to_json(&"selected"));
             } else {
@@ -1550,9 +2453,7 @@ pub fn edit_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
             // TODO: Add test to check if this access restriction works
             let (mut user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
             let group = opt_group.ok_or(MedalError::AccessDenied)?;
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
+            authorize(&session, Action::EditUser, &group_resource(conn, &group))?;
 
             user.firstname = Some(firstname);
             user.lastname = Some(lastname);
@@ -1583,9 +2484,7 @@ pub fn edit_profile<T: MedalConnection>(conn: &T, session_token: &str, user_id:
 
 pub fn teacher_infos<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
     let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
-    if !session.is_teacher {
-        return Err(MedalError::AccessDenied);
-    }
+    authorize(&session, Action::ViewTeacherMaterial, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
@@ -1594,15 +2493,22 @@ pub fn teacher_infos<T: MedalConnection>(conn: &T, session_token: &str) -> Medal
 }
 
 pub fn admin_index<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ViewAdminArea, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
 
+    for &(key, action) in [("can_search_users", Action::SearchUsers),
+                           ("can_export", Action::ExportResults),
+                           ("can_manage_contests", Action::ManageContests),
+                           ("can_view_reports", Action::ViewReports),
+                           ("can_view_audit_log", Action::ViewAuditLog),
+                           ("can_cleanup", Action::Cleanup)].iter()
+    {
+        data.insert(key.to_string(), to_json(&is_authorized(&session, action, &Resource::Site)));
+    }
+
     Ok(("admin".to_string(), data))
 }
 
@@ -1614,11 +2520,8 @@ pub fn admin_search_users<T: MedalConnection>(conn: &T, session_token: &str,
                                                Option<String>,
                                                Option<String>))
                                               -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::SearchUsers, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
@@ -1649,40 +2552,27 @@ pub fn admin_search_users<T: MedalConnection>(conn: &T, session_token: &str,
     Ok(("admin_search_results".to_string(), data))
 }
 
+#[derive(Serialize)]
+struct RoleInfo {
+    name: &'static str,
+    title: String,
+    selected: bool,
+}
+
 pub fn admin_show_user<T: MedalConnection>(conn: &T, user_id: i32, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
 
     let mut data = json_val::Map::new();
 
     let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
-
-    if !session.is_admin() {
-        // Check access for teachers
-        if let Some(group) = opt_group.clone() {
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
-        } else if user_id != session.id {
-            return Err(MedalError::AccessDenied);
-        }
-    }
+    let resource = user_resource(conn, &user, opt_group.as_ref());
+    authorize(&session, Action::ViewUser, &resource)?;
 
     fill_user_data(&session, &mut data);
     fill_user_data_prefix(&user, &mut data, "user_");
     data.insert("user_logincode".to_string(), to_json(&user.logincode));
     data.insert("user_id".to_string(), to_json(&user.id));
-    let grade = if user.grade >= 200 {
-        "Kein Schüler mehr".to_string()
-    } else if user.grade >= 11 {
-        format!("{} ({})", user.grade % 100, if user.grade >= 100 { "G9" } else { "G8" })
-    } else {
-        format!("{}", user.grade)
-    };
-    data.insert("user_grade".to_string(), to_json(&grade));
+    data.insert("user_grade".to_string(), to_json(&grades::scale().name(user.grade)));
     data.insert("user_oauthid".to_string(), to_json(&user.oauth_foreign_id));
     data.insert("user_oauthprovider".to_string(), to_json(&user.oauth_provider));
 
@@ -1691,14 +2581,15 @@ pub fn admin_show_user<T: MedalConnection>(conn: &T, user_id: i32, session_token
         data.insert("user_group_name".to_string(), to_json(&group.name));
     }
 
+    let groups = conn.get_groups(user_id);
+    let owns_groups = groups.iter().any(|group| group.admin == user_id);
     let groups: Vec<GroupInfo> =
-        conn.get_groups(user_id)
-            .iter()
-            .map(|g| GroupInfo { id: g.id.unwrap(),
-                                 name: g.name.clone(),
-                                 tag: g.tag.clone(),
-                                 code: g.groupcode.clone() })
-            .collect();
+        groups.iter()
+              .map(|g| GroupInfo { id: g.id.unwrap(),
+                                   name: g.name.clone(),
+                                   tag: g.tag.clone(),
+                                   code: g.groupcode.clone() })
+              .collect();
     data.insert("user_group".to_string(), to_json(&groups));
 
     let parts = conn.get_all_participations_complete(user_id);
@@ -1712,49 +2603,80 @@ pub fn admin_show_user<T: MedalConnection>(conn: &T, user_id: i32, session_token
     data.insert("user_participations".to_string(), to_json(&pi));
     data.insert("has_protected_participations".to_string(), to_json(&has_protected_participations));
     data.insert("can_delete".to_string(),
-                to_json(&((!has_protected_participations || session.is_admin()) && groups.len() == 0)));
+                to_json(&(is_authorized(&session, Action::DeleteUser, &resource)
+                          && (!has_protected_participations
+                              || is_authorized(&session, Action::DeleteProtected, &Resource::Site))
+                          && !owns_groups)));
+    data.insert("can_edit".to_string(), to_json(&is_authorized(&session, Action::EditUser, &resource)));
+    data.insert("can_move".to_string(), to_json(&is_authorized(&session, Action::MoveUserToGroup, &Resource::Site)));
+    data.insert("can_revoke_sessions".to_string(),
+                to_json(&(user.session_token.is_some() && is_authorized(&session, Action::RevokeSessions, &resource))));
+
+    if is_authorized(&session, Action::ManageRoles, &Resource::Site) {
+        let roles: Vec<RoleInfo> =
+            Role::ALL.iter()
+                     .map(|&role| RoleInfo { name: role.name(),
+                                             title: i18n::translate(&format!("role-{}", role.name())),
+                                             selected: user.has_role(role) })
+                     .collect();
+        data.insert("roles".to_string(), to_json(&roles));
+    }
 
     Ok(("admin_user".to_string(), data))
 }
 
+/// A user as recorded in the audit log
+#[derive(Serialize)]
+struct AuditUser<'a> {
+    firstname: Option<&'a str>,
+    lastname: Option<&'a str>,
+    logincode: Option<&'a str>,
+    username: Option<&'a str>,
+    group: Option<i32>,
+}
+
+fn audit_user_summary(user: &SessionUser) -> String {
+    audit_value(&AuditUser { firstname: user.firstname.as_deref(),
+                             lastname: user.lastname.as_deref(),
+                             logincode: user.logincode.as_deref(),
+                             username: user.username.as_deref(),
+                             group: user.managed_by })
+}
+
+/// A reference to a group in the audit log
+#[derive(Serialize)]
+struct AuditGroupId {
+    group: i32,
+}
+
 pub fn admin_delete_user<T: MedalConnection>(conn: &T, user_id: i32, session_token: &str, csrf_token: &str)
                                              -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
     }
 
-    let (_, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
-
-    if !session.is_admin() {
-        // Check access for teachers
-        if let Some(group) = opt_group {
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
-        } else {
-            return Err(MedalError::AccessDenied);
-        }
-    }
+    let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+    authorize(&session, Action::DeleteUser, &user_resource(conn, &user, opt_group.as_ref()))?;
 
     let parts = conn.get_all_participations_complete(user_id);
     let has_protected_participations = parts.iter().any(|p| p.1.protected);
-    let groups = conn.get_groups(user_id);
+    let owns_groups = conn.get_groups(user_id).iter().any(|group| group.admin == user_id);
 
     let mut data = json_val::Map::new();
-    if has_protected_participations && !session.is_admin() {
-        data.insert("reason".to_string(), to_json(&"Benutzer hat Teilnahmen an geschützten Wettbewerben."));
+    if has_protected_participations && !is_authorized(&session, Action::DeleteProtected, &Resource::Site) {
+        data.insert("reason".to_string(), to_json(&i18n::translate("delete-user-protected-participations")));
         Ok(("delete_fail".to_string(), data))
-    } else if groups.len() > 0 {
-        data.insert("reason".to_string(), to_json(&"Benutzer ist Administrator von Gruppen."));
+    } else if owns_groups {
+        data.insert("reason".to_string(), to_json(&i18n::translate("delete-user-group-admin")));
         Ok(("delete_fail".to_string(), data))
     } else {
         conn.delete_user(user_id);
+        conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                                 target_group: user.managed_by,
+                                                 before: Some(audit_user_summary(&user)),
+                                                 ..AuditLogEntry::new(&session, "delete_user") });
         Ok(("delete_ok".to_string(), data))
     }
 }
@@ -1762,61 +2684,97 @@ pub fn admin_delete_user<T: MedalConnection>(conn: &T, user_id: i32, session_tok
 pub fn admin_move_user_to_group<T: MedalConnection>(conn: &T, user_id: i32, group_id: i32, session_token: &str,
                                                     csrf_token: &str)
                                                     -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::MoveUserToGroup, &Resource::Site)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
     }
 
-    let (_, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
-
-    if !session.is_admin() {
-        // Check access for teachers
-        if let Some(group) = opt_group {
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
-        } else {
-            return Err(MedalError::AccessDenied);
-        }
-    }
-
     let mut data = json_val::Map::new();
     if conn.get_group_complete(group_id).is_some() {
         if let Some(mut user) = conn.get_user_by_id(user_id) {
+            let before = user.managed_by.map(|group| audit_value(&AuditGroupId { group }));
             user.managed_by = Some(group_id);
             conn.save_session(user);
+            conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                                     target_group: Some(group_id),
+                                                     before,
+                                                     after: Some(audit_value(&AuditGroupId { group: group_id })),
+                                                     ..AuditLogEntry::new(&session, "move_user_to_group") });
             Ok(("delete_ok".to_string(), data))
         } else {
-            data.insert("reason".to_string(), to_json(&"Benutzer existiert nicht."));
+            data.insert("reason".to_string(), to_json(&i18n::translate("delete-user-not-found")));
             Ok(("delete_fail".to_string(), data))
         }
     } else {
-        data.insert("reason".to_string(), to_json(&"Gruppe existiert nicht."));
+        data.insert("reason".to_string(), to_json(&i18n::translate("delete-group-not-found")));
         Ok(("delete_fail".to_string(), data))
     }
 }
 
-pub fn admin_show_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+/// Replace the roles of the user with id `user_id` by the roles named in `role_names`. Unknown names are ignored.
+pub fn admin_set_user_roles<T: MedalConnection>(conn: &T, user_id: i32, role_names: &[String], session_token: &str,
+                                                csrf_token: &str)
+                                                -> MedalResult<()> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ManageRoles, &Resource::Site)?;
 
-    let group = conn.get_group_complete(group_id).unwrap(); // TODO handle error
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
 
-    if !session.is_admin() {
-        // Check access for teachers
-        if group.admin != session.id {
-            return Err(MedalError::AccessDenied);
-        }
+    let user = conn.get_user_by_id(user_id).ok_or(MedalError::UnknownId)?;
+
+    let mut roles: Vec<Role> = role_names.iter().filter_map(|name| Role::from_name(name)).collect();
+    roles.sort();
+    roles.dedup();
+
+    if roles != user.roles {
+        let role_list = |roles: &[Role]| roles.iter().map(|role| role.name()).collect::<Vec<_>>().join(", ");
+        conn.set_user_roles(user_id, &roles);
+        conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                                 before: Some(role_list(&user.roles)),
+                                                 after: Some(role_list(&roles)),
+                                                 ..AuditLogEntry::new(&session, "set_roles") });
+    }
+
+    Ok(())
+}
+
+/// Logs the user `user_id` out on all devices. Returns the revoked session token of the user if the user was logged
+/// in, so that server-side session stores can forget the sessions containing it.
+pub fn admin_revoke_user_sessions<T: MedalConnection>(conn: &T, user_id: i32, session_token: &str, csrf_token: &str)
+                                                      -> MedalResult<Option<String>> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+    authorize(&session, Action::RevokeSessions, &user_resource(conn, &user, opt_group.as_ref()))?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
     }
 
+    let revoked_token = user.session_token.clone();
+    if let Some(ref token) = revoked_token {
+        conn.logout(token);
+        conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                                 ..AuditLogEntry::new(&session, "revoke_sessions") });
+    }
+
+    Ok(revoked_token)
+}
+
+pub fn admin_show_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str,
+                                            query_string: Option<String>)
+                                            -> MedalValueResult {
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    let group = conn.get_group_complete(group_id).ok_or(MedalError::UnknownId)?;
+    let resource = group_resource(conn, &group);
+    authorize(&session, Action::ViewGroup, &resource)?;
+    let can_search_users = is_authorized(&session, Action::SearchUsers, &Resource::Site);
+
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
 
@@ -1828,7 +2786,7 @@ pub fn admin_show_group<T: MedalConnection>(conn: &T, group_id: i32, session_tok
     let v: Vec<MemberInfo> =
         group.members
              .iter()
-             .filter(|m| session.is_admin() || m.firstname.is_some() || m.lastname.is_some())
+             .filter(|m| can_search_users || m.firstname.is_some() || m.lastname.is_some())
              .map(|m| MemberInfo { id: m.id,
                                    firstname: m.firstname.clone().unwrap_or_else(|| "".to_string()),
                                    lastname: m.lastname.clone().unwrap_or_else(|| "".to_string()),
@@ -1840,7 +2798,7 @@ pub fn admin_show_group<T: MedalConnection>(conn: &T, group_id: i32, session_tok
                                             Some(4) => "…",
                                             _ => "?",
                                         }).to_string(),
-                                   grade: grade_to_string(m.grade),
+                                   grade: grades::scale().name(m.grade),
                                    logincode: m.logincode.clone().unwrap_or_else(|| "".to_string()) })
              .collect();
 
@@ -1851,47 +2809,85 @@ pub fn admin_show_group<T: MedalConnection>(conn: &T, group_id: i32, session_tok
     data.insert("groupname".to_string(), to_json(&gi.name));
     data.insert("group_admin_id".to_string(), to_json(&group.admin));
     data.insert("has_protected_participations".to_string(), to_json(&has_protected_participations));
-    data.insert("can_delete".to_string(), to_json(&(!has_protected_participations || session.is_admin())));
+    data.insert("can_delete".to_string(),
+                to_json(&(is_authorized(&session, Action::DeleteGroup, &resource)
+                          && (!has_protected_participations
+                              || is_authorized(&session, Action::DeleteProtected, &Resource::Site)))));
+    data.insert("can_edit".to_string(), to_json(&is_authorized(&session, Action::EditUser, &resource)));
 
     let user = conn.get_user_by_id(group.admin).ok_or(MedalError::AccessDenied)?;
     data.insert("group_admin_firstname".to_string(), to_json(&user.firstname));
     data.insert("group_admin_lastname".to_string(), to_json(&user.lastname));
 
+    let teachers: Vec<GroupAdminInfo> = conn.get_group_admins(group_id)
+                                            .into_iter()
+                                            .map(|admin| GroupAdminInfo { id: admin.id,
+                                                                          firstname: admin.firstname,
+                                                                          lastname: admin.lastname,
+                                                                          accepted: admin.accepted,
+                                                                          is_self: admin.id == session.id })
+                                            .collect();
+    data.insert("teachers".to_string(), to_json(&teachers));
+    data.insert("can_manage_teachers".to_string(),
+                to_json(&is_authorized(&session, Action::ManageGroupAdmins, &resource)));
+
+    if let Some(query) = query_string {
+        if let Some(status) = query.strip_prefix("status=") {
+            if ["Invited",
+                "AlreadyInvited",
+                "InviteeNotFound",
+                "Removed",
+                "Transferred",
+                "Accepted",
+                "LoginLinksRevoked"].contains(&status)
+            {
+                data.insert(status.to_string(), to_json(&true));
+            }
+        }
+    }
+
     Ok(("admin_group".to_string(), data))
 }
 
 pub fn admin_delete_group<T: MedalConnection>(conn: &T, group_id: i32, session_token: &str, csrf_token: &str)
                                               -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
     }
 
     let group = conn.get_group(group_id).unwrap(); // TODO handle error
-
-    if !session.is_admin() {
-        // Check access for teachers
-        if group.admin != session.id {
-            return Err(MedalError::AccessDenied);
-        }
-    }
+    authorize(&session, Action::DeleteGroup, &group_resource(conn, &group))?;
 
     let mut data = json_val::Map::new();
-    if conn.group_has_protected_participations(group_id) && !session.is_admin() {
-        data.insert("reason".to_string(), to_json(&"Gruppe hat Mitglieder mit geschützten Teilnahmen."));
+    if conn.group_has_protected_participations(group_id)
+       && !is_authorized(&session, Action::DeleteProtected, &Resource::Site)
+    {
+        data.insert("reason".to_string(), to_json(&i18n::translate("delete-group-protected-participations")));
         Ok(("delete_fail".to_string(), data))
     } else {
-        conn.delete_all_users_for_group(group_id);
-        conn.delete_group(group_id);
+        let n_members = conn.get_group_complete(group_id).map(|group| group.members.len()).unwrap_or(0);
+        let before = audit_value(&AuditGroup { name: &group.name, groupcode: &group.groupcode, members: n_members });
+        conn.transaction(|| {
+                conn.delete_all_users_for_group(group_id);
+                conn.delete_group(group_id);
+                conn.add_audit_log_entry(AuditLogEntry { target_group: Some(group_id),
+                                                         before: Some(before),
+                                                         ..AuditLogEntry::new(&session, "delete_group") });
+            });
         Ok(("delete_ok".to_string(), data))
     }
 }
 
+/// A group as recorded in the audit log
+#[derive(Serialize)]
+struct AuditGroup<'a> {
+    name: &'a str,
+    groupcode: &'a str,
+    members: usize,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct SubmissionResult {
     id: i32,
@@ -1911,26 +2907,92 @@ struct TaskgroupResult {
     tasks: Vec<TaskResult>,
 }
 
+#[derive(Serialize, Deserialize)]
+struct SubmissionHistoryEntry {
+    id: i32,
+    number: usize,
+    date: String,
+    grade: i32,
+    nonvalidated_grade: i32,
+    validated: bool,
+    needs_validation: bool,
+    compare_left: bool,
+    compare_right: bool,
+}
+
+/// Show all submissions of the user with id `user_id` for the task with id `task_id`.
+///
+/// If `compare` contains the ids of two of these submissions, their values are compared side by side.
+pub fn show_submission_history<T: MedalConnection>(conn: &T, user_id: i32, task_id: i32, session_token: &str,
+                                                   compare: Option<(i32, i32)>)
+                                                   -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    let (user, group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+    authorize(&session, Action::ViewParticipation, &user_resource(conn, &user, group.as_ref()))?;
+
+    let (task, taskgroup, contest) = conn.get_task_by_id_complete(task_id).ok_or(MedalError::UnknownId)?;
+
+    let submissions = conn.get_all_submissions(user_id, task_id, None);
+
+    // Default to comparing the two latest submissions
+    let compare = compare.or_else(|| match submissions.len() {
+                             0 | 1 => None,
+                             n => Some((submissions[n - 2].id.unwrap(), submissions[n - 1].id.unwrap())),
+                         });
+
+    let history: Vec<SubmissionHistoryEntry> =
+        submissions.iter()
+                   .enumerate()
+                   .map(|(n, s)| SubmissionHistoryEntry { id: s.id.unwrap(),
+                                                          number: n + 1,
+                                                          date:
+                                                              self::time::strftime("%e. %b %Y, %H:%M:%S",
+                                                                                   &self::time::at(s.date)).unwrap(),
+                                                          grade: s.grade,
+                                                          nonvalidated_grade: s.nonvalidated_grade,
+                                                          validated: s.validated,
+                                                          needs_validation: s.needs_validation,
+                                                          compare_left: compare.map(|c| c.0) == s.id,
+                                                          compare_right: compare.map(|c| c.1) == s.id })
+                   .collect();
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+    fill_user_data_prefix(&user, &mut data, "user_");
+
+    data.insert("user_id".to_string(), to_json(&user_id));
+    data.insert("task_id".to_string(), to_json(&task_id));
+    data.insert("stars".to_string(), to_json(&task.stars));
+    data.insert("taskgroupname".to_string(), to_json(&taskgroup.name));
+    data.insert("contestid".to_string(), to_json(&contest.id));
+    data.insert("contestname".to_string(), to_json(&contest.name));
+    data.insert("submissions".to_string(), to_json(&history));
+
+    if let Some((left_id, right_id)) = compare {
+        let find = |id| submissions.iter().position(|s| s.id == Some(id)).ok_or(MedalError::UnknownId);
+        let (left, right) = (find(left_id)?, find(right_id)?);
+        let diff =
+            diff::diff_lines(&diff::prettify(&submissions[left].value), &diff::prettify(&submissions[right].value));
+
+        data.insert("compare_left".to_string(), to_json(&history[left]));
+        data.insert("compare_right".to_string(), to_json(&history[right]));
+        match diff {
+            Some(diff) => data.insert("diff".to_string(), to_json(&diff)),
+            None => data.insert("diff_too_large".to_string(), to_json(&true)),
+        };
+    }
+
+    Ok(("submission_history".to_string(), data))
+}
+
 pub fn admin_show_participation<T: MedalConnection>(conn: &T, user_id: i32, contest_id: i32, session_token: &str)
                                                     -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
-
-    let (_, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
-
-    if !session.is_admin() {
-        // Check access for teachers
-        if let Some(ref group) = opt_group {
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
-        } else {
-            return Err(MedalError::AccessDenied);
-        }
-    }
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+    let resource = user_resource(conn, &user, opt_group.as_ref());
+    authorize(&session, Action::ViewParticipation, &resource)?;
 
     let contest = conn.get_contest_by_id_complete(contest_id).ok_or(MedalError::UnknownId)?;
 
@@ -1980,55 +3042,141 @@ pub fn admin_show_participation<T: MedalConnection>(conn: &T, user_id: i32, cont
     data.insert("start_date".to_string(),
                 to_json(&self::time::strftime("%e. %b %Y, %H:%M", &self::time::at(participation.start)).unwrap()));
 
-    data.insert("can_delete".to_string(), to_json(&(!contest.protected || session.is_admin.unwrap_or(false))));
+    let left_secs = participation_left_secs(contest.duration, &participation, time::get_time());
+    data.insert("extra_time".to_string(), to_json(&participation.extra_time));
+    data.insert("is_paused".to_string(), to_json(&participation.paused_since.is_some()));
+    data.insert("is_time_left".to_string(), to_json(&(left_secs >= 0)));
+    let shown_secs = left_secs.max(0);
+    data.insert("time_left_formatted".to_string(),
+                to_json(&format!("{}:{:02}:{:02}", shown_secs / 3600, (shown_secs / 60) % 60, shown_secs % 60)));
+    let can_adjust_time = contest.duration > 0 && is_authorized(&session, Action::AdjustParticipationTime, &resource);
+    data.insert("can_adjust_time".to_string(), to_json(&can_adjust_time));
+
+    data.insert("can_delete".to_string(),
+                to_json(&(is_authorized(&session, Action::DeleteParticipation, &resource)
+                          && (!contest.protected
+                              || is_authorized(&session, Action::DeleteProtected, &Resource::Site)))));
     Ok(("admin_participation".to_string(), data))
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeAdjustment {
+    /// Set the extra time of the participation to the given number of minutes
+    ExtraTime(i32),
+    Pause,
+    Resume,
+    /// Start the timer again from the full duration. Submissions are kept.
+    Restart,
+}
+
+/// Change the timer of the participation of the user with id `user_id` in the contest with id `contest_id`
+pub fn admin_adjust_participation_time<T: MedalConnection>(conn: &T, user_id: i32, contest_id: i32,
+                                                           adjustment: TimeAdjustment, session_token: &str,
+                                                           csrf_token: &str)
+                                                           -> MedalResult<()> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
+    authorize(&session, Action::AdjustParticipationTime, &user_resource(conn, &user, opt_group.as_ref()))?;
+
+    let mut participation = conn.get_participation(user_id, contest_id).ok_or(MedalError::UnknownId)?;
+    let before = participation_time_summary(&participation);
+
+    let now = time::get_time();
+    match adjustment {
+        TimeAdjustment::ExtraTime(minutes) => participation.extra_time = minutes.max(0),
+        TimeAdjustment::Pause => {
+            if participation.paused_since.is_none() {
+                participation.paused_since = Some(now);
+            }
+        }
+        TimeAdjustment::Resume => {
+            if let Some(paused_since) = participation.paused_since.take() {
+                participation.paused_secs += (now.sec - paused_since.sec) as i32;
+            }
+        }
+        TimeAdjustment::Restart => {
+            participation.start = now;
+            participation.paused_secs = 0;
+            participation.paused_since = None;
+        }
+    }
+
+    conn.save_participation_time(&participation);
+    conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                             target_group: user.managed_by,
+                                             target_contest: Some(contest_id),
+                                             before: Some(before),
+                                             after: Some(participation_time_summary(&participation)),
+                                             ..AuditLogEntry::new(&session, "adjust_participation_time") });
+
+    Ok(())
+}
+
+/// Timer state of a participation as recorded in the audit log
+#[derive(Serialize)]
+struct ParticipationTimeSummary {
+    start: i64,
+    extra_time: i32,
+    paused_secs: i32,
+    paused_since: Option<i64>,
+}
+
+/// Language independent JSON summary of the timer of `participation` for the audit log. Times are given in seconds
+/// since the epoch.
+fn participation_time_summary(participation: &Participation) -> String {
+    let summary = ParticipationTimeSummary { start: participation.start.sec,
+                                             extra_time: participation.extra_time,
+                                             paused_secs: participation.paused_secs,
+                                             paused_since: participation.paused_since.map(|since| since.sec) };
+    audit_value(&summary)
+}
+
 pub fn admin_delete_participation<T: MedalConnection>(conn: &T, user_id: i32, contest_id: i32, session_token: &str,
                                                       csrf_token: &str)
                                                       -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_teacher_or_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
     }
 
     let (user, opt_group) = conn.get_user_and_group_by_id(user_id).ok_or(MedalError::AccessDenied)?;
-    let _part = conn.get_participation(user.id, contest_id).ok_or(MedalError::AccessDenied)?;
+    let part = conn.get_participation(user.id, contest_id).ok_or(MedalError::AccessDenied)?;
     let contest = conn.get_contest_by_id_complete(contest_id).ok_or(MedalError::UnknownId)?;
 
-    if !session.is_admin() {
-        // Check access for teachers
-        if contest.protected {
-            return Err(MedalError::AccessDenied);
-        }
-
-        if let Some(group) = opt_group {
-            if group.admin != session.id {
-                return Err(MedalError::AccessDenied);
-            }
-        } else {
-            return Err(MedalError::AccessDenied);
-        }
+    authorize(&session, Action::DeleteParticipation, &user_resource(conn, &user, opt_group.as_ref()))?;
+    if contest.protected {
+        authorize(&session, Action::DeleteProtected, &Resource::Site)?;
     }
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
 
+    let before = audit_value(&AuditParticipation { contest: &contest.name, start: part.start.sec });
     conn.delete_participation(user_id, contest_id);
+    conn.add_audit_log_entry(AuditLogEntry { target_user: Some(user_id),
+                                             target_group: user.managed_by,
+                                             target_contest: Some(contest_id),
+                                             before: Some(before),
+                                             ..AuditLogEntry::new(&session, "delete_participation") });
     Ok(("delete_ok".to_string(), data))
 }
 
+/// A participation as recorded in the audit log. `start` is given in seconds since the epoch.
+#[derive(Serialize)]
+struct AuditParticipation<'a> {
+    contest: &'a str,
+    start: i64,
+}
+
 pub fn admin_show_contests<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ExportResults, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
@@ -2038,37 +3186,381 @@ pub fn admin_show_contests<T: MedalConnection>(conn: &T, session_token: &str) ->
     contests.reverse();
 
     data.insert("contests".to_string(), to_json(&contests));
+    data.insert("can_manage_contests".to_string(),
+                to_json(&is_authorized(&session, Action::ManageContests, &Resource::Site)));
 
     Ok(("admin_contests".to_string(), data))
 }
 
-pub fn admin_contest_export<T: MedalConnection>(conn: &T, contest_id: i32, session_token: &str) -> MedalResult<String> {
-    conn.get_session(&session_token)
-        .ensure_logged_in()
-        .ok_or(MedalError::NotLoggedIn)?
-        .ensure_admin()
-        .ok_or(MedalError::AccessDenied)?;
+pub fn admin_show_contest_export<T: MedalConnection>(conn: &T, contest_id: i32, session_token: &str)
+                                                     -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ExportResults, &Resource::Site)?;
+
+    let contest = conn.get_contest_by_id(contest_id).ok_or(MedalError::UnknownId)?;
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    data.insert("contest_id".to_string(), to_json(&contest_id));
+    data.insert("contestname".to_string(), to_json(&contest.name));
+    data.insert("columns".to_string(), to_json(&export::EXPORT_COLUMNS));
+
+    Ok(("admin_contest_export".to_string(), data))
+}
+
+/// Check that the session belongs to an admin and load the contest to be exported by `export::write_contest_results`.
+///
+/// Returns the complete contest and a file name for the download.
+pub fn admin_contest_export<T: MedalConnection>(conn: &T, contest_id: i32, session_token: &str,
+                                                format: export::ExportFormat)
+                                                -> MedalResult<(Contest, String)> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ExportResults, &Resource::Site)?;
 
     let contest = conn.get_contest_by_id_complete(contest_id).ok_or(MedalError::UnknownId)?;
 
-    let taskgroup_ids: Vec<(i32, String)> =
-        contest.taskgroups.into_iter().map(|tg| (tg.id.unwrap(), tg.name)).collect();
-    let filename = format!("contest_{}__{}__{}.csv",
+    let filename = format!("contest_{}__{}.{}",
                            contest_id,
                            self::time::strftime("%F_%H-%M-%S", &self::time::now()).unwrap(),
-                           helpers::make_filename_secret());
+                           format.extension());
+
+    conn.add_audit_log_entry(AuditLogEntry { target_contest: Some(contest_id),
+                                             after: Some(filename.clone()),
+                                             ..AuditLogEntry::new(&session, "export_contest") });
 
-    conn.export_contest_results_to_file(contest_id, &taskgroup_ids, &format!("./export/{}", filename));
+    Ok((contest, filename))
+}
+
+/// Time range of a report from the dates `from` and `until` (both inclusive, format YYYY-MM-DD). Without dates, the
+/// report covers the current year. Days start at midnight UTC, as the report is grouped by UTC days and hours.
+fn report_range(from: Option<String>, until: Option<String>) -> (time::Timespec, time::Timespec) {
+    let parse_date =
+        |date: &str| -> Option<time::Timespec> { Some(time::strptime(date, "%Y-%m-%d").ok()?.to_timespec()) };
+    let now = time::now_utc();
+    let from = from.as_deref()
+                   .and_then(parse_date)
+                   .unwrap_or_else(|| parse_date(&time::strftime("%Y-01-01", &now).unwrap()).unwrap());
+    let until = until.as_deref()
+                     .and_then(parse_date)
+                     .unwrap_or_else(|| parse_date(&time::strftime("%F", &now).unwrap()).unwrap());
+    (from, until + time::Duration::days(1))
+}
+
+pub fn admin_show_reports<T: MedalConnection>(conn: &T, session_token: &str, from: Option<String>,
+                                              until: Option<String>)
+                                              -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ViewReports, &Resource::Site)?;
+
+    let (from, until) = report_range(from, until);
+    let report = reporting::create_report(conn, from, until);
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    data.insert("report".to_string(), to_json(&report));
+
+    Ok(("admin_reports".to_string(), data))
+}
+
+/// Check that the session may see reports and collect the report to be exported by `reporting::write_report`.
+///
+/// Returns the report and a file name for the download.
+pub fn admin_report_export<T: MedalConnection>(conn: &T, session_token: &str, from: Option<String>,
+                                               until: Option<String>, format: export::ExportFormat)
+                                               -> MedalResult<(reporting::Report, String)> {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ViewReports, &Resource::Site)?;
+
+    let (from, until) = report_range(from, until);
+    let report = reporting::create_report(conn, from, until);
+
+    let filename = format!("report_{}_{}.{}", report.from, report.until, format.extension());
+
+    Ok((report, filename))
+}
+
+#[derive(Serialize, Deserialize)]
+struct AuditLogInfo {
+    id: i32,
+    date: String,
+    actor: Option<i32>,
+    actor_name: Option<String>,
+    action: String,
+    target_user: Option<i32>,
+    target_group: Option<i32>,
+    target_contest: Option<i32>,
+    before: Option<String>,
+    after: Option<String>,
+    before_fields: Vec<AuditLogField>,
+    after_fields: Vec<AuditLogField>,
+}
+
+/// A field of a `before` or `after` value of the audit log. `label` is the message id of its name.
+#[derive(Serialize, Deserialize)]
+struct AuditLogField {
+    label: String,
+    value: String,
+}
+
+/// Fields of the audit log values that are times in seconds since the epoch
+const AUDIT_LOG_TIMESTAMPS: [&str; 2] = ["start", "paused_since"];
+
+/// Language independent JSON value of the `before` or `after` field of the audit log. Its fields are translated when
+/// the log is shown.
+fn audit_value<S: ::serde::Serialize>(value: &S) -> String { serde_json::to_string(value).unwrap() }
+
+/// The fields of an audit log value written by `audit_value`. Older values that are no JSON objects have no fields
+/// and are shown as they are.
+fn audit_log_fields(value: &Option<String>) -> Vec<AuditLogField> {
+    let object = match value.as_ref().and_then(|value| serde_json::from_str(value).ok()) {
+        Some(json_val::Value::Object(object)) => object,
+        _ => return Vec::new(),
+    };
+
+    object.into_iter()
+          .filter(|(_, value)| !value.is_null())
+          .map(|(key, value)| {
+              let value = match value {
+                  json_val::Value::String(value) => value,
+                  json_val::Value::Number(ref time) if AUDIT_LOG_TIMESTAMPS.contains(&key.as_str()) => {
+                      let time = time::Timespec::new(time.as_i64().unwrap_or(0), 0);
+                      self::time::strftime("%F %H:%M:%S", &self::time::at(time)).unwrap()
+                  }
+                  value => value.to_string(),
+              };
+              AuditLogField { label: format!("audit-field-{}", key.replace('_', "-")), value }
+          })
+          .collect()
+}
+
+pub fn admin_show_audit_log<T: MedalConnection>(conn: &T, session_token: &str, user_id: Option<i32>,
+                                                group_id: Option<i32>, from: Option<String>, until: Option<String>)
+                                                -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ViewAuditLog, &Resource::Site)?;
+
+    let parse_date = |date: &Option<String>| -> Option<time::Timespec> {
+        Some(time::strptime(date.as_ref()?, "%Y-%m-%d").ok()?.to_timespec())
+    };
+    let from_date = parse_date(&from);
+    // The end date is inclusive
+    let until_date = parse_date(&until).map(|date| date + time::Duration::days(1));
+
+    let entries: Vec<AuditLogInfo> =
+        conn.search_audit_log(user_id, group_id, from_date, until_date)
+            .into_iter()
+            .map(|entry| AuditLogInfo { id: entry.id.unwrap(),
+                                        date:
+                                            self::time::strftime("%F %H:%M:%S", &self::time::at(entry.date)).unwrap(),
+                                        actor: entry.actor,
+                                        actor_name: entry.actor_name,
+                                        action: entry.action,
+                                        target_user: entry.target_user,
+                                        target_group: entry.target_group,
+                                        target_contest: entry.target_contest,
+                                        before_fields: audit_log_fields(&entry.before),
+                                        after_fields: audit_log_fields(&entry.after),
+                                        before: entry.before,
+                                        after: entry.after })
+            .collect();
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    data.insert("entries".to_string(), to_json(&entries));
+    data.insert("no_results".to_string(), to_json(&entries.is_empty()));
+    data.insert("user_id".to_string(), to_json(&user_id));
+    data.insert("group_id".to_string(), to_json(&group_id));
+    data.insert("from".to_string(), to_json(&from));
+    data.insert("until".to_string(), to_json(&until));
+
+    Ok(("admin_audit".to_string(), data))
+}
+
+#[derive(Default, Serialize)]
+pub struct ContestScanReport {
+    /// Contests that have not been known before
+    pub added: Vec<String>,
+    /// Contests whose definition has been loaded again
+    pub changed: Vec<String>,
+    /// Contests that are not defined anymore and have been hidden
+    pub hidden: Vec<String>,
+    /// Problems of files that could not be loaded. Contests defined in these files are left untouched.
+    pub errors: Vec<String>,
+    /// Number of files that did not change
+    pub unchanged: usize,
+}
+
+impl ContestScanReport {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.hidden.is_empty() && self.errors.is_empty()
+    }
+
+    pub fn summary(&self) -> ContestScanSummary {
+        ContestScanSummary { added: self.added.len(),
+                             changed: self.changed.len(),
+                             hidden: self.hidden.len(),
+                             errors: self.errors.len(),
+                             unchanged: self.unchanged }
+    }
+}
+
+/// Numbers of contests and files of a `ContestScanReport`, as shown on the admin page and recorded in the audit log
+#[derive(Serialize)]
+pub struct ContestScanSummary {
+    pub added: usize,
+    pub changed: usize,
+    pub hidden: usize,
+    pub errors: usize,
+    pub unchanged: usize,
+}
+
+impl std::fmt::Display for ContestScanSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f,
+               "{} added, {} changed, {} hidden, {} errors, {} files unchanged",
+               self.added, self.changed, self.hidden, self.errors, self.unchanged)
+    }
+}
+
+fn contest_description(contest: &Contest) -> String {
+    format!("{} ({}{})", contest.name, contest.location, contest.filename)
+}
+
+/// Serializes the scans of the watcher thread and of the admin page
+static CONTEST_SCAN: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Load the contest and task files in `task_dir` into the database.
+///
+/// Unless `full` is set, only files that changed since the last scan are loaded. Contests of files that have been
+/// removed are hidden. With `full`, every file is loaded again and all contests that are not defined in any file
+/// anymore are hidden, even if the removal of their file has not been recorded. Contests of files that can not be
+/// loaded keep their current version and visibility in both cases.
+pub fn rescan_contests<T: MedalConnection>(conn: &T, task_dir: &str, full: bool) -> ContestScanReport {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    let _scan = CONTEST_SCAN.lock().unwrap_or_else(|e| e.into_inner());
+    let start = std::time::Instant::now();
+    let mut report = ContestScanReport::default();
+
+    let files = contestreader_yaml::get_all_contest_files(task_dir);
+    let known: BTreeMap<(String, String), String> = conn.get_contest_file_hashes()
+                                                        .into_iter()
+                                                        .map(|(location, filename, hash)| ((location, filename), hash))
+                                                        .collect();
+    let old_contests = conn.get_contest_list();
+
+    for file in &files {
+        let unchanged = known.get(&(file.location.clone(), file.filename.clone())) == Some(&file.hash);
+        if unchanged && !full {
+            report.unchanged += 1;
+            continue;
+        }
+
+        let mut new_contests = match file.read_contests() {
+            Ok(contests) => contests,
+            Err(problems) => {
+                report.errors.extend(problems.iter().map(|problem| problem.to_string()));
+                continue;
+            }
+        };
+
+        let old: Vec<&Contest> = old_contests.iter().filter(|contest| file.contains(contest)).collect();
+        let old_ids: Vec<i32> = old.iter().map(|contest| contest.id.unwrap()).collect();
+        conn.replace_contests(&old_ids, &mut new_contests);
+
+        let mut new_filenames = BTreeSet::new();
+        for contest in &new_contests {
+            let existed = old.iter().any(|o| o.filename == contest.filename);
+            if contest.public {
+                new_filenames.insert(contest.filename.clone());
+            }
+            if !existed {
+                report.added.push(contest_description(contest));
+            } else if !unchanged {
+                report.changed.push(contest_description(contest));
+            }
+        }
+        if unchanged {
+            report.unchanged += 1;
+        }
 
-    Ok(filename)
+        report.hidden.extend(old.iter()
+                                .filter(|o| o.public && !new_filenames.contains(&o.filename))
+                                .map(|o| contest_description(o)));
+
+        conn.set_contest_file_hash(&file.location, &file.filename, Some(&file.hash));
+    }
+
+    // Files that have been removed
+    for (location, filename) in known.keys() {
+        if files.iter().any(|file| &file.location == location && &file.filename == filename) {
+            continue;
+        }
+        for contest in old_contests.iter().filter(|c| contestreader_yaml::is_defined_in(c, location, filename)) {
+            conn.hide_contest(contest.id.unwrap());
+            if contest.public {
+                report.hidden.push(contest_description(contest));
+            }
+        }
+        conn.set_contest_file_hash(location, filename, None);
+    }
+
+    // Contests whose file has been removed before its hash was recorded, e.g. by an older version
+    if full {
+        for contest in old_contests.iter().filter(|c| !files.iter().any(|file| file.contains(c))) {
+            if known.keys().any(|(location, filename)| contestreader_yaml::is_defined_in(contest, location, filename)) {
+                continue; // Already hidden above
+            }
+            conn.hide_contest(contest.id.unwrap());
+            if contest.public {
+                report.hidden.push(contest_description(contest));
+            }
+        }
+    }
+
+    metrics::observe_contest_scan(start.elapsed());
+    report
+}
+
+pub fn admin_show_contest_scan<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ManageContests, &Resource::Site)?;
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+
+    Ok(("admin_contest_scan".to_string(), data))
+}
+
+pub fn admin_do_contest_scan<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str, task_dir: &str)
+                                                 -> MedalValueResult {
+    let session = conn.get_session(session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::ManageContests, &Resource::Site)?;
+
+    if session.csrf_token != csrf_token {
+        return Err(MedalError::CsrfCheckFailed);
+    }
+
+    let report = rescan_contests(conn, task_dir, false);
+    let summary = report.summary();
+    conn.add_audit_log_entry(AuditLogEntry { after: Some(audit_value(&summary)),
+                                             ..AuditLogEntry::new(&session, "rescan_contests") });
+
+    let mut data = json_val::Map::new();
+    fill_user_data(&session, &mut data);
+    data.insert("summary".to_string(), to_json(&summary));
+    data.insert("report".to_string(), to_json(&report));
+    data.insert("scanned".to_string(), to_json(&true));
+
+    Ok(("admin_contest_scan".to_string(), data))
 }
 
 pub fn admin_show_cleanup<T: MedalConnection>(conn: &T, session_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::Cleanup, &Resource::Site)?;
 
     let mut data = json_val::Map::new();
     fill_user_data(&session, &mut data);
@@ -2076,12 +3568,18 @@ pub fn admin_show_cleanup<T: MedalConnection>(conn: &T, session_token: &str) ->
     Ok(("admin_cleanup".to_string(), data))
 }
 
+/// Numbers of deleted accounts as recorded in the audit log
+#[derive(Serialize)]
+struct AuditCleanup {
+    students: i32,
+    groups: i32,
+    teachers: i32,
+    others: i32,
+}
+
 pub fn admin_do_cleanup<T: MedalConnection>(conn: &T, session_token: &str, csrf_token: &str) -> MedalValueResult {
-    let session = conn.get_session(&session_token)
-                      .ensure_logged_in()
-                      .ok_or(MedalError::NotLoggedIn)?
-                      .ensure_admin()
-                      .ok_or(MedalError::AccessDenied)?;
+    let session = conn.get_session(&session_token).ensure_logged_in().ok_or(MedalError::NotLoggedIn)?;
+    authorize(&session, Action::Cleanup, &Resource::Site)?;
 
     if session.csrf_token != csrf_token {
         return Err(MedalError::CsrfCheckFailed);
@@ -2096,12 +3594,15 @@ pub fn admin_do_cleanup<T: MedalConnection>(conn: &T, session_token: &str, csrf_
 
     let mut data = json_val::Map::new();
     if let Ok((n_user, n_group, n_teacher, n_other)) = result {
+        let after =
+            audit_value(&AuditCleanup { students: n_user, groups: n_group, teachers: n_teacher, others: n_other });
+        conn.add_audit_log_entry(AuditLogEntry { after: Some(after), ..AuditLogEntry::new(&session, "cleanup") });
         let infodata = format!(",\"n_user\":{},\"n_group\":{},\"n_teacher\":{},\"n_other\":{}",
                                n_user, n_group, n_teacher, n_other);
         data.insert("data".to_string(), to_json(&infodata));
         Ok(("delete_ok".to_string(), data))
     } else {
-        data.insert("reason".to_string(), to_json(&"Fehler."));
+        data.insert("reason".to_string(), to_json(&i18n::translate("cleanup-failed")));
         Ok(("delete_fail".to_string(), data))
     }
 }
@@ -2118,7 +3619,7 @@ pub fn do_session_cleanup<T: MedalConnection>(conn: &T) -> MedalValueResult {
         data.insert("data".to_string(), to_json(&infodata));
         Ok(("delete_ok".to_string(), data))
     } else {
-        data.insert("reason".to_string(), to_json(&"Fehler."));
+        data.insert("reason".to_string(), to_json(&i18n::translate("cleanup-failed")));
         Ok(("delete_fail".to_string(), data))
     }
 }
@@ -2146,18 +3647,24 @@ pub struct ForeignUserData {
 
 pub fn login_oauth<T: MedalConnection>(conn: &T, user_data: ForeignUserData, oauth_provider_id: String)
                                        -> Result<(String, bool), (String, json_val::Map<String, json_val::Value>)> {
+    let is_teacher = user_data.foreign_type != UserType::User;
+    // Grade of new accounts
+    let grade = if is_teacher { grades::scale().teacher_grade() } else { 0 };
+    let sex = match user_data.sex {
+        UserSex::Male => Some(1),
+        UserSex::Female => Some(2),
+        UserSex::Unknown => Some(0),
+    };
     match conn.login_foreign(None,
                              &oauth_provider_id,
                              &user_data.foreign_id,
-                             (user_data.foreign_type != UserType::User,
+                             (is_teacher,
                               user_data.foreign_type == UserType::Admin,
                               &user_data.firstname,
                               &user_data.lastname,
-                              match user_data.sex {
-                                  UserSex::Male => Some(1),
-                                  UserSex::Female => Some(2),
-                                  UserSex::Unknown => Some(0),
-                              })) {
+                              sex,
+                              grade))
+    {
         Ok((session_token, last_activity)) => {
             let redirect_profile = if let Some(last_activity) = last_activity {
                 let now = time::get_time();
@@ -2169,7 +3676,7 @@ pub fn login_oauth<T: MedalConnection>(conn: &T, user_data: ForeignUserData, oau
         }
         Err(()) => {
             let mut data = json_val::Map::new();
-            data.insert("reason".to_string(), to_json(&"OAuth-Login failed.".to_string()));
+            data.insert("reason".to_string(), to_json(&i18n::translate("oauth-login-failed")));
             Err(("login".to_owned(), data))
         }
     }
@@ -77,15 +77,18 @@ impl MedalObject<Connection> for Task {
         let id = match self.get_id() {
             Some(id) => {
                 let query = "UPDATE task
-                             SET taskgroup = $1, location = $2, language = $3, stars = $4
-                             WHERE id = $5";
-                conn.execute(query, &[&self.taskgroup, &self.location, &self.language, &self.stars, &id]).unwrap();
+                             SET taskgroup = $1, location = $2, language = $3, stars = $4, checker = $5
+                             WHERE id = $6";
+                conn.execute(query,
+                             &[&self.taskgroup, &self.location, &self.language, &self.stars, &self.checker, &id])
+                    .unwrap();
                 id
             }
             None => {
-                let query = "INSERT INTO task (taskgroup, location, language, stars)
-                             VALUES ($1, $2, $3, $4)";
-                conn.execute(query, &[&self.taskgroup, &self.location, &self.language, &self.stars]).unwrap();
+                let query = "INSERT INTO task (taskgroup, location, language, stars, checker)
+                             VALUES ($1, $2, $3, $4, $5)";
+                conn.execute(query, &[&self.taskgroup, &self.location, &self.language, &self.stars, &self.checker])
+                    .unwrap();
                 conn.get_last_id().unwrap()
             }
         };
@@ -134,116 +137,167 @@ impl MedalObject<Connection> for Taskgroup {
 }
 
 impl MedalObject<Connection> for Contest {
-    fn save(&mut self, conn: &Connection) {
-        let query = "SELECT id
-                     FROM contest
-                     WHERE location = $1
-                     AND filename = $2";
-        conn.query_map_one(query, &[&self.location, &self.filename], |row| row.get(0))
-            .unwrap_or(None)
-            .map(|id| {
-                self.set_id(id);
-            })
-            .unwrap_or(()); // Err means no entry yet and is expected result
-
-        let id = match self.get_id() {
-            Some(id) => {
-                let query = "DELETE FROM contest_tags
-                             WHERE id = $1";
-                conn.execute(query, &[&id]).unwrap();
-
-                let query = "UPDATE contest
-                             SET location = $2,filename = $3, name = $4, duration = $5, public = $6, start_date = $7,
-                                 end_date = $8, review_start_date = $9, review_end_date = $10, min_grade = $11,
-                                 max_grade = $12, positionalnumber = $13, protected = $14, requires_login = $15,
-                                 requires_contest = $16, secret = $17, message = $18, image = $19, language = $20,
-                                 category = $21, standalone_task = $22
-                             WHERE id = $1";
-                conn.execute(query,
-                             &[&id,
-                               &self.location,
-                               &self.filename,
-                               &self.name,
-                               &self.duration,
-                               &self.public,
-                               &self.start,
-                               &self.end,
-                               &self.review_start,
-                               &self.review_end,
-                               &self.min_grade,
-                               &self.max_grade,
-                               &self.positionalnumber,
-                               &self.protected,
-                               &self.requires_login,
-                               &self.requires_contest,
-                               &self.secret,
-                               &self.message,
-                               &self.image,
-                               &self.language,
-                               &self.category,
-                               &self.standalone_task])
-                    .unwrap();
-                id
-            }
-            None => {
-                let query = "INSERT INTO contest (location, filename, name, duration, public, start_date, end_date,
-                                                  review_start_date, review_end_date, min_grade, max_grade,
-                                                  positionalnumber, protected, requires_login, requires_contest, secret,
-                                                  message, image, language, category, standalone_task)
-                             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)";
-                conn.execute(query,
-                             &[&self.location,
-                               &self.filename,
-                               &self.name,
-                               &self.duration,
-                               &self.public,
-                               &self.start,
-                               &self.end,
-                               &self.review_start,
-                               &self.review_end,
-                               &self.min_grade,
-                               &self.max_grade,
-                               &self.positionalnumber,
-                               &self.protected,
-                               &self.requires_login,
-                               &self.requires_contest,
-                               &self.secret,
-                               &self.message,
-                               &self.image,
-                               &self.language,
-                               &self.category,
-                               &self.standalone_task])
-                    .unwrap();
-                conn.get_last_id().unwrap()
-            }
-        };
-        self.set_id(id);
+    fn save(&mut self, conn: &Connection) { conn.transaction(|| save_contest(self, conn)); }
+}
 
-        if self.tags.len() > 0 {
-            let tagstring = self.tags.join(",");
-            let query = "INSERT INTO contest_tags (id, tags)
-                         VALUES ($1, $2)";
-            conn.execute(query, &[&id, &tagstring]).unwrap();
+/// Save `contest` with its taskgroups and tasks as part of an already running transaction
+fn save_contest(contest: &mut Contest, conn: &Connection) {
+    let query = "SELECT id
+                 FROM contest
+                 WHERE location = $1
+                 AND filename = $2";
+    conn.query_map_one(query, &[&contest.location, &contest.filename], |row| row.get(0))
+        .unwrap_or(None)
+        .map(|id| {
+            contest.set_id(id);
+        })
+        .unwrap_or(()); // Err means no entry yet and is expected result
+
+    let id = match contest.get_id() {
+        Some(id) => {
+            let query = "DELETE FROM contest_tags
+                         WHERE id = $1";
+            conn.execute(query, &[&id]).unwrap();
+
+            let query = "UPDATE contest
+                         SET location = $2,filename = $3, name = $4, duration = $5, public = $6, start_date = $7,
+                             end_date = $8, review_start_date = $9, review_end_date = $10, min_grade = $11,
+                             max_grade = $12, positionalnumber = $13, protected = $14, requires_login = $15,
+                             requires_contest = $16, secret = $17, message = $18, image = $19, language = $20,
+                             category = $21, standalone_task = $22, tie_breaking = $23, public_scoreboard = $24,
+                             qualification = $25
+                         WHERE id = $1";
+            conn.execute(query,
+                         &[&id,
+                           &contest.location,
+                           &contest.filename,
+                           &contest.name,
+                           &contest.duration,
+                           &contest.public,
+                           &contest.start,
+                           &contest.end,
+                           &contest.review_start,
+                           &contest.review_end,
+                           &contest.min_grade,
+                           &contest.max_grade,
+                           &contest.positionalnumber,
+                           &contest.protected,
+                           &contest.requires_login,
+                           &contest.requires_contest,
+                           &contest.secret,
+                           &contest.message,
+                           &contest.image,
+                           &contest.language,
+                           &contest.category,
+                           &contest.standalone_task,
+                           &contest.tie_breaking,
+                           &contest.public_scoreboard,
+                           &contest.qualification])
+                .unwrap();
+            id
         }
-
-        for taskgroup in &mut self.taskgroups {
-            taskgroup.contest = id;
-            taskgroup.save(conn);
+        None => {
+            let query = "INSERT INTO contest (location, filename, name, duration, public, start_date, end_date,
+                                              review_start_date, review_end_date, min_grade, max_grade,
+                                              positionalnumber, protected, requires_login, requires_contest, secret,
+                                              message, image, language, category, standalone_task, tie_breaking,
+                                              public_scoreboard, qualification)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+                                 $22, $23, $24)";
+            conn.execute(query,
+                         &[&contest.location,
+                           &contest.filename,
+                           &contest.name,
+                           &contest.duration,
+                           &contest.public,
+                           &contest.start,
+                           &contest.end,
+                           &contest.review_start,
+                           &contest.review_end,
+                           &contest.min_grade,
+                           &contest.max_grade,
+                           &contest.positionalnumber,
+                           &contest.protected,
+                           &contest.requires_login,
+                           &contest.requires_contest,
+                           &contest.secret,
+                           &contest.message,
+                           &contest.image,
+                           &contest.language,
+                           &contest.category,
+                           &contest.standalone_task,
+                           &contest.tie_breaking,
+                           &contest.public_scoreboard,
+                           &contest.qualification])
+                .unwrap();
+            conn.get_last_id().unwrap()
         }
-        {
-            use std::io::Write;
-            print!(",");
-            std::io::stdout().flush().unwrap();
+    };
+    contest.set_id(id);
+
+    if !contest.tags.is_empty() {
+        let tagstring = contest.tags.join(",");
+        let query = "INSERT INTO contest_tags (id, tags)
+                     VALUES ($1, $2)";
+        conn.execute(query, &[&id, &tagstring]).unwrap();
+    }
+
+    for taskgroup in &mut contest.taskgroups {
+        taskgroup.contest = id;
+        taskgroup.save(conn);
+    }
+
+    {
+        use std::io::Write;
+        print!(",");
+        std::io::stdout().flush().unwrap();
+    }
+}
+
+/// Rolls back the transaction if it is dropped before `commit`, which happens while unwinding from a panic
+struct TransactionGuard<'a> {
+    conn: &'a Connection,
+    committed: bool,
+}
+
+impl<'a> TransactionGuard<'a> {
+    fn begin(conn: &'a Connection) -> Self {
+        conn.batch_execute("BEGIN").unwrap();
+        TransactionGuard { conn, committed: false }
+    }
+
+    fn commit(mut self) {
+        // If the COMMIT fails, the transaction is still open and has to be rolled back on drop
+        self.conn.batch_execute("COMMIT").unwrap();
+        self.committed = true;
+    }
+}
+
+impl Drop for TransactionGuard<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            // Panicking again while unwinding would abort, so errors are ignored
+            let _ = self.conn.batch_execute("ROLLBACK");
         }
     }
 }
 
 impl MedalConnection for Connection {
     fn reconnect(config: &config::Config) -> Self { Self::reconnect_concrete(config) }
+    fn connect_reader(config: &config::Config) -> Option<Self> { Self::connect_reader_concrete(config) }
 
     fn dbtype(&self) -> &'static str { "postgres" }
 
+    fn transaction<T, F>(&self, f: F) -> T
+        where F: FnOnce() -> T {
+        let guard = TransactionGuard::begin(self);
+        let result = f();
+        guard.commit();
+        result
+    }
+
     fn migration_already_applied(&self, name: &str) -> bool {
+        let _timer = DbMethodTimer::start("migration_already_applied");
         let create_string = "CREATE TABLE IF NOT EXISTS migrations (name TEXT PRIMARY KEY);";
         self.execute(create_string, &[]).unwrap();
 
@@ -252,6 +306,7 @@ impl MedalConnection for Connection {
     }
 
     fn apply_migration(&mut self, name: &str, contents: &str) {
+        let _timer = DbMethodTimer::start("apply_migration");
         print!("Applying migration `{}` … ", name);
 
         let tx = self.transaction().unwrap();
@@ -265,6 +320,7 @@ impl MedalConnection for Connection {
     }
 
     fn code_exists(&self, code: &str) -> bool {
+        let _timer = DbMethodTimer::start("code_exists");
         let query = "SELECT (
                        SELECT COUNT(*) FROM session WHERE logincode = $1
                      ) + (
@@ -278,9 +334,11 @@ impl MedalConnection for Connection {
 
     // fn get_session<T: ToSql>(&self, key: T, keyname: &str) -> Option<SessionUser> {
     fn get_session(&self, key: &str) -> Option<SessionUser> {
+        let _timer = DbMethodTimer::start("get_session");
         let query = "SELECT id, csrf_token, last_login, last_activity, account_created, username, password,
                             salt, logincode, email, email_unconfirmed, email_confirmationcode, firstname, lastname, street,
-                            zip, city, nation, grade, sex, is_admin, is_teacher, managed_by, oauth_provider, oauth_foreign_id
+                            zip, city, nation, grade, sex, is_admin, is_teacher, managed_by, oauth_provider, oauth_foreign_id,
+                            language
                      FROM session
                      WHERE session_token = $1";
         let session = self.query_map_one(query, &[&key], |row| SessionUser { id: row.get(0),
@@ -312,8 +370,11 @@ impl MedalConnection for Connection {
                                                                              managed_by: row.get(22),
 
                                                                              oauth_provider: row.get(23),
-                                                                             oauth_foreign_id: row.get(24) })
+                                                                             oauth_foreign_id: row.get(24),
+                                                                             language: row.get(25),
+                                                                             roles: Vec::new() })
                           .ok()??;
+        let session = SessionUser { roles: self.get_user_roles(session.id), ..session };
 
         let session_duration = Duration::hours(12);
         let mimimal_activity_update_duration = Duration::minutes(3);
@@ -321,7 +382,8 @@ impl MedalConnection for Connection {
 
         if let Some(last_activity) = session.last_activity {
             if now < last_activity + session_duration {
-                if now > last_activity + mimimal_activity_update_duration {
+                // Read-only connections skip the update. The activity is then updated by a later request.
+                if now > last_activity + mimimal_activity_update_duration && !self.is_read_only() {
                     let query = "UPDATE session
                                  SET last_activity = $1
                                  WHERE id = $2";
@@ -338,7 +400,15 @@ impl MedalConnection for Connection {
         // TODO: What should happen here?
         None
     }
+    fn get_session_language(&self, key: &str) -> Option<String> {
+        let _timer = DbMethodTimer::start("get_session_language");
+        let query = "SELECT language
+                     FROM session
+                     WHERE session_token = $1";
+        self.query_map_one(query, &[&key], |row| row.get::<_, Option<String>>(0)).ok()??
+    }
     fn save_session(&self, session: SessionUser) {
+        let _timer = DbMethodTimer::start("save_session");
         self.execute("UPDATE session
                       SET username = $1,
                           password = $2,
@@ -355,8 +425,10 @@ impl MedalConnection for Connection {
                           is_teacher = $13,
                           managed_by = $14,
                           email = $15,
-                          email_unconfirmed = $16
-                      WHERE id = $17",
+                          email_unconfirmed = $16,
+                          email_confirmationcode = $17,
+                          language = $18
+                      WHERE id = $19",
                      &[&session.username,
                        &session.password,
                        &session.salt,
@@ -373,10 +445,13 @@ impl MedalConnection for Connection {
                        &session.managed_by,
                        &session.email,
                        &session.email_unconfirmed,
+                       &session.email_confirmationcode,
+                       &session.language,
                        &session.id])
             .unwrap();
     }
     fn new_session(&self, session_token: &str) -> SessionUser {
+        let _timer = DbMethodTimer::start("new_session");
         let csrf_token = helpers::make_csrf_token();
 
         let now = time::get_time();
@@ -392,12 +467,14 @@ impl MedalConnection for Connection {
     }
     fn session_set_activity_dates(&self, session_id: i32, account_created: Option<time::Timespec>,
                                   last_login: Option<time::Timespec>, last_activity: Option<time::Timespec>) {
+        let _timer = DbMethodTimer::start("session_set_activity_dates");
         let query = "UPDATE session
                      SET account_created = $2, last_login = $3, last_activity = $4
                      WHERE id = $1";
         self.execute(query, &[&session_id, &account_created, &last_login, &last_activity]).unwrap();
     }
     fn get_session_or_new(&self, key: &str) -> Result<SessionUser, ()> {
+        let _timer = DbMethodTimer::start("get_session_or_new");
         fn disable_old_session_and_create_new(conn: &Connection, key: &str) -> Result<SessionUser, ()> {
             let query = "UPDATE session
                          SET session_token = $1
@@ -415,10 +492,11 @@ impl MedalConnection for Connection {
     }
 
     fn get_user_by_id(&self, user_id: i32) -> Option<SessionUser> {
+        let _timer = DbMethodTimer::start("get_user_by_id");
         let query = "SELECT session_token, csrf_token, last_login, last_activity, account_created, username, password,
                             salt, logincode, email, email_unconfirmed, email_confirmationcode, firstname, lastname,
                             street, zip, city, nation, grade, sex, is_admin, is_teacher, managed_by, oauth_provider,
-                            oauth_foreign_id
+                            oauth_foreign_id, language
                      FROM session
                      WHERE id = $1";
         self.query_map_one(query, &[&user_id], |row| SessionUser { id: user_id,
@@ -450,11 +528,41 @@ impl MedalConnection for Connection {
                                                                    managed_by: row.get(22),
 
                                                                    oauth_provider: row.get(23),
-                                                                   oauth_foreign_id: row.get(24) })
+                                                                   oauth_foreign_id: row.get(24),
+                                                                   language: row.get(25),
+                                                                   roles: Vec::new() })
             .ok()?
+            .map(|user| SessionUser { roles: self.get_user_roles(user_id), ..user })
+    }
+
+    fn get_user_roles(&self, user_id: i32) -> Vec<Role> {
+        let _timer = DbMethodTimer::start("get_user_roles");
+        let query = "SELECT role
+                     FROM session_role
+                     WHERE session = $1";
+        let mut roles: Vec<Role> = self.query_map_many(query, &[&user_id], |row| row.get::<_, String>(0))
+                                       .unwrap()
+                                       .iter()
+                                       .filter_map(|name| Role::from_name(name))
+                                       .collect();
+        roles.sort();
+        roles
+    }
+    fn set_user_roles(&self, user_id: i32, roles: &[Role]) {
+        let _timer = DbMethodTimer::start("set_user_roles");
+        let query = "DELETE FROM session_role
+                     WHERE session = $1";
+        self.execute(query, &[&user_id]).unwrap();
+
+        let query = "INSERT INTO session_role (session, role)
+                     VALUES ($1, $2)";
+        for role in roles {
+            self.execute(query, &[&user_id, &role.name()]).unwrap();
+        }
     }
 
     fn get_user_and_group_by_id(&self, user_id: i32) -> Option<(SessionUser, Option<Group>)> {
+        let _timer = DbMethodTimer::start("get_user_and_group_by_id");
         let session = self.get_user_by_id(user_id)?;
 
         let group_id = match session.managed_by {
@@ -480,6 +588,7 @@ impl MedalConnection for Connection {
 
     //TODO: use session
     fn login(&self, _session: Option<&str>, username: &str, password: &str) -> Result<String, ()> {
+        let _timer = DbMethodTimer::start("login");
         let query = "SELECT id, password, salt
                      FROM session
                      WHERE username = $1";
@@ -515,6 +624,7 @@ impl MedalConnection for Connection {
 
     //TODO: use session
     fn login_with_code(&self, _session: Option<&str>, logincode: &str) -> Result<String, ()> {
+        let _timer = DbMethodTimer::start("login_with_code");
         if logincode == "" {
             return Err(());
         }
@@ -543,8 +653,14 @@ impl MedalConnection for Connection {
 
     //TODO: use session
     fn login_foreign(&self, _session: Option<&str>, provider_id: &str, foreign_id: &str,
-                     (is_teacher, is_admin, firstname, lastname, sex): (bool, bool, &str, &str, Option<i32>))
+                     (is_teacher, is_admin, firstname, lastname, sex, grade): (bool,
+                      bool,
+                      &str,
+                      &str,
+                      Option<i32>,
+                      i32))
                      -> Result<(String, Option<time::Timespec>), ()> {
+        let _timer = DbMethodTimer::start("login_foreign");
         let session_token = helpers::make_session_token();
         let csrf_token = helpers::make_csrf_token();
         let now = time::get_time();
@@ -587,7 +703,7 @@ impl MedalConnection for Connection {
                                &now,
                                &now,
                                &now,
-                               &(if is_teacher { 255 } else { 0 }),
+                               &grade,
                                &sex,
                                &is_teacher,
                                &is_admin,
@@ -604,6 +720,7 @@ impl MedalConnection for Connection {
 
     //TODO: use session
     fn create_user_with_groupcode(&self, _session: Option<&str>, groupcode: &str) -> Result<String, ()> {
+        let _timer = DbMethodTimer::start("create_user_with_groupcode");
         if groupcode == "" {
             return Err(());
         }
@@ -642,53 +759,101 @@ impl MedalConnection for Connection {
         Ok(session_token)
     }
 
-    fn create_group_with_users(&self, mut group: Group) {
-        // Generate group ID:
-        group.save(self);
+    fn create_group_with_users(&self, group: Group) -> i32 {
+        self.transaction(|| self.create_groups_with_users(vec![group])[0])
+    }
 
+    fn create_groups_with_users(&self, groups: Vec<Group>) -> Vec<i32> {
+        let _timer = DbMethodTimer::start("create_groups_with_users");
         let now = time::get_time();
 
-        for user in group.members {
-            let csrf_token = helpers::make_csrf_token();
-
-            let mut logincode = String::new();
-            for i in 0..10 {
-                if i == 9 {
-                    panic!("ERROR: Too many logincode collisions! Give up ...");
-                }
-                logincode = helpers::make_logincode();
-                if !self.code_exists(&logincode) {
-                    break;
-                }
-                println!("WARNING: Logincode collision! Retrying ...");
-            }
-
-            let query = "INSERT INTO session (firstname, lastname, csrf_token, account_created, logincode, grade, sex,
-                                              is_teacher, managed_by)
-                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)";
-            self.execute(query,
-                         &[&user.firstname,
-                           &user.lastname,
-                           &csrf_token,
-                           &now,
-                           &logincode,
-                           &user.grade,
-                           &user.sex,
-                           &false,
-                           &group.id])
-                .unwrap();
-        }
+        groups.into_iter()
+              .map(|mut group| {
+                  // Generate group ID:
+                  group.save(self);
+
+                  for user in group.members {
+                      let csrf_token = helpers::make_csrf_token();
+
+                      let mut logincode = String::new();
+                      for i in 0..10 {
+                          if i == 9 {
+                              panic!("ERROR: Too many logincode collisions! Give up ...");
+                          }
+                          logincode = helpers::make_logincode();
+                          if !self.code_exists(&logincode) {
+                              break;
+                          }
+                          println!("WARNING: Logincode collision! Retrying ...");
+                      }
+
+                      let query = "INSERT INTO session (firstname, lastname, csrf_token, account_created,
+                                                        logincode, grade, sex, is_teacher, managed_by)
+                                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)";
+                      self.execute(query,
+                                   &[&user.firstname,
+                                     &user.lastname,
+                                     &csrf_token,
+                                     &now,
+                                     &logincode,
+                                     &user.grade,
+                                     &user.sex,
+                                     &false,
+                                     &group.id])
+                          .unwrap();
+                  }
+
+                  group.id.unwrap()
+              })
+              .collect()
     }
 
     fn logout(&self, session: &str) {
+        let _timer = DbMethodTimer::start("logout");
         let query = "UPDATE session
                      SET session_token = NULL
                      WHERE session_token = $1";
         self.execute(query, &[&session]).unwrap();
     }
 
+    fn get_web_session(&self, web_session_id: &str) -> Vec<(String, String)> {
+        let _timer = DbMethodTimer::start("get_web_session");
+        let query = "SELECT key, value
+                     FROM web_session
+                     WHERE id = $1";
+        self.query_map_many(query, &[&web_session_id], |row| (row.get(0), row.get(1))).unwrap()
+    }
+
+    fn save_web_session(&self, web_session_id: &str, values: &[(String, String)]) {
+        let _timer = DbMethodTimer::start("save_web_session");
+        // Concurrent requests of the same web session must not mix their values
+        self.transaction(|| {
+                let query = "DELETE FROM web_session
+                             WHERE id = $1";
+                self.execute(query, &[&web_session_id]).unwrap();
+
+                let now = time::get_time();
+                let query = "INSERT INTO web_session (id, key, value, last_change)
+                             VALUES ($1, $2, $3, $4)";
+                for (key, value) in values {
+                    self.execute(query, &[&web_session_id, key, value, &now]).unwrap();
+                }
+            })
+    }
+
+    fn remove_web_sessions_with_value(&self, key: &str, value: &str) {
+        let _timer = DbMethodTimer::start("remove_web_sessions_with_value");
+        let query = "DELETE FROM web_session
+                     WHERE id IN (SELECT id
+                                  FROM web_session
+                                  WHERE key = $1
+                                  AND value = $2)";
+        self.execute(query, &[&key, &value]).unwrap();
+    }
+
     fn signup(&self, session_token: &str, username: &str, email: &str, password_hash: String, salt: &str)
               -> SignupResult {
+        let _timer = DbMethodTimer::start("signup");
         let mut session_user = self.get_session_or_new(&session_token).unwrap();
 
         if session_user.is_logged_in() {
@@ -703,9 +868,9 @@ impl MedalConnection for Connection {
             //This username already exists!
             return SignupResult::UsernameTaken;
         }
-        if let Ok(None) = self.query_map_one("SELECT email, email_unconfirmed FROM session WHERE email = $1 OR email_unconfirmed = $1",
+        if let Ok(None) = self.query_map_one("SELECT email FROM session WHERE email = $1",
                                              &[&email],
-                                             |row| -> (Option<String>, Option<String>) { (row.get(0), row.get(1)) })
+                                             |row| -> Option<String> { row.get(0) })
         {
         } else {
             //This email already exists!
@@ -721,7 +886,84 @@ impl MedalConnection for Connection {
         SignupResult::SignedUp
     }
 
+    fn get_user_id_by_username(&self, username: &str) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_user_id_by_username");
+        let query = "SELECT id
+                     FROM session
+                     WHERE username = $1";
+        self.query_map_one(query, &[&username], |row| row.get(0)).ok()?
+    }
+
+    fn get_user_id_by_logincode(&self, logincode: &str) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_user_id_by_logincode");
+        let query = "SELECT id
+                     FROM session
+                     WHERE logincode = $1";
+        self.query_map_one(query, &[&logincode], |row| row.get(0)).ok()?
+    }
+
+    fn get_user_id_by_email(&self, email: &str) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_user_id_by_email");
+        let query = "SELECT id
+                     FROM session
+                     WHERE email = $1
+                     LIMIT 1";
+        self.query_map_one(query, &[&email], |row| row.get(0)).ok()?
+    }
+
+    fn get_user_id_by_email_confirmationcode(&self, code: &str) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_user_id_by_email_confirmationcode");
+        let query = "SELECT id
+                     FROM session
+                     WHERE email_confirmationcode = $1";
+        self.query_map_one(query, &[&code], |row| row.get(0)).ok()?
+    }
+
+    fn add_password_reset_token(&self, user_id: i32, token_hash: &str) {
+        let _timer = DbMethodTimer::start("add_password_reset_token");
+        let query = "INSERT INTO password_reset (token_hash, session, created)
+                     VALUES ($1, $2, $3)";
+        self.execute(query, &[&token_hash, &user_id, &time::get_time()]).unwrap();
+    }
+
+    fn get_user_id_by_password_reset_token(&self, token_hash: &str, min_created: time::Timespec) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_user_id_by_password_reset_token");
+        let query = "SELECT session
+                     FROM password_reset
+                     WHERE token_hash = $1
+                     AND created >= $2";
+        self.query_map_one(query, &[&token_hash, &min_created], |row| row.get(0)).ok()?
+    }
+
+    fn remove_password_reset_tokens(&self, user_id: i32) {
+        let _timer = DbMethodTimer::start("remove_password_reset_tokens");
+        let query = "DELETE FROM password_reset
+                     WHERE session = $1";
+        self.execute(query, &[&user_id]).unwrap();
+    }
+
+    fn get_login_link_generation(&self, user_id: i32) -> i32 {
+        let _timer = DbMethodTimer::start("get_login_link_generation");
+        let query = "SELECT generation
+                     FROM login_link
+                     WHERE session = $1";
+        self.query_map_one(query, &[&user_id], |row| row.get(0)).unwrap().unwrap_or(0)
+    }
+
+    fn revoke_login_links(&self, user_id: i32) {
+        let _timer = DbMethodTimer::start("revoke_login_links");
+        let generation = self.get_login_link_generation(user_id) + 1;
+        let query = "DELETE FROM login_link
+                     WHERE session = $1";
+        self.execute(query, &[&user_id]).unwrap();
+
+        let query = "INSERT INTO login_link (session, generation)
+                     VALUES ($1, $2)";
+        self.execute(query, &[&user_id, &generation]).unwrap();
+    }
+
     fn load_submission(&self, session: &SessionUser, task: i32, subtask: Option<&str>) -> Option<Submission> {
+        let _timer = DbMethodTimer::start("load_submission");
         match subtask {
             None => {
                 let query = "SELECT id, grade, validated, nonvalidated_grade, value, date, needs_validation
@@ -767,6 +1009,7 @@ impl MedalConnection for Connection {
         }
     }
     fn get_all_submissions(&self, session_id: i32, task: i32, subtask: Option<&str>) -> Vec<Submission> {
+        let _timer = DbMethodTimer::start("get_all_submissions");
         match subtask {
             None => {
                 let query = "SELECT id, grade, validated, nonvalidated_grade, value, date, needs_validation
@@ -790,16 +1033,30 @@ impl MedalConnection for Connection {
         }
     }
     fn submit_submission(&self, mut submission: Submission) {
-        submission.save(self);
+        let _timer = DbMethodTimer::start("submit_submission");
+        self.transaction(|| {
+                submission.save(self);
+
+                // The grade claimed by the browser only counts once the checker of the task has validated it, see
+                // `validate_submission`
+                let query = "SELECT 1
+                             FROM task
+                             WHERE id = $1
+                             AND checker IS NOT NULL";
+                if submission.needs_validation && self.exists(query, &[&submission.task]) {
+                    return;
+                }
 
-        let mut grade = self.get_grade_by_submission(submission.id.unwrap());
-        if grade.grade.is_none() || submission.grade > grade.grade.unwrap() {
-            grade.grade = Some(submission.grade);
-            grade.validated = false;
-            grade.save(self);
-        }
+                let mut grade = self.get_grade_by_submission(submission.id.unwrap());
+                if grade.grade.is_none() || submission.grade > grade.grade.unwrap() {
+                    grade.grade = Some(submission.grade);
+                    grade.validated = false;
+                    grade.save(self);
+                }
+            })
     }
     fn get_grade_by_submission(&self, submission_id: i32) -> Grade {
+        let _timer = DbMethodTimer::start("get_grade_by_submission");
         let query = "SELECT grade.taskgroup, grade.session, grade.grade, grade.validated
                      FROM grade
                      JOIN task ON grade.taskgroup = task.taskgroup
@@ -825,8 +1082,28 @@ impl MedalConnection for Connection {
             })
     }
 
+    fn get_first_submission_dates(&self, group_id: i32, contest_id: i32)
+                                  -> Vec<(i32, i32, time::Timespec, time::Timespec)> {
+        let _timer = DbMethodTimer::start("get_first_submission_dates");
+        let query = "SELECT student.id, taskgroup.id, participation.start_date, MIN(submission.date)
+                     FROM participation
+                     JOIN session AS student ON participation.session = student.id
+                     JOIN taskgroup ON taskgroup.contest = participation.contest
+                     JOIN task ON task.taskgroup = taskgroup.id
+                     JOIN submission ON submission.task = task.id AND submission.session = student.id
+                     WHERE student.managed_by = $1
+                     AND participation.contest = $2
+                     AND taskgroup.active = $3
+                     GROUP BY student.id, taskgroup.id, participation.start_date";
+        self.query_map_many(query, &[&group_id, &contest_id, &true], |row| {
+                (row.get(0), row.get(1), row.get(2), row.get(3))
+            })
+            .unwrap()
+    }
+
     fn get_contest_groups_grades(&self, session_id: i32, contest_id: i32)
                                  -> (Vec<String>, Vec<(Group, Vec<(UserInfo, Vec<Grade>)>)>) {
+        let _timer = DbMethodTimer::start("get_contest_groups_grades");
         let query = "SELECT id, name
                      FROM taskgroup
                      WHERE contest = $1
@@ -844,13 +1121,15 @@ impl MedalConnection for Connection {
 
         let query = "SELECT grade.taskgroup, grade.session, grade.grade, grade.validated, usergroup.id, usergroup.name,
                             usergroup.groupcode, usergroup.tag, student.id, student.username, student.logincode,
-                            student.firstname, student.lastname, student.grade AS sgrade, participation.annotation
+                            student.firstname, student.lastname, student.grade AS sgrade, participation.annotation,
+                            usergroup.admin
                      FROM grade
                      JOIN taskgroup ON grade.taskgroup = taskgroup.id
                      JOIN session AS student ON grade.session = student.id
                      JOIN usergroup ON student.managed_by = usergroup.id
                      JOIN participation ON participation.session = student.id AND participation.contest = $2
-                     WHERE usergroup.admin = $1
+                     WHERE (usergroup.admin = $1
+                            OR usergroup.id IN (SELECT usergroup FROM usergroup_admin WHERE session = $1 AND accepted = $3))
                      AND taskgroup.contest = $2
                      AND taskgroup.active = $3
                      ORDER BY usergroup.id, sgrade, student.lastname, student.firstname, student.id,
@@ -862,7 +1141,7 @@ impl MedalConnection for Connection {
                              name: row.get(5),
                              groupcode: row.get(6),
                              tag: row.get(7),
-                             admin: session_id,
+                             admin: row.get(15),
                              members: Vec::new() },
                      UserInfo { id: row.get(8),
                                 username: row.get(9),
@@ -912,6 +1191,7 @@ impl MedalConnection for Connection {
         }
     }
     fn get_contest_user_grades(&self, session_token: &str, contest_id: i32) -> Vec<Grade> {
+        let _timer = DbMethodTimer::start("get_contest_user_grades");
         let query = "SELECT id, name
                      FROM taskgroup
                      WHERE contest = $1
@@ -952,7 +1232,116 @@ impl MedalConnection for Connection {
         grades
     }
 
+    fn get_contest_ranking_data(&self, contest_id: i32) -> (Vec<String>, Vec<RankingParticipant>) {
+        let _timer = DbMethodTimer::start("get_contest_ranking_data");
+        let query = "SELECT id, name
+                     FROM taskgroup
+                     WHERE contest = $1
+                     AND active = $2
+                     ORDER BY positionalnumber";
+        let tasknames: Vec<(i32, String)> =
+            self.query_map_many(query, &[&contest_id, &true], |row| (row.get(0), row.get(1))).unwrap();
+
+        let mut taskindex: ::std::collections::BTreeMap<i32, usize> = ::std::collections::BTreeMap::new();
+
+        let n_tasks = tasknames.len();
+        for (index, (i, _)) in tasknames.iter().enumerate() {
+            taskindex.insert(*i, index);
+        }
+
+        let query = "SELECT session.id, session.username, session.logincode, session.firstname, session.lastname,
+                            session.grade, participation.annotation, session.sex, usergroup.id, usergroup.name,
+                            usergroup.admin, teacher.oauth_foreign_id
+                     FROM participation
+                     JOIN session ON participation.session = session.id
+                     LEFT JOIN usergroup ON session.managed_by = usergroup.id
+                     LEFT JOIN session AS teacher ON usergroup.admin = teacher.id
+                     WHERE participation.contest = $1
+                     ORDER BY session.id";
+        let mut participants: Vec<RankingParticipant> =
+            self.query_map_many(query, &[&contest_id], |row| {
+                    // For PMS teachers, the foreign id has the form "<teacher id>/<school id>"
+                    let school_id =
+                        row.get::<_, Option<String>>(11).and_then(|id| id.split('/').nth(1).map(|s| s.to_owned()));
+
+                    RankingParticipant { user: UserInfo { id: row.get(0),
+                                                          username: row.get(1),
+                                                          logincode: row.get(2),
+                                                          firstname: row.get(3),
+                                                          lastname: row.get(4),
+                                                          grade: row.get(5),
+                                                          annotation: row.get(6) },
+                                         sex: row.get(7),
+                                         group_id: row.get(8),
+                                         group_name: row.get(9),
+                                         group_admin: row.get(10),
+                                         school_id,
+                                         grades: vec![None; n_tasks],
+                                         attempts: 0,
+                                         final_score_reached: None }
+                })
+                .unwrap();
+
+        let mut userindex: ::std::collections::BTreeMap<i32, usize> = ::std::collections::BTreeMap::new();
+        for (index, participant) in participants.iter().enumerate() {
+            userindex.insert(participant.user.id, index);
+        }
+
+        let query = "SELECT grade.session, grade.taskgroup, grade.grade
+                     FROM grade
+                     JOIN taskgroup ON grade.taskgroup = taskgroup.id
+                     WHERE taskgroup.contest = $1
+                     AND taskgroup.active = $2";
+        let grades: Vec<(i32, i32, Option<i32>)> =
+            self.query_map_many(query, &[&contest_id, &true], |row| (row.get(0), row.get(1), row.get(2))).unwrap();
+        for (user, taskgroup, grade) in grades {
+            if let (Some(&u), Some(&t)) = (userindex.get(&user), taskindex.get(&taskgroup)) {
+                participants[u].grades[t] = grade;
+            }
+        }
+
+        // For every grade a participant has reached in a taskgroup, find out when it was first reached. The time the
+        // final grade of the last taskgroup was reached is the time the final score was reached.
+        let query = "SELECT submission.session, task.taskgroup, submission.grade, MIN(submission.date), COUNT(*)
+                     FROM submission
+                     JOIN task ON submission.task = task.id
+                     JOIN taskgroup ON task.taskgroup = taskgroup.id
+                     WHERE taskgroup.contest = $1
+                     AND taskgroup.active = $2
+                     GROUP BY submission.session, task.taskgroup, submission.grade";
+        let submissions: Vec<(i32, i32, i32, Option<time::Timespec>, i64)> =
+            self.query_map_many(query, &[&contest_id, &true], |row| {
+                    (row.get(0), row.get(1), row.get(2), row.get(3), row.get(4))
+                })
+                .unwrap();
+        let mut reached: ::std::collections::BTreeMap<(usize, usize), time::Timespec> =
+            ::std::collections::BTreeMap::new();
+        for (user, taskgroup, grade, date, count) in submissions {
+            if let (Some(&u), Some(&t)) = (userindex.get(&user), taskindex.get(&taskgroup)) {
+                participants[u].attempts += count as i32;
+
+                let final_grade = participants[u].grades[t].unwrap_or(0);
+                if let Some(date) = date {
+                    if final_grade > 0 && grade >= final_grade {
+                        let first = reached.entry((u, t)).or_insert(date);
+                        if date < *first {
+                            *first = date;
+                        }
+                    }
+                }
+            }
+        }
+        for ((u, _), date) in reached {
+            if Some(date) > participants[u].final_score_reached {
+                participants[u].final_score_reached = Some(date);
+            }
+        }
+
+        (tasknames.into_iter().map(|(_, name)| name).collect(), participants)
+    }
+
     fn get_taskgroup_user_grade(&self, session_token: &str, taskgroup_id: i32) -> Grade {
+        let _timer = DbMethodTimer::start("get_taskgroup_user_grade");
         let query = "SELECT grade.taskgroup, grade.session, grade.grade, grade.validated
                      FROM grade
                      JOIN session ON session.id = grade.session
@@ -967,31 +1356,10 @@ impl MedalConnection for Connection {
     }
 
     /* Warning: This function makes no use of rusts typeb safety. Handle with care when changeing */
-    fn export_contest_results_to_file(&self, contest_id: i32, taskgroups: &[(i32, String)], filename: &str) {
-        use std::fs::OpenOptions;
-        let file = OpenOptions::new().write(true).create(true).truncate(true).open(filename).unwrap();
-        let mut headers = vec!["id",
-                               "username",
-                               "logincode",
-                               "oauth_foreign_id",
-                               "oauth_provider",
-                               "firstname",
-                               "lastname",
-                               "grade",
-                               "sex",
-                               "is_teacher",
-                               "group_id",
-                               "group_name",
-                               "group_tag",
-                               "teacher_id",
-                               "teacher_firstname",
-                               "teacher_lastname",
-                               "teacher_oauth_foreign_id",
-                               "teacher_oauth_school_id",
-                               "teacher_oauth_provider",
-                               "contest_id",
-                               "start_date"];
-
+    fn export_contest_results(&self, contest_id: i32, taskgroup_ids: &[i32], task_ids: &[i32],
+                              count_submissions: bool, after_session: i32, limit: i64,
+                              callback: &mut dyn FnMut(ContestResultRow)) {
+        let _timer = DbMethodTimer::start("export_contest_results");
         let mut select_part = String::new();
         let mut join_part = String::new();
 
@@ -999,7 +1367,7 @@ impl MedalConnection for Connection {
 
         join_params.push(&contest_id);
 
-        for (n, (id, name)) in taskgroups.iter().enumerate() {
+        for (n, id) in taskgroup_ids.iter().enumerate() {
             use std::fmt::Write;
 
             write!(select_part, ",\n g{}.grade ", n).unwrap();
@@ -1008,11 +1376,41 @@ impl MedalConnection for Connection {
                    n,
                    n,
                    n,
-                   n + 2).unwrap();
+                   join_params.len() + 1).unwrap();
+            join_params.push(id);
+        }
+
+        for id in task_ids {
+            use std::fmt::Write;
+
+            // Correlated subqueries only look at the submissions of the sessions in the current batch
+            write!(select_part,
+                   ",\n (SELECT MAX(grade)
+                         FROM submission
+                         WHERE submission.session = session.id
+                         AND submission.task = ${}) ",
+                   join_params.len() + 1).unwrap();
             join_params.push(id);
-            headers.push(&name);
         }
 
+        let n_counts = if count_submissions { taskgroup_ids.len() } else { 0 };
+        for id in taskgroup_ids.iter().take(n_counts) {
+            use std::fmt::Write;
+
+            write!(select_part,
+                   ",\n (SELECT COUNT(*)
+                         FROM submission
+                         JOIN task ON submission.task = task.id
+                         WHERE submission.session = session.id
+                         AND task.taskgroup = ${}) ",
+                   join_params.len() + 1).unwrap();
+            join_params.push(id);
+        }
+
+        let after_session_param = join_params.len() + 1;
+        join_params.push(&after_session);
+        join_params.push(&limit);
+
         let query = format!("SELECT session.id,
                                     session.username,
                                     session.logincode,
@@ -1039,65 +1437,52 @@ impl MedalConnection for Connection {
                              {}
                              LEFT JOIN usergroup ON session.managed_by = usergroup.id
                              LEFT JOIN session AS teacher ON usergroup.admin = teacher.id
-                             WHERE participation.contest = $1",
-                            select_part, join_part);
-
-        use csv::Writer;
-        let mut wtr = Writer::from_writer(file);
-        wtr.serialize(&headers).unwrap();
-        wtr.flush().unwrap();
-
-        let file = wtr.into_inner().unwrap();
-        let mut wtr = Writer::from_writer(file);
+                             WHERE participation.contest = $1
+                             AND session.id > ${}
+                             ORDER BY session.id
+                             LIMIT ${}",
+                            select_part,
+                            join_part,
+                            after_session_param,
+                            after_session_param + 1);
+
+        let grades_start = 20;
+        let task_grades_start = grades_start + taskgroup_ids.len();
+        let counts_start = task_grades_start + task_ids.len();
+        let counts_end = counts_start + n_counts;
 
         self.query_map_many(&query, join_params.as_slice(), |row| {
-                let mut points = Vec::new();
-                for i in 20..20 + taskgroups.len() {
-                    points.push(row.get::<_, Option<i32>>(i));
-                }
-
-                let teacher_oauth_and_school_id = row.get::<_, Option<String>>(16);
-                let (teacher_oauth_id, teacher_school_id) = if let Some(toasi) = teacher_oauth_and_school_id {
-                    let mut v = toasi.split('/');
-                    let oid: Option<String> = v.next().map(|s| s.to_owned());
-                    let sid: Option<String> = v.next().map(|s| s.to_owned());
-                    (oid, sid)
-                } else {
-                    (None, None)
-                };
-
-                // Serialized as several tuples because Serde only supports tuples up to a certain length
-                // (16 according to https://docs.serde.rs/serde/trait.Deserialize.html)
-                wtr.serialize(((row.get::<_, i32>(0),
-                                row.get::<_, Option<String>>(1),
-                                row.get::<_, Option<String>>(2),
-                                row.get::<_, Option<String>>(3),
-                                row.get::<_, Option<String>>(4),
-                                row.get::<_, Option<String>>(5),
-                                row.get::<_, Option<String>>(6),
-                                row.get::<_, i32>(7),
-                                row.get::<_, Option<i32>>(8),
-                                row.get::<_, bool>(9)),
-                               (row.get::<_, Option<i32>>(10),
-                                row.get::<_, Option<String>>(11),
-                                row.get::<_, Option<String>>(12),
-                                row.get::<_, Option<i32>>(13),
-                                row.get::<_, Option<String>>(14),
-                                row.get::<_, Option<String>>(15),
-                                teacher_oauth_id,
-                                teacher_school_id,
-                                row.get::<_, Option<String>>(17)),
-                               row.get::<_, Option<i32>>(18),
-                               row.get::<_, Option<time::Timespec>>(19)
-                                  .map(|ts| self::time::strftime("%FT%T%z", &time::at(ts)).unwrap()),
-                               points))
-                   .unwrap();
+                let task_grades = (task_grades_start..counts_start).map(|i| row.get(i)).collect();
+                let submission_counts = (counts_start..counts_end).map(|i| row.get(i)).collect();
+                callback(ContestResultRow { id: row.get(0),
+                                            username: row.get(1),
+                                            logincode: row.get(2),
+                                            oauth_foreign_id: row.get(3),
+                                            oauth_provider: row.get(4),
+                                            firstname: row.get(5),
+                                            lastname: row.get(6),
+                                            grade: row.get(7),
+                                            sex: row.get(8),
+                                            is_teacher: row.get(9),
+                                            group_id: row.get(10),
+                                            group_name: row.get(11),
+                                            group_tag: row.get(12),
+                                            teacher_id: row.get(13),
+                                            teacher_firstname: row.get(14),
+                                            teacher_lastname: row.get(15),
+                                            teacher_oauth_foreign_id: row.get(16),
+                                            teacher_oauth_provider: row.get(17),
+                                            contest_id: row.get(18),
+                                            start_date: row.get(19),
+                                            grades: (grades_start..task_grades_start).map(|i| row.get(i)).collect(),
+                                            task_grades,
+                                            submission_counts });
             })
             .unwrap();
-        wtr.flush().unwrap();
     }
 
     fn insert_contest_annotations(&self, contest_id: i32, annotations: Vec<(i32, Option<String>)>) -> i32 {
+        let _timer = DbMethodTimer::start("insert_contest_annotations");
         let batch_size = 10;
         let query_batch = "UPDATE participation
                            SET annotation = batchdata.annotation
@@ -1167,6 +1552,7 @@ impl MedalConnection for Connection {
 
     fn get_submission_by_id_complete_shallow_contest(&self, submission_id: i32)
                                                      -> Option<(Submission, Task, Taskgroup, Contest)> {
+        let _timer = DbMethodTimer::start("get_submission_by_id_complete_shallow_contest");
         let query = "SELECT submission.session, submission.grade, submission.validated, submission.nonvalidated_grade,
                             submission.needs_validation, submission.subtask_identifier, submission.value,
                             submission.date,
@@ -1194,7 +1580,8 @@ impl MedalConnection for Connection {
                         taskgroup: row.get(11),
                         location: row.get(9),
                         language: row.get(10),
-                        stars: row.get(11) },
+                        stars: row.get(11),
+                        checker: None },
                  Taskgroup { id: row.get(12),
                              contest: row.get(16),
                              name: row.get(13),
@@ -1223,6 +1610,9 @@ impl MedalConnection for Connection {
                            language: None,
                            category: None,
                            standalone_task: None,
+                           tie_breaking: None,
+                           public_scoreboard: None,
+                           qualification: None,
                            tags: Vec::new(),
                            taskgroups: Vec::new() })
             })
@@ -1230,9 +1620,11 @@ impl MedalConnection for Connection {
     }
 
     fn get_contest_list(&self) -> Vec<Contest> {
+        let _timer = DbMethodTimer::start("get_contest_list");
         let query = "SELECT id, location, filename, name, duration, public, start_date, end_date, review_start_date,
                             review_end_date, min_grade, max_grade, positionalnumber, protected, requires_login,
-                            requires_contest, secret, message, image, language, category, standalone_task, tags
+                            requires_contest, secret, message, image, language, category, standalone_task, tags,
+                            tie_breaking, public_scoreboard, qualification
                      FROM contest
                      LEFT JOIN contest_tags USING (id)
                      ORDER BY positionalnumber DESC";
@@ -1258,6 +1650,9 @@ impl MedalConnection for Connection {
                                                         language: row.get(19),
                                                         category: row.get(20),
                                                         standalone_task: row.get(21),
+                                                        tie_breaking: row.get(23),
+                                                        public_scoreboard: row.get(24),
+                                                        qualification: row.get(25),
                                                         tags: row.get::<_, Option<String>>(22)
                                                                  .map(|tags| {
                                                                      tags.split(',').map(|tag| tag.to_owned()).collect()
@@ -1268,9 +1663,11 @@ impl MedalConnection for Connection {
     }
 
     fn get_contest_by_id(&self, contest_id: i32) -> Option<Contest> {
+        let _timer = DbMethodTimer::start("get_contest_by_id");
         let query = "SELECT location, filename, name, duration, public, start_date, end_date, review_start_date,
                             review_end_date, min_grade, max_grade, protected, requires_login, requires_contest, secret,
-                            message, image, language, category, standalone_task
+                            message, image, language, category, standalone_task, tie_breaking, public_scoreboard,
+                            qualification
                      FROM contest
                      WHERE id = $1";
         self.query_map_one(query, &[&contest_id], |row| Contest { id: Some(contest_id),
@@ -1295,19 +1692,24 @@ impl MedalConnection for Connection {
                                                                   language: row.get(17),
                                                                   category: row.get(18),
                                                                   standalone_task: row.get(19),
+                                                                  tie_breaking: row.get(20),
+                                                                  public_scoreboard: row.get(21),
+                                                                  qualification: row.get(22),
                                                                   tags: Vec::new(),
                                                                   taskgroups: Vec::new() })
             .unwrap()
     }
 
     fn get_contest_by_id_complete(&self, contest_id: i32) -> Option<Contest> {
+        let _timer = DbMethodTimer::start("get_contest_by_id_complete");
         let query = "SELECT contest.location, contest.filename, contest.name, contest.duration, contest.public,
                             contest.start_date, contest.end_date, contest.review_start_date, contest.review_end_date,
                             contest.min_grade, contest.max_grade, contest.protected, contest.requires_login,
                             contest.requires_contest, contest.secret, contest.message, contest.image, contest.language,
                             contest.category, contest.standalone_task,
                             taskgroup.id, taskgroup.name,
-                            task.id, task.location, task.language, task.stars
+                            task.id, task.location, task.language, task.stars,
+                            contest.tie_breaking, contest.public_scoreboard, contest.qualification
                      FROM contest
                      JOIN taskgroup ON contest.id = taskgroup.contest
                      JOIN task ON taskgroup.id = task.taskgroup
@@ -1337,6 +1739,9 @@ impl MedalConnection for Connection {
                                                   language: row.get(17),
                                                   category: row.get(18),
                                                   standalone_task: row.get(19),
+                                                  tie_breaking: row.get(26),
+                                                  public_scoreboard: row.get(27),
+                                                  qualification: row.get(28),
                                                   tags: Vec::new(),
                                                   taskgroups: Vec::new() },
                                         Taskgroup { id: Some(row.get(20)),
@@ -1349,7 +1754,8 @@ impl MedalConnection for Connection {
                                                taskgroup: row.get(20),
                                                location: row.get(23),
                                                language: row.get(24),
-                                               stars: row.get(25) })
+                                               stars: row.get(25),
+                                               checker: None })
                                    })
                                    .unwrap();
         let mut taskgroupcontest_iter = taskgroupcontest.into_iter();
@@ -1374,12 +1780,14 @@ impl MedalConnection for Connection {
     }
 
     fn get_contest_by_id_partial(&self, contest_id: i32) -> Option<Contest> {
+        let _timer = DbMethodTimer::start("get_contest_by_id_partial");
         let query = "SELECT contest.location, contest.filename, contest.name, contest.duration, contest.public,
                             contest.start_date, contest.end_date, contest.review_start_date, contest.review_end_date,
                             contest.min_grade, contest.max_grade, contest.protected, contest.requires_login,
                             contest.requires_contest, contest.secret, contest.message, contest.image, contest.language,
                             contest.category, contest.standalone_task,
-                            taskgroup.id, taskgroup.name
+                            taskgroup.id, taskgroup.name, contest.tie_breaking, contest.public_scoreboard,
+                            contest.qualification
                      FROM contest
                      JOIN taskgroup ON contest.id = taskgroup.contest
                      WHERE contest.id = $1
@@ -1407,6 +1815,9 @@ impl MedalConnection for Connection {
                                                   language: row.get(17),
                                                   category: row.get(18),
                                                   standalone_task: row.get(19),
+                                                  tie_breaking: row.get(22),
+                                                  public_scoreboard: row.get(23),
+                                                  qualification: row.get(24),
                                                   tags: Vec::new(),
                                                   taskgroups: Vec::new() },
                                         Taskgroup { id: Some(row.get(20)),
@@ -1434,37 +1845,68 @@ impl MedalConnection for Connection {
     }
 
     fn get_participation(&self, session_id: i32, contest_id: i32) -> Option<Participation> {
-        let query = "SELECT start_date
+        let _timer = DbMethodTimer::start("get_participation");
+        let query = "SELECT start_date, extra_time, paused_secs, paused_since
                      FROM participation
                      WHERE session = $1
                      AND contest = $2";
         self.query_map_one(query, &[&session_id, &contest_id], |row| Participation { contest: contest_id,
                                                                                      user: session_id,
-                                                                                     start: row.get(0) })
+                                                                                     start: row.get(0),
+                                                                                     extra_time: row.get(1),
+                                                                                     paused_secs: row.get(2),
+                                                                                     paused_since: row.get(3) })
             .ok()?
     }
 
     fn get_own_participation(&self, session: &str, contest_id: i32) -> Option<Participation> {
-        let query = "SELECT session, start_date
+        let _timer = DbMethodTimer::start("get_own_participation");
+        let query = "SELECT session, start_date, extra_time, paused_secs, paused_since
                      FROM participation
                      JOIN session ON session.id = session
                      WHERE session.session_token = $1
                      AND contest = $2";
         self.query_map_one(query, &[&session, &contest_id], |row| Participation { contest: contest_id,
                                                                                   user: row.get(0),
-                                                                                  start: row.get(1) })
+                                                                                  start: row.get(1),
+                                                                                  extra_time: row.get(2),
+                                                                                  paused_secs: row.get(3),
+                                                                                  paused_since: row.get(4) })
             .ok()?
     }
 
+    fn save_participation_time(&self, participation: &Participation) {
+        let _timer = DbMethodTimer::start("save_participation_time");
+        let query = "UPDATE participation
+                     SET start_date = $3, extra_time = $4, paused_secs = $5, paused_since = $6
+                     WHERE session = $1
+                     AND contest = $2";
+        self.execute(query,
+                     &[&participation.user,
+                       &participation.contest,
+                       &participation.start,
+                       &participation.extra_time,
+                       &participation.paused_secs,
+                       &participation.paused_since])
+            .unwrap();
+    }
+
     fn get_all_participations_complete(&self, session_id: i32) -> Vec<(Participation, Contest)> {
+        let _timer = DbMethodTimer::start("get_all_participations_complete");
         let query = "SELECT participation.start_date, contest.id, location, filename, name, duration, public,
                             contest.start_date, end_date, review_start_date, review_end_date, min_grade, max_grade,
-                            protected, requires_login, requires_contest, secret, message, category
+                            protected, requires_login, requires_contest, secret, message, category,
+                            participation.extra_time, participation.paused_secs, participation.paused_since
                      FROM participation
                      JOIN contest ON participation.contest = contest.id
                      WHERE participation.session = $1 AND (standalone_task IS NULL OR standalone_task = FALSE)";
         self.query_map_many(query, &[&session_id], |row| {
-                (Participation { contest: row.get(1), user: session_id, start: row.get(0) },
+                (Participation { contest: row.get(1),
+                                 user: session_id,
+                                 start: row.get(0),
+                                 extra_time: row.get(19),
+                                 paused_secs: row.get(20),
+                                 paused_since: row.get(21) },
                  Contest { id: Some(row.get(1)),
                            location: row.get(2),
                            filename: row.get(3),
@@ -1487,6 +1929,9 @@ impl MedalConnection for Connection {
                            language: None,
                            category: row.get(18),
                            standalone_task: None,
+                           tie_breaking: None,
+                           public_scoreboard: None,
+                           qualification: None,
                            tags: Vec::new(),
                            taskgroups: Vec::new() })
             })
@@ -1494,23 +1939,33 @@ impl MedalConnection for Connection {
     }
 
     fn count_all_stars(&self, session_id: i32) -> i32 {
+        let _timer = DbMethodTimer::start("count_all_stars");
         let query = "SELECT COALESCE(SUM(grade.grade), 0) AS stars
                      FROM grade
                      WHERE session = $1";
         self.query_map_one(query, &[&session_id], |row| -> i64 { row.get(0) }).unwrap().unwrap() as i32
     }
 
-    fn has_participation_by_contest_file(&self, session_id: i32, location: &str, filename: &str) -> bool {
-        let query = "SELECT participation.contest
+    fn get_contest_id_by_file(&self, location: &str, filename: &str) -> Option<i32> {
+        let _timer = DbMethodTimer::start("get_contest_id_by_file");
+        let query = "SELECT id
+                     FROM contest
+                     WHERE location = $1
+                     AND filename = $2";
+        self.query_map_one(query, &[&location, &filename], |row| row.get(0)).ok()?
+    }
+
+    fn get_participation_annotation(&self, session_id: i32, contest_id: i32) -> Option<String> {
+        let _timer = DbMethodTimer::start("get_participation_annotation");
+        let query = "SELECT annotation
                      FROM participation
-                     JOIN contest ON participation.contest = contest.id
-                     WHERE participation.session = $1
-                     AND contest.location = $2
-                     AND contest.filename = $3";
-        self.exists(query, &[&session_id, &location, &filename])
+                     WHERE session = $1
+                     AND contest = $2";
+        self.query_map_one(query, &[&session_id, &contest_id], |row| row.get(0)).ok()??
     }
 
     fn new_participation(&self, session: &str, contest_id: i32) -> Result<Participation, ()> {
+        let _timer = DbMethodTimer::start("new_participation");
         let query = "SELECT session, start_date
                      FROM participation
                      JOIN session ON session.id = session
@@ -1532,24 +1987,27 @@ impl MedalConnection for Connection {
         }
     }
     fn get_task_by_id(&self, task_id: i32) -> Option<Task> {
-        let query = "SELECT location, language, stars, taskgroup
+        let _timer = DbMethodTimer::start("get_task_by_id");
+        let query = "SELECT location, language, stars, taskgroup, checker
                      FROM task
                      WHERE id = $1";
         self.query_map_one(query, &[&task_id], |row| Task { id: Some(task_id),
                                                             taskgroup: row.get(3),
                                                             location: row.get(0),
                                                             language: row.get(1),
-                                                            stars: row.get(2) })
+                                                            stars: row.get(2),
+                                                            checker: row.get(4) })
             .unwrap()
     }
     fn get_task_by_id_complete(&self, task_id: i32) -> Option<(Task, Taskgroup, Contest)> {
+        let _timer = DbMethodTimer::start("get_task_by_id_complete");
         let query = "SELECT task.location, task.language, task.stars,
                             taskgroup.id, taskgroup.name, taskgroup.active,
                             contest.id, contest.location, contest.filename, contest.name, contest.duration,
                             contest.public, contest.start_date, contest.end_date, contest.review_start_date,
                             contest.review_end_date, contest.min_grade, contest.max_grade, contest.protected,
                             contest.requires_login, contest.requires_contest, contest.secret, contest.message,
-                            contest.category, contest.standalone_task
+                            contest.category, contest.standalone_task, task.checker
                      FROM contest
                      JOIN taskgroup ON taskgroup.contest = contest.id
                      JOIN task ON task.taskgroup = taskgroup.id
@@ -1559,7 +2017,8 @@ impl MedalConnection for Connection {
                         taskgroup: row.get(3),
                         location: row.get(0),
                         language: row.get(1),
-                        stars: row.get(2) },
+                        stars: row.get(2),
+                        checker: row.get(25) },
                  Taskgroup { id: Some(row.get(3)),
                              contest: row.get(6),
                              name: row.get(4),
@@ -1588,72 +2047,92 @@ impl MedalConnection for Connection {
                            language: None,
                            category: row.get(23),
                            standalone_task: row.get(24),
+                           tie_breaking: None,
+                           public_scoreboard: None,
+                           qualification: None,
                            tags: Vec::new(),
                            taskgroups: Vec::new() })
             })
             .unwrap()
     }
 
-    fn get_submission_to_validate(&self, tasklocation: &str, subtask: Option<&str>) -> i32 {
-        match subtask {
-            Some(st) => {
-                let query = "SELECT id
-                             FROM submission
-                             JOIN task ON submission.task = task.id
-                             WHERE task.location = $1
-                             AND subtask_identifier = $2
-                             AND needs_validation = 1
-                             LIMIT 1";
-                self.query_map_one(query, &[&tasklocation, &st], |row| row.get(0)).unwrap().unwrap()
-            }
-            None => {
-                let query = "SELECT id
-                             FROM submission
-                             JOIN task ON submission.task = task.id
-                             WHERE task.location = $1
-                             AND needs_validation = 1
-                             LIMIT 1";
-                self.query_map_one(query, &[&tasklocation], |row| row.get(0)).unwrap().unwrap()
-            }
-        }
-    }
-
-    fn find_next_submission_to_validate(&self, userid: i32, taskgroupid: i32) {
-        let query = "SELECT id, validated
+    fn get_next_submission_to_validate(&self) -> Option<Submission> {
+        let _timer = DbMethodTimer::start("get_next_submission_to_validate");
+        let query = "SELECT submission.id, submission.session, submission.task, submission.grade,
+                            submission.validated, submission.nonvalidated_grade, submission.subtask_identifier,
+                            submission.value, submission.date
                      FROM submission
-                     JOIN task ON submission.task = task.id
-                     WHERE task.taskgroup = $1
-                     AND submission.session = $2
-                     ORDER BY value DESC id DESC
+                     JOIN task ON task.id = submission.task
+                     WHERE submission.needs_validation = $1
+                     AND task.checker IS NOT NULL
+                     ORDER BY submission.id
                      LIMIT 1";
-        let (id, validated): (i32, bool) =
-            self.query_map_one(query, &[&taskgroupid, &userid], |row| (row.get(0), row.get(1))).unwrap().unwrap();
-        if !validated {
-            let query = "UPDATE submission
-                         SET needs_validation = 1
-                         WHERE id = $1";
-            self.execute(query, &[&id]).unwrap();
-        }
+        self.query_map_one(query, &[&true], |row| Submission { id: Some(row.get(0)),
+                                                               user: row.get(1),
+                                                               task: row.get(2),
+                                                               grade: row.get(3),
+                                                               validated: row.get(4),
+                                                               nonvalidated_grade: row.get(5),
+                                                               needs_validation: true,
+                                                               subtask_identifier: row.get(6),
+                                                               value: row.get(7),
+                                                               date: row.get(8) })
+            .unwrap()
+    }
+
+    fn validate_submission(&self, submission_id: i32, grade: i32) {
+        let _timer = DbMethodTimer::start("validate_submission");
+        self.transaction(|| {
+                let query = "UPDATE submission
+                             SET grade = $1, validated = $2, needs_validation = $3
+                             WHERE id = $4";
+                self.execute(query, &[&grade, &true, &false, &submission_id]).unwrap();
+
+                // The validated grade can be lower than the submitted one, so the best grade has to be recomputed from all
+                // submissions of the taskgroup instead of only being raised as in `submit_submission`. Submissions that are
+                // still waiting for their checker only count once they are validated.
+                let mut grade = self.get_grade_by_submission(submission_id);
+                let query = "SELECT submission.grade, submission.validated
+                             FROM submission
+                             JOIN task ON task.id = submission.task
+                             WHERE task.taskgroup = $1
+                             AND submission.session = $2
+                             AND (submission.needs_validation = $3 OR task.checker IS NULL)
+                             ORDER BY submission.grade DESC, submission.validated DESC
+                             LIMIT 1";
+                let (best_grade, validated) =
+                    self.query_map_one(query, &[&grade.taskgroup, &grade.user, &false], |row| (row.get(0), row.get(1)))
+                        .unwrap()
+                        .unwrap(); // There is at least the submission we just validated
+                grade.grade = Some(best_grade);
+                grade.validated = validated;
+                grade.save(self);
+            })
     }
 
     fn add_group(&self, group: &mut Group) { group.save(self); }
 
     fn get_groups(&self, session_id: i32) -> Vec<Group> {
-        let query = "SELECT id, name, groupcode, tag
+        let _timer = DbMethodTimer::start("get_groups");
+        let query = "SELECT id, name, groupcode, tag, admin
                      FROM usergroup
-                     WHERE admin = $1";
-        self.query_map_many(query, &[&session_id], |row| Group { id: Some(row.get(0)),
-                                                                 name: row.get(1),
-                                                                 groupcode: row.get(2),
-                                                                 tag: row.get(3),
-                                                                 admin: session_id,
-                                                                 members: Vec::new() })
+                     WHERE admin = $1
+                     OR id IN (SELECT usergroup FROM usergroup_admin WHERE session = $1 AND accepted = $2)
+                     ORDER BY id";
+        self.query_map_many(query, &[&session_id, &true], |row| Group { id: Some(row.get(0)),
+                                                                        name: row.get(1),
+                                                                        groupcode: row.get(2),
+                                                                        tag: row.get(3),
+                                                                        admin: row.get(4),
+                                                                        members: Vec::new() })
             .unwrap()
     }
     fn get_groups_complete(&self, _session_id: i32) -> Vec<Group> {
+        let _timer = DbMethodTimer::start("get_groups_complete");
         unimplemented!();
     }
     fn get_group(&self, group_id: i32) -> Option<Group> {
+        let _timer = DbMethodTimer::start("get_group");
         let query = "SELECT name, groupcode, tag, admin
                      FROM usergroup
                      WHERE id  = $1";
@@ -1666,6 +2145,7 @@ impl MedalConnection for Connection {
             .unwrap()
     }
     fn group_has_protected_participations(&self, group_id: i32) -> bool {
+        let _timer = DbMethodTimer::start("group_has_protected_participations");
         let query = "SELECT EXISTS(
                          SELECT session.id
                          FROM session
@@ -1677,6 +2157,7 @@ impl MedalConnection for Connection {
         self.query_map_one(query, &[&group_id, &true], |row| row.get(0)).unwrap().unwrap()
     }
     fn get_group_complete(&self, group_id: i32) -> Option<Group> {
+        let _timer = DbMethodTimer::start("get_group_complete");
         let query = "SELECT name, groupcode, tag, admin
                      FROM usergroup
                      WHERE id  = $1";
@@ -1686,13 +2167,12 @@ impl MedalConnection for Connection {
                                                                               tag: row.get(2),
                                                                               admin: row.get(3),
                                                                               members: Vec::new() })
-                            .unwrap()
-                            .unwrap(); // TODO handle error
+                            .unwrap()?;
 
         let query = "SELECT id, session_token, csrf_token, last_login, last_activity, account_created, username,
                             password, logincode, email, email_unconfirmed, email_confirmationcode, firstname, lastname,
                             street, zip, city, nation, grade, sex, is_admin, is_teacher, oauth_provider,
-                            oauth_foreign_id, salt
+                            oauth_foreign_id, salt, language
                      FROM session
                      WHERE managed_by = $1
                      ORDER BY id";
@@ -1726,27 +2206,107 @@ impl MedalConnection for Connection {
                                                                                      managed_by: Some(group_id),
 
                                                                                      oauth_provider: row.get(22),
-                                                                                     oauth_foreign_id: row.get(23) })
+                                                                                     oauth_foreign_id: row.get(23),
+                                                                                     language: row.get(25),
+                                                                                     roles: Vec::new() })
                             .unwrap();
         Some(group)
     }
 
+    fn get_group_admins(&self, group_id: i32) -> Vec<GroupAdmin> {
+        let _timer = DbMethodTimer::start("get_group_admins");
+        let query = "SELECT session.id, session.firstname, session.lastname, usergroup_admin.accepted
+                     FROM usergroup_admin
+                     JOIN session ON usergroup_admin.session = session.id
+                     WHERE usergroup_admin.usergroup = $1
+                     ORDER BY usergroup_admin.invited, session.id";
+        self.query_map_many(query, &[&group_id], |row| GroupAdmin { id: row.get(0),
+                                                                    firstname: row.get(1),
+                                                                    lastname: row.get(2),
+                                                                    accepted: row.get(3) })
+            .unwrap()
+    }
+    fn get_group_invitations(&self, user_id: i32) -> Vec<Group> {
+        let _timer = DbMethodTimer::start("get_group_invitations");
+        let query = "SELECT usergroup.id, usergroup.name, usergroup.groupcode, usergroup.tag, usergroup.admin
+                     FROM usergroup_admin
+                     JOIN usergroup ON usergroup_admin.usergroup = usergroup.id
+                     WHERE usergroup_admin.session = $1
+                     AND usergroup_admin.accepted = $2
+                     ORDER BY usergroup_admin.invited";
+        self.query_map_many(query, &[&user_id, &false], |row| Group { id: Some(row.get(0)),
+                                                                      name: row.get(1),
+                                                                      groupcode: row.get(2),
+                                                                      tag: row.get(3),
+                                                                      admin: row.get(4),
+                                                                      members: Vec::new() })
+            .unwrap()
+    }
+    fn add_group_admin(&self, group_id: i32, user_id: i32) {
+        let _timer = DbMethodTimer::start("add_group_admin");
+        let query = "INSERT INTO usergroup_admin (usergroup, session, accepted, invited)
+                     VALUES ($1, $2, $3, $4)";
+        self.execute(query, &[&group_id, &user_id, &false, &time::get_time()]).unwrap();
+    }
+    fn accept_group_admin(&self, group_id: i32, user_id: i32) {
+        let _timer = DbMethodTimer::start("accept_group_admin");
+        let query = "UPDATE usergroup_admin
+                     SET accepted = $3
+                     WHERE usergroup = $1
+                     AND session = $2";
+        self.execute(query, &[&group_id, &user_id, &true]).unwrap();
+    }
+    fn remove_group_admin(&self, group_id: i32, user_id: i32) {
+        let _timer = DbMethodTimer::start("remove_group_admin");
+        let query = "DELETE FROM usergroup_admin
+                     WHERE usergroup = $1
+                     AND session = $2";
+        self.execute(query, &[&group_id, &user_id]).unwrap();
+    }
+    fn transfer_group_ownership(&self, group_id: i32, user_id: i32) {
+        let _timer = DbMethodTimer::start("transfer_group_ownership");
+        let query = "SELECT admin
+                     FROM usergroup
+                     WHERE id = $1";
+        let previous_owner: i32 = self.query_map_one(query, &[&group_id], |row| row.get(0)).unwrap().unwrap();
+
+        self.remove_group_admin(group_id, user_id);
+        let query = "UPDATE usergroup
+                     SET admin = $2
+                     WHERE id = $1";
+        self.execute(query, &[&group_id, &user_id]).unwrap();
+        self.add_group_admin(group_id, previous_owner);
+        self.accept_group_admin(group_id, previous_owner);
+    }
+
     fn delete_user(&self, user_id: i32) {
+        let _timer = DbMethodTimer::start("delete_user");
+        let query = "DELETE FROM usergroup_admin
+                     WHERE session = $1";
+        self.execute(query, &[&user_id]).unwrap();
+
         let query = "DELETE FROM session
                      WHERE id = $1";
         self.execute(query, &[&user_id]).unwrap();
     }
     fn delete_all_users_for_group(&self, group_id: i32) {
+        let _timer = DbMethodTimer::start("delete_all_users_for_group");
         let query = "DELETE FROM session
                      WHERE managed_by = $1";
         self.execute(query, &[&group_id]).unwrap();
     }
     fn delete_group(&self, group_id: i32) {
+        let _timer = DbMethodTimer::start("delete_group");
+        let query = "DELETE FROM usergroup_admin
+                     WHERE usergroup = $1";
+        self.execute(query, &[&group_id]).unwrap();
+
         let query = "DELETE FROM usergroup
                      WHERE id = $1";
         self.execute(query, &[&group_id]).unwrap();
     }
     fn delete_participation(&self, user_id: i32, contest_id: i32) {
+        let _timer = DbMethodTimer::start("delete_participation");
         let query = "DELETE FROM submission
                      WHERE id IN (
                          SELECT submission.id FROM submission
@@ -1781,6 +2341,7 @@ impl MedalConnection for Connection {
          Option<String>))
         -> Result<Vec<(i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>,
                   Vec<(i32, String, String, String)>> {
+        let _timer = DbMethodTimer::start("get_search_users");
         if let Some(id) = s_id {
             let query = "SELECT id, firstname, lastname, logincode, oauth_foreign_id, oauth_provider
                          FROM session
@@ -1838,126 +2399,124 @@ impl MedalConnection for Connection {
     fn remove_old_users_and_groups(&self, maxstudentage: time::Timespec, maxteacherage: Option<time::Timespec>,
                                    maxage: Option<time::Timespec>)
                                    -> Result<(i32, i32, i32, i32), ()> {
-        // Get list of all groups where students will be removed
-        let query = "SELECT managed_by
-                     FROM session
-                     WHERE username IS NULL AND password IS NULL AND oauth_foreign_id IS NULL AND oauth_provider IS NULL AND managed_by IS NOT NULL
-                     AND ((last_login < $1 AND last_activity < $1)
-                          OR (last_login < $1 AND last_activity IS NULL)
-                          OR (last_login IS NULL AND last_activity < $1)
-                          OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
-        let mut groups: Vec<i32> = self.query_map_many(query, &[&maxstudentage], |row| row.get(0)).unwrap();
-
-        // Remove students
-        let query = "DELETE
-                     FROM session
-                     WHERE username IS NULL AND password IS NULL AND oauth_foreign_id IS NULL AND oauth_provider IS NULL
-                     AND ((last_login < $1 AND last_activity < $1)
-                          OR (last_login < $1 AND last_activity IS NULL)
-                          OR (last_login IS NULL AND last_activity < $1)
-                          OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
-        self.execute(query, &[&maxstudentage]).unwrap();
-
-        // Bookkeeping
-        let n_users = groups.len() as i32;
-        let mut n_groups: i32 = 0;
-        let mut n_teachers: i32 = 0;
-        let mut n_other: i32 = 0;
-
-        // Get list of groups, where users have been removed from
-        groups.sort_unstable();
-        groups.dedup();
-
-        // Delete all groups that became empty by removing students
-        let query = "SELECT count(*)
-                     FROM session
-                     WHERE managed_by = $1;";
-        for group in groups {
-            let groupsize: i64 = self.query_map_one(query, &[&group], |row| row.get(0)).unwrap().unwrap();
-
-            if groupsize == 0 {
+        let _timer = DbMethodTimer::start("remove_old_users_and_groups");
+        self.transaction(|| {
+                // Get list of all groups where students will be removed
+                let query = "SELECT managed_by
+                             FROM session
+                             WHERE username IS NULL AND password IS NULL AND oauth_foreign_id IS NULL AND oauth_provider IS NULL AND managed_by IS NOT NULL
+                             AND ((last_login < $1 AND last_activity < $1)
+                                  OR (last_login < $1 AND last_activity IS NULL)
+                                  OR (last_login IS NULL AND last_activity < $1)
+                                  OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
+                let mut groups: Vec<i32> = self.query_map_many(query, &[&maxstudentage], |row| row.get(0)).unwrap();
+
+                // Remove students
                 let query = "DELETE
-                             FROM usergroup
-                             WHERE id = $1";
-                self.execute(query, &[&group]).unwrap();
-
-                n_groups += 1;
-            }
-        }
-
-        // Delete all other empty groups that are too old but never had any users
-        let query = "SELECT id
-                     FROM usergroup
-                     WHERE group_created < $1";
-        let groups: Vec<i32> = self.query_map_many(query, &[&maxstudentage], |row| row.get(0)).unwrap();
-        let query = "SELECT count(*)
-                     FROM session
-                     WHERE managed_by = $1;";
-        for group in groups {
-            let groupsize: i64 = self.query_map_one(query, &[&group], |row| row.get(0)).unwrap().unwrap();
+                             FROM session
+                             WHERE username IS NULL AND password IS NULL AND oauth_foreign_id IS NULL AND oauth_provider IS NULL
+                             AND ((last_login < $1 AND last_activity < $1)
+                                  OR (last_login < $1 AND last_activity IS NULL)
+                                  OR (last_login IS NULL AND last_activity < $1)
+                                  OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
+                self.execute(query, &[&maxstudentage]).unwrap();
+
+                // Bookkeeping
+                let n_users = groups.len() as i32;
+                let mut n_groups: i32 = 0;
+                let mut n_teachers: i32 = 0;
+                let mut n_other: i32 = 0;
+
+                // Get list of groups, where users have been removed from
+                groups.sort_unstable();
+                groups.dedup();
+
+                // Delete all groups that became empty by removing students
+                let query = "SELECT count(*)
+                             FROM session
+                             WHERE managed_by = $1;";
+                for group in groups {
+                    let groupsize: i64 = self.query_map_one(query, &[&group], |row| row.get(0)).unwrap().unwrap();
+
+                    if groupsize == 0 {
+                        self.delete_group(group);
+
+                        n_groups += 1;
+                    }
+                }
 
-            if groupsize == 0 {
-                let query = "DELETE
+                // Delete all other empty groups that are too old but never had any users
+                let query = "SELECT id
                              FROM usergroup
-                             WHERE id = $1";
-                self.execute(query, &[&group]).unwrap();
+                             WHERE group_created < $1";
+                let groups: Vec<i32> = self.query_map_many(query, &[&maxstudentage], |row| row.get(0)).unwrap();
+                let query = "SELECT count(*)
+                             FROM session
+                             WHERE managed_by = $1;";
+                for group in groups {
+                    let groupsize: i64 = self.query_map_one(query, &[&group], |row| row.get(0)).unwrap().unwrap();
+
+                    if groupsize == 0 {
+                        self.delete_group(group);
+
+                        n_groups += 1;
+                    }
+                }
 
-                n_groups += 1;
-            }
-        }
+                // Remove teachers
+                let query = "SELECT id
+                             FROM session
+                             WHERE is_teacher = $1
+                             AND ((last_login < $2 AND last_activity < $2)
+                                  OR (last_login < $2 AND last_activity IS NULL)
+                                  OR (last_login IS NULL AND last_activity < $2)
+                                  OR (last_login IS NULL AND last_activity IS NULL AND account_created < $2))";
+                if let Some(maxteacherage) = maxteacherage {
+                    let teachers: Vec<i32> = self.query_map_many(query, &[&true, &maxteacherage], |row| row.get(1)).unwrap();
+
+                    // Only remove if no groups are remaining
+                    let query = "SELECT count(*)
+                                 FROM usergroup
+                                 WHERE admin = $1;";
+                    for teacher in teachers {
+                        let groupcount: i64 = self.query_map_one(query, &[&teacher], |row| row.get(0)).unwrap().unwrap();
+
+                        if groupcount == 0 {
+                            let query = "DELETE
+                                         FROM session
+                                         WHERE id = $1";
+                            self.execute(query, &[&teacher]).unwrap();
+
+                            n_teachers += 1;
+                        }
+                    }
+                }
 
-        // Remove teachers
-        let query = "SELECT id
-                     FROM session
-                     WHERE is_teacher = $1
-                     AND ((last_login < $2 AND last_activity < $2)
-                          OR (last_login < $2 AND last_activity IS NULL)
-                          OR (last_login IS NULL AND last_activity < $2)
-                          OR (last_login IS NULL AND last_activity IS NULL AND account_created < $2))";
-        if let Some(maxteacherage) = maxteacherage {
-            let teachers: Vec<i32> = self.query_map_many(query, &[&true, &maxteacherage], |row| row.get(1)).unwrap();
-
-            // Only remove if no groups are remaining
-            let query = "SELECT count(*)
-                         FROM usergroup
-                         WHERE admin = $1;";
-            for teacher in teachers {
-                let groupcount: i64 = self.query_map_one(query, &[&teacher], |row| row.get(0)).unwrap().unwrap();
+                // Remove other users
+                if let Some(maxage) = maxage {
+                    let query = "SELECT count(*)
+                                 FROM session
+                                 WHERE ((last_login < $1 AND last_activity < $1)
+                                        OR (last_login < $1 AND last_activity IS NULL)
+                                        OR (last_login IS NULL AND last_activity < $1)
+                                        OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
+                    n_other = self.query_map_one(query, &[&maxage], |row| row.get::<_, i64>(0) as i32).unwrap().unwrap();
 
-                if groupcount == 0 {
                     let query = "DELETE
                                  FROM session
-                                 WHERE id = $1";
-                    self.execute(query, &[&teacher]).unwrap();
-
-                    n_teachers += 1;
+                                 WHERE ((last_login < $1 AND last_activity < $1)
+                                        OR (last_login < $1 AND last_activity IS NULL)
+                                        OR (last_login IS NULL AND last_activity < $1)
+                                        OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
+                    self.execute(query, &[&maxage]).unwrap();
                 }
-            }
-        }
-
-        // Remove other users
-        if let Some(maxage) = maxage {
-            let query = "SELECT count(*)
-                         FROM session
-                         WHERE ((last_login < $1 AND last_activity < $1)
-                                OR (last_login < $1 AND last_activity IS NULL)
-                                OR (last_login IS NULL AND last_activity < $1)
-                                OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
-            n_other = self.query_map_one(query, &[&maxage], |row| row.get::<_, i64>(0) as i32).unwrap().unwrap();
-
-            let query = "DELETE
-                         FROM session
-                         WHERE ((last_login < $1 AND last_activity < $1)
-                                OR (last_login < $1 AND last_activity IS NULL)
-                                OR (last_login IS NULL AND last_activity < $1)
-                                OR (last_login IS NULL AND last_activity IS NULL AND account_created < $1))";
-            self.execute(query, &[&maxage]).unwrap();
-        }
 
-        Ok((n_users, n_groups, n_teachers, n_other))
+                Ok((n_users, n_groups, n_teachers, n_other))
+            })
     }
 
     fn remove_temporary_sessions(&self, maxage: time::Timespec) -> Result<(i32, String), ()> {
+        let _timer = DbMethodTimer::start("remove_temporary_sessions");
         // WARNING: This function could possibly be dangerous if the login possibilities change in a way
         // that not every possibility is covered her …
         // TODO: How can we make sure, this function is always safe, even in cases of changes elsewhere?
@@ -1995,6 +2554,20 @@ impl MedalConnection for Connection {
                      AND oauth_foreign_id IS NULL";
         self.execute(query, &[&maxage]).unwrap();
 
+        // Web sessions of the database session store are removed when they have not changed for a long time and do
+        // not belong to a session anymore
+        let query = "DELETE
+                     FROM web_session
+                     WHERE id IN (SELECT id
+                                  FROM web_session
+                                  GROUP BY id
+                                  HAVING max(last_change) < $1)
+                     AND id NOT IN (SELECT web_session.id
+                                    FROM web_session
+                                    JOIN session ON session.session_token = web_session.value
+                                    WHERE web_session.key = 'medal_session')";
+        self.execute(query, &[&maxage]).unwrap();
+
         let result = format!("{}", n_session);
         let query = if cache.is_some() {
             "UPDATE string_cache
@@ -2009,7 +2582,56 @@ impl MedalConnection for Connection {
         Ok((n_session, self::time::strftime("%e. %b %Y, %H:%M", &time::at(cache.unwrap_or(("".to_string(), now)).1)).unwrap_or("could not format".to_string())))
     }
 
+    fn add_audit_log_entry(&self, entry: AuditLogEntry) {
+        let _timer = DbMethodTimer::start("add_audit_log_entry");
+        let query = "INSERT INTO audit_log (actor, actor_name, action, target_user, target_group, target_contest,
+                                            before, after, date)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)";
+        self.execute(query,
+                     &[&entry.actor,
+                       &entry.actor_name,
+                       &entry.action,
+                       &entry.target_user,
+                       &entry.target_group,
+                       &entry.target_contest,
+                       &entry.before,
+                       &entry.after,
+                       &entry.date])
+            .unwrap();
+    }
+
+    fn search_audit_log(&self, user_id: Option<i32>, group_id: Option<i32>, from: Option<time::Timespec>,
+                        until: Option<time::Timespec>)
+                        -> Vec<AuditLogEntry> {
+        let _timer = DbMethodTimer::start("search_audit_log");
+        let query = "SELECT id, actor, actor_name, action, target_user, target_group, target_contest, before, after,
+                            date
+                     FROM audit_log
+                     WHERE ($1 IS NULL OR actor = $1 OR target_user = $1)
+                     AND ($2 IS NULL OR target_group = $2)
+                     AND ($3 IS NULL OR date >= $3)
+                     AND ($4 IS NULL OR date < $4)
+                     ORDER BY id DESC
+                     LIMIT 1000";
+        self.query_map_many(query, &[&user_id, &group_id, &from, &until], |row| AuditLogEntry { id: Some(row.get(0)),
+                                                                                                actor: row.get(1),
+                                                                                                actor_name:
+                                                                                                    row.get(2),
+                                                                                                action: row.get(3),
+                                                                                                target_user:
+                                                                                                    row.get(4),
+                                                                                                target_group:
+                                                                                                    row.get(5),
+                                                                                                target_contest:
+                                                                                                    row.get(6),
+                                                                                                before: row.get(7),
+                                                                                                after: row.get(8),
+                                                                                                date: row.get(9) })
+            .unwrap()
+    }
+
     fn get_debug_information(&self) -> String {
+        let _timer = DbMethodTimer::start("get_debug_information");
         let now = time::get_time();
         let cache_key = "dbstatus";
 
@@ -2129,6 +2751,115 @@ impl MedalConnection for Connection {
         result
     }
 
+    fn get_active_session_count(&self, since: time::Timespec) -> i64 {
+        let _timer = DbMethodTimer::start("get_active_session_count");
+        let query = "SELECT count(*)
+                     FROM session
+                     WHERE last_activity > $1";
+        self.query_map_one(query, &[&since], |row| row.get(0)).unwrap().unwrap()
+    }
+
+    fn get_report_participations_per_contest(&self, from: time::Timespec, until: time::Timespec)
+                                             -> Vec<(i32, String, i64)> {
+        let _timer = DbMethodTimer::start("get_report_participations_per_contest");
+        let query = "SELECT contest.id, contest.name, count(*)
+                     FROM participation
+                     JOIN contest ON participation.contest = contest.id
+                     WHERE participation.start_date >= $1
+                     AND participation.start_date < $2
+                     GROUP BY contest.id, contest.name
+                     ORDER BY contest.id";
+        self.query_map_many(query, &[&from, &until], |row| (row.get(0), row.get(1), row.get(2))).unwrap()
+    }
+
+    fn get_report_participations_by_participant(&self, from: time::Timespec, until: time::Timespec)
+                                                -> Vec<(Option<String>, i32, Option<i32>, i64)> {
+        let _timer = DbMethodTimer::start("get_report_participations_by_participant");
+        let query = "SELECT session.oauth_provider, session.grade, session.sex, count(*)
+                     FROM participation
+                     JOIN session ON participation.session = session.id
+                     WHERE participation.start_date >= $1
+                     AND participation.start_date < $2
+                     GROUP BY session.oauth_provider, session.grade, session.sex";
+        self.query_map_many(query, &[&from, &until], |row| (row.get(0), row.get(1), row.get(2), row.get(3))).unwrap()
+    }
+
+    fn get_report_teachers(&self, from: time::Timespec, until: time::Timespec) -> Vec<(i32, Option<String>)> {
+        let _timer = DbMethodTimer::start("get_report_teachers");
+        let query = "SELECT DISTINCT teacher.id, teacher.oauth_foreign_id
+                     FROM participation
+                     JOIN session AS student ON participation.session = student.id
+                     JOIN usergroup ON student.managed_by = usergroup.id
+                     JOIN session AS teacher ON usergroup.admin = teacher.id
+                     WHERE participation.start_date >= $1
+                     AND participation.start_date < $2";
+        self.query_map_many(query, &[&from, &until], |row| (row.get(0), row.get(1))).unwrap()
+    }
+
+    // Both databases store the dates in UTC, Postgres as "YYYY-MM-DD HH:MM:SS" and SQLite as "YYYY-MM-DDTHH:MM:SSZ".
+    // Day and hour are cut out of the text representation, which works the same for both.
+    fn get_report_account_creations_per_day(&self, from: time::Timespec, until: time::Timespec) -> Vec<(String, i64)> {
+        let _timer = DbMethodTimer::start("get_report_account_creations_per_day");
+        let query = "SELECT substr(CAST(account_created AS TEXT), 1, 10), COUNT(*)
+                     FROM session
+                     WHERE account_created >= $1
+                     AND account_created < $2
+                     AND (username IS NOT NULL OR logincode IS NOT NULL OR oauth_foreign_id IS NOT NULL)
+                     GROUP BY 1
+                     ORDER BY 1";
+        self.query_map_many(query, &[&from, &until], |row| (row.get(0), row.get(1))).unwrap()
+    }
+
+    fn get_report_submissions_per_hour(&self, from: time::Timespec, until: time::Timespec) -> Vec<(String, i64)> {
+        let _timer = DbMethodTimer::start("get_report_submissions_per_hour");
+        let query = "SELECT substr(CAST(date AS TEXT), 1, 10) || ' ' || substr(CAST(date AS TEXT), 12, 2) || ':00',
+                            COUNT(*)
+                     FROM submission
+                     WHERE date >= $1
+                     AND date < $2
+                     GROUP BY 1
+                     ORDER BY 1";
+        self.query_map_many(query, &[&from, &until], |row| (row.get(0), row.get(1))).unwrap()
+    }
+
     fn reset_all_contest_visibilities(&self) { self.execute("UPDATE contest SET public = $1", &[&false]).unwrap(); }
     fn reset_all_taskgroup_visibilities(&self) { self.execute("UPDATE taskgroup SET active = $1", &[&false]).unwrap(); }
+    fn hide_contest(&self, contest_id: i32) {
+        let _timer = DbMethodTimer::start("hide_contest");
+        self.execute("UPDATE contest SET public = $1 WHERE id = $2", &[&false, &contest_id]).unwrap();
+        self.execute("UPDATE taskgroup SET active = $1 WHERE contest = $2", &[&false, &contest_id]).unwrap();
+    }
+
+    fn replace_contests(&self, old_contest_ids: &[i32], contests: &mut [Contest]) {
+        let _timer = DbMethodTimer::start("replace_contests");
+        self.transaction(|| {
+                for &contest_id in old_contest_ids {
+                    self.hide_contest(contest_id);
+                }
+                for contest in contests {
+                    save_contest(contest, self);
+                }
+            });
+    }
+
+    fn get_contest_file_hashes(&self) -> Vec<(String, String, String)> {
+        let _timer = DbMethodTimer::start("get_contest_file_hashes");
+        let query = "SELECT location, filename, hash
+                     FROM contest_file";
+        self.query_map_many(query, &[], |row| (row.get(0), row.get(1), row.get(2))).unwrap()
+    }
+
+    fn set_contest_file_hash(&self, location: &str, filename: &str, hash: Option<&str>) {
+        let _timer = DbMethodTimer::start("set_contest_file_hash");
+        let query = "DELETE FROM contest_file
+                     WHERE location = $1
+                     AND filename = $2";
+        self.execute(query, &[&location, &filename]).unwrap();
+
+        if let Some(hash) = hash {
+            let query = "INSERT INTO contest_file (location, filename, hash, scanned)
+                         VALUES ($1, $2, $3, $4)";
+            self.execute(query, &[&location, &filename, &hash, &time::get_time()]).unwrap();
+        }
+    }
 }
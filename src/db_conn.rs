@@ -14,6 +14,7 @@
 
 use config;
 use db_objects::*;
+use permissions::Role;
 
 #[derive(Debug)]
 pub enum SignupResult {
@@ -28,9 +29,18 @@ pub enum SignupResult {
 /// in the medal platform.
 pub trait MedalConnection {
     fn reconnect(config: &config::Config) -> Self;
+    /// Open an additional connection that is only used for reading, if the database supports it (SQLite). Returns
+    /// `None` otherwise.
+    fn connect_reader(config: &config::Config) -> Option<Self>
+        where Self: Sized;
 
     fn dbtype(&self) -> &'static str;
 
+    /// Run `f` in a database transaction. The changes of `f` are committed when it returns and rolled back if it
+    /// panics, e.g. on a failed statement. Transactions can not be nested.
+    fn transaction<T, F>(&self, f: F) -> T
+        where F: FnOnce() -> T;
+
     fn migration_already_applied(&self, name: &str) -> bool;
     fn apply_migration(&mut self, name: &str, contents: &str);
 
@@ -42,6 +52,11 @@ pub trait MedalConnection {
     /// `None` otherwise.
     fn get_session(&self, key: &str) -> Option<SessionUser>;
 
+    /// Get the language chosen by the user of the session with the session token `key`.
+    ///
+    /// Unlike `get_session`, this does not check whether the session is expired and does not update its activity.
+    fn get_session_language(&self, key: &str) -> Option<String>;
+
     /// Create a new anonymous session with the session token `key`.
     ///
     /// Returns the `SessionUser` of the session.
@@ -61,6 +76,11 @@ pub trait MedalConnection {
     /// Returns an `Option` that can contain the `SessionUser` of the session if the session exists or `None` otherwise.
     fn get_user_by_id(&self, user_id: i32) -> Option<SessionUser>;
 
+    /// Roles of the user with id `user_id`. They are also loaded by `get_session` and `get_user_by_id`.
+    fn get_user_roles(&self, user_id: i32) -> Vec<Role>;
+    /// Replace the roles of the user with id `user_id` by `roles`.
+    fn set_user_roles(&self, user_id: i32, roles: &[Role]);
+
     /// Try to get session and user group associated to the id `user_id`.
     ///
     /// Returns an `Option` that can contain a pair of `SessionUser` and `Option<Group>` of the session and optionally
@@ -74,27 +94,71 @@ pub trait MedalConnection {
     fn login(&self, session: Option<&str>, username: &str, password: &str) -> Result<String, ()>;
     fn login_with_code(&self, session: Option<&str>, logincode: &str) -> Result<String, ()>;
     fn login_foreign(&self, session: Option<&str>, provider_id: &str, foreign_id: &str,
-                     _: (bool, bool, &str, &str, Option<i32>))
+                     _: (bool, bool, &str, &str, Option<i32>, i32))
                      -> Result<(String, Option<time::Timespec>), ()>;
     fn create_user_with_groupcode(&self, session: Option<&str>, groupcode: &str) -> Result<String, ()>;
-    fn create_group_with_users(&self, group: Group);
+    fn create_group_with_users(&self, group: Group) -> i32;
+    /// Create all groups with their members. Has to be called inside `transaction`, so that either all or none of them
+    /// are created. Returns the ids of the groups.
+    fn create_groups_with_users(&self, groups: Vec<Group>) -> Vec<i32>;
 
     /// Logs out the user identified by session token `session` by resetting the uesr's session token in the database
     /// to `NULL`.
     fn logout(&self, session: &str);
 
+    /// Load the values of the web session `web_session_id` of the database session store.
+    fn get_web_session(&self, web_session_id: &str) -> Vec<(String, String)>;
+    /// Replace the values of the web session `web_session_id`. Without values the web session is removed.
+    fn save_web_session(&self, web_session_id: &str, values: &[(String, String)]);
+    /// Remove all web sessions in which `key` is set to `value`.
+    fn remove_web_sessions_with_value(&self, key: &str, value: &str);
+
     fn signup(&self, session_token: &str, username: &str, email: &str, password_hash: String, salt: &str)
               -> SignupResult;
 
+    fn get_user_id_by_username(&self, username: &str) -> Option<i32>;
+    fn get_user_id_by_logincode(&self, logincode: &str) -> Option<i32>;
+    /// Find the user with the confirmed email address `email`. Unconfirmed addresses can be entered by anyone and
+    /// are therefore ignored.
+    fn get_user_id_by_email(&self, email: &str) -> Option<i32>;
+    fn get_user_id_by_email_confirmationcode(&self, code: &str) -> Option<i32>;
+
+    /// Store the hash (see `helpers::hash_token`) of a password reset token of the user.
+    fn add_password_reset_token(&self, user_id: i32, token_hash: &str);
+    /// Find the user a password reset token with hash `token_hash` has been created for. Tokens created before
+    /// `min_created` are ignored.
+    fn get_user_id_by_password_reset_token(&self, token_hash: &str, min_created: time::Timespec) -> Option<i32>;
+    fn remove_password_reset_tokens(&self, user_id: i32);
+    /// Generation of the login links of the user. Only links of the current generation log the user in.
+    fn get_login_link_generation(&self, user_id: i32) -> i32;
+    /// Revoke the login links of the user by starting a new generation
+    fn revoke_login_links(&self, user_id: i32);
+
     fn load_submission(&self, session: &SessionUser, task: i32, subtask: Option<&str>) -> Option<Submission>;
     fn get_all_submissions(&self, session_id: i32, task: i32, subtask: Option<&str>) -> Vec<Submission>;
     fn submit_submission(&self, submission: Submission);
     fn get_grade_by_submission(&self, submission_id: i32) -> Grade;
+    /// For each student of group `group_id` and each taskgroup of contest `contest_id` the student has submitted a
+    /// solution for: The ids of student and taskgroup, the start of the participation and the date of the first
+    /// submission.
+    fn get_first_submission_dates(&self, group_id: i32, contest_id: i32)
+                                  -> Vec<(i32, i32, time::Timespec, time::Timespec)>;
     fn get_contest_groups_grades(&self, session_id: i32, contest_id: i32)
                                  -> (Vec<String>, Vec<(Group, Vec<(UserInfo, Vec<Grade>)>)>);
     fn get_taskgroup_user_grade(&self, session: &str, taskgroup_id: i32) -> Grade;
     fn get_contest_user_grades(&self, session: &str, contest_id: i32) -> Vec<Grade>;
-    fn export_contest_results_to_file(&self, contest_id: i32, taskgroups_ids: &[(i32, String)], filename: &str);
+    /// Get the names of the active taskgroups of a contest together with the grades and submission statistics of
+    /// all participants of that contest, unordered.
+    fn get_contest_ranking_data(&self, contest_id: i32) -> (Vec<String>, Vec<RankingParticipant>);
+    /// Call `callback` for at most `limit` participations in the contest with id `contest_id`, ordered by the id of
+    /// the session and starting after the session with id `after_session`.
+    ///
+    /// The grades are fetched for the taskgroups with ids `taskgroup_ids`, the best submission for the tasks with ids
+    /// `task_ids`. Submissions are only counted if `count_submissions` is set.
+    #[allow(clippy::too_many_arguments)]
+    fn export_contest_results(&self, contest_id: i32, taskgroup_ids: &[i32], task_ids: &[i32],
+                              count_submissions: bool, after_session: i32, limit: i64,
+                              callback: &mut dyn FnMut(ContestResultRow));
 
     fn insert_contest_annotations(&self, contest_id: i32, annotations: Vec<(i32, Option<String>)>) -> i32;
 
@@ -131,10 +195,13 @@ pub trait MedalConnection {
     ///
     /// Returns an `Vec` that contains pairs of all participations with their associated contests.
     fn get_all_participations_complete(&self, session_id: i32) -> Vec<(Participation, Contest)>;
+    /// Store the start, extra time and pauses of `participation`.
+    fn save_participation_time(&self, participation: &Participation);
 
     fn count_all_stars(&self, session_id: i32) -> i32;
 
-    fn has_participation_by_contest_file(&self, session_id: i32, location: &str, filename: &str) -> bool;
+    fn get_contest_id_by_file(&self, location: &str, filename: &str) -> Option<i32>;
+    fn get_participation_annotation(&self, session_id: i32, contest_id: i32) -> Option<String>;
 
     /// Start a new participation of the session identified by the session token `session` for the contest with the
     /// contest id `contest_id`. It checks whether the session is allowed to start the participation.
@@ -145,8 +212,15 @@ pub trait MedalConnection {
     fn get_task_by_id(&self, task_id: i32) -> Option<Task>;
     fn get_task_by_id_complete(&self, task_id: i32) -> Option<(Task, Taskgroup, Contest)>;
 
-    fn get_submission_to_validate(&self, tasklocation: &str, subtask: Option<&str>) -> i32;
-    fn find_next_submission_to_validate(&self, userid: i32, taskgroupid: i32);
+    /// Get the oldest submission that is marked as `needs_validation` and belongs to a task with a checker.
+    ///
+    /// Returns an `Option` that can contain the `Submission` or `None` if there is nothing to validate.
+    fn get_next_submission_to_validate(&self) -> Option<Submission>;
+    /// Store the result of the validation of the submission with id `submission_id`.
+    ///
+    /// The submission is no longer marked as `needs_validation` and the grade of the taskgroup is recomputed from all
+    /// submissions that do not wait for validation.
+    fn validate_submission(&self, submission_id: i32, grade: i32);
 
     fn add_group(&self, group: &mut Group);
     fn get_groups(&self, session_id: i32) -> Vec<Group>;
@@ -155,6 +229,17 @@ pub trait MedalConnection {
     fn group_has_protected_participations(&self, session_id: i32) -> bool;
     fn get_group_complete(&self, group_id: i32) -> Option<Group>;
 
+    /// The teachers invited to manage the group with id `group_id` besides its owner, accepted or not.
+    fn get_group_admins(&self, group_id: i32) -> Vec<GroupAdmin>;
+    /// The groups the user with id `user_id` has been invited to but did not yet accept.
+    fn get_group_invitations(&self, user_id: i32) -> Vec<Group>;
+    fn add_group_admin(&self, group_id: i32, user_id: i32);
+    fn accept_group_admin(&self, group_id: i32, user_id: i32);
+    fn remove_group_admin(&self, group_id: i32, user_id: i32);
+    /// Make the teacher with id `user_id` the owner of the group with id `group_id`. The previous owner stays a
+    /// group admin.
+    fn transfer_group_ownership(&self, group_id: i32, user_id: i32);
+
     fn delete_user(&self, user_id: i32);
     fn delete_all_users_for_group(&self, group_id: i32);
     fn delete_group(&self, group_id: i32);
@@ -164,15 +249,55 @@ pub trait MedalConnection {
                                    -> Result<(i32, i32, i32, i32), ()>;
     fn remove_temporary_sessions(&self, maxage: time::Timespec) -> Result<(i32, String), ()>;
 
+    /// Append `entry` to the audit log.
+    fn add_audit_log_entry(&self, entry: AuditLogEntry);
+    /// Search the audit log, newest entries first.
+    ///
+    /// If `user_id` is given, only entries where this user is the actor or the target are returned. If `group_id` is
+    /// given, only entries targeting this group are returned. `from` and `until` restrict the date of the entries.
+    fn search_audit_log(&self, user_id: Option<i32>, group_id: Option<i32>, from: Option<time::Timespec>,
+                        until: Option<time::Timespec>)
+                        -> Vec<AuditLogEntry>;
+
     fn get_search_users(
         &self, _: (Option<i32>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>))
         -> Result<Vec<(i32, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)>,
                   Vec<(i32, String, String, String)>>;
 
     fn get_debug_information(&self) -> String;
+    /// Number of sessions with activity since `since`
+    fn get_active_session_count(&self, since: time::Timespec) -> i64;
+
+    // Data for the platform-wide reports. All of them only consider participations started, accounts created or
+    // submissions made in the time range from `from` (inclusive) to `until` (exclusive).
+
+    /// Id, name and number of participations of each contest with participations
+    fn get_report_participations_per_contest(&self, from: time::Timespec, until: time::Timespec)
+                                             -> Vec<(i32, String, i64)>;
+    /// Number of participations by oauth provider, grade and sex of the participant
+    fn get_report_participations_by_participant(&self, from: time::Timespec, until: time::Timespec)
+                                                -> Vec<(Option<String>, i32, Option<i32>, i64)>;
+    /// Id and oauth id of all teachers that have a group with participations
+    fn get_report_teachers(&self, from: time::Timespec, until: time::Timespec) -> Vec<(i32, Option<String>)>;
+    /// Number of created accounts that can be logged in to per day (UTC, formatted as YYYY-MM-DD), in
+    /// chronological order. Days without new accounts are left out.
+    fn get_report_account_creations_per_day(&self, from: time::Timespec, until: time::Timespec) -> Vec<(String, i64)>;
+    /// Number of submissions per hour (UTC, formatted as YYYY-MM-DD HH:00), in chronological order. Hours without
+    /// submissions are left out.
+    fn get_report_submissions_per_hour(&self, from: time::Timespec, until: time::Timespec) -> Vec<(String, i64)>;
 
     fn reset_all_contest_visibilities(&self);
     fn reset_all_taskgroup_visibilities(&self);
+    /// Make the contest non-public and deactivate its taskgroups. Saving the contest again reactivates them.
+    fn hide_contest(&self, contest_id: i32);
+    /// Hide the contests with the ids `old_contest_ids` and save `contests` in a single transaction, so that contests
+    /// that are defined again never appear hidden to other connections.
+    fn replace_contests(&self, old_contest_ids: &[i32], contests: &mut [Contest]);
+
+    /// Get location, filename and hash of all contest files loaded so far.
+    fn get_contest_file_hashes(&self) -> Vec<(String, String, String)>;
+    /// Remember the hash of a loaded contest file. If `hash` is `None`, the file is forgotten.
+    fn set_contest_file_hash(&self, location: &str, filename: &str, hash: Option<&str>);
 }
 
 pub trait MedalObject<T: MedalConnection> {
@@ -24,6 +24,8 @@ use time::Duration;
 use db_conn::{MedalConnection, MedalObject, SignupResult};
 use db_objects::*;
 use helpers;
+use metrics::DbMethodTimer;
+use permissions::Role;
 
 fn gen_tosql_vector() -> Vec<&'static dyn postgres::types::ToSql> { Vec::new() }
 
@@ -37,6 +39,10 @@ trait Queryable {
     fn get_last_id(&self) -> Option<i32>;
 
     fn reconnect_concrete(config: &config::Config) -> Self;
+    fn connect_reader_concrete(config: &config::Config) -> Option<Self>
+        where Self: Sized;
+    /// Whether the connection has been opened by `connect_reader_concrete`
+    fn is_read_only(&self) -> bool;
 }
 
 impl Queryable for Connection {
@@ -69,6 +75,11 @@ impl Queryable for Connection {
     fn reconnect_concrete(config: &config::Config) -> Self {
         postgres::Connection::connect(config.database_url.clone().unwrap(), postgres::TlsMode::None).unwrap()
     }
+
+    // All connections of the pool are used for writing
+    fn connect_reader_concrete(_config: &config::Config) -> Option<Self> { None }
+
+    fn is_read_only(&self) -> bool { false }
 }
 
 impl MedalObject<Connection> for Grade {
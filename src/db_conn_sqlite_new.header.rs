@@ -24,6 +24,8 @@ use time::Duration;
 use db_conn::{MedalConnection, MedalObject, SignupResult};
 use db_objects::*;
 use helpers;
+use metrics::DbMethodTimer;
+use permissions::Role;
 
 fn gen_tosql_vector() -> Vec<&'static dyn rusqlite::types::ToSql> { Vec::new() }
 
@@ -37,6 +39,10 @@ trait Queryable {
     fn get_last_id(&self) -> Option<i32>;
 
     fn reconnect_concrete(config: &config::Config) -> Self;
+    fn connect_reader_concrete(config: &config::Config) -> Option<Self>
+        where Self: Sized;
+    /// Whether the connection has been opened by `connect_reader_concrete`
+    fn is_read_only(&self) -> bool;
 }
 
 impl Queryable for Connection {
@@ -67,7 +73,26 @@ impl Queryable for Connection {
     fn get_last_id(&self) -> Option<i32> { self.query_row("SELECT last_insert_rowid()", &[], |row| row.get(0)).ok() }
 
     fn reconnect_concrete(config: &config::Config) -> Self {
-        rusqlite::Connection::open(config.database_file.clone().unwrap()).unwrap()
+        let conn = rusqlite::Connection::open(config.database_file.clone().unwrap()).unwrap();
+        // Same settings as the connection opened in `main`. Without the timeout, a write fails immediately while
+        // another connection (e.g. of a background worker) is writing.
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        conn.busy_timeout(std::time::Duration::from_secs(5)).unwrap();
+        conn
+    }
+
+    fn connect_reader_concrete(config: &config::Config) -> Option<Self> {
+        let conn = rusqlite::Connection::open_with_flags(config.database_file.clone()?,
+                                                         rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        // Wait instead of failing while the writer checkpoints the WAL
+        conn.busy_timeout(std::time::Duration::from_secs(5)).unwrap();
+        // Marks the connection for `is_read_only`
+        conn.execute_batch("PRAGMA query_only = ON").unwrap();
+        Some(conn)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.query_row("PRAGMA query_only", &[], |row| row.get::<_, i64>(0)).map(|query_only| query_only != 0).unwrap()
     }
 }
 
@@ -16,6 +16,8 @@ extern crate time;
 
 use self::time::{Duration, Timespec};
 
+use permissions::Role;
+
 #[derive(Clone, Debug)]
 pub struct SessionUser {
     pub id: i32,
@@ -45,15 +47,19 @@ pub struct SessionUser {
     pub is_admin: Option<bool>,
     pub is_teacher: bool,
     pub managed_by: Option<i32>,
+    pub roles: Vec<Role>,
 
     pub oauth_foreign_id: Option<String>,
     pub oauth_provider: Option<String>,
+    /// Preferred language of the user interface, e.g. `en`. `None` uses the language of the browser.
+    pub language: Option<String>,
     // pub oauth_extra_data: Option<String>,
 
     // pub pms_id: Option<i32>,
     // pub pms_school_id: Option<i32>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Sex {
     #[allow(dead_code)]
     NotStated = 0,
@@ -86,6 +92,16 @@ pub struct Group {
     pub members: Vec<SessionUser>,
 }
 
+/// A teacher that has been invited to manage a group together with the group's owner
+#[derive(Clone, Debug)]
+pub struct GroupAdmin {
+    pub id: i32,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    /// Whether the teacher accepted the invitation. Only then the teacher has access to the group.
+    pub accepted: bool,
+}
+
 #[derive(Debug)]
 pub struct Contest {
     pub id: Option<i32>,
@@ -110,6 +126,9 @@ pub struct Contest {
     pub language: Option<String>,
     pub category: Option<String>,
     pub standalone_task: Option<bool>,
+    pub tie_breaking: Option<String>,
+    pub public_scoreboard: Option<bool>,
+    pub qualification: Option<String>,
     pub tags: Vec<String>,
     pub taskgroups: Vec<Taskgroup>,
 }
@@ -131,6 +150,7 @@ pub struct Task {
     pub location: String,
     pub language: Option<String>,
     pub stars: i32,
+    pub checker: Option<String>,
 }
 
 pub struct Submission {
@@ -154,10 +174,75 @@ pub struct Grade {
     pub validated: bool,
 }
 
+/// Everything the ranking needs to know about one participation in a contest
+#[derive(Clone)]
+pub struct RankingParticipant {
+    pub user: UserInfo,
+    pub sex: Option<i32>,
+    pub group_id: Option<i32>,
+    pub group_name: Option<String>,
+    pub group_admin: Option<i32>,
+    pub school_id: Option<String>,
+    pub grades: Vec<Option<i32>>,
+    pub attempts: i32,
+    pub final_score_reached: Option<Timespec>,
+}
+
+/// One participation in a contest as it appears in the results export
+pub struct ContestResultRow {
+    pub id: i32,
+    pub username: Option<String>,
+    pub logincode: Option<String>,
+    pub oauth_foreign_id: Option<String>,
+    pub oauth_provider: Option<String>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub grade: i32,
+    pub sex: Option<i32>,
+    pub is_teacher: bool,
+    pub group_id: Option<i32>,
+    pub group_name: Option<String>,
+    pub group_tag: Option<String>,
+    pub teacher_id: Option<i32>,
+    pub teacher_firstname: Option<String>,
+    pub teacher_lastname: Option<String>,
+    pub teacher_oauth_foreign_id: Option<String>,
+    pub teacher_oauth_provider: Option<String>,
+    pub contest_id: i32,
+    pub start_date: Option<Timespec>,
+    /// Grade per taskgroup, in the order of the requested taskgroups
+    pub grades: Vec<Option<i32>>,
+    /// Best submission per task, in the order of the requested tasks
+    pub task_grades: Vec<Option<i32>>,
+    /// Number of submissions per taskgroup, in the order of the requested taskgroups
+    pub submission_counts: Vec<i64>,
+}
+
+/// One entry of the audit log, recording an admin or teacher action
+#[derive(Clone, Debug)]
+pub struct AuditLogEntry {
+    pub id: Option<i32>,
+    pub actor: Option<i32>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub target_user: Option<i32>,
+    pub target_group: Option<i32>,
+    pub target_contest: Option<i32>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub date: Timespec,
+}
+
 pub struct Participation {
     pub contest: i32,
     pub user: i32,
     pub start: Timespec,
+    /// Minutes added to the duration of the contest for this participation
+    pub extra_time: i32,
+    /// Seconds the participation has been paused in total, not counting a pause that is still running
+    pub paused_secs: i32,
+    /// Start of the running pause, if the participation is currently paused
+    pub paused_since: Option<Timespec>,
 }
 
 pub trait HasId {
@@ -216,9 +301,11 @@ impl SessionUser {
             is_admin: Some(false),
             is_teacher: false,
             managed_by: None,
+            roles: Vec::new(),
 
             oauth_foreign_id: None,
             oauth_provider: None,
+            language: None,
             // oauth_extra_data: Option<String>,
 
             //pms_id: None,
@@ -254,9 +341,11 @@ impl SessionUser {
                       is_admin: None,
                       is_teacher: false,
                       managed_by: None,
+                      roles: Vec::new(),
 
                       oauth_foreign_id: None,
-                      oauth_provider: None }
+                      oauth_provider: None,
+                      language: None }
     }
 
     pub fn is_alive(&self) -> bool {
@@ -277,6 +366,13 @@ impl SessionUser {
 
     pub fn is_admin(&self) -> bool { self.is_admin == Some(true) }
 
+    pub fn has_role(&self, role: Role) -> bool { self.roles.contains(&role) }
+
+    /// For PMS teachers, the foreign id has the form "<teacher id>/<school id>"
+    pub fn school_id(&self) -> Option<String> {
+        self.oauth_foreign_id.as_ref()?.split('/').nth(1).map(|s| s.to_owned())
+    }
+
     pub fn ensure_alive(self) -> Option<Self> {
         if self.is_alive() {
             Some(self)
@@ -292,30 +388,6 @@ impl SessionUser {
             None
         }
     }
-
-    pub fn ensure_teacher(self) -> Option<Self> {
-        if self.is_logged_in() && self.is_teacher() {
-            Some(self)
-        } else {
-            None
-        }
-    }
-
-    pub fn ensure_admin(self) -> Option<Self> {
-        if self.is_logged_in() && self.is_admin() {
-            Some(self)
-        } else {
-            None
-        }
-    }
-
-    pub fn ensure_teacher_or_admin(self) -> Option<Self> {
-        if self.is_logged_in() && (self.is_admin() || self.is_teacher()) {
-            Some(self)
-        } else {
-            None
-        }
-    }
 }
 
 impl Taskgroup {
@@ -324,22 +396,38 @@ impl Taskgroup {
     }
 }
 
+impl AuditLogEntry {
+    pub fn new(actor: &SessionUser, action: &str) -> Self {
+        let actor_name = match (&actor.firstname, &actor.lastname, &actor.username) {
+            (Some(firstname), Some(lastname), _) => Some(format!("{} {}", firstname, lastname)),
+            (_, _, Some(username)) => Some(username.clone()),
+            _ => None,
+        };
+        AuditLogEntry { id: None,
+                        actor: Some(actor.id),
+                        actor_name,
+                        action: action.to_string(),
+                        target_user: None,
+                        target_group: None,
+                        target_contest: None,
+                        before: None,
+                        after: None,
+                        date: time::get_time() }
+    }
+}
+
 impl Task {
     pub fn new(location: String, language: Option<String>, stars: i32) -> Self {
-        Task { id: None, taskgroup: 0, location, language, stars }
+        Task { id: None, taskgroup: 0, location, language, stars, checker: None }
     }
 }
 
 pub trait OptionSession {
     fn ensure_alive(self) -> Self;
     fn ensure_logged_in(self) -> Self;
-    fn ensure_teacher(self) -> Self;
-    fn ensure_admin(self) -> Self;
 }
 
 impl OptionSession for Option<SessionUser> {
     fn ensure_alive(self) -> Self { self?.ensure_alive() }
     fn ensure_logged_in(self) -> Self { self?.ensure_logged_in() }
-    fn ensure_teacher(self) -> Self { self?.ensure_teacher() }
-    fn ensure_admin(self) -> Self { self?.ensure_admin() }
 }
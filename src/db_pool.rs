@@ -0,0 +1,203 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Pool of database connections shared by the request handlers.
+//!
+//! Every handler checks out a connection for the time it needs the database and returns it afterwards, so that
+//! requests only wait for each other if all connections are in use. The connections are opened with
+//! `MedalConnection::reconnect` and `MedalConnection::connect_reader`:
+//!
+//! * PostgreSQL: `database_pool_size` connections (default: 4), all of them used for reading and writing.
+//! * SQLite: One connection for writing, since SQLite only allows one writer at a time anyway, and `sqlite_readers`
+//!   read-only connections (default: 0). With WAL mode, the readers do not block the writer and vice versa.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex};
+use std::time::Instant;
+
+use config::Config;
+use db_conn::MedalConnection;
+use metrics;
+
+const DEFAULT_POSTGRES_POOL_SIZE: usize = 4;
+
+/// Idle connections of one kind and the condition variable to wait for a returned connection
+struct Connections<C> {
+    idle: Mutex<Vec<C>>,
+    returned: Condvar,
+}
+
+impl<C> Connections<C> {
+    fn new(connections: Vec<C>) -> Self { Connections { idle: Mutex::new(connections), returned: Condvar::new() } }
+
+    fn take(&self) -> C {
+        let start = Instant::now();
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        let conn = loop {
+            match idle.pop() {
+                Some(conn) => break conn,
+                None => idle = self.returned.wait(idle).unwrap_or_else(|e| e.into_inner()),
+            }
+        };
+        metrics::observe_connection_wait(start.elapsed());
+        conn
+    }
+
+    fn put_back(&self, conn: C) {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).push(conn);
+        self.returned.notify_one();
+    }
+}
+
+pub struct ConnectionPool<C> {
+    connections: Connections<C>,
+    readers: Option<Connections<C>>,
+}
+
+impl<C> ConnectionPool<C> where C: MedalConnection
+{
+    /// Create a pool of the connection `conn` and the additional connections configured in `config`.
+    pub fn open(conn: C, config: &Config) -> Self {
+        let pool_size = match conn.dbtype() {
+            "postgres" => config.database_pool_size.unwrap_or(DEFAULT_POSTGRES_POOL_SIZE).max(1),
+            _ => 1,
+        };
+
+        let mut connections = vec![conn];
+        connections.extend((1..pool_size).map(|_| C::reconnect(config)));
+        let readers: Vec<C> =
+            (0..config.sqlite_readers.unwrap_or(0)).map_while(|_| C::connect_reader(config)).collect();
+
+        ConnectionPool { connections: Connections::new(connections),
+                         readers: if readers.is_empty() { None } else { Some(Connections::new(readers)) } }
+    }
+
+    /// Check out a connection for reading and writing. Waits until a connection is free.
+    pub fn get(&self) -> PooledConnection<'_, C> {
+        PooledConnection { conn: Some(self.connections.take()), connections: &self.connections }
+    }
+
+    /// Check out a connection that is only used for reading. Without read-only connections, this is the same as `get`.
+    ///
+    /// Core functions called with a reader must not write anything besides the activity of the session, which is
+    /// skipped on read-only connections.
+    pub fn get_reader(&self) -> PooledConnection<'_, C> {
+        match self.readers {
+            Some(ref readers) => PooledConnection { conn: Some(readers.take()), connections: readers },
+            None => self.get(),
+        }
+    }
+}
+
+/// Connection checked out of a `ConnectionPool`. It is returned to the pool when dropped.
+pub struct PooledConnection<'a, C> {
+    // Only `None` while dropping
+    conn: Option<C>,
+    connections: &'a Connections<C>,
+}
+
+impl<C> Deref for PooledConnection<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C { self.conn.as_ref().unwrap() }
+}
+
+impl<C> DerefMut for PooledConnection<'_, C> {
+    fn deref_mut(&mut self) -> &mut C { self.conn.as_mut().unwrap() }
+}
+
+impl<C> Drop for PooledConnection<'_, C> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.connections.put_back(conn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use db_apply_migrations;
+
+    fn database_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("medal_pool_test_{}_{}.db", name, std::process::id()));
+        for suffix in &["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        path
+    }
+
+    #[test]
+    fn connections_are_returned_to_the_pool() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let pool = Arc::new(ConnectionPool::open(conn, &Config::default()));
+
+        let first = pool.get();
+        let waiting_pool = pool.clone();
+        let waiting = std::thread::spawn(move || waiting_pool.get().dbtype());
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        assert_eq!(waiting.join().unwrap(), "sqlite_v2");
+    }
+
+    #[test]
+    fn readers_see_written_data_and_do_not_write() {
+        let path = database_file("readers");
+        let config = Config { database_file: Some(path.clone()), sqlite_readers: Some(2), ..Config::default() };
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA journal_mode = WAL; CREATE TABLE test (value INTEGER);").unwrap();
+        let pool = ConnectionPool::open(conn, &config);
+
+        let writer = pool.get();
+        let reader = pool.get_reader();
+        let second_reader = pool.get_reader();
+        writer.execute("INSERT INTO test (value) VALUES (42)", &[]).unwrap();
+
+        let value: i32 = reader.query_row("SELECT value FROM test", &[], |row| row.get(0)).unwrap();
+        assert_eq!(value, 42);
+        assert!(second_reader.execute("INSERT INTO test (value) VALUES (43)", &[]).is_err());
+
+        drop((writer, reader, second_reader));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn only_writers_update_the_session_activity() {
+        let path = database_file("activity");
+        let config = Config { database_file: Some(path.clone()), sqlite_readers: Some(1), ..Config::default() };
+        let mut conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA journal_mode = WAL;").unwrap();
+        db_apply_migrations::test(&mut conn);
+        let session_id = conn.new_session("token").id;
+        let an_hour_ago = time::get_time() - time::Duration::hours(1);
+        conn.session_set_activity_dates(session_id, None, None, Some(an_hour_ago));
+        let pool = ConnectionPool::open(conn, &config);
+
+        let reader = pool.get_reader();
+        assert_eq!(reader.get_session("token").unwrap().last_activity.unwrap().sec, an_hour_ago.sec);
+        assert_eq!(reader.get_session("token").unwrap().last_activity.unwrap().sec, an_hour_ago.sec);
+
+        pool.get().get_session("token").unwrap();
+        assert!(reader.get_session("token").unwrap().last_activity.unwrap().sec > an_hour_ago.sec);
+
+        drop(reader);
+        let _ = std::fs::remove_file(&path);
+    }
+}
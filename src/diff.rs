@@ -0,0 +1,199 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Line based comparison of two submission values for the side by side view of the submission history.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Unchanged,
+    /// The line was replaced by a different line
+    Changed,
+    /// The line only exists in the left value
+    Removed,
+    /// The line only exists in the right value
+    Added,
+}
+
+/// One row of the side by side view. Line numbers start at 1.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub left_number: Option<usize>,
+    pub left: Option<String>,
+    pub right_number: Option<usize>,
+    pub right: Option<String>,
+}
+
+/// Submissions are often stored as JSON in a single line. Pretty-print them, so the comparison shows what changed.
+pub fn prettify(value: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(value) {
+        Ok(json @ serde_json::Value::Object(_)) | Ok(json @ serde_json::Value::Array(_)) => {
+            serde_json::to_string_pretty(&json).unwrap_or_else(|_| value.to_string())
+        }
+        _ => value.to_string(),
+    }
+}
+
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Maximal number of entries of the table of `edit_ops`, which uses 4 bytes per entry
+const MAX_TABLE_SIZE: usize = 4_000_000;
+
+/// Compute the longest common subsequence of the lines and return the edit operations to get from `left` to `right`.
+///
+/// Returns `None` if the values differ in too many lines to be compared within `MAX_TABLE_SIZE`.
+fn edit_ops(left: &[&str], right: &[&str]) -> Option<Vec<Op>> {
+    // Equal lines at the beginning and at the end do not need to be part of the table
+    let prefix = left.iter().zip(right).take_while(|(l, r)| l == r).count();
+    let suffix = left[prefix..].iter().rev().zip(right[prefix..].iter().rev()).take_while(|(l, r)| l == r).count();
+    let (n, m) = (left.len() - prefix - suffix, right.len() - prefix - suffix);
+
+    if (n + 1).saturating_mul(m + 1) > MAX_TABLE_SIZE {
+        return None;
+    }
+    let (left, right) = (&left[prefix..prefix + n], &right[prefix..prefix + m]);
+
+    // lcs[i][j] is the length of the longest common subsequence of left[i..] and right[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if left[i] == right[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if left[i] == right[j] {
+            ops.push(Op::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(Op::Delete(prefix + i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(|i| Op::Delete(prefix + i)));
+    ops.extend((j..m).map(|j| Op::Insert(prefix + j)));
+    ops.extend((0..suffix).map(|k| Op::Equal(prefix + n + k, prefix + m + k)));
+    Some(ops)
+}
+
+/// Compare `left` and `right` line by line.
+///
+/// Removed and added lines between two unchanged lines are paired up as changed lines, so that they appear next to
+/// each other in the side by side view. Returns `None` if the values are too large to be compared.
+pub fn diff_lines(left: &str, right: &str) -> Option<Vec<DiffLine>> {
+    let left: Vec<&str> = left.lines().collect();
+    let right: Vec<&str> = right.lines().collect();
+
+    let mut result = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    let flush = |result: &mut Vec<DiffLine>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for k in 0..removed.len().max(added.len()) {
+            let l = removed.get(k).cloned();
+            let r = added.get(k).cloned();
+            let kind = match (l, r) {
+                (Some(_), Some(_)) => DiffKind::Changed,
+                (Some(_), None) => DiffKind::Removed,
+                _ => DiffKind::Added,
+            };
+            result.push(DiffLine { kind,
+                                   left_number: l.map(|i| i + 1),
+                                   left: l.map(|i| left[i].to_string()),
+                                   right_number: r.map(|j| j + 1),
+                                   right: r.map(|j| right[j].to_string()) });
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for op in edit_ops(&left, &right)? {
+        match op {
+            Op::Equal(i, j) => {
+                flush(&mut result, &mut removed, &mut added);
+                result.push(DiffLine { kind: DiffKind::Unchanged,
+                                       left_number: Some(i + 1),
+                                       left: Some(left[i].to_string()),
+                                       right_number: Some(j + 1),
+                                       right: Some(right[j].to_string()) });
+            }
+            Op::Delete(i) => removed.push(i),
+            Op::Insert(j) => added.push(j),
+        }
+    }
+    flush(&mut result, &mut removed, &mut added);
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_submission_values() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne").unwrap();
+        let kinds: Vec<DiffKind> = diff.iter().map(|line| line.kind).collect();
+        assert_eq!(kinds,
+                   vec![DiffKind::Unchanged,
+                        DiffKind::Changed,
+                        DiffKind::Unchanged,
+                        DiffKind::Unchanged,
+                        DiffKind::Added]);
+        assert_eq!(diff[1].left, Some("b".to_string()));
+        assert_eq!(diff[1].right, Some("x".to_string()));
+        assert_eq!(diff[4].left_number, None);
+        assert_eq!(diff[4].right_number, Some(5));
+
+        let diff = diff_lines("a\nb", "b").unwrap();
+        assert_eq!(diff[0].kind, DiffKind::Removed);
+        assert_eq!(diff[1].kind, DiffKind::Unchanged);
+        assert_eq!(diff[1].left_number, Some(2));
+        assert_eq!(diff[1].right_number, Some(1));
+
+        assert!(diff_lines("", "").unwrap().is_empty());
+
+        assert_eq!(prettify("{\"a\":1}"), "{\n  \"a\": 1\n}");
+        assert_eq!(prettify("print(1)"), "print(1)");
+        assert_eq!(prettify("42"), "42");
+    }
+
+    #[test]
+    fn large_values_are_only_compared_if_few_lines_differ() {
+        let left: Vec<String> = (0..10000).map(|i| i.to_string()).collect();
+        let mut right = left.clone();
+        right[5000] = "changed".to_string();
+
+        let diff = diff_lines(&left.join("\n"), &right.join("\n")).unwrap();
+        assert_eq!(diff.len(), 10000);
+        assert_eq!(diff[5000].kind, DiffKind::Changed);
+        assert_eq!(diff[5000].left_number, Some(5001));
+        assert_eq!(diff[9999].kind, DiffKind::Unchanged);
+        assert_eq!(diff[9999].right_number, Some(10000));
+
+        let reversed: Vec<String> = left.iter().rev().cloned().collect();
+        assert!(diff_lines(&left.join("\n"), &reversed.join("\n")).is_none());
+    }
+}
@@ -0,0 +1,293 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Export of contest results as CSV, Excel-compatible CSV or JSON Lines.
+//!
+//! The export is written directly into a `Write` (usually the body of the HTTP response), so no file containing
+//! personal data is ever stored on the server. The participations are read from the database in batches, so the
+//! database connection is not kept busy while a slow client downloads the export.
+
+use std::io::{self, Write};
+use std::ops::Deref;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use db_conn::MedalConnection;
+use db_objects::{Contest, ContestResultRow};
+
+/// Number of participations that are read from the database at once
+const BATCH_SIZE: i64 = 1000;
+
+/// Columns with participant data that can be selected for the export, in the order they are written
+pub const EXPORT_COLUMNS: &[&str] = &["id",
+                                      "username",
+                                      "logincode",
+                                      "oauth_foreign_id",
+                                      "oauth_provider",
+                                      "firstname",
+                                      "lastname",
+                                      "grade",
+                                      "sex",
+                                      "is_teacher",
+                                      "group_id",
+                                      "group_name",
+                                      "group_tag",
+                                      "teacher_id",
+                                      "teacher_firstname",
+                                      "teacher_lastname",
+                                      "teacher_oauth_foreign_id",
+                                      "teacher_oauth_school_id",
+                                      "teacher_oauth_provider",
+                                      "contest_id",
+                                      "start_date"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    /// Plain comma separated values
+    Csv,
+    /// Semicolon separated values with a byte order mark, as expected by spreadsheet applications
+    CsvBom,
+    /// One JSON object per participation and line
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        match name {
+            "csv" => Some(ExportFormat::Csv),
+            "csvbom" => Some(ExportFormat::CsvBom),
+            "jsonl" => Some(ExportFormat::JsonLines),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::CsvBom => "text/csv; charset=utf-8",
+            ExportFormat::JsonLines => "application/x-ndjson; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::CsvBom => "csv",
+            ExportFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Selected participant columns. Unknown names are ignored, the order of `EXPORT_COLUMNS` is kept.
+    pub columns: Vec<String>,
+    /// Add the best submission of each task in addition to the taskgroup grade
+    pub subtasks: bool,
+    /// Add the number of submissions for each taskgroup
+    pub submission_counts: bool,
+}
+
+impl ExportOptions {
+    fn selected_columns(&self) -> Vec<&'static str> {
+        EXPORT_COLUMNS.iter().cloned().filter(|column| self.columns.iter().any(|c| c == column)).collect()
+    }
+}
+
+fn to_value<S: Serialize>(value: S) -> Value { serde_json::to_value(value).unwrap() }
+
+fn column_value(row: &ContestResultRow, column: &str) -> Value {
+    // The school id is stored together with the teacher's oauth id as `<oauth id>/<school id>`
+    let mut teacher_oauth = row.teacher_oauth_foreign_id.as_ref().map(|toasi| toasi.split('/'));
+
+    match column {
+        "id" => to_value(row.id),
+        "username" => to_value(row.username.clone()),
+        "logincode" => to_value(row.logincode.clone()),
+        "oauth_foreign_id" => to_value(row.oauth_foreign_id.clone()),
+        "oauth_provider" => to_value(row.oauth_provider.clone()),
+        "firstname" => to_value(row.firstname.clone()),
+        "lastname" => to_value(row.lastname.clone()),
+        "grade" => to_value(row.grade),
+        "sex" => to_value(row.sex),
+        "is_teacher" => to_value(row.is_teacher),
+        "group_id" => to_value(row.group_id),
+        "group_name" => to_value(row.group_name.clone()),
+        "group_tag" => to_value(row.group_tag.clone()),
+        "teacher_id" => to_value(row.teacher_id),
+        "teacher_firstname" => to_value(row.teacher_firstname.clone()),
+        "teacher_lastname" => to_value(row.teacher_lastname.clone()),
+        "teacher_oauth_foreign_id" => to_value(teacher_oauth.as_mut().and_then(|v| v.next()).map(str::to_owned)),
+        "teacher_oauth_school_id" => to_value(teacher_oauth.as_mut().and_then(|v| v.nth(1)).map(str::to_owned)),
+        "teacher_oauth_provider" => to_value(row.teacher_oauth_provider.clone()),
+        "contest_id" => to_value(row.contest_id),
+        "start_date" => to_value(row.start_date.map(|ts| time::strftime("%FT%T%z", &time::at(ts)).unwrap())),
+        _ => Value::Null,
+    }
+}
+
+fn csv_field(value: Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+fn csv_headers(contest: &Contest, columns: &[&str], options: &ExportOptions) -> Vec<String> {
+    let mut headers: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+    for tg in &contest.taskgroups {
+        headers.push(tg.name.clone());
+        if options.subtasks {
+            headers.extend(tg.tasks.iter().map(|t| format!("{} ({}*)", tg.name, t.stars)));
+        }
+        if options.submission_counts {
+            headers.push(format!("{} (submissions)", tg.name));
+        }
+    }
+    headers
+}
+
+fn csv_record(row: &ContestResultRow, contest: &Contest, columns: &[&str], options: &ExportOptions) -> Vec<String> {
+    let mut record: Vec<String> = columns.iter().map(|c| csv_field(column_value(row, c))).collect();
+    let mut task_grades = row.task_grades.iter();
+    for (n, tg) in contest.taskgroups.iter().enumerate() {
+        record.push(csv_field(to_value(row.grades[n])));
+        if options.subtasks {
+            record.extend(task_grades.by_ref().take(tg.tasks.len()).map(|g| csv_field(to_value(*g))));
+        }
+        if options.submission_counts {
+            record.push(row.submission_counts[n].to_string());
+        }
+    }
+    record
+}
+
+fn json_object(row: &ContestResultRow, contest: &Contest, columns: &[&str], options: &ExportOptions) -> Value {
+    let mut object = Map::new();
+    for column in columns {
+        object.insert(column.to_string(), column_value(row, column));
+    }
+
+    let mut task_grades = row.task_grades.iter();
+    let mut taskgroups = Vec::new();
+    for (n, tg) in contest.taskgroups.iter().enumerate() {
+        let mut taskgroup = Map::new();
+        taskgroup.insert("id".to_string(), to_value(tg.id));
+        taskgroup.insert("name".to_string(), to_value(tg.name.clone()));
+        taskgroup.insert("grade".to_string(), to_value(row.grades[n]));
+        if options.subtasks {
+            let mut tasks = Vec::new();
+            for (t, grade) in tg.tasks.iter().zip(task_grades.by_ref()) {
+                let mut task = Map::new();
+                task.insert("id".to_string(), to_value(t.id));
+                task.insert("stars".to_string(), to_value(t.stars));
+                task.insert("grade".to_string(), to_value(*grade));
+                tasks.push(Value::Object(task));
+            }
+            taskgroup.insert("tasks".to_string(), Value::Array(tasks));
+        }
+        if options.submission_counts {
+            taskgroup.insert("submissions".to_string(), to_value(row.submission_counts[n]));
+        }
+        taskgroups.push(Value::Object(taskgroup));
+    }
+    object.insert("taskgroups".to_string(), Value::Array(taskgroups));
+
+    Value::Object(object)
+}
+
+/// Read the results of all participations of the contest in batches of `BATCH_SIZE` and pass each batch to
+/// `write_batch`. `conn` is called for every batch and the connection it returns is released before the batch is
+/// written.
+fn for_each_batch<T, F, G>(conn: &F, contest_id: i32, taskgroup_ids: &[i32], task_ids: &[i32],
+                           count_submissions: bool,
+                           mut write_batch: impl FnMut(Vec<ContestResultRow>) -> io::Result<()>)
+                           -> io::Result<()>
+    where T: MedalConnection,
+          F: Fn() -> G,
+          G: Deref<Target = T>
+{
+    let mut after_session = 0;
+    loop {
+        let mut rows = Vec::new();
+        conn().export_contest_results(contest_id,
+                                      taskgroup_ids,
+                                      task_ids,
+                                      count_submissions,
+                                      after_session,
+                                      BATCH_SIZE,
+                                      &mut |row| rows.push(row));
+
+        let last_batch = (rows.len() as i64) < BATCH_SIZE;
+        if let Some(row) = rows.last() {
+            after_session = row.id;
+        }
+        write_batch(rows)?;
+
+        if last_batch {
+            return Ok(());
+        }
+    }
+}
+
+/// Write the results of all participations of `contest` to `out`. `conn` is called whenever the export needs the
+/// database, e.g. to lock the shared connection.
+///
+/// `contest` has to be loaded completely, i.e. including its taskgroups and tasks.
+pub fn write_contest_results<T, F, G>(conn: F, contest: &Contest, options: &ExportOptions, out: &mut dyn Write)
+                                      -> io::Result<()>
+    where T: MedalConnection,
+          F: Fn() -> G,
+          G: Deref<Target = T>
+{
+    let columns = options.selected_columns();
+    let taskgroup_ids: Vec<i32> = contest.taskgroups.iter().map(|tg| tg.id.unwrap()).collect();
+    let task_ids: Vec<i32> = if options.subtasks {
+        contest.taskgroups.iter().flat_map(|tg| tg.tasks.iter().map(|t| t.id.unwrap())).collect()
+    } else {
+        Vec::new()
+    };
+
+    let contest_id = contest.id.unwrap();
+
+    match options.format {
+        ExportFormat::Csv | ExportFormat::CsvBom => {
+            let mut builder = csv::WriterBuilder::new();
+            if options.format == ExportFormat::CsvBom {
+                out.write_all("\u{feff}".as_bytes())?;
+                builder.delimiter(b';').terminator(csv::Terminator::CRLF);
+            }
+            let mut wtr = builder.from_writer(out);
+            wtr.write_record(csv_headers(contest, &columns, options))?;
+
+            for_each_batch(&conn, contest_id, &taskgroup_ids, &task_ids, options.submission_counts, |rows| {
+                for row in rows {
+                    wtr.write_record(csv_record(&row, contest, &columns, options))?;
+                }
+                Ok(())
+            })?;
+            wtr.flush()
+        }
+        ExportFormat::JsonLines => {
+            for_each_batch(&conn, contest_id, &taskgroup_ids, &task_ids, options.submission_counts, |rows| {
+                for row in rows {
+                    serde_json::to_writer(&mut *out, &json_object(&row, contest, &columns, options))?;
+                    out.write_all(b"\n")?;
+                }
+                Ok(())
+            })?;
+            out.flush()
+        }
+    }
+}
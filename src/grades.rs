@@ -0,0 +1,201 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! The grades of the school system.
+//!
+//! The grade of a user is stored as a number. The grade scale defines the label of each number, the order in which
+//! the grades are offered and the level that is compared with `min_grade` and `max_grade` of contests. The default
+//! scale is the German school system, in which e.g. grade 12 in G9 (stored as 112) is on the level of grade 11 in G8.
+//! Other school systems can be configured with `grades` in the configuration.
+
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+use i18n;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GradeDefinition {
+    /// Number stored for the grade
+    pub value: i32,
+    /// Name of the grade or id of a message in `locales/` containing the name
+    pub label: String,
+    /// Grade that is compared with the grade restrictions of contests (default: `value`)
+    #[serde(default)]
+    pub level: Option<i32>,
+    /// Marks the choices for people that are not students. They are outside of every grade restriction.
+    #[serde(default)]
+    pub not_student: Option<NotStudent>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotStudent {
+    NotYet,
+    NoLonger,
+}
+
+/// Position of a grade for the grade restrictions of contests
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GradeLevel {
+    NotYetStudent,
+    Level(i32),
+    NoLongerStudent,
+}
+
+/// Grade in a selection, e.g. in the profile
+#[derive(Serialize, Deserialize)]
+pub struct GradeOption {
+    pub value: i32,
+    pub name: String,
+    pub selected: bool,
+    pub not_yet_student: bool,
+}
+
+#[derive(Debug)]
+pub struct GradeScale {
+    /// Grades in the order they are offered
+    grades: Vec<GradeDefinition>,
+}
+
+static SCALE: OnceLock<GradeScale> = OnceLock::new();
+
+fn grade(value: i32, label: &str, level: i32) -> GradeDefinition {
+    GradeDefinition { value, label: label.to_string(), level: Some(level), not_student: None }
+}
+
+fn not_student(value: i32, label: &str, not_student: NotStudent) -> GradeDefinition {
+    GradeDefinition { value, label: label.to_string(), level: None, not_student: Some(not_student) }
+}
+
+impl Default for GradeScale {
+    /// The German school system with G8 and G9
+    fn default() -> Self {
+        let mut grades = vec![not_student(0, "grade-0", NotStudent::NotYet)];
+        grades.extend((1..=10).map(|value| grade(value, &value.to_string(), value)));
+        grades.extend(vec![grade(11, "grade-11", 11),
+                           grade(12, "grade-12", 12),
+                           grade(111, "grade-111", 10),
+                           grade(112, "grade-112", 11),
+                           grade(113, "grade-113", 12),
+                           grade(114, "grade-114", 13),
+                           not_student(255, "grade-255", NotStudent::NoLonger)]);
+        GradeScale { grades }
+    }
+}
+
+/// Use the grades of the configuration instead of the default scale. Has to be called before the scale is used.
+pub fn configure(grades: Vec<GradeDefinition>) -> Result<(), String> {
+    let scale = GradeScale::new(grades)?;
+    SCALE.set(scale).map_err(|_| "The grade scale is already in use".to_string())
+}
+
+/// The configured grade scale
+pub fn scale() -> &'static GradeScale { SCALE.get_or_init(GradeScale::default) }
+
+impl GradeScale {
+    pub fn new(grades: Vec<GradeDefinition>) -> Result<Self, String> {
+        let mut values = BTreeSet::new();
+        for grade in &grades {
+            if !values.insert(grade.value) {
+                return Err(format!("Grade {} is defined twice", grade.value));
+            }
+            if grade.level.is_some() && grade.not_student.is_some() {
+                return Err(format!("Grade {} has a level but is not a student grade", grade.value));
+            }
+        }
+        Ok(GradeScale { grades })
+    }
+
+    pub fn get(&self, value: i32) -> Option<&GradeDefinition> { self.grades.iter().find(|grade| grade.value == value) }
+
+    /// Name of the grade in the language of the current thread. Unknown grades are shown as number.
+    pub fn name(&self, value: i32) -> String {
+        match self.get(value) {
+            Some(grade) => i18n::translate(&grade.label),
+            None => value.to_string(),
+        }
+    }
+
+    /// Level of the grade. Unknown grades are compared by their number.
+    pub fn level(&self, value: i32) -> GradeLevel {
+        match self.get(value) {
+            Some(GradeDefinition { not_student: Some(NotStudent::NotYet), .. }) => GradeLevel::NotYetStudent,
+            Some(GradeDefinition { not_student: Some(NotStudent::NoLonger), .. }) => GradeLevel::NoLongerStudent,
+            Some(grade) => GradeLevel::Level(grade.level.unwrap_or(value)),
+            None => GradeLevel::Level(value),
+        }
+    }
+
+    /// Is the grade `value` below the grade restriction `min_grade` of a contest?
+    pub fn is_below(&self, value: i32, min_grade: i32) -> bool { self.level(value) < GradeLevel::Level(min_grade) }
+
+    /// Is the grade `value` above the grade restriction `max_grade` of a contest?
+    pub fn is_above(&self, value: i32, max_grade: i32) -> bool { self.level(value) > GradeLevel::Level(max_grade) }
+
+    /// Grade of new accounts of teachers: The first grade for people that are no longer students, if there is any
+    pub fn teacher_grade(&self) -> i32 {
+        self.grades.iter().find(|grade| grade.not_student == Some(NotStudent::NoLonger)).map_or(0, |grade| grade.value)
+    }
+
+    /// All grades for a selection with `selected` being selected
+    pub fn options(&self, selected: Option<i32>) -> Vec<GradeOption> {
+        self.grades
+            .iter()
+            .map(|grade| GradeOption { value: grade.value,
+                                       name: i18n::translate(&grade.label),
+                                       selected: selected == Some(grade.value),
+                                       not_yet_student: grade.not_student == Some(NotStudent::NotYet) })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn german_grades_are_compared_by_level() {
+        let scale = GradeScale::default();
+        assert_eq!(scale.name(7), "7");
+        assert_eq!(scale.name(112), "12 (G9)");
+        assert_eq!(scale.name(255), "Kein Schüler mehr");
+        assert_eq!(i18n::with_language("en", || scale.name(114)), "Vocational school");
+
+        // 12 (G9) is on the level of 11 (G8)
+        assert!(!scale.is_below(112, 11) && !scale.is_above(112, 11));
+        assert!(scale.is_above(12, 11));
+        assert!(scale.is_below(111, 11));
+        assert!(scale.is_below(0, 1) && !scale.is_above(0, 3));
+        assert!(scale.is_above(255, 13) && !scale.is_below(255, 3));
+        assert_eq!(scale.teacher_grade(), 255);
+    }
+
+    #[test]
+    fn grades_can_be_configured() {
+        let scale = GradeScale::new(vec![grade(1, "1. Schulstufe", 1),
+                                         grade(9, "9. Schulstufe", 9),
+                                         grade(10, "Polytechnische Schule", 9),
+                                         not_student(99, "grade-255", NotStudent::NoLonger)]).unwrap();
+        assert_eq!(scale.name(10), "Polytechnische Schule");
+        assert!(!scale.is_above(10, 9));
+        assert!(scale.is_above(99, 9));
+        assert_eq!(scale.teacher_grade(), 99);
+
+        let options = scale.options(Some(9));
+        assert_eq!(options.len(), 4);
+        assert!(options[1].selected && !options[0].selected);
+
+        assert!(GradeScale::new(vec![grade(1, "1", 1), grade(1, "2", 2)]).is_err());
+    }
+}
@@ -0,0 +1,483 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Import of groups and their members from CSV files, e.g. exports of school administration software.
+//!
+//! The uploaded file is decoded and parsed on the server. Delimiter, header row and columns are detected if they are
+//! not given. Every row is validated, so that teachers see all errors in a preview before any group is created.
+
+use std::collections::BTreeMap;
+
+use core::SexInformation;
+use db_objects::Sex;
+use grades;
+use i18n;
+
+/// Maximal length of group names and names of participants
+const MAX_LENGTH: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 or UTF-16 if the file starts with a byte order mark or is valid UTF-8, Windows-1252 otherwise
+    Auto,
+    Utf8,
+    Windows1252,
+    Utf16,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "" | "auto" => Some(Encoding::Auto),
+            "utf-8" => Some(Encoding::Utf8),
+            "windows-1252" => Some(Encoding::Windows1252),
+            "utf-16" => Some(Encoding::Utf16),
+            _ => None,
+        }
+    }
+}
+
+/// Characters of the bytes 0x80 to 0x9F in Windows-1252. The other bytes are the same as in ISO 8859-1.
+const WINDOWS_1252: [char; 32] = ['€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž',
+                                  '\u{8f}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ',
+                                  '\u{9d}', 'ž', 'Ÿ'];
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes.iter()
+         .map(|&byte| match byte {
+             0x80..=0x9f => WINDOWS_1252[byte as usize - 0x80],
+             _ => byte as char,
+         })
+         .collect()
+}
+
+/// Encode `text` in Windows-1252. Characters that do not exist in Windows-1252 are replaced by `?`.
+pub fn encode_windows_1252(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\0'..='\u{7f}' | '\u{a0}'..='\u{ff}' => c as u8,
+            _ => WINDOWS_1252.iter().position(|&other| other == c).map_or(b'?', |i| 0x80 + i as u8),
+        })
+        .collect()
+}
+
+fn decode_utf16(bytes: &[u8]) -> Option<String> {
+    let (big_endian, bytes) = match bytes {
+        [0xfe, 0xff, rest @ ..] => (true, rest),
+        [0xff, 0xfe, rest @ ..] => (false, rest),
+        _ => (false, bytes),
+    };
+    let units: Vec<u16> = bytes.chunks(2)
+                               .map(|pair| match pair {
+                                   [a, b] if big_endian => u16::from_be_bytes([*a, *b]),
+                                   [a, b] => u16::from_le_bytes([*a, *b]),
+                                   _ => 0xfffd,
+                               })
+                               .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// Decode the uploaded file. Returns a message for the user if the file can not be decoded.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Result<String, String> {
+    let utf8 = |bytes: &[u8]| String::from_utf8(bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes).to_vec()).ok();
+    let text = match encoding {
+        Encoding::Utf8 => utf8(bytes),
+        Encoding::Windows1252 => Some(decode_windows_1252(bytes)),
+        Encoding::Utf16 => decode_utf16(bytes),
+        Encoding::Auto if bytes.starts_with(b"\xfe\xff") || bytes.starts_with(b"\xff\xfe") => decode_utf16(bytes),
+        Encoding::Auto => utf8(bytes).or_else(|| Some(decode_windows_1252(bytes))),
+    };
+    text.ok_or_else(|| i18n::translate("import-invalid-encoding"))
+}
+
+/// The data of a participant and the column it is read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Group,
+    Grade,
+    Firstname,
+    Lastname,
+    Sex,
+}
+
+pub const FIELDS: [Field; 5] = [Field::Group, Field::Grade, Field::Firstname, Field::Lastname, Field::Sex];
+
+impl Field {
+    /// Name of the field in forms
+    pub fn id(self) -> &'static str {
+        match self {
+            Field::Group => "group",
+            Field::Grade => "grade",
+            Field::Firstname => "firstname",
+            Field::Lastname => "lastname",
+            Field::Sex => "sex",
+        }
+    }
+
+    pub fn label(self) -> String { i18n::translate(&format!("import-column-{}", self.id())) }
+
+    /// Column headers that are recognized for this field (lower case)
+    fn headers(self) -> &'static [&'static str] {
+        match self {
+            Field::Group => &["gruppe", "gruppenname", "klasse", "kurs", "group"],
+            Field::Grade => &["stufe", "jahrgangsstufe", "jgst", "jgst.", "klassenstufe", "grade"],
+            Field::Firstname => &["vorname", "vornamen", "rufname", "firstname", "first name"],
+            Field::Lastname => &["nachname", "familienname", "name", "lastname", "last name", "surname"],
+            Field::Sex => &["geschlecht", "sex", "gender"],
+        }
+    }
+}
+
+/// Column of each field. The columns are counted from 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Columns {
+    pub group: Option<usize>,
+    pub grade: Option<usize>,
+    pub firstname: Option<usize>,
+    pub lastname: Option<usize>,
+    pub sex: Option<usize>,
+}
+
+impl Columns {
+    /// The order of the CSV files of the Informatik-Biber: group, grade, first name, last name, sex
+    fn standard() -> Self {
+        Columns { group: Some(0), grade: Some(1), firstname: Some(2), lastname: Some(3), sex: Some(4) }
+    }
+
+    fn from_header(header: &[String]) -> Self {
+        let find = |field: Field| {
+            header.iter().position(|cell| field.headers().contains(&cell.trim().to_lowercase().as_str()))
+        };
+        Columns { group: find(Field::Group),
+                  grade: find(Field::Grade),
+                  firstname: find(Field::Firstname),
+                  lastname: find(Field::Lastname),
+                  sex: find(Field::Sex) }
+    }
+
+    pub fn get(&self, field: Field) -> Option<usize> {
+        match field {
+            Field::Group => self.group,
+            Field::Grade => self.grade,
+            Field::Firstname => self.firstname,
+            Field::Lastname => self.lastname,
+            Field::Sex => self.sex,
+        }
+    }
+}
+
+/// Settings of the import. Settings that are `None` are detected from the file.
+#[derive(Clone, Debug, Default)]
+pub struct ImportOptions {
+    pub delimiter: Option<u8>,
+    pub header: Option<bool>,
+    pub columns: Option<Columns>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportRow {
+    /// Line in the file
+    pub line: u64,
+    pub group: String,
+    pub grade: i32,
+    pub grade_name: String,
+    pub firstname: String,
+    pub lastname: String,
+    pub sex: Option<i32>,
+    pub sex_name: String,
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GroupImport {
+    pub delimiter: char,
+    pub header: bool,
+    #[serde(skip)]
+    pub columns: Columns,
+    /// Names of the columns for choosing the columns of the fields: The header or "Column n"
+    pub column_names: Vec<String>,
+    /// Errors that concern the whole file
+    pub errors: Vec<String>,
+    pub rows: Vec<ImportRow>,
+}
+
+impl GroupImport {
+    pub fn error_count(&self) -> usize {
+        self.errors.len() + self.rows.iter().map(|row| row.errors.len()).sum::<usize>()
+    }
+
+    /// The rows by group name
+    pub fn groups(&self) -> BTreeMap<&str, Vec<&ImportRow>> {
+        let mut groups: BTreeMap<&str, Vec<&ImportRow>> = BTreeMap::new();
+        for row in &self.rows {
+            groups.entry(&row.group).or_default().push(row);
+        }
+        groups
+    }
+}
+
+/// The most frequent of the usual delimiters in the first line. Comma if there is none of them.
+fn detect_delimiter(text: &str) -> u8 {
+    let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or("");
+    let count = |delimiter: u8| first_line.bytes().filter(|byte| *byte == delimiter).count();
+    [b';', b'\t'].iter().fold(b',', |best, &delimiter| if count(delimiter) > count(best) { delimiter } else { best })
+}
+
+fn parse_grade(value: &str) -> Option<i32> {
+    let scale = grades::scale();
+    // The number of the grade or its name, e.g. "112" or "12 (G9)"
+    value.parse().ok().filter(|grade| scale.get(*grade).is_some()).or_else(|| {
+                                                                      scale.options(None)
+                                                                           .into_iter()
+                                                                           .find(|option| {
+                                                                               option.name.to_lowercase()
+                                                                               == value.to_lowercase()
+                                                                           })
+                                                                           .map(|option| option.value)
+                                                                  })
+}
+
+fn parse_sex(value: &str) -> Result<Option<Sex>, ()> {
+    match value.to_lowercase().as_str() {
+        "" => Ok(None),
+        "m" | "männlich" | "maennlich" | "male" | "junge" => Ok(Some(Sex::Male)),
+        "w" | "f" | "weiblich" | "female" | "mädchen" | "maedchen" => Ok(Some(Sex::Female)),
+        "d" | "divers" | "diverse" => Ok(Some(Sex::Diverse)),
+        "a" | "o" | "anderes" | "other" => Ok(Some(Sex::Other)),
+        "-" | "keine angabe" | "not stated" => Ok(Some(Sex::NotStated)),
+        _ => Err(()),
+    }
+}
+
+fn sex_name(sex: Option<Sex>) -> String {
+    match sex {
+        None => String::new(),
+        Some(Sex::NotStated) => i18n::translate("sex-not-stated"),
+        Some(Sex::Male) => i18n::translate("sex-male"),
+        Some(Sex::Female) => i18n::translate("sex-female"),
+        Some(Sex::Diverse) => i18n::translate("sex-diverse"),
+        Some(Sex::Other) => i18n::translate("sex-other"),
+    }
+}
+
+fn is_allowed(sex: Option<Sex>, sex_infos: &SexInformation) -> bool {
+    match sex {
+        None => !sex_infos.require_sex,
+        Some(Sex::NotStated) => sex_infos.allow_sex_na,
+        Some(Sex::Diverse) => sex_infos.allow_sex_diverse,
+        Some(Sex::Other) => sex_infos.allow_sex_other,
+        Some(Sex::Male) | Some(Sex::Female) => true,
+    }
+}
+
+fn parse_row(line: u64, record: &[String], columns: &Columns, sex_infos: &SexInformation) -> ImportRow {
+    let mut errors = Vec::new();
+    let cell = |field: Field| -> String {
+        columns.get(field)
+               .and_then(|column| record.get(column))
+               .map(|value| value.trim().to_string())
+               .unwrap_or_default()
+    };
+    let (group, grade, firstname, lastname, sex) =
+        (cell(Field::Group), cell(Field::Grade), cell(Field::Firstname), cell(Field::Lastname), cell(Field::Sex));
+
+    for &(field, value) in &[(Field::Group, &group), (Field::Firstname, &firstname), (Field::Lastname, &lastname)] {
+        if value.chars().count() > MAX_LENGTH {
+            let max = MAX_LENGTH.to_string();
+            errors.push(i18n::translate_with("import-too-long", &[("field", &field.label()), ("max", &max)]));
+        }
+    }
+    if group.is_empty() {
+        errors.push(i18n::translate_with("import-value-missing", &[("field", &Field::Group.label())]));
+    }
+    if firstname.is_empty() && lastname.is_empty() {
+        errors.push(i18n::translate("import-name-missing"));
+    }
+
+    // Values that can not be read are shown as they are
+    let (grade_value, grade_name) = match parse_grade(&grade) {
+        Some(value) => (value, grades::scale().name(value)),
+        None if grade.is_empty() => {
+            errors.push(i18n::translate_with("import-value-missing", &[("field", &Field::Grade.label())]));
+            (0, grade)
+        }
+        None => {
+            errors.push(i18n::translate_with("import-unknown-grade", &[("value", &grade)]));
+            (0, grade)
+        }
+    };
+
+    let (sex_value, sex_name) = match parse_sex(&sex) {
+        Ok(parsed) if is_allowed(parsed, sex_infos) => (parsed, sex_name(parsed)),
+        Ok(None) => {
+            errors.push(i18n::translate_with("import-value-missing", &[("field", &Field::Sex.label())]));
+            (None, sex)
+        }
+        Ok(_) => {
+            errors.push(i18n::translate_with("import-sex-not-allowed", &[("value", &sex)]));
+            (None, sex)
+        }
+        Err(()) => {
+            errors.push(i18n::translate_with("import-unknown-sex", &[("value", &sex)]));
+            (None, sex)
+        }
+    };
+
+    ImportRow { line,
+                group,
+                grade: grade_value,
+                grade_name,
+                firstname,
+                lastname,
+                sex: sex_value.map(|sex| sex as i32),
+                sex_name,
+                errors }
+}
+
+/// Parse and validate the decoded file.
+pub fn parse(text: &str, options: &ImportOptions, sex_infos: &SexInformation) -> GroupImport {
+    let delimiter = options.delimiter.unwrap_or_else(|| detect_delimiter(text));
+    let mut errors = Vec::new();
+
+    let mut records = Vec::new();
+    let mut reader =
+        csv::ReaderBuilder::new().delimiter(delimiter).has_headers(false).flexible(true).from_reader(text.as_bytes());
+    for result in reader.records() {
+        match result {
+            Ok(record) => {
+                // The line count of the CSV reader does not count empty lines and the position of a record can be
+                // before empty lines
+                let start = record.position().map_or(0, |position| position.byte() as usize);
+                let start = text.len() - text[start..].trim_start_matches(&['\r', '\n'][..]).len();
+                let line = text[..start].matches('\n').count() as u64 + 1;
+                let cells: Vec<String> = record.iter().map(str::to_string).collect();
+                // Skip empty lines, e.g. at the end of the file
+                if cells.iter().any(|cell| !cell.trim().is_empty()) {
+                    records.push((line, cells));
+                }
+            }
+            Err(e) => {
+                errors.push(i18n::translate_with("import-invalid-csv", &[("error", &e.to_string())]));
+                break;
+            }
+        }
+    }
+
+    let header_columns = records.first().map(|(_, cells)| Columns::from_header(cells)).unwrap_or_default();
+    let header = options.header.unwrap_or_else(|| header_columns != Columns::default());
+    let columns = match (options.columns, header) {
+        (Some(columns), _) => columns,
+        (None, true) => header_columns,
+        (None, false) => Columns::standard(),
+    };
+
+    let column_count = records.iter().map(|(_, cells)| cells.len()).max().unwrap_or(0);
+    let column_names = (0..column_count).map(|column| {
+                                            let number = (column + 1).to_string();
+                                            let name = i18n::translate_with("import-column", &[("number", &number)]);
+                                            match records.first().and_then(|(_, cells)| cells.get(column)) {
+                                                Some(cell) if header => format!("{}: {}", name, cell),
+                                                _ => name,
+                                            }
+                                        })
+                                        .collect();
+
+    for field in FIELDS.iter().filter(|field| **field != Field::Sex) {
+        if columns.get(*field).is_none() {
+            errors.push(i18n::translate_with("import-no-column", &[("field", &field.label())]));
+        }
+    }
+
+    let rows: Vec<ImportRow> = records.iter()
+                                      .skip(if header { 1 } else { 0 })
+                                      .map(|(line, cells)| parse_row(*line, cells, &columns, sex_infos))
+                                      .collect();
+    if rows.is_empty() && errors.is_empty() {
+        errors.push(i18n::translate("import-empty"));
+    }
+
+    GroupImport { delimiter: delimiter as char, header, columns, column_names, errors, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sex_infos() -> SexInformation {
+        SexInformation { require_sex: false, allow_sex_na: true, allow_sex_diverse: true, allow_sex_other: false }
+    }
+
+    #[test]
+    fn files_are_decoded() {
+        assert_eq!(decode(b"\xef\xbb\xbfM\xc3\xbcller", Encoding::Auto).unwrap(), "Müller");
+        assert_eq!(decode(b"M\xfcller \x80", Encoding::Auto).unwrap(), "Müller €");
+        assert_eq!(decode(b"\xff\xfeM\x00\xfc\x00", Encoding::Auto).unwrap(), "Mü");
+        assert!(decode(b"M\xfcller", Encoding::Utf8).is_err());
+    }
+
+    #[test]
+    fn header_and_columns_are_detected() {
+        let text = "Nr;Vorname;Nachname;Klasse;Stufe;Geschlecht;Bemerkung\n\
+                    1;Gabi;Musterfrau;7a;7;w;\n\
+                    \n\
+                    2;Max;Mustermann;7a;7;m;x\n";
+        let import = parse(text, &ImportOptions::default(), &sex_infos());
+        assert_eq!(import.delimiter, ';');
+        assert!(import.header);
+        assert_eq!(import.column_names[3], "Spalte 4: Klasse");
+        assert_eq!(import.error_count(), 0);
+        assert_eq!(import.rows.len(), 2);
+        assert_eq!(import.rows[1].line, 4);
+        assert_eq!(import.rows[1].firstname, "Max");
+        assert_eq!(import.rows[1].sex, Some(Sex::Male as i32));
+        assert_eq!(import.groups()["7a"].len(), 2);
+    }
+
+    #[test]
+    fn every_row_error_is_reported() {
+        let text = "7a,7,Gabi,Musterfrau,w\n\
+                    7a,7,Max\n\
+                    7b,15,Ferdinand,Fallbeispiel,x\n\
+                    7b,12 (G9),Erika,Beispiel,o\n";
+        let import = parse(text, &ImportOptions::default(), &sex_infos());
+        assert!(!import.header);
+        assert!(import.rows[0].errors.is_empty());
+        // Missing columns are empty values and the sex is optional
+        assert!(import.rows[1].errors.is_empty());
+        assert_eq!(import.rows[2].errors, vec!["Unbekannte Jahrgangsstufe „15“", "Unbekanntes Geschlecht „x“"]);
+        assert_eq!(import.rows[3].grade, 112);
+        assert_eq!(import.rows[3].errors, vec!["Geschlecht „o“ ist nicht erlaubt"]);
+        assert_eq!(import.error_count(), 3);
+
+        let options =
+            ImportOptions { columns: Some(Columns { sex: None, ..Columns::standard() }), ..Default::default() };
+        let import = parse(text, &options, &SexInformation { require_sex: true, ..sex_infos() });
+        assert_eq!(import.rows[0].errors, vec!["„Geschlecht“ fehlt"]);
+
+        let options = ImportOptions { columns: Some(Columns::default()), ..Default::default() };
+        assert_eq!(parse(text, &options, &sex_infos()).errors.len(), 4);
+    }
+
+    #[test]
+    fn rows_longer_than_the_header_are_named() {
+        let text = "Klasse;Stufe;Vorname;Nachname
+                    7a;7;Gabi;Musterfrau;w
+";
+        let import = parse(text, &ImportOptions::default(), &sex_infos());
+        assert!(import.header);
+        assert_eq!(import.column_names,
+                   vec!["Spalte 1: Klasse", "Spalte 2: Stufe", "Spalte 3: Vorname", "Spalte 4: Nachname", "Spalte 5"]);
+        assert_eq!(import.rows.len(), 1);
+    }
+}
@@ -18,6 +18,7 @@ use rand::{
     distributions::{Alphanumeric, Distribution},
     thread_rng, Rng,
 };
+use sha2::{Digest, Sha256};
 
 struct LowercaseAlphanumeric;
 impl Distribution<char> for LowercaseAlphanumeric {
@@ -59,6 +60,18 @@ pub fn make_salt() -> String { make_ambiguous_code(10) }
 
 pub fn make_filename_secret() -> String { make_ambiguous_code(10) }
 
+pub fn make_email_confirmationcode() -> String { make_ambiguous_code(20) }
+
+pub fn make_password_reset_token() -> String { make_ambiguous_code(20) }
+
+/// Hash of a random token that is stored instead of the token, so that a leaked database does not contain usable
+/// tokens. Unlike passwords, random tokens do not need a salt or a slow hash function.
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::default();
+    hasher.input(token.as_bytes());
+    hasher.result().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
 pub fn make_groupcode() -> String { make_unambiguous_lowercase_code_prefix(7, "g") } // 1 week @ 10/s, about 5700 groups
 
 pub fn make_logincode() -> String { make_unambiguous_lowercase_code_prefix(9, "u") } // 1 y @ 10/s, about 110000 users
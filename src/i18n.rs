@@ -0,0 +1,259 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Translations of the user interface.
+//!
+//! The messages are read from the catalogs `locales/<language>.ftl`, which use a subset of the Fluent syntax:
+//!
+//! ```text
+//! # Comment
+//! login-failed = Login fehlgeschlagen. Bitte erneut versuchen.
+//! task-title = Aufgabe „{ $taskgroup }“ in { $contest }
+//! ```
+//!
+//! Messages missing in a catalog are taken from the German catalog. The language of a request is chosen by the web
+//! framework (see `negotiate`) and set for the current thread with `with_language`, so that core functions and the
+//! `t` template helper can translate without passing the language around.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Language of the messages that are used if a message is missing in a catalog
+pub const DEFAULT_LANGUAGE: &str = "de";
+
+const LOCALES_DIR: &str = "./locales/";
+
+#[derive(Debug, Default)]
+pub struct Catalogs {
+    /// Messages by language and message id
+    messages: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+static CATALOGS: OnceLock<Catalogs> = OnceLock::new();
+
+thread_local! {
+    static CURRENT_LANGUAGE: RefCell<String> = RefCell::new(DEFAULT_LANGUAGE.to_string());
+}
+
+/// The catalogs in `locales/`, loaded on first use
+pub fn catalogs() -> &'static Catalogs {
+    CATALOGS.get_or_init(|| Catalogs::load(Path::new(LOCALES_DIR)).unwrap_or_else(|e| panic!("{}", e)))
+}
+
+/// Parse a catalog into a map from message id to message.
+fn parse(source: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut messages = BTreeMap::new();
+    for (number, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((id, message)) if !id.trim().is_empty() => {
+                messages.insert(id.trim().to_string(), message.trim().to_string());
+            }
+            _ => return Err(format!("line {}: expected 'message-id = message'", number + 1)),
+        }
+    }
+    Ok(messages)
+}
+
+/// Replace the placeholders `{ $name }` in `message` by the values of `args`. Other placeholders are kept.
+pub fn format_message(message: &str, args: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        result.push_str(&rest[..start]);
+        let name = rest[start + 1..end].trim().trim_start_matches('$');
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => result.push_str(value),
+            None => result.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parse an entry of an `Accept-Language` header like `en-US;q=0.8` into the language tag and its quality.
+fn parse_language_range(entry: &str) -> Option<(String, f32)> {
+    let mut parts = entry.split(';');
+    let tag = parts.next()?.trim().to_lowercase();
+    let quality = match parts.find_map(|part| part.trim().strip_prefix("q=")) {
+        Some(quality) => quality.trim().parse().ok()?,
+        None => 1.0,
+    };
+    if tag.is_empty() || quality <= 0.0 {
+        None
+    } else {
+        Some((tag, quality))
+    }
+}
+
+/// Parse an `Accept-Language` header into the language tags, most preferred first.
+pub fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut languages: Vec<(String, f32)> = header.split(',').filter_map(parse_language_range).collect();
+    // Stable sort keeps the order of the header for equal qualities
+    languages.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    languages.into_iter().map(|(tag, _)| tag).collect()
+}
+
+impl Catalogs {
+    /// Load all catalogs `<language>.ftl` in `dir`.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("Could not read {}: {}", dir.display(), e))?;
+        let mut catalogs = Catalogs::default();
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            if path.extension() != Some("ftl".as_ref()) {
+                continue;
+            }
+            let language = path.file_stem().unwrap().to_string_lossy().to_string();
+            let source = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+            catalogs.add(&language, &source).map_err(|e| format!("{}: {}", path.display(), e))?;
+        }
+        Ok(catalogs)
+    }
+
+    pub fn add(&mut self, language: &str, source: &str) -> Result<(), String> {
+        self.messages.insert(language.to_string(), parse(source)?);
+        Ok(())
+    }
+
+    pub fn has_language(&self, language: &str) -> bool { self.messages.contains_key(language) }
+
+    /// The available languages and their names, e.g. `("en", "English")`
+    pub fn languages(&self) -> Vec<(String, String)> {
+        self.messages
+            .keys()
+            .map(|language| (language.clone(), self.translate(language, "language-name", &[])))
+            .collect()
+    }
+
+    /// Translate the message `id` into `language`. Unknown message ids are returned unchanged.
+    pub fn translate(&self, language: &str, id: &str, args: &[(&str, &str)]) -> String {
+        let message =
+            [language, DEFAULT_LANGUAGE].iter().filter_map(|language| self.messages.get(*language)?.get(id)).next();
+        match message {
+            Some(message) => format_message(message, args),
+            None => id.to_string(),
+        }
+    }
+
+    /// Choose the language of the user interface: The preference of the user, the `Accept-Language` header of the
+    /// browser or the configured default, whichever is available first.
+    pub fn negotiate(&self, preference: Option<&str>, accept_language: Option<&str>, default: &str) -> String {
+        let mut candidates: Vec<String> = preference.into_iter().map(str::to_string).collect();
+        for tag in accept_language.map(parse_accept_language).unwrap_or_default() {
+            // "en-US" is also accepted as "en"
+            let primary = tag.split('-').next().unwrap_or_default().to_string();
+            candidates.push(tag);
+            candidates.push(primary);
+        }
+        candidates.push(default.to_string());
+
+        candidates.into_iter()
+                  .find(|language| self.has_language(language))
+                  .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+    }
+}
+
+/// Run `f` with `language` as language of the current thread.
+pub fn with_language<T, F>(language: &str, f: F) -> T
+    where F: FnOnce() -> T {
+    let previous = CURRENT_LANGUAGE.with(|current| current.replace(language.to_string()));
+    let result = f();
+    CURRENT_LANGUAGE.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+/// The language of the current thread
+pub fn current_language() -> String { CURRENT_LANGUAGE.with(|current| current.borrow().clone()) }
+
+/// Translate the message `id` into the language of the current thread.
+pub fn translate(id: &str) -> String { translate_with(id, &[]) }
+
+/// Translate the message `id` with the placeholders `args` into the language of the current thread.
+pub fn translate_with(id: &str, args: &[(&str, &str)]) -> String { catalogs().translate(&current_language(), id, args) }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_catalogs() -> Catalogs {
+        let mut catalogs = Catalogs::default();
+        catalogs.add("de", "# Deutsch\nlanguage-name = Deutsch\ngreeting = Hallo { $name }!\nonly-de = Nur deutsch")
+                .unwrap();
+        catalogs.add("en", "language-name = English\ngreeting = Hello { $name }!").unwrap();
+        catalogs
+    }
+
+    #[test]
+    fn messages_are_translated_with_fallback() {
+        let catalogs = test_catalogs();
+        assert_eq!(catalogs.translate("en", "greeting", &[("name", "Alice")]), "Hello Alice!");
+        assert_eq!(catalogs.translate("de", "greeting", &[("name", "Alice")]), "Hallo Alice!");
+        assert_eq!(catalogs.translate("en", "only-de", &[]), "Nur deutsch");
+        assert_eq!(catalogs.translate("da", "greeting", &[]), "Hallo { $name }!");
+        assert_eq!(catalogs.translate("en", "unknown", &[]), "unknown");
+        assert!(parse("no equals sign").is_err());
+    }
+
+    #[test]
+    fn language_is_negotiated() {
+        let catalogs = test_catalogs();
+        assert_eq!(parse_accept_language("da, en-GB;q=0.8, de;q=0.9, fr;q=0"), vec!["da", "de", "en-gb"]);
+        assert_eq!(catalogs.negotiate(Some("en"), Some("de"), "de"), "en");
+        assert_eq!(catalogs.negotiate(Some("da"), Some("fr, en-US;q=0.5"), "de"), "en");
+        assert_eq!(catalogs.negotiate(None, Some("da"), "en"), "en");
+        assert_eq!(catalogs.negotiate(None, None, "da"), "de");
+    }
+
+    #[test]
+    fn all_catalogs_can_be_loaded() {
+        let catalogs = catalogs();
+        for language in &["de", "en", "da"] {
+            assert!(catalogs.has_language(language));
+        }
+        assert_eq!(with_language("en", || translate("grade-114")), "Vocational school");
+        assert_eq!(translate("grade-114"), "Berufsschule");
+    }
+
+    #[test]
+    fn all_messages_are_translated() {
+        let catalogs = catalogs();
+        let german = &catalogs.messages[DEFAULT_LANGUAGE];
+        for (language, messages) in &catalogs.messages {
+            let missing: Vec<&String> = german.keys().filter(|id| !messages.contains_key(*id)).collect();
+            assert!(missing.is_empty(), "Messages missing in {}: {:?}", language, missing);
+        }
+
+        for dir in &["templates/default", "templates/jwinf"] {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                let source = fs::read_to_string(&path).unwrap();
+                for call in source.split("{{t \"").skip(1) {
+                    let id = &call[..call.find('"').unwrap()];
+                    assert!(german.contains_key(id), "Unknown message {} in {}", id, path.display());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Signed login links.
+//!
+//! A login link `<self_url>/login/link/<user id>/<expiry>/<signature>` logs a member of a group in without typing
+//! the login code, e.g. by scanning the QR code on the login sheets. The signature is an HMAC-SHA256 of the user id,
+//! the expiry, the login code and the generation of the login links of the user. Therefore a link stops working
+//! when it expires, when the login code changes or when the links of the user are revoked, which starts a new
+//! generation. The login code itself is not part of the link.
+
+use sha2::{Digest, Sha256};
+use time;
+
+const BLOCK_SIZE: usize = 64;
+
+fn sha256(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha256::default();
+    for part in parts {
+        hasher.input(part);
+    }
+    hasher.result().to_vec()
+}
+
+/// HMAC-SHA256 as specified in RFC 2104
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut key = if key.len() > BLOCK_SIZE { sha256(&[key]) } else { key.to_vec() };
+    key.resize(BLOCK_SIZE, 0);
+    let inner_key: Vec<u8> = key.iter().map(|byte| byte ^ 0x36).collect();
+    let outer_key: Vec<u8> = key.iter().map(|byte| byte ^ 0x5c).collect();
+    sha256(&[&outer_key, &sha256(&[&inner_key, message])])
+}
+
+/// Compare without returning early, so that the time does not reveal how much of a signature is correct
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |difference, (x, y)| difference | (x ^ y)) == 0
+}
+
+pub struct LoginLinkSigner {
+    secret: Vec<u8>,
+    validity: time::Duration,
+}
+
+impl LoginLinkSigner {
+    pub fn new(secret: &str, validity_days: i64) -> Self {
+        LoginLinkSigner { secret: secret.as_bytes().to_vec(), validity: time::Duration::days(validity_days) }
+    }
+
+    fn signature(&self, user_id: i32, expires: i64, logincode: &str, generation: i32) -> String {
+        let message = format!("{}:{}:{}:{}", user_id, expires, logincode, generation);
+        hmac_sha256(&self.secret, message.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// New login link for the user, valid from `now` on for the configured number of days
+    pub fn link(&self, self_url: &str, user_id: i32, logincode: &str, generation: i32, now: time::Timespec) -> String {
+        let expires = (now + self.validity).sec;
+        format!("{}/login/link/{}/{}/{}",
+                self_url,
+                user_id,
+                expires,
+                self.signature(user_id, expires, logincode, generation))
+    }
+
+    /// Is the link with `expires` and `signature` a valid login link of the user with `logincode` at time `now`?
+    pub fn verify(&self, user_id: i32, expires: i64, signature: &str, logincode: &str, generation: i32,
+                  now: time::Timespec)
+                  -> bool {
+        now.sec <= expires
+        && constant_time_eq(self.signature(user_id, expires, logincode, generation).as_bytes(), signature.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String { bytes.iter().map(|byte| format!("{:02x}", byte)).collect() }
+
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        assert_eq!(hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+                   "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+        // Keys longer than a block are hashed first
+        assert_eq!(hex(&hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")),
+                   "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+    }
+
+    #[test]
+    fn links_are_verified() {
+        let signer = LoginLinkSigner::new("secret", 30);
+        let now = time::Timespec::new(1_600_000_000, 0);
+        let link = signer.link("https://example.org", 42, "uabcdefgh", 0, now);
+
+        let parts: Vec<&str> = link.trim_start_matches("https://example.org/login/link/").split('/').collect();
+        assert_eq!(parts[0], "42");
+        let expires: i64 = parts[1].parse().unwrap();
+        assert_eq!(expires, 1_600_000_000 + 30 * 24 * 60 * 60);
+        let signature = parts[2];
+
+        assert!(signer.verify(42, expires, signature, "uabcdefgh", 0, now));
+        assert!(signer.verify(42, expires, signature, "uabcdefgh", 0, time::Timespec::new(expires, 0)));
+        // Expired, other user, changed login code, revoked, changed expiry or other secret
+        assert!(!signer.verify(42, expires, signature, "uabcdefgh", 0, time::Timespec::new(expires + 1, 0)));
+        assert!(!signer.verify(43, expires, signature, "uabcdefgh", 0, now));
+        assert!(!signer.verify(42, expires, signature, "uabcdefgi", 0, now));
+        assert!(!signer.verify(42, expires, signature, "uabcdefgh", 1, now));
+        assert!(!signer.verify(42, expires + 1, signature, "uabcdefgh", 0, now));
+        assert!(!LoginLinkSigner::new("other", 30).verify(42, expires, signature, "uabcdefgh", 0, now));
+    }
+}
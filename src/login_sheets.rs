@@ -0,0 +1,389 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Printable login sheets of a group as PDF.
+//!
+//! The first page is a summary for the teacher with the login codes of all members. It is followed by a card for
+//! every member with the name, the login code, the group code, a QR code of the login link and instructions. Cards
+//! are printed eight to a page (layout `cards`) or one per page (layout `pages`). The title and the instructions can
+//! be configured with `login_sheets` in the configuration.
+//!
+//! The PDF only uses the standard fonts Helvetica and Courier, so text is written in Windows-1252. Characters that
+//! do not exist in Windows-1252 are printed as `?`.
+
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use qrcode::{Color, EcLevel, QrCode};
+
+use group_import::encode_windows_1252;
+use i18n;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LoginSheetConfig {
+    /// `cards` (eight cards per page, default) or `pages` (one page per member)
+    pub layout: Option<Layout>,
+    /// Title of each card, as text or as message id of a translation in `locales/`
+    pub title: Option<String>,
+    /// Paragraphs below the login code, as text or as message ids. They can contain the placeholders `{ $login_url }`,
+    /// `{ $logincode }`, `{ $groupcode }`, `{ $group }` and `{ $name }`.
+    pub instructions: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    Cards,
+    Pages,
+}
+
+pub struct LoginSheetMember {
+    pub name: String,
+    pub grade: String,
+    pub logincode: String,
+    /// Link that is shown as QR code
+    pub login_link: String,
+}
+
+pub struct LoginSheets {
+    pub group: String,
+    pub groupcode: String,
+    /// URL of the login page, printed in the instructions
+    pub login_url: String,
+    pub members: Vec<LoginSheetMember>,
+}
+
+// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 50.0;
+
+const CARD_WIDTH: f32 = PAGE_WIDTH / 2.0;
+const CARD_HEIGHT: f32 = PAGE_HEIGHT / 4.0;
+const CARD_MARGIN: f32 = 20.0;
+const CARDS_PER_PAGE: usize = 8;
+const QR_SIZE: f32 = 90.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+const MONOSPACE: Name = Name(b"F3");
+const FONTS: [(Name, &[u8]); 3] = [(REGULAR, b"Helvetica"), (BOLD, b"Helvetica-Bold"), (MONOSPACE, b"Courier-Bold")];
+
+/// Widths of the characters `' '` to `'~'` in Helvetica in thousandths of the font size
+const HELVETICA_WIDTHS: [u16; 95] = [278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+                                     556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+                                     1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+                                     667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+                                     333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+                                     556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584];
+
+/// Approximate width of `text` in Helvetica. Other characters are assumed to be as wide as a digit.
+fn text_width(text: &str, size: f32) -> f32 {
+    let width: u32 = text.chars()
+                         .map(|c| match c {
+                             ' '..='~' => HELVETICA_WIDTHS[c as usize - ' ' as usize] as u32,
+                             _ => 556,
+                         })
+                         .sum();
+    width as f32 * size / 1000.0
+}
+
+/// Font size not larger than `size` at which `text` fits into `width`. Bold text is about 10 % wider.
+fn fit_bold(text: &str, size: f32, width: f32) -> f32 {
+    let needed = text_width(text, size) * 1.1;
+    if needed > width {
+        size * width / needed
+    } else {
+        size
+    }
+}
+
+/// Break `text` at spaces into lines that fit into `width`
+fn wrap(text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if line.is_empty() {
+            line = word.to_string();
+        } else if text_width(&format!("{} {}", line, word), size) > width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = format!("{} {}", line, word);
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn text(content: &mut Content, font: Name, size: f32, x: f32, y: f32, text: &str) {
+    content.begin_text();
+    content.set_font(font, size).next_line(x, y).show(Str(&encode_windows_1252(text)));
+    content.end_text();
+}
+
+/// Draw the QR code of `data` with its upper left corner at `(x, y)`
+fn qr_code(content: &mut Content, data: &str, x: f32, y: f32, size: f32) {
+    let code = match QrCode::with_error_correction_level(data, EcLevel::M) {
+        Ok(code) => code,
+        // Too long for a QR code
+        Err(_) => return,
+    };
+    let width = code.width();
+    let module = size / width as f32;
+    let colors = code.to_colors();
+    for (row, colors) in colors.chunks(width).enumerate() {
+        // Adjacent dark modules are drawn as one rectangle, so that no seams are visible between them
+        let mut column = 0;
+        while column < width {
+            let run = colors[column..].iter().take_while(|&&color| color == Color::Dark).count();
+            if run > 0 {
+                content.rect(x + column as f32 * module, y - (row + 1) as f32 * module, run as f32 * module, module);
+            }
+            column += run.max(1);
+        }
+    }
+    content.fill_nonzero();
+}
+
+/// Translate `label` if it is a message id and fill in the placeholders
+fn format(label: &str, args: &[(&str, &str)]) -> String { i18n::format_message(&i18n::translate(label), args) }
+
+/// Draw the card of `member` with its lower left corner at the origin
+fn card(content: &mut Content, sheets: &LoginSheets, member: &LoginSheetMember, config: &LoginSheetConfig) {
+    let args = [("login_url", sheets.login_url.as_str()),
+                ("logincode", member.logincode.as_str()),
+                ("groupcode", sheets.groupcode.as_str()),
+                ("group", sheets.group.as_str()),
+                ("name", member.name.as_str())];
+    let top = CARD_HEIGHT - CARD_MARGIN;
+    let text_width = CARD_WIDTH - 3.0 * CARD_MARGIN - QR_SIZE;
+
+    let title = format(config.title.as_deref().unwrap_or("login-sheet-title"), &args);
+    text(content, BOLD, fit_bold(&title, 13.0, text_width), CARD_MARGIN, top - 13.0, &title);
+    text(content, BOLD, fit_bold(&member.name, 11.0, text_width), CARD_MARGIN, top - 32.0, &member.name);
+    let group = i18n::translate_with("login-sheet-group", &[("group", &sheets.group), ("grade", &member.grade)]);
+    text(content, REGULAR, 9.0, CARD_MARGIN, top - 46.0, &group);
+    text(content, REGULAR, 9.0, CARD_MARGIN, top - 64.0, &i18n::translate("login-sheet-logincode"));
+    text(content, MONOSPACE, 16.0, CARD_MARGIN, top - 81.0, &member.logincode);
+    let groupcode = i18n::translate_with("login-sheet-groupcode", &[("groupcode", &sheets.groupcode)]);
+    text(content, REGULAR, 8.0, CARD_MARGIN, top - 96.0, &groupcode);
+
+    qr_code(content, &member.login_link, CARD_WIDTH - CARD_MARGIN - QR_SIZE, top, QR_SIZE);
+
+    let default_instructions = vec!["login-sheet-instructions".to_string()];
+    let mut y = top - QR_SIZE - 14.0;
+    for paragraph in config.instructions.as_ref().unwrap_or(&default_instructions) {
+        for line in wrap(&format(paragraph, &args), 8.0, CARD_WIDTH - 2.0 * CARD_MARGIN) {
+            // Text that does not fit on the card is left out instead of being printed on the next card
+            if y < CARD_MARGIN / 2.0 {
+                return;
+            }
+            text(content, REGULAR, 8.0, CARD_MARGIN, y, &line);
+            y -= 10.0;
+        }
+        y -= 3.0;
+    }
+}
+
+/// Dashed lines along which the cards of a page are cut apart
+fn cut_lines(content: &mut Content) {
+    content.save_state();
+    content.set_stroke_gray(0.6).set_line_width(0.5).set_dash_pattern([3.0, 3.0], 0.0);
+    content.move_to(CARD_WIDTH, 0.0).line_to(CARD_WIDTH, PAGE_HEIGHT);
+    for row in 1..CARDS_PER_PAGE / 2 {
+        content.move_to(0.0, row as f32 * CARD_HEIGHT).line_to(PAGE_WIDTH, row as f32 * CARD_HEIGHT);
+    }
+    content.stroke();
+    content.restore_state();
+}
+
+/// Pages of the summary for the teacher
+fn summary(sheets: &LoginSheets) -> Vec<Content> {
+    let mut pages = Vec::new();
+    let mut content = Content::new();
+    let mut y = PAGE_HEIGHT - PAGE_MARGIN - 16.0;
+    let width = PAGE_WIDTH - 2.0 * PAGE_MARGIN;
+
+    let title = i18n::translate_with("login-sheet-summary-title", &[("group", &sheets.group)]);
+    text(&mut content, BOLD, fit_bold(&title, 16.0, width), PAGE_MARGIN, y, &title);
+    y -= 24.0;
+    let groupcode = i18n::translate_with("login-sheet-groupcode", &[("groupcode", &sheets.groupcode)]);
+    text(&mut content, REGULAR, 10.0, PAGE_MARGIN, y, &groupcode);
+    y -= 20.0;
+    let count = sheets.members.len().to_string();
+    let explanation =
+        i18n::translate_with("login-sheet-summary", &[("count", &count), ("login_url", &sheets.login_url)]);
+    for line in wrap(&explanation, 10.0, width) {
+        text(&mut content, REGULAR, 10.0, PAGE_MARGIN, y, &line);
+        y -= 13.0;
+    }
+
+    let columns = [PAGE_MARGIN, PAGE_MARGIN + 280.0, PAGE_MARGIN + 390.0];
+    let header = |content: &mut Content, y: f32| {
+        for (x, label) in columns.iter().zip(&["login-sheet-name", "login-sheet-grade", "login-sheet-logincode"]) {
+            text(content, BOLD, 10.0, *x, y, &i18n::translate(label));
+        }
+    };
+    y -= 16.0;
+    header(&mut content, y);
+    for member in &sheets.members {
+        y -= 16.0;
+        if y < PAGE_MARGIN {
+            pages.push(std::mem::replace(&mut content, Content::new()));
+            y = PAGE_HEIGHT - PAGE_MARGIN - 10.0;
+            header(&mut content, y);
+            y -= 16.0;
+        }
+        text(&mut content, REGULAR, fit_bold(&member.name, 10.0, 270.0), columns[0], y, &member.name);
+        text(&mut content, REGULAR, 10.0, columns[1], y, &member.grade);
+        text(&mut content, MONOSPACE, 10.0, columns[2], y, &member.logincode);
+    }
+    pages.push(content);
+    pages
+}
+
+/// Pages with the cards of all members
+fn cards(sheets: &LoginSheets, config: &LoginSheetConfig) -> Vec<Content> {
+    match config.layout.unwrap_or(Layout::Cards) {
+        Layout::Cards => sheets.members
+                               .chunks(CARDS_PER_PAGE)
+                               .map(|members| {
+                                   let mut content = Content::new();
+                                   for (i, member) in members.iter().enumerate() {
+                                       let x = (i % 2) as f32 * CARD_WIDTH;
+                                       let y = PAGE_HEIGHT - (i / 2 + 1) as f32 * CARD_HEIGHT;
+                                       content.save_state();
+                                       content.transform([1.0, 0.0, 0.0, 1.0, x, y]);
+                                       card(&mut content, sheets, member, config);
+                                       content.restore_state();
+                                   }
+                                   cut_lines(&mut content);
+                                   content
+                               })
+                               .collect(),
+        Layout::Pages => {
+            // The card scaled to the width of the page
+            let scale = (PAGE_WIDTH - 2.0 * PAGE_MARGIN) / CARD_WIDTH;
+            sheets.members
+                  .iter()
+                  .map(|member| {
+                      let mut content = Content::new();
+                      content.save_state();
+                      content.transform([scale,
+                                         0.0,
+                                         0.0,
+                                         scale,
+                                         PAGE_MARGIN,
+                                         PAGE_HEIGHT - PAGE_MARGIN - scale * CARD_HEIGHT]);
+                      card(&mut content, sheets, member, config);
+                      content.restore_state();
+                      content
+                  })
+                  .collect()
+        }
+    }
+}
+
+/// The login sheets of a group as PDF
+pub fn render(sheets: &LoginSheets, config: &LoginSheetConfig) -> Vec<u8> {
+    let mut pages = summary(sheets);
+    pages.extend(cards(sheets, config));
+
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let info_id = Ref::new(3);
+    let font_ids: Vec<Ref> = (0..FONTS.len() as i32).map(|i| Ref::new(4 + i)).collect();
+    let first_page = 4 + FONTS.len() as i32;
+    let page_ids: Vec<Ref> = (0..pages.len() as i32).map(|i| Ref::new(first_page + 2 * i)).collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.document_info(info_id).title(TextStr(&sheets.group)).creator(TextStr("medal"));
+    pdf.pages(page_tree_id).kids(page_ids.iter().copied()).count(pages.len() as i32);
+    for ((_, base_font), id) in FONTS.iter().zip(&font_ids) {
+        pdf.type1_font(*id).base_font(Name(base_font)).encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+    for (content, page_id) in pages.into_iter().zip(page_ids) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT)).parent(page_tree_id).contents(content_id);
+        let mut resources = page.resources();
+        let mut fonts = resources.fonts();
+        for ((name, _), id) in FONTS.iter().zip(&font_ids) {
+            fonts.pair(*name, *id);
+        }
+        fonts.finish();
+        resources.finish();
+        page.finish();
+        pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(i: usize) -> LoginSheetMember {
+        let logincode = format!("uabcdef{:02}", i);
+        LoginSheetMember { name: format!("Jürgen Müller {}", i),
+                           grade: "7".to_string(),
+                           login_link: format!("https://example.org/login?code={}", logincode),
+                           logincode }
+    }
+
+    fn group(members: usize) -> LoginSheets {
+        LoginSheets { group: "7a".to_string(),
+                      groupcode: "gabcdef".to_string(),
+                      login_url: "https://example.org/login".to_string(),
+                      members: (0..members).map(member).collect() }
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    /// Is `text` shown on a page of `pdf`?
+    fn shows(pdf: &[u8], text: &str) -> bool {
+        let mut content = Content::new();
+        content.show(Str(&encode_windows_1252(text)));
+        contains(pdf, &content.finish())
+    }
+
+    #[test]
+    fn lines_are_wrapped() {
+        assert_eq!(wrap("Eins zwei drei", 10.0, 1000.0), vec!["Eins zwei drei"]);
+        assert_eq!(wrap("Eins zwei  drei", 10.0, 50.0), vec!["Eins zwei", "drei"]);
+        assert_eq!(wrap("Überlangeswort kurz", 10.0, 20.0), vec!["Überlangeswort", "kurz"]);
+        assert!(fit_bold("Maximilian-Alexander Mustermann-Schmidt", 11.0, 150.0) < 11.0);
+    }
+
+    #[test]
+    fn cards_and_pages_are_rendered() {
+        let pdf = render(&group(9), &LoginSheetConfig::default());
+        assert!(pdf.starts_with(b"%PDF-"));
+        // Summary and two pages with cards
+        assert!(contains(&pdf, b"/Count 3"));
+        assert!(shows(&pdf, "Jürgen Müller 8"));
+        assert!(shows(&pdf, "uabcdef08"));
+
+        let config = LoginSheetConfig { layout: Some(Layout::Pages),
+                                        title: Some("Informatik-Biber { $group }".to_string()),
+                                        instructions: Some(vec!["Gehe auf { $login_url }.".to_string()]) };
+        let pdf = render(&group(2), &config);
+        assert!(contains(&pdf, b"/Count 3"));
+        assert!(shows(&pdf, "Informatik-Biber 7a"));
+        assert!(shows(&pdf, "Gehe auf https://example.org/login."));
+    }
+}
@@ -0,0 +1,168 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Sending mails via SMTP or into a maildir (for testing)
+
+use std::path::{Path, PathBuf};
+
+use lettre::smtp::authentication::Credentials;
+use lettre::{ClientSecurity, ClientTlsParameters, SendableEmail, SmtpClient, Transport};
+use lettre_email::EmailBuilder;
+use native_tls::TlsConnector;
+
+use base64;
+use config::Config;
+use time;
+
+#[derive(Debug)]
+pub enum MailError {
+    InvalidMail(String),
+    Smtp(String),
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for MailError {
+    fn from(e: std::io::Error) -> Self { MailError::Io(e) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SmtpSecurity {
+    /// TLS from the start of the connection (usually port 465)
+    Tls,
+    /// Upgrade to TLS with STARTTLS (usually port 587)
+    StartTls,
+    /// Unencrypted (only for local mail servers)
+    None,
+}
+
+enum MailTransport {
+    Smtp {
+        server: String,
+        port: u16,
+        security: SmtpSecurity,
+        credentials: Option<Credentials>,
+    },
+    /// Write mails into `new/` of a maildir instead of sending them
+    Maildir(PathBuf),
+}
+
+pub struct Mailer {
+    from: String,
+    transport: MailTransport,
+    /// Base URL for links in mails
+    pub self_url: String,
+}
+
+impl Mailer {
+    /// Create a mailer from the `mail_*` and `smtp_*` settings. Returns `None` if sending mails is not configured.
+    pub fn from_config(config: &Config) -> Option<Mailer> {
+        let from = config.mail_from.clone()?;
+        let self_url = config.self_url.clone()?;
+
+        let transport = if let Some(maildir) = &config.mail_maildir {
+            MailTransport::Maildir(PathBuf::from(maildir))
+        } else {
+            let security = match config.smtp_security.as_deref() {
+                None | Some("tls") => SmtpSecurity::Tls,
+                Some("starttls") => SmtpSecurity::StartTls,
+                Some("none") => SmtpSecurity::None,
+                Some(other) => {
+                    eprintln!("Unknown smtp_security '{}', mails are not sent.", other);
+                    return None;
+                }
+            };
+            let default_port = match security {
+                SmtpSecurity::Tls => 465,
+                SmtpSecurity::StartTls => 587,
+                SmtpSecurity::None => 25,
+            };
+            let credentials = match (&config.smtp_username, &config.smtp_password) {
+                (Some(username), Some(password)) => Some(Credentials::new(username.clone(), password.clone())),
+                _ => None,
+            };
+
+            MailTransport::Smtp { server: config.smtp_server.clone()?,
+                                  port: config.smtp_port.unwrap_or(default_port),
+                                  security,
+                                  credentials }
+        };
+
+        Some(Mailer { from, transport, self_url })
+    }
+
+    /// Send a plain text mail. Mails via SMTP are sent in the background, errors are only logged.
+    pub fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), MailError> {
+        let email = EmailBuilder::new().to(to)
+                                       .from(self.from.as_str())
+                                       .subject(encode_header(subject))
+                                       .text(body)
+                                       .build()
+                                       .map_err(|e| MailError::InvalidMail(e.to_string()))?;
+
+        match &self.transport {
+            MailTransport::Smtp { server, port, security, credentials } => {
+                let tls = || -> Result<ClientTlsParameters, MailError> {
+                    let connector = TlsConnector::new().map_err(|e| MailError::Smtp(e.to_string()))?;
+                    Ok(ClientTlsParameters::new(server.clone(), connector))
+                };
+                let security = match security {
+                    SmtpSecurity::Tls => ClientSecurity::Wrapper(tls()?),
+                    SmtpSecurity::StartTls => ClientSecurity::Required(tls()?),
+                    SmtpSecurity::None => ClientSecurity::None,
+                };
+
+                let mut client =
+                    SmtpClient::new((server.as_str(), *port), security).map_err(|e| MailError::Smtp(e.to_string()))?;
+                if let Some(credentials) = credentials {
+                    client = client.credentials(credentials.clone());
+                }
+
+                // Talking to the mail server can take a while, so do not block the caller (which usually holds the
+                // database connection)
+                let email: SendableEmail = email.into();
+                let to = to.to_string();
+                std::thread::spawn(move || {
+                    if let Err(e) = client.transport().send(email) {
+                        eprintln!("Could not send mail to {}: {}", to, e);
+                    }
+                });
+            }
+            MailTransport::Maildir(maildir) => write_to_maildir(maildir, email.into())?,
+        }
+        Ok(())
+    }
+}
+
+/// Encode non-ASCII header values as RFC 2047 encoded word
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(value))
+    }
+}
+
+fn write_to_maildir(maildir: &Path, email: SendableEmail) -> Result<(), MailError> {
+    for subdir in &["tmp", "new", "cur"] {
+        std::fs::create_dir_all(maildir.join(subdir))?;
+    }
+
+    let now = time::get_time();
+    let filename = format!("{}.{}_{}.medal", now.sec, now.nsec, email.message_id());
+    let tmp_path = maildir.join("tmp").join(&filename);
+
+    std::fs::write(&tmp_path, email.message_to_string()?)?;
+    std::fs::rename(&tmp_path, maildir.join("new").join(&filename))?;
+    Ok(())
+}
@@ -21,14 +21,21 @@ extern crate router;
 #[macro_use]
 extern crate serde_derive;
 
+extern crate base64;
 extern crate csv;
 extern crate handlebars_iron;
 extern crate iron_sessionstorage;
+extern crate lettre;
+extern crate lettre_email;
 extern crate mount;
+extern crate native_tls;
 extern crate params;
+extern crate pdf_writer;
 extern crate persistent;
+extern crate qrcode;
 extern crate rand;
 extern crate reqwest;
+extern crate serde;
 extern crate serde_json;
 extern crate serde_yaml;
 extern crate sha2;
@@ -37,6 +44,8 @@ extern crate structopt;
 extern crate time;
 extern crate urlencoded;
 
+#[cfg(unix)]
+extern crate libc;
 #[cfg(feature = "postgres")]
 extern crate postgres;
 #[cfg(feature = "rusqlite")]
@@ -48,15 +57,32 @@ pub mod config;
 pub mod contestreader_yaml;
 pub mod core;
 pub mod db_conn;
+pub mod diff;
+pub mod export;
+pub mod grades;
+pub mod group_import;
 pub mod helpers;
+pub mod i18n;
+pub mod login_links;
+pub mod login_sheets;
+pub mod mail;
+pub mod metrics;
+pub mod permissions;
+pub mod qualification;
+pub mod ranking;
+pub mod reporting;
+pub mod statistics;
+pub mod validation;
 
 mod db_apply_migrations;
 mod db_conn_postgres;
 mod db_conn_sqlite_new;
 mod db_objects;
+mod db_pool;
+mod session_backend;
 mod webfw_iron;
 
-use db_conn::{MedalConnection, MedalObject};
+use db_conn::MedalConnection;
 use helpers::SetPassword;
 use webfw_iron::start_server;
 
@@ -66,13 +92,53 @@ fn refresh_all_contests<C>(conn: &mut C)
     where C: MedalConnection,
           db_objects::Contest: db_conn::MedalObject<C>
 {
-    conn.reset_all_contest_visibilities();
-    conn.reset_all_taskgroup_visibilities();
+    let report = core::rescan_contests(conn, "tasks/", true);
 
-    let v = contestreader_yaml::get_all_contest_info("tasks/");
+    for error in &report.errors {
+        println!("{}", error);
+    }
+    print!(" {}", report.summary());
+}
 
-    for mut contest_info in v {
-        contest_info.save(conn);
+/// Rescan the contests periodically using a separate database connection.
+fn run_contest_rescan_worker<C>(config: Config)
+    where C: MedalConnection,
+          db_objects::Contest: db_conn::MedalObject<C>
+{
+    let conn = C::reconnect(&config);
+    let interval = std::time::Duration::from_secs(config.contest_rescan_interval.unwrap_or(60));
+    let mut last_errors = Vec::new();
+
+    loop {
+        std::thread::sleep(interval);
+
+        // A failed database statement panics. The contests of the file are rolled back then and the next scan tries
+        // again, instead of ending the worker.
+        let report = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                                        core::rescan_contests(&conn, "tasks/", false)
+                                                    })) {
+            Ok(report) => report,
+            Err(_) => {
+                eprintln!("Could not save the contest rescan, retrying in {:?}", interval);
+                continue;
+            }
+        };
+        for contest in &report.added {
+            println!("Contest added: {}", contest);
+        }
+        for contest in &report.changed {
+            println!("Contest changed: {}", contest);
+        }
+        for contest in &report.hidden {
+            println!("Contest hidden: {}", contest);
+        }
+        // Files with errors are checked again on every scan, so only report new errors
+        if report.errors != last_errors {
+            for error in &report.errors {
+                println!("{}", error);
+            }
+            last_errors = report.errors;
+        }
     }
 }
 
@@ -125,6 +191,14 @@ fn prepare_and_start_server<C>(mut conn: C, config: Config)
     if config.only_contest_scan != Some(true) {
         add_admin_user(&mut conn, config.reset_admin_pw.unwrap_or(false));
 
+        let validation_config = config.clone();
+        std::thread::spawn(move || validation::run_validation_worker::<C>(validation_config));
+
+        if config.contest_rescan_interval.unwrap_or(0) > 0 {
+            let rescan_config = config.clone();
+            std::thread::spawn(move || run_contest_rescan_worker::<C>(rescan_config));
+        }
+
         #[cfg(feature = "webbrowser")]
         let self_url = config.self_url.clone();
         #[cfg(feature = "webbrowser")]
@@ -156,12 +230,38 @@ fn open_browser_window(self_url: &str) {
     }
 }
 
+fn check_all_contests() -> bool {
+    print!("Checking contests …");
+    let problems = contestreader_yaml::check_all_contest_info("tasks/");
+    println!();
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    let errors = problems.iter().filter(|p| p.severity == contestreader_yaml::Severity::Error).count();
+    println!("{} error(s), {} warning(s)", errors, problems.len() - errors);
+    errors == 0
+}
+
 fn main() {
     let config = config::get_config();
 
     #[cfg(feature = "debug")]
     println!("Using config: {:#?}", config);
 
+    if let Some(grades) = config.grades.clone() {
+        if let Err(e) = grades::configure(grades) {
+            println!("Invalid grades in config: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if config.check_tasks == Some(true) {
+        let ok = check_all_contests();
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
     #[cfg(feature = "postgres")]
     {
         if let Some(url) = config.database_url.clone() {
@@ -196,6 +296,15 @@ fn main() {
             }
             println!("Sqlite FOREIGN KEY support enabled");
 
+            // The background workers write using their own connections, so wait for them instead of failing
+            conn.busy_timeout(std::time::Duration::from_secs(5)).unwrap();
+
+            if config.sqlite_readers.unwrap_or(0) > 0 {
+                // Readers do not block the writer in WAL mode. The mode is stored in the database file.
+                let journal_mode: String = conn.query_row("PRAGMA journal_mode = WAL", &[], |row| row.get(0)).unwrap();
+                println!("Sqlite journal mode {}", journal_mode);
+            }
+
             prepare_and_start_server(conn, config);
             return;
         }
@@ -0,0 +1,212 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Metrics of the running server, served at `/metrics` in the Prometheus text format.
+//!
+//! The metrics are collected in global statics, since the database connection does not know anything about the
+//! web framework. All counters start at zero when the server is started.
+//!
+//! Every request and database method updates the metrics, so they only consist of atomics. A lock is only taken
+//! exclusively when a route, status code or method is seen for the first time.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the histogram buckets in seconds
+const BUCKETS: [f64; 12] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+struct Histogram {
+    /// Cumulative counts, i.e. `buckets[i]` counts all observations up to `BUCKETS[i]`
+    buckets: [AtomicU64; 12],
+    sum_nanos: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Histogram { buckets: [const { AtomicU64::new(0) }; 12], sum_nanos: AtomicU64::new(0), count: AtomicU64::new(0) }
+    }
+
+    fn observe(&self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        for (bucket, &bound) in self.buckets.iter().zip(BUCKETS.iter()) {
+            if secs <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.sum_nanos.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `labels` are written into the braces of each sample, e.g. `route="login"`
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        for (bucket, bound) in self.buckets.iter().zip(BUCKETS.iter()) {
+            let bucket = bucket.load(Ordering::Relaxed);
+            writeln!(out, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, separator, bound, bucket).unwrap();
+        }
+        let sum = Duration::from_nanos(self.sum_nanos.load(Ordering::Relaxed)).as_secs_f64();
+        let count = self.count.load(Ordering::Relaxed);
+        writeln!(out, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, separator, count).unwrap();
+        if labels.is_empty() {
+            writeln!(out, "{}_sum {}", name, sum).unwrap();
+            writeln!(out, "{}_count {}", name, count).unwrap();
+        } else {
+            writeln!(out, "{}_sum{{{}}} {}", name, labels, sum).unwrap();
+            writeln!(out, "{}_count{{{}}} {}", name, labels, count).unwrap();
+        }
+    }
+}
+
+/// Metrics of the same kind by label. Known labels are only read-locked, so concurrent observations do not wait for
+/// each other.
+struct Family<K, V> {
+    entries: RwLock<BTreeMap<K, V>>,
+}
+
+impl<K: Ord, V> Family<K, V> {
+    const fn new() -> Self { Family { entries: RwLock::new(BTreeMap::new()) } }
+
+    fn entries(&self) -> RwLockReadGuard<'_, BTreeMap<K, V>> { self.entries.read().unwrap_or_else(|e| e.into_inner()) }
+
+    /// Call `f` with the metric of `key`, which is created with `new` when the label is seen for the first time
+    fn with<R>(&self, key: K, new: fn() -> V, f: impl FnOnce(&V) -> R) -> R {
+        if let Some(value) = self.entries().get(&key) {
+            return f(value);
+        }
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        f(entries.entry(key).or_insert_with(new))
+    }
+}
+
+/// Number of requests by route and status code
+static REQUESTS: Family<(&str, u16), AtomicU64> = Family::new();
+static REQUEST_DURATIONS: Family<&str, Histogram> = Family::new();
+static DB_METHOD_DURATIONS: Family<&str, Histogram> = Family::new();
+static CONNECTION_WAIT: Histogram = Histogram::new();
+static SUBMISSIONS: AtomicU64 = AtomicU64::new(0);
+static CONTEST_SCANS: AtomicU64 = AtomicU64::new(0);
+static LAST_CONTEST_SCAN_NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// Count a request to the route `route` (the route id of the router) that took `duration`.
+pub fn observe_request(route: &'static str, status: u16, duration: Duration) {
+    REQUESTS.with((route, status), || AtomicU64::new(0), |count| count.fetch_add(1, Ordering::Relaxed));
+    REQUEST_DURATIONS.with(route, Histogram::new, |histogram| histogram.observe(duration));
+}
+
+/// Measures the time of a `MedalConnection` method until it is dropped
+pub struct DbMethodTimer {
+    method: &'static str,
+    start: Instant,
+}
+
+impl DbMethodTimer {
+    pub fn start(method: &'static str) -> Self { DbMethodTimer { method, start: Instant::now() } }
+}
+
+impl Drop for DbMethodTimer {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        DB_METHOD_DURATIONS.with(self.method, Histogram::new, |histogram| histogram.observe(duration));
+    }
+}
+
+/// Record how long a request had to wait for a free database connection
+pub fn observe_connection_wait(duration: Duration) { CONNECTION_WAIT.observe(duration); }
+
+pub fn count_submission() { SUBMISSIONS.fetch_add(1, Ordering::Relaxed); }
+
+pub fn observe_contest_scan(duration: Duration) {
+    LAST_CONTEST_SCAN_NANOS.store(duration.as_nanos() as u64, Ordering::Relaxed);
+    CONTEST_SCANS.fetch_add(1, Ordering::Relaxed);
+}
+
+fn header(out: &mut String, name: &str, metric_type: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, metric_type).unwrap();
+}
+
+/// Write all metrics in the Prometheus text format. `active_sessions` is taken from the database by the caller.
+pub fn render(active_sessions: i64) -> String {
+    let mut out = String::new();
+
+    header(&mut out, "medal_http_requests_total", "counter", "Number of HTTP requests by route and status code.");
+    for ((route, status), count) in REQUESTS.entries().iter() {
+        let count = count.load(Ordering::Relaxed);
+        writeln!(out, "medal_http_requests_total{{route=\"{}\",status=\"{}\"}} {}", route, status, count).unwrap();
+    }
+
+    header(&mut out, "medal_http_request_duration_seconds", "histogram", "Time to handle HTTP requests by route.");
+    for (route, histogram) in REQUEST_DURATIONS.entries().iter() {
+        histogram.render(&mut out, "medal_http_request_duration_seconds", &format!("route=\"{}\"", route));
+    }
+
+    header(&mut out, "medal_db_method_duration_seconds", "histogram", "Time spent in database methods.");
+    for (method, histogram) in DB_METHOD_DURATIONS.entries().iter() {
+        histogram.render(&mut out, "medal_db_method_duration_seconds", &format!("method=\"{}\"", method));
+    }
+
+    header(&mut out,
+           "medal_db_connection_wait_seconds",
+           "histogram",
+           "Time requests waited for a free database connection.");
+    CONNECTION_WAIT.render(&mut out, "medal_db_connection_wait_seconds", "");
+
+    header(&mut out, "medal_active_sessions", "gauge", "Number of sessions active during the last hour.");
+    writeln!(out, "medal_active_sessions {}", active_sessions).unwrap();
+
+    header(&mut out,
+           "medal_submissions_total",
+           "counter",
+           "Number of saved submissions. Use rate() to get the submissions per minute.");
+    writeln!(out, "medal_submissions_total {}", SUBMISSIONS.load(Ordering::Relaxed)).unwrap();
+
+    header(&mut out, "medal_contest_scans_total", "counter", "Number of contest scans.");
+    let contest_scans = CONTEST_SCANS.load(Ordering::Relaxed);
+    writeln!(out, "medal_contest_scans_total {}", contest_scans).unwrap();
+
+    if contest_scans > 0 {
+        let duration = Duration::from_nanos(LAST_CONTEST_SCAN_NANOS.load(Ordering::Relaxed));
+        header(&mut out, "medal_contest_scan_duration_seconds", "gauge", "Duration of the last contest scan.");
+        writeln!(out, "medal_contest_scan_duration_seconds {}", duration.as_secs_f64()).unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new();
+        histogram.observe(Duration::from_millis(3));
+        histogram.observe(Duration::from_millis(200));
+        histogram.observe(Duration::from_secs(10));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test", "route=\"login\"");
+
+        assert!(out.contains("test_bucket{route=\"login\",le=\"0.0025\"} 0\n"));
+        assert!(out.contains("test_bucket{route=\"login\",le=\"0.005\"} 1\n"));
+        assert!(out.contains("test_bucket{route=\"login\",le=\"0.25\"} 2\n"));
+        assert!(out.contains("test_bucket{route=\"login\",le=\"5\"} 2\n"));
+        assert!(out.contains("test_bucket{route=\"login\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_count{route=\"login\"} 3\n"));
+    }
+}
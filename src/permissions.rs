@@ -0,0 +1,296 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Roles of staff accounts and the central access check `authorize`.
+//!
+//! Besides the roles assigned in the admin area, the access rights of a session depend on whether it belongs to an
+//! admin (who may do everything), to a teacher or to the owner of the group a resource belongs to.
+
+use core::MedalError;
+use db_objects::SessionUser;
+
+/// Roles that grant parts of the admin rights without making an account admin
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    /// Manages contests: rescans contest files, uploads admissions and exports results
+    ContestManager,
+    /// Reads groups, students and results of the teachers of the own school
+    SchoolCoordinator,
+    /// Looks up users and participations to help them, corrects user data and removes participations
+    Support,
+    /// Reads everything including the audit log, but does not change anything
+    Auditor,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::ContestManager, Role::SchoolCoordinator, Role::Support, Role::Auditor];
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        match name {
+            "contest_manager" => Some(Role::ContestManager),
+            "school_coordinator" => Some(Role::SchoolCoordinator),
+            "support" => Some(Role::Support),
+            "auditor" => Some(Role::Auditor),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::ContestManager => "contest_manager",
+            Role::SchoolCoordinator => "school_coordinator",
+            Role::Support => "support",
+            Role::Auditor => "auditor",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Open the start page of the admin area
+    ViewAdminArea,
+    /// Search all users and groups
+    SearchUsers,
+    /// Open the ranking of a contest. Which participants are shown is checked with `ViewParticipation`.
+    ViewRanking,
+    CreateGroup,
+    ViewTeacherMaterial,
+    /// Rescan contest files and upload admissions
+    ManageContests,
+    ExportResults,
+    /// Look at and export platform-wide statistics, e.g. participations per contest
+    ViewReports,
+    ViewAuditLog,
+    /// Delete old accounts
+    Cleanup,
+    ManageRoles,
+    MoveUserToGroup,
+    /// Delete users, groups or participations with participations in protected contests
+    DeleteProtected,
+
+    ViewUser,
+    EditUser,
+    DeleteUser,
+    ViewGroup,
+    DeleteGroup,
+    /// Invite and remove co-teachers of a group and transfer its ownership
+    ManageGroupAdmins,
+    /// Read the submissions and results of a user
+    ViewParticipation,
+    DeleteParticipation,
+    /// Grant extra time, pause or restart the timer of a participation
+    AdjustParticipationTime,
+    /// Log a user out on all devices
+    RevokeSessions,
+}
+
+impl Action {
+    fn is_read_only(self) -> bool {
+        matches!(self,
+                 Action::ViewAdminArea
+                 | Action::SearchUsers
+                 | Action::ViewRanking
+                 | Action::ExportResults
+                 | Action::ViewReports
+                 | Action::ViewAuditLog
+                 | Action::ViewUser
+                 | Action::ViewGroup
+                 | Action::ViewParticipation)
+    }
+}
+
+/// What an action is performed on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resource {
+    /// Actions concerning the whole site
+    Site,
+    /// A group and the users managed by it. `owner` is the id and `school_id` the school of the teacher owning the
+    /// group, `co_admins` are the ids of the teachers managing the group together with the owner.
+    Group { owner: i32, co_admins: Vec<i32>, school_id: Option<String> },
+    /// A user that is not managed by a group
+    User { id: i32, school_id: Option<String> },
+}
+
+impl Resource {
+    fn school_id(&self) -> Option<&str> {
+        match self {
+            Resource::Site => None,
+            Resource::Group { school_id, .. } | Resource::User { school_id, .. } => school_id.as_deref(),
+        }
+    }
+}
+
+fn role_allows(session: &SessionUser, role: Role, action: Action, resource: &Resource) -> bool {
+    match role {
+        Role::ContestManager => matches!(action,
+                                         Action::ViewAdminArea
+                                         | Action::ViewRanking
+                                         | Action::ManageContests
+                                         | Action::ExportResults
+                                         | Action::ViewReports
+                                         | Action::ViewParticipation),
+        Role::SchoolCoordinator => match action {
+            Action::ViewRanking => true,
+            Action::ViewUser | Action::ViewGroup | Action::ViewParticipation => {
+                resource.school_id().is_some() && resource.school_id() == session.school_id().as_deref()
+            }
+            _ => false,
+        },
+        Role::Support => matches!(action,
+                                  Action::ViewAdminArea
+                                  | Action::SearchUsers
+                                  | Action::ViewRanking
+                                  | Action::ViewUser
+                                  | Action::ViewGroup
+                                  | Action::ViewParticipation
+                                  | Action::EditUser
+                                  | Action::MoveUserToGroup
+                                  | Action::DeleteParticipation
+                                  | Action::RevokeSessions),
+        Role::Auditor => action.is_read_only(),
+    }
+}
+
+fn teacher_allows(action: Action) -> bool {
+    matches!(action, Action::ViewRanking | Action::CreateGroup | Action::ViewTeacherMaterial)
+}
+
+fn owner_allows(session: &SessionUser, action: Action, resource: &Resource) -> bool {
+    match *resource {
+        Resource::Group { owner, .. } if owner == session.id => matches!(action,
+                                                                         Action::ViewUser
+                                                                         | Action::EditUser
+                                                                         | Action::DeleteUser
+                                                                         | Action::ViewGroup
+                                                                         | Action::DeleteGroup
+                                                                         | Action::ManageGroupAdmins
+                                                                         | Action::ViewParticipation
+                                                                         | Action::DeleteParticipation
+                                                                         | Action::AdjustParticipationTime),
+        // Co-teachers can do everything the owner can, except for deleting the group and changing its admins
+        Resource::Group { ref co_admins, .. } if co_admins.contains(&session.id) => {
+            matches!(action,
+                     Action::ViewUser
+                     | Action::EditUser
+                     | Action::DeleteUser
+                     | Action::ViewGroup
+                     | Action::ViewParticipation
+                     | Action::DeleteParticipation
+                     | Action::AdjustParticipationTime)
+        }
+        // Teachers can look at their own account in the admin area
+        Resource::User { id, .. } if id == session.id => action == Action::ViewUser && session.is_teacher(),
+        _ => false,
+    }
+}
+
+/// Check whether `session` may perform `action` on `resource`
+pub fn is_authorized(session: &SessionUser, action: Action, resource: &Resource) -> bool {
+    if !session.is_logged_in() {
+        return false;
+    }
+    if session.is_admin() {
+        return true;
+    }
+
+    (session.is_teacher() && teacher_allows(action))
+    || owner_allows(session, action, resource)
+    || session.roles.iter().any(|&role| role_allows(session, role, action, resource))
+}
+
+/// Like `is_authorized`, but fails with `MedalError::AccessDenied`
+pub fn authorize(session: &SessionUser, action: Action, resource: &Resource) -> Result<(), MedalError> {
+    if is_authorized(session, action, resource) {
+        Ok(())
+    } else {
+        Err(MedalError::AccessDenied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: i32, roles: Vec<Role>) -> SessionUser {
+        let mut session = SessionUser::minimal(id, "token".to_string(), "csrf".to_string());
+        session.last_activity = Some(::time::get_time());
+        session.password = Some("hash".to_string());
+        session.roles = roles;
+        session
+    }
+
+    #[test]
+    fn authorize_checks_roles_and_ownership() {
+        let group = Resource::Group { owner: 1, co_admins: vec![5], school_id: Some("42".to_string()) };
+        let other_group = Resource::Group { owner: 2, co_admins: Vec::new(), school_id: Some("43".to_string()) };
+
+        let owner = session(1, Vec::new());
+        assert!(is_authorized(&owner, Action::DeleteUser, &group));
+        assert!(is_authorized(&owner, Action::ManageGroupAdmins, &group));
+        assert!(!is_authorized(&owner, Action::ViewGroup, &other_group));
+        assert!(!is_authorized(&owner, Action::SearchUsers, &Resource::Site));
+
+        let co_admin = session(5, Vec::new());
+        assert!(is_authorized(&co_admin, Action::ViewParticipation, &group));
+        assert!(is_authorized(&co_admin, Action::EditUser, &group));
+        assert!(is_authorized(&co_admin, Action::AdjustParticipationTime, &group));
+        assert!(!is_authorized(&co_admin, Action::DeleteGroup, &group));
+        assert!(!is_authorized(&co_admin, Action::ManageGroupAdmins, &group));
+        assert!(!is_authorized(&co_admin, Action::RevokeSessions, &group));
+        assert!(!is_authorized(&co_admin, Action::ViewGroup, &other_group));
+
+        let mut not_logged_in = session(1, Vec::new());
+        not_logged_in.password = None;
+        assert!(!is_authorized(&not_logged_in, Action::ViewGroup, &group));
+
+        let support = session(3, vec![Role::Support]);
+        assert!(is_authorized(&support, Action::SearchUsers, &Resource::Site));
+        assert!(is_authorized(&support, Action::ViewParticipation, &other_group));
+        assert!(is_authorized(&support, Action::DeleteParticipation, &other_group));
+        assert!(!is_authorized(&support, Action::DeleteProtected, &Resource::Site));
+        assert!(!is_authorized(&support, Action::AdjustParticipationTime, &other_group));
+        assert!(is_authorized(&support, Action::RevokeSessions, &other_group));
+        assert!(!is_authorized(&support, Action::ViewAuditLog, &Resource::Site));
+        assert!(!is_authorized(&support, Action::ViewReports, &Resource::Site));
+
+        let contest_manager = session(3, vec![Role::ContestManager]);
+        assert!(is_authorized(&contest_manager, Action::ViewReports, &Resource::Site));
+        assert!(!is_authorized(&contest_manager, Action::SearchUsers, &Resource::Site));
+
+        let auditor = session(3, vec![Role::Auditor]);
+        assert!(is_authorized(&auditor, Action::ViewAuditLog, &Resource::Site));
+        assert!(is_authorized(&auditor, Action::ViewReports, &Resource::Site));
+        assert!(!is_authorized(&auditor, Action::EditUser, &group));
+
+        let mut coordinator = session(3, vec![Role::SchoolCoordinator]);
+        coordinator.oauth_foreign_id = Some("7/42".to_string());
+        assert!(is_authorized(&coordinator, Action::ViewParticipation, &group));
+        assert!(!is_authorized(&coordinator, Action::ViewParticipation, &other_group));
+        assert!(!is_authorized(&coordinator, Action::EditUser, &group));
+        assert!(!is_authorized(&coordinator, Action::ViewGroup, &Resource::User { id: 5, school_id: None }));
+
+        let mut admin = session(4, Vec::new());
+        admin.is_admin = Some(true);
+        assert!(is_authorized(&admin, Action::ManageRoles, &Resource::Site));
+        assert!(is_authorized(&admin, Action::DeleteProtected, &Resource::Site));
+    }
+
+    #[test]
+    fn role_names_roundtrip() {
+        for &role in Role::ALL.iter() {
+            assert_eq!(Role::from_name(role.name()), Some(role));
+        }
+        assert_eq!(Role::from_name("admin"), None);
+    }
+}
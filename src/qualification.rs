@@ -0,0 +1,226 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Evaluation of the qualification rules that decide whether a participant may start a contest.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json;
+
+use db_conn::MedalConnection;
+use db_objects::{Contest, RankingParticipant, SessionUser};
+use i18n;
+use ranking::{self, RankingFilter, TieBreak};
+
+/// Time for which the ranks of a preceding contest are reused by `top_per_grade` rules
+const GRADE_RANKS_LIFETIME: Duration = Duration::from_secs(300);
+
+/// A rule of the `qualification` list in `contest.yaml`. A participant fulfills the rule if they participated in the
+/// preceding `contest` and meet every threshold that is set. Fulfilling one of the rules of a contest is sufficient.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QualificationRule {
+    /// File name of the preceding contest, relative to the directory of the contest declaring the rule
+    pub contest: String,
+    /// Minimal share of the stars in the preceding contest, in percent
+    pub min_percentage: Option<i32>,
+    /// Number of best participants per grade in the preceding contest that qualify
+    pub top_per_grade: Option<usize>,
+    /// Annotation of the participation in the preceding contest, as set by the admission CSV upload
+    pub annotation: Option<String>,
+}
+
+impl QualificationRule {
+    fn participation_only(contest: &str) -> Self {
+        QualificationRule { contest: contest.to_string(), min_percentage: None, top_per_grade: None, annotation: None }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct QualificationCheck {
+    pub fulfilled: bool,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Qualification {
+    pub is_qualified: bool,
+    pub checks: Vec<QualificationCheck>,
+}
+
+/// Ranks of the participants of a contest within their grade, by grade and user id
+struct GradeRanks {
+    computed: Instant,
+    ranks: BTreeMap<(i32, i32), usize>,
+}
+
+/// Ranks of the preceding contests by contest id. Ranking a contest needs the results of all of its participants,
+/// which is too expensive to be done on every page view of every participant of the follow-up contest.
+///
+/// The ranks of a contest are dropped by `invalidate_grade_ranks` whenever a grade of the contest changes. Other
+/// changes, e.g. participants removed by an admin, show up after at most `GRADE_RANKS_LIFETIME`.
+///
+/// Every contest has its own lock, so that computing the ranks of one contest does not hold up the lookups of others.
+static GRADE_RANKS: Mutex<BTreeMap<i32, Arc<Mutex<Option<GradeRanks>>>>> = Mutex::new(BTreeMap::new());
+
+fn compute_grade_ranks(participants: Vec<RankingParticipant>, tie_breaks: &[TieBreak]) -> BTreeMap<(i32, i32), usize> {
+    let mut grades: BTreeMap<i32, Vec<RankingParticipant>> = BTreeMap::new();
+    for participant in participants {
+        grades.entry(participant.user.grade).or_default().push(participant);
+    }
+
+    let mut ranks = BTreeMap::new();
+    for (grade, participants) in grades {
+        for entry in ranking::compute_ranking(participants, tie_breaks, &RankingFilter::default()) {
+            ranks.insert((grade, entry.participant.user.id), entry.rank);
+        }
+    }
+    ranks
+}
+
+/// Rank of the user with id `user_id` within the grade `grade` in `contest`. The ranks of the contest are computed at
+/// most once every `GRADE_RANKS_LIFETIME`.
+fn grade_rank<T: MedalConnection>(conn: &T, contest: &Contest, grade: i32, user_id: i32) -> Option<usize> {
+    let contest_id = contest.id.unwrap();
+
+    let contest_ranks = GRADE_RANKS.lock().unwrap_or_else(|e| e.into_inner()).entry(contest_id).or_default().clone();
+
+    // The lock of the contest is kept while its ranks are computed, so that participants checking their qualification
+    // at the same time wait for the ranks instead of computing them again
+    let mut grade_ranks = contest_ranks.lock().unwrap_or_else(|e| e.into_inner());
+    let outdated = match *grade_ranks {
+        Some(ref grade_ranks) => grade_ranks.computed.elapsed() > GRADE_RANKS_LIFETIME,
+        None => true,
+    };
+    if outdated {
+        let (_, participants) = conn.get_contest_ranking_data(contest_id);
+        let ranks = compute_grade_ranks(participants, &TieBreak::of_contest(contest));
+        *grade_ranks = Some(GradeRanks { computed: Instant::now(), ranks });
+    }
+
+    grade_ranks.as_ref().unwrap().ranks.get(&(grade, user_id)).copied()
+}
+
+/// Drop the cached ranks of the contest with id `contest_id`, so that they are computed again on the next lookup.
+pub fn invalidate_grade_ranks(contest_id: i32) {
+    GRADE_RANKS.lock().unwrap_or_else(|e| e.into_inner()).remove(&contest_id);
+}
+
+/// Returns the rules of `contest`. Contests that only list `requires_contest` get one rule per listed contest that
+/// just requires a participation.
+fn get_rules(contest: &Contest) -> Option<Result<Vec<QualificationRule>, serde_json::Error>> {
+    if let Some(ref rules) = contest.qualification {
+        // Produced by `serde_json::to_string` in contestreader_yaml.rs
+        return Some(serde_json::from_str(rules));
+    }
+
+    // Produced by `config.requires_contest.map(|list| list.join(",")),` in contestreader_yaml.rs
+    let required_contests = contest.requires_contest.as_ref()?.split(',');
+    Some(Ok(required_contests.map(QualificationRule::participation_only).collect()))
+}
+
+fn check_rule<T: MedalConnection>(conn: &T, session: &SessionUser, location: &str, rule: &QualificationRule)
+                                  -> QualificationCheck {
+    let required_contest =
+        conn.get_contest_id_by_file(location, &rule.contest).and_then(|id| conn.get_contest_by_id_complete(id));
+    let required_contest = match required_contest {
+        Some(required_contest) => required_contest,
+        None => {
+            let reason = i18n::translate_with("qualification-unknown-contest", &[("contest", &rule.contest)]);
+            return QualificationCheck { fulfilled: false, reasons: vec![reason] };
+        }
+    };
+    let contest_id = required_contest.id.unwrap();
+
+    let contest_args = [("contest", required_contest.name.as_str())];
+    if conn.get_participation(session.id, contest_id).is_none() {
+        let reason = i18n::translate_with("qualification-not-participated", &contest_args);
+        return QualificationCheck { fulfilled: false, reasons: vec![reason] };
+    }
+
+    let mut fulfilled = true;
+    let mut reasons = vec![i18n::translate_with("qualification-participated", &contest_args)];
+
+    if let Some(min_percentage) = rule.min_percentage {
+        let grades = match session.session_token {
+            Some(ref session_token) => conn.get_contest_user_grades(session_token, contest_id),
+            None => Vec::new(),
+        };
+        let total_points: i32 = grades.iter().filter_map(|grade| grade.grade).sum();
+        let max_total_points: i32 =
+            required_contest.taskgroups
+                            .iter()
+                            .map(|taskgroup| taskgroup.tasks.iter().map(|task| task.stars).max().unwrap_or(0))
+                            .sum();
+        let percentage = if max_total_points > 0 { (total_points * 100) / max_total_points } else { 0 };
+
+        let args: [(&str, &str); 2] =
+            [("percentage", &percentage.to_string()), ("required", &min_percentage.to_string())];
+        if percentage >= min_percentage {
+            reasons.push(i18n::translate_with("qualification-percentage-reached", &args));
+        } else {
+            fulfilled = false;
+            reasons.push(i18n::translate_with("qualification-percentage-missed", &args));
+        }
+    }
+
+    if let Some(top_per_grade) = rule.top_per_grade {
+        match grade_rank(conn, &required_contest, session.grade, session.id) {
+            Some(rank) => {
+                let args: [(&str, &str); 2] = [("rank", &rank.to_string()), ("top", &top_per_grade.to_string())];
+                if rank <= top_per_grade {
+                    reasons.push(i18n::translate_with("qualification-rank-reached", &args));
+                } else {
+                    fulfilled = false;
+                    reasons.push(i18n::translate_with("qualification-rank-missed", &args));
+                }
+            }
+            None => {
+                fulfilled = false;
+                reasons.push(i18n::translate("qualification-not-ranked"))
+            }
+        }
+    }
+
+    if let Some(ref annotation) = rule.annotation {
+        if conn.get_participation_annotation(session.id, contest_id).as_ref() == Some(annotation) {
+            reasons.push(i18n::translate("qualification-admitted"));
+        } else {
+            fulfilled = false;
+            reasons.push(i18n::translate("qualification-not-admitted"));
+        }
+    }
+
+    QualificationCheck { fulfilled, reasons }
+}
+
+/// Check whether the user of `session` qualifies for `contest`. Returns `None` if the contest does not have any
+/// qualification requirements.
+pub fn check_qualification<T: MedalConnection>(conn: &T, session: &SessionUser, contest: &Contest)
+                                               -> Option<Qualification> {
+    let rules = match get_rules(contest)? {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("Qualification rules of contest {} could not be parsed: {}", contest.id.unwrap_or(0), e);
+            let reason = i18n::translate("qualification-invalid-rules");
+            let check = QualificationCheck { fulfilled: false, reasons: vec![reason] };
+            return Some(Qualification { is_qualified: false, checks: vec![check] });
+        }
+    };
+    let checks: Vec<QualificationCheck> =
+        rules.iter().map(|rule| check_rule(conn, session, &contest.location, rule)).collect();
+
+    Some(Qualification { is_qualified: checks.iter().any(|check| check.fulfilled), checks })
+}
@@ -0,0 +1,160 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Computation of contest rankings from the data returned by `MedalConnection::get_contest_ranking_data`.
+
+use std::cmp::Ordering;
+
+use db_objects::{Contest, RankingParticipant};
+
+/// Rules to order participants with the same total score. They are declared as the `tie_breaking` list in
+/// `contest.yaml` and are applied in the order given there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TieBreak {
+    /// The participant who reached their final score first ranks higher
+    EarliestSubmission,
+    /// The participant with fewer submissions ranks higher
+    FewestAttempts,
+}
+
+impl TieBreak {
+    pub fn from_name(name: &str) -> Option<TieBreak> {
+        match name {
+            "earliest_submission" => Some(TieBreak::EarliestSubmission),
+            "fewest_attempts" => Some(TieBreak::FewestAttempts),
+            _ => None,
+        }
+    }
+
+    /// Parse the comma separated list stored in `Contest::tie_breaking`. Unknown names are skipped.
+    pub fn parse_list(list: &str) -> Vec<TieBreak> { list.split(',').filter_map(TieBreak::from_name).collect() }
+
+    pub fn of_contest(contest: &Contest) -> Vec<TieBreak> {
+        contest.tie_breaking.as_ref().map(|list| TieBreak::parse_list(list)).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RankingFilter {
+    pub grade: Option<i32>,
+    pub group_id: Option<i32>,
+    pub school_id: Option<String>,
+    pub sex: Option<i32>,
+}
+
+impl RankingFilter {
+    pub fn matches(&self, participant: &RankingParticipant) -> bool {
+        (self.grade.is_none() || self.grade == Some(participant.user.grade))
+        && (self.group_id.is_none() || self.group_id == participant.group_id)
+        && (self.school_id.is_none() || self.school_id == participant.school_id)
+        && (self.sex.is_none() || self.sex == Some(participant.sex.unwrap_or(0)))
+    }
+}
+
+pub struct RankingEntry {
+    /// Participants that cannot be separated by score and tie breaking rules share the same rank
+    pub rank: usize,
+    pub total: i32,
+    pub participant: RankingParticipant,
+}
+
+fn total_score(participant: &RankingParticipant) -> i32 { participant.grades.iter().filter_map(|g| *g).sum() }
+
+fn compare(a: &RankingEntry, b: &RankingEntry, tie_breaks: &[TieBreak]) -> Ordering {
+    let mut ordering = b.total.cmp(&a.total);
+    for tie_break in tie_breaks {
+        ordering = ordering.then_with(|| match tie_break {
+                               TieBreak::EarliestSubmission => {
+                                   match (a.participant.final_score_reached, b.participant.final_score_reached) {
+                                       (Some(a), Some(b)) => a.cmp(&b),
+                                       (Some(_), None) => Ordering::Less,
+                                       (None, Some(_)) => Ordering::Greater,
+                                       (None, None) => Ordering::Equal,
+                                   }
+                               }
+                               TieBreak::FewestAttempts => a.participant.attempts.cmp(&b.participant.attempts),
+                           });
+    }
+    ordering
+}
+
+/// Rank all participants matching `filter` by their total score, best first. Ranks are assigned after filtering,
+/// so filtering by grade yields the ranking within that grade.
+pub fn compute_ranking(participants: Vec<RankingParticipant>, tie_breaks: &[TieBreak], filter: &RankingFilter)
+                       -> Vec<RankingEntry> {
+    let mut entries: Vec<RankingEntry> =
+        participants.into_iter()
+                    .filter(|participant| filter.matches(participant))
+                    .map(|participant| RankingEntry { rank: 0, total: total_score(&participant), participant })
+                    .collect();
+
+    entries.sort_by(|a, b| compare(a, b, tie_breaks).then_with(|| a.participant.user.id.cmp(&b.participant.user.id)));
+
+    for i in 0..entries.len() {
+        entries[i].rank = if i > 0 && compare(&entries[i - 1], &entries[i], tie_breaks) == Ordering::Equal {
+            entries[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_participant(id: i32, grades: Vec<Option<i32>>, attempts: i32, reached: Option<i64>) -> RankingParticipant {
+        use db_objects::UserInfo;
+
+        RankingParticipant { user: UserInfo { id, grade: 5 + id % 2, ..Default::default() },
+                             sex: None,
+                             group_id: None,
+                             group_name: None,
+                             group_admin: None,
+                             school_id: None,
+                             grades,
+                             attempts,
+                             final_score_reached: reached.map(|sec| ::time::Timespec::new(sec, 0)) }
+    }
+
+    #[test]
+    fn ranking_tie_breaking() {
+        let participants = vec![test_participant(1, vec![Some(3), Some(2)], 4, Some(200)),
+                                test_participant(2, vec![Some(4), Some(1)], 2, Some(300)),
+                                test_participant(3, vec![Some(5), Some(4)], 9, Some(500)),
+                                test_participant(4, vec![Some(2), Some(3)], 4, Some(100)),
+                                test_participant(5, vec![None, None], 0, None)];
+
+        let rank_of = |entries: &[RankingEntry]| -> Vec<(i32, usize)> {
+            entries.iter().map(|e| (e.participant.user.id, e.rank)).collect()
+        };
+
+        let ranking = compute_ranking(participants.clone(), &[], &RankingFilter::default());
+        assert_eq!(rank_of(&ranking), vec![(3, 1), (1, 2), (2, 2), (4, 2), (5, 5)]);
+        assert_eq!(ranking[0].total, 9);
+
+        let ranking = compute_ranking(participants.clone(), &[TieBreak::EarliestSubmission], &RankingFilter::default());
+        assert_eq!(rank_of(&ranking), vec![(3, 1), (4, 2), (1, 3), (2, 4), (5, 5)]);
+
+        let ranking = compute_ranking(participants.clone(),
+                                      &TieBreak::parse_list("fewest_attempts,earliest_submission"),
+                                      &RankingFilter::default());
+        assert_eq!(rank_of(&ranking), vec![(3, 1), (2, 2), (4, 3), (1, 4), (5, 5)]);
+
+        let ranking = compute_ranking(participants, &[], &RankingFilter { grade: Some(5), ..Default::default() });
+        assert_eq!(rank_of(&ranking), vec![(2, 1), (4, 1)]);
+    }
+}
@@ -0,0 +1,191 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Platform-wide statistics for the admin area, e.g. the yearly participation numbers.
+//!
+//! A report only contains aggregated numbers and no personal data. It can be exported in the formats of the contest
+//! export, with one line per number.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use db_conn::MedalConnection;
+use export::ExportFormat;
+use grades;
+use i18n;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ContestCount {
+    pub contest_id: i32,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LabelCount {
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Report {
+    /// First day of the report (UTC, as all dates of the report)
+    pub from: String,
+    /// Last day of the report (inclusive)
+    pub until: String,
+    pub participations: i64,
+    pub participations_per_contest: Vec<ContestCount>,
+    pub new_accounts_per_day: Vec<LabelCount>,
+    /// Only hours with submissions are listed
+    pub submissions_per_hour: Vec<LabelCount>,
+    pub participations_by_oauth_provider: Vec<LabelCount>,
+    pub participations_by_grade: Vec<LabelCount>,
+    pub participations_by_sex: Vec<LabelCount>,
+    /// Number of teachers with participating groups
+    pub teachers: usize,
+    /// Number of schools of the teachers with participating groups. Only schools known from PMS logins are counted.
+    pub schools: usize,
+}
+
+fn grade_name(grade: i32) -> String { grades::scale().name(grade) }
+
+fn sex_name(sex: Option<i32>) -> String {
+    i18n::translate(match sex {
+                        Some(1) => "sex-male",
+                        Some(2) => "sex-female",
+                        Some(3) => "sex-diverse",
+                        Some(4) => "sex-other",
+                        _ => "sex-not-stated",
+                    })
+}
+
+fn label_counts<K>(counts: impl IntoIterator<Item = (K, i64)>, label: impl Fn(K) -> String) -> Vec<LabelCount> {
+    counts.into_iter().map(|(key, count)| LabelCount { label: label(key), count }).collect()
+}
+
+fn count_by<K: Ord>(counts: &mut BTreeMap<K, i64>, key: K, count: i64) { *counts.entry(key).or_insert(0) += count; }
+
+fn format_date(date: time::Timespec, format: &str) -> String { time::strftime(format, &time::at_utc(date)).unwrap() }
+
+/// Collect the report of the time range from `from` (inclusive) to `until` (exclusive).
+pub fn create_report<T: MedalConnection>(conn: &T, from: time::Timespec, until: time::Timespec) -> Report {
+    let participations_per_contest: Vec<ContestCount> =
+        conn.get_report_participations_per_contest(from, until)
+            .into_iter()
+            .map(|(contest_id, name, count)| ContestCount { contest_id, name, count })
+            .collect();
+
+    let mut by_oauth_provider = BTreeMap::new();
+    let mut by_grade = BTreeMap::new();
+    let mut by_sex = BTreeMap::new();
+    for (oauth_provider, grade, sex, count) in conn.get_report_participations_by_participant(from, until) {
+        count_by(&mut by_oauth_provider, oauth_provider, count);
+        count_by(&mut by_grade, grade, count);
+        // "Not stated" and unknown are the same for the report
+        count_by(&mut by_sex, sex.filter(|&sex| sex != 0), count);
+    }
+
+    let new_accounts_per_day = conn.get_report_account_creations_per_day(from, until);
+    let submissions_per_hour = conn.get_report_submissions_per_hour(from, until);
+
+    let teachers = conn.get_report_teachers(from, until);
+    let schools: BTreeSet<String> =
+        teachers.iter()
+                .filter_map(|(_, oauth_foreign_id)| oauth_foreign_id.as_ref()?.split('/').nth(1).map(str::to_owned))
+                .collect();
+
+    Report { from: format_date(from, "%F"),
+             until: format_date(until - time::Duration::days(1), "%F"),
+             participations: participations_per_contest.iter().map(|c| c.count).sum(),
+             participations_per_contest,
+             new_accounts_per_day: label_counts(new_accounts_per_day, |date| date),
+             submissions_per_hour: label_counts(submissions_per_hour, |hour| hour),
+             participations_by_oauth_provider: label_counts(by_oauth_provider, |provider| {
+                 provider.unwrap_or_else(|| i18n::translate("report-without-oauth"))
+             }),
+             participations_by_grade: label_counts(by_grade, grade_name),
+             participations_by_sex: label_counts(by_sex, sex_name),
+             teachers: teachers.len(),
+             schools: schools.len() }
+}
+
+/// The numbers of the report as section, label and value, in the order they are shown
+fn report_lines(report: &Report) -> Vec<(&'static str, String, i64)> {
+    let mut lines = vec![("participations", "total".to_string(), report.participations),
+                         ("teachers", "total".to_string(), report.teachers as i64),
+                         ("schools", "total".to_string(), report.schools as i64)];
+    for c in &report.participations_per_contest {
+        lines.push(("participations_per_contest", format!("{} ({})", c.name, c.contest_id), c.count));
+    }
+    for &(section, counts) in &[("participations_by_oauth_provider", &report.participations_by_oauth_provider),
+                                ("participations_by_grade", &report.participations_by_grade),
+                                ("participations_by_sex", &report.participations_by_sex),
+                                ("new_accounts_per_day", &report.new_accounts_per_day),
+                                ("submissions_per_hour", &report.submissions_per_hour)]
+    {
+        lines.extend(counts.iter().map(|c| (section, c.label.clone(), c.count)));
+    }
+    lines
+}
+
+/// Write `report` to `out` with one line per number.
+pub fn write_report(report: &Report, format: ExportFormat, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        ExportFormat::Csv | ExportFormat::CsvBom => {
+            let mut builder = csv::WriterBuilder::new();
+            if format == ExportFormat::CsvBom {
+                out.write_all("\u{feff}".as_bytes())?;
+                builder.delimiter(b';').terminator(csv::Terminator::CRLF);
+            }
+            let mut wtr = builder.from_writer(out);
+            wtr.write_record(["from", "until", "section", "label", "value"])?;
+            for (section, label, value) in report_lines(report) {
+                wtr.write_record([&report.from, &report.until, section, &label, &value.to_string()])?;
+            }
+            wtr.flush()
+        }
+        ExportFormat::JsonLines => {
+            #[derive(Serialize)]
+            struct ReportLine<'a> {
+                from: &'a str,
+                until: &'a str,
+                section: &'a str,
+                label: String,
+                value: i64,
+            }
+
+            for (section, label, value) in report_lines(report) {
+                let line = ReportLine { from: &report.from, until: &report.until, section, label, value };
+                serde_json::to_writer(&mut *out, &line).map_err(io::Error::from)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_of_grades_and_sexes() {
+        assert_eq!(grade_name(5), "5");
+        assert_eq!(grade_name(12), "12 (G8)");
+        assert_eq!(grade_name(113), "13 (G9)");
+        assert_eq!(grade_name(255), "Kein Schüler mehr");
+        assert_eq!(sex_name(Some(2)), "weiblich");
+        assert_eq!(sex_name(None), "Keine Angabe");
+    }
+}
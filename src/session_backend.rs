@@ -0,0 +1,226 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Stores for the web session, selected with `session_backend` in the configuration.
+//!
+//! With signed cookies, the whole session is kept by the browser. The database and Redis stores only send a random
+//! session id to the browser and keep the session on the server, so that the sessions of a user can be removed when the
+//! user is logged out on all devices.
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use iron::prelude::*;
+#[cfg(feature = "redis")]
+use iron_sessionstorage::backends::RedisBackend;
+use iron_sessionstorage::cookie::Cookie;
+use iron_sessionstorage::{RawSession, SessionBackend};
+use persistent::Read;
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+
+use db_conn::MedalConnection;
+use db_pool::ConnectionPool;
+use webfw_iron::SharedDatabasePool;
+
+const COOKIE_NAME: &str = "medal_session_id";
+
+/// Key of the session token in the web session
+pub const SESSION_TOKEN_KEY: &str = "medal_session";
+
+/// The configured session store, kept to remove the sessions of users that are logged out on all devices
+#[derive(Clone)]
+pub enum SessionStore {
+    SignedCookie,
+    Database,
+    #[cfg(feature = "redis")]
+    Redis(RedisBackend),
+}
+
+/// Remove all web sessions containing the revoked `session_token` from the session store.
+pub fn revoke_sessions<T: MedalConnection>(conn: &T, store: &SessionStore, session_token: &str) {
+    match store {
+        // Signed cookies can not be revoked. The session token in the cookie is invalid nevertheless.
+        SessionStore::SignedCookie => (),
+        SessionStore::Database => conn.remove_web_sessions_with_value(SESSION_TOKEN_KEY, session_token),
+        #[cfg(feature = "redis")]
+        SessionStore::Redis(backend) => {
+            if let Err(e) = backend.revoke(SESSION_TOKEN_KEY, session_token) {
+                println!("Could not revoke sessions in Redis: {}", e);
+            }
+        }
+    }
+}
+
+fn make_web_session_id() -> String { thread_rng().sample_iter(&Alphanumeric).take(32).collect() }
+
+fn web_session_id_from_cookie(req: &Request) -> Option<String> {
+    let cookies = req.headers.get::<iron::headers::Cookie>()?;
+    cookies.iter()
+           .filter_map(|c| Cookie::parse(c.clone()).ok())
+           .find(|c| c.name() == COOKIE_NAME)
+           .map(|c| c.value().to_string())
+}
+
+pub struct DatabaseSession<C>
+    where C: MedalConnection + 'static
+{
+    id: String,
+    /// Id of the web session that has been replaced by a new id and has to be removed
+    replaced_id: Option<String>,
+    values: BTreeMap<String, String>,
+    /// Whether the values or the id have changed since the web session has been loaded
+    dirty: bool,
+    pool: Arc<ConnectionPool<C>>,
+}
+
+impl<C> DatabaseSession<C> where C: MedalConnection + 'static
+{
+    fn renew_id(&mut self) {
+        if self.replaced_id.is_none() {
+            self.replaced_id = Some(self.id.clone());
+        }
+        self.id = make_web_session_id();
+        self.dirty = true;
+    }
+}
+
+impl<C> RawSession for DatabaseSession<C> where C: MedalConnection + 'static
+{
+    fn get_raw(&self, key: &str) -> IronResult<Option<String>> { Ok(self.values.get(key).cloned()) }
+
+    fn set_raw(&mut self, key: &str, value: String) -> IronResult<()> {
+        if self.values.get(key) == Some(&value) {
+            return Ok(());
+        }
+        // A new session token means a login: Use a new web session id, so that a session id planted before the login
+        // can not be used to take over the session
+        if key == SESSION_TOKEN_KEY {
+            self.renew_id();
+        }
+        self.values.insert(key.to_string(), value);
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn clear(&mut self) -> IronResult<()> {
+        self.values.clear();
+        self.renew_id();
+        Ok(())
+    }
+
+    fn write(&self, res: &mut Response) -> IronResult<()> {
+        // Unchanged web sessions are not written again, so that page views do not wait for the database writer
+        if !self.dirty && self.replaced_id.is_none() {
+            return Ok(());
+        }
+
+        {
+            let conn = self.pool.get();
+            if let Some(ref replaced_id) = self.replaced_id {
+                conn.save_web_session(replaced_id, &[]);
+            }
+            let values: Vec<(String, String)> = self.values.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+            conn.save_web_session(&self.id, &values);
+        }
+
+        let cookie = Cookie::build(COOKIE_NAME, self.id.clone()).http_only(true).path("/").finish();
+        if let Some(cookies) = res.headers.get_mut::<iron::headers::SetCookie>() {
+            cookies.push(format!("{}", cookie));
+            return Ok(());
+        }
+        res.headers.set(iron::headers::SetCookie(vec![format!("{}", cookie)]));
+        Ok(())
+    }
+}
+
+/// Keeps the web sessions in the medal database
+pub struct DatabaseSessionBackend<C> {
+    // `fn() -> C` keeps the backend `Sync` even if the connection is not
+    connection_type: PhantomData<fn() -> C>,
+}
+
+impl<C> DatabaseSessionBackend<C> {
+    pub fn new() -> Self { DatabaseSessionBackend { connection_type: PhantomData } }
+}
+
+impl<C> SessionBackend for DatabaseSessionBackend<C> where C: MedalConnection + std::marker::Send + 'static
+{
+    type S = DatabaseSession<C>;
+
+    fn from_request(&self, req: &mut Request) -> Self::S {
+        let pool = req.get::<Read<SharedDatabasePool<C>>>().unwrap();
+
+        let existing_id = web_session_id_from_cookie(req);
+
+        let values: BTreeMap<String, String> = match existing_id {
+            Some(ref id) => pool.get().get_web_session(id).into_iter().collect(),
+            None => BTreeMap::new(),
+        };
+        // Only accept ids of existing web sessions
+        let id = match existing_id {
+            Some(id) if !values.is_empty() => id,
+            _ => make_web_session_id(),
+        };
+
+        DatabaseSession { id, replaced_id: None, values, dirty: false, pool }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use config::Config;
+    use db_apply_migrations;
+
+    fn new_pool() -> Arc<ConnectionPool<rusqlite::Connection>> {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        db_apply_migrations::test(&mut conn);
+        Arc::new(ConnectionPool::open(conn, &Config::default()))
+    }
+
+    fn load_session(pool: &Arc<ConnectionPool<rusqlite::Connection>>, id: &str)
+                    -> DatabaseSession<rusqlite::Connection> {
+        let values = pool.get().get_web_session(id).into_iter().collect();
+        DatabaseSession { id: id.to_string(), replaced_id: None, values, dirty: false, pool: pool.clone() }
+    }
+
+    #[test]
+    fn login_from_web_session_without_token_renews_id() {
+        let pool = new_pool();
+        pool.get().save_web_session("planted", &[("language".to_string(), "de".to_string())]);
+
+        let mut session = load_session(&pool, "planted");
+        session.set_raw(SESSION_TOKEN_KEY, "token".to_string()).unwrap();
+        assert_ne!(session.id, "planted");
+        session.write(&mut Response::new()).unwrap();
+
+        assert!(pool.get().get_web_session("planted").is_empty());
+        assert_eq!(pool.get().get_web_session(&session.id).len(), 2);
+    }
+
+    #[test]
+    fn unchanged_web_session_is_not_written() {
+        let pool = new_pool();
+        pool.get().save_web_session("unchanged", &[(SESSION_TOKEN_KEY.to_string(), "token".to_string())]);
+
+        let mut session = load_session(&pool, "unchanged");
+        session.set_raw(SESSION_TOKEN_KEY, "token".to_string()).unwrap();
+        assert_eq!(session.id, "unchanged");
+        let mut response = Response::new();
+        session.write(&mut response).unwrap();
+        assert!(response.headers.get::<iron::headers::SetCookie>().is_none());
+    }
+}
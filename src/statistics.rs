@@ -0,0 +1,178 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                         *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+//! Statistics of the results of a group in a contest, computed from the grades returned by
+//! `MedalConnection::get_contest_groups_grades` and the dates returned by `MedalConnection::get_first_submission_dates`.
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TaskgroupStatistics {
+    pub name: String,
+    pub max_stars: i32,
+    /// `distribution[k]` is the number of students with exactly `k` stars. Students without a submission count as
+    /// having 0 stars.
+    pub distribution: Vec<usize>,
+    /// Number of students without any submission
+    pub not_attempted: usize,
+    /// `reached_percent[k - 1]` is the share of students (in percent) with at least `k` stars
+    pub reached_percent: Vec<usize>,
+    pub average_stars: f64,
+    /// Average number of seconds from the start of the participation to the first submission of the students who
+    /// submitted something
+    pub average_secs_to_first_submission: Option<i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GroupStatistics {
+    pub n_students: usize,
+    pub taskgroups: Vec<TaskgroupStatistics>,
+    /// Average number of seconds from the start of the participation to the first submission to any task
+    pub average_secs_to_first_submission: Option<i64>,
+    /// Index of the taskgroup in which the students reached the lowest share of the possible stars
+    pub hardest_taskgroup: Option<usize>,
+}
+
+fn average(values: &[i64]) -> Option<i64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<i64>() / values.len() as i64)
+    }
+}
+
+fn percent(count: usize, total: usize) -> usize { (count * 100 + total / 2).checked_div(total).unwrap_or(0) }
+
+/// Compute the statistics of a group.
+///
+/// `taskgroups` are the names and the maximal number of stars of the taskgroups, `grades` contains for every student
+/// the grades in the taskgroups in the same order. `first_submissions` lists student id, index of the taskgroup and the
+/// seconds from the start of the participation to the first submission to this taskgroup.
+pub fn group_statistics(taskgroups: &[(String, i32)], grades: &[Vec<Option<i32>>],
+                        first_submissions: &[(i32, usize, i64)])
+                        -> GroupStatistics {
+    let n_students = grades.len();
+
+    let mut first_submission_secs: Vec<Vec<i64>> = vec![Vec::new(); taskgroups.len()];
+    let mut first_submission_per_student: BTreeMap<i32, i64> = BTreeMap::new();
+    for &(student, index, secs) in first_submissions {
+        if let Some(secs_of_taskgroup) = first_submission_secs.get_mut(index) {
+            secs_of_taskgroup.push(secs);
+        }
+        let entry = first_submission_per_student.entry(student).or_insert(secs);
+        *entry = (*entry).min(secs);
+    }
+
+    let taskgroup_statistics: Vec<TaskgroupStatistics> =
+        taskgroups.iter()
+                  .enumerate()
+                  .map(|(index, (name, max_stars))| {
+                      let max_stars = (*max_stars).max(0);
+                      let mut distribution = vec![0; max_stars as usize + 1];
+                      let mut not_attempted = 0;
+                      let mut total_stars = 0;
+
+                      for student_grades in grades {
+                          match student_grades.get(index).cloned().unwrap_or(None) {
+                              Some(grade) => {
+                                  let grade = grade.max(0).min(max_stars);
+                                  distribution[grade as usize] += 1;
+                                  total_stars += grade;
+                              }
+                              None => {
+                                  distribution[0] += 1;
+                                  not_attempted += 1;
+                              }
+                          }
+                      }
+
+                      let reached_percent =
+                          (1..=max_stars as usize).map(|stars| percent(distribution[stars..].iter().sum(), n_students))
+                                                  .collect();
+
+                      TaskgroupStatistics { name: name.clone(),
+                                            max_stars,
+                                            distribution,
+                                            not_attempted,
+                                            reached_percent,
+                                            average_stars: if n_students > 0 {
+                                                f64::from(total_stars) / n_students as f64
+                                            } else {
+                                                0.0
+                                            },
+                                            average_secs_to_first_submission: average(&first_submission_secs[index]) }
+                  })
+                  .collect();
+
+    let hardest_taskgroup = if n_students > 0 {
+        taskgroup_statistics.iter()
+                            .enumerate()
+                            .filter(|(_, statistics)| statistics.max_stars > 0)
+                            .map(|(index, statistics)| {
+                                (index, statistics.average_stars / f64::from(statistics.max_stars))
+                            })
+                            .fold(None, |hardest: Option<(usize, f64)>, (index, share)| match hardest {
+                                Some((_, hardest_share)) if hardest_share <= share => hardest,
+                                _ => Some((index, share)),
+                            })
+                            .map(|(index, _)| index)
+    } else {
+        None
+    };
+
+    let first_submission_per_student: Vec<i64> = first_submission_per_student.values().cloned().collect();
+
+    GroupStatistics { n_students,
+                      taskgroups: taskgroup_statistics,
+                      average_secs_to_first_submission: average(&first_submission_per_student),
+                      hardest_taskgroup }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_of_a_small_group() {
+        let taskgroups = vec![("Biber".to_string(), 3), ("Labyrinth".to_string(), 2)];
+        let grades = vec![vec![Some(3), Some(0)], vec![Some(1), None], vec![None, Some(2)], vec![Some(3), Some(1)]];
+        let first_submissions = vec![(1, 0, 60), (1, 1, 300), (2, 0, 120), (3, 1, 30), (4, 0, 90), (4, 1, 600)];
+
+        let statistics = group_statistics(&taskgroups, &grades, &first_submissions);
+
+        assert_eq!(statistics.n_students, 4);
+        assert_eq!(statistics.taskgroups[0].distribution, vec![1, 1, 0, 2]);
+        assert_eq!(statistics.taskgroups[0].not_attempted, 1);
+        assert_eq!(statistics.taskgroups[0].reached_percent, vec![75, 50, 50]);
+        assert_eq!(statistics.taskgroups[0].average_stars, 1.75);
+        assert_eq!(statistics.taskgroups[0].average_secs_to_first_submission, Some(90));
+        assert_eq!(statistics.taskgroups[1].distribution, vec![2, 1, 1]);
+        assert_eq!(statistics.taskgroups[1].reached_percent, vec![50, 25]);
+        assert_eq!(statistics.taskgroups[1].average_secs_to_first_submission, Some(310));
+        // Per student: 60, 120, 30 and 90 seconds
+        assert_eq!(statistics.average_secs_to_first_submission, Some(75));
+        // 1.75 of 3 stars vs. 0.75 of 2 stars
+        assert_eq!(statistics.hardest_taskgroup, Some(1));
+    }
+
+    #[test]
+    fn statistics_without_students() {
+        let statistics = group_statistics(&[("Biber".to_string(), 3)], &[], &[]);
+
+        assert_eq!(statistics.taskgroups[0].distribution, vec![0, 0, 0, 0]);
+        assert_eq!(statistics.taskgroups[0].reached_percent, vec![0, 0, 0]);
+        assert_eq!(statistics.average_secs_to_first_submission, None);
+        assert_eq!(statistics.hardest_taskgroup, None);
+    }
+}
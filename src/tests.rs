@@ -1,6 +1,7 @@
 use super::*;
 
-use db_objects::{Contest, Task, Taskgroup};
+use db_conn::MedalObject;
+use db_objects::{Contest, Group, Task, Taskgroup};
 
 use reqwest::StatusCode;
 use std::path::Path;
@@ -17,6 +18,14 @@ fn addsimpleuser(conn: &mut rusqlite::Connection, username: String, password: St
 fn run<P, F>(p: P, f: F)
     where F: Fn(u16),
           P: Fn(&mut rusqlite::Connection) + std::marker::Send + 'static
+{
+    run_with_config(p, |_| {}, f)
+}
+
+fn run_with_config<P, Q, F>(p: P, q: Q, f: F)
+    where F: Fn(u16),
+          P: Fn(&mut rusqlite::Connection) + std::marker::Send + 'static,
+          Q: Fn(&mut Config) + std::marker::Send + 'static
 {
     use std::sync::mpsc::channel;
     let (start_tx, start_rx) = channel();
@@ -51,6 +60,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         contest.save(&conn);
@@ -78,6 +90,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -111,6 +126,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -144,6 +162,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupRenameName".to_string(), None);
@@ -185,6 +206,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -222,6 +246,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -259,6 +286,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -292,6 +322,9 @@ fn run<P, F>(p: P, f: F)
                                     language: None,
                                     category: None,
                                     standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
                                     tags: Vec::new(),
                                     taskgroups: Vec::new() };
         let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
@@ -311,6 +344,7 @@ fn run<P, F>(p: P, f: F)
 
         config.port = Some(port);
         config.cookie_signing_secret = Some("testtesttesttesttesttesttesttest".to_string());
+        q(&mut config);
         let message = format!("Could not start server on port {}", port);
         let mut srvr = start_server(conn, config).expect(&message);
 
@@ -1795,6 +1829,219 @@ fn check_teacher_can_delete_users_and_groups() {
         })
 }
 
+#[test]
+fn check_audit_log() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+            sim_login_groupcode(&client, port, &groupcode, ("Test", "Student", "1", ""));
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "profile").send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            let params = [("csrf_token", &csrf)];
+            let resp = client.ppost(port, "admin/user/3").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let resp = client.ppost(port, "admin/group/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            // Teachers can not read the audit log
+            let resp = client.pget(port, "admin/audit/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testadm", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/audit/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>delete_user</td>"));
+            assert!(content.contains("<td>delete_group</td>"));
+            assert!(content.contains("<td>Vorname: Test<br>Nachname: Student<br>Logincode: "));
+            assert!(content.contains("<td>Name: Groupname<br>Gruppencode: "));
+
+            let mut resp = client.pget(port, "admin/audit/?user=3").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>delete_user</td>"));
+            assert!(!content.contains("<td>delete_group</td>"));
+
+            let mut resp = client.pget(port, "admin/audit/?group=1").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>delete_user</td>"));
+            assert!(content.contains("<td>delete_group</td>"));
+
+            let mut resp = client.pget(port, "admin/audit/?user=1&until=2000-01-01").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(!content.contains("<td>delete_user</td>"));
+            assert!(content.contains("Keine Einträge gefunden."));
+
+            let mut resp = client.pget(port, "admin/audit/?user=1&from=2000-01-01").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>delete_user</td>"));
+            assert!(content.contains("<td>delete_group</td>"));
+        })
+}
+
+#[test]
+fn check_contest_export() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+            sim_login_groupcode(&client, port, &groupcode, ("Test", "Student", "5", ""));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "67"));
+            sim_participate(&client, port, "1", ("OtherData", "80"));
+
+            // Only admins can export results
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "admin/contest/1/export/download").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testadm", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/contest/1/export").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("name=\"column\" value=\"logincode\""));
+
+            let mut resp = client.pget(port, "admin/contest/1/export/download?format=csv").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.headers().get("Content-Type").unwrap().to_str().unwrap().starts_with("text/csv"));
+            let content = resp.text().unwrap();
+            let mut lines = content.lines();
+            let header = lines.next().unwrap();
+            assert!(header.starts_with("id,username,logincode,"));
+            assert!(header.ends_with(",contest_id,start_date,TaskgroupName"));
+            let row = lines.next().unwrap();
+            assert!(row.starts_with("3,"));
+            assert!(row.contains(",Test,Student,5,"));
+            assert_eq!(lines.next(), None);
+
+            let mut resp = client.pget(port,
+                                       "admin/contest/1/export/download?format=csvbom&column=firstname&column=id\
+                                        &subtasks=1&submissions=1")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.starts_with("\u{feff}id;firstname;TaskgroupName;TaskgroupName (3*);TaskgroupName (4*);\
+                                         TaskgroupName (submissions)\r\n"));
+            assert!(content.ends_with("\r\n3;Test;2;2;;2\r\n"));
+
+            let mut resp = client.pget(port,
+                                       "admin/contest/1/export/download?format=jsonl&column=lastname&subtasks=1\
+                                        &submissions=1")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            let lines: Vec<&str> = content.lines().collect();
+            assert_eq!(lines.len(), 1);
+            let object: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+            assert_eq!(object["lastname"], "Student");
+            assert!(object.get("firstname").is_none());
+            assert_eq!(object["taskgroups"][0]["name"], "TaskgroupName");
+            assert_eq!(object["taskgroups"][0]["tasks"][0]["grade"], 2);
+            assert_eq!(object["taskgroups"][0]["tasks"][1]["grade"], serde_json::Value::Null);
+            assert_eq!(object["taskgroups"][0]["submissions"], 2);
+
+            // Exports are not stored in a public directory anymore
+            let resp = client.pget(port, "export/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        })
+}
+
+#[test]
+fn check_submission_history() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testusr2".to_string(), "testpw2".to_string(), true, false);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+            sim_login_groupcode(&client, port, &groupcode, ("Test", "Student", "5", ""));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("first\nsecond\nthird", "67"));
+            sim_participate(&client, port, "1", ("first\nchanged\nthird", "80"));
+
+            // Students can not use the history
+            let resp = client.pget(port, "admin/user/3/task/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // Teachers can only see the members of their own groups
+            let resp = login(port, &client, "testusr2", "testpw2");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "admin/user/3/task/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/user/3/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("href=\"task/1\""));
+
+            // The two latest submissions are compared by default
+            let mut resp = client.pget(port, "admin/user/3/task/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td><input type=\"radio\" name=\"left\" value=\"1\" checked></td>"));
+            assert!(content.contains("<td><input type=\"radio\" name=\"right\" value=\"2\" checked></td>"));
+            assert!(content.contains("Vergleich von Einsendung 1 und 2"));
+            assert_eq!(content.matches("<tr class=\"diff-unchanged\">").count(), 2);
+            assert_eq!(content.matches("<tr class=\"diff-changed\">").count(), 1);
+            assert!(content.contains("<td>second</td>"));
+            assert!(content.contains("<td>changed</td>"));
+
+            let mut resp = client.pget(port, "admin/user/3/task/1?left=2&right=2").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert_eq!(content.matches("<tr class=\"diff-unchanged\">").count(), 3);
+            assert!(!content.contains("<tr class=\"diff-changed\">"));
+
+            // Only submissions of this user and task can be compared
+            let resp = client.pget(port, "admin/user/3/task/1?left=1&right=99").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        })
+}
+
 #[test]
 fn check_teacher_can_not_delete_protected_users_and_groups() {
     run(|conn| {
@@ -1852,3 +2099,1967 @@ fn check_teacher_can_not_delete_protected_users_and_groups() {
             assert_eq!(resp.status(), StatusCode::FOUND);
         })
 }
+
+#[test]
+fn check_contest_ranking_and_scoreboard() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testusr2".to_string(), "testpw2".to_string(), true, false);
+
+            // ID: 1, saved before the default contests
+            let mut contest = Contest { id: None,
+                                        location: "directory".to_string(),
+                                        filename: "ranking.yaml".to_string(),
+                                        name: "RankingContestName".to_string(),
+                                        duration: 60,
+                                        public: true,
+                                        start: None,
+                                        end: None,
+                                        review_start: None,
+                                        review_end: None,
+                                        min_grade: None,
+                                        max_grade: None,
+                                        positionalnumber: None,
+                                        protected: false,
+                                        requires_login: None,
+                                        requires_contest: None,
+                                        secret: None,
+                                        message: None,
+                                        image: None,
+                                        language: None,
+                                        category: None,
+                                        standalone_task: None,
+                                        tie_breaking: Some("fewest_attempts".to_string()),
+                                        public_scoreboard: Some(true),
+                                        qualification: None,
+                                        tags: Vec::new(),
+                                        taskgroups: Vec::new() };
+            let mut taskgroup = Taskgroup::new("FirstTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir1".to_string(), None, 3)); // ID: 1
+            contest.taskgroups.push(taskgroup);
+            let mut taskgroup = Taskgroup::new("SecondTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir2".to_string(), None, 4)); // ID: 2
+            contest.taskgroups.push(taskgroup);
+            contest.save(conn);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+
+            // 3 + 2 points with two submissions
+            sim_login_groupcode(&client, port, &groupcode, ("Anna", "Alpha", "5", "2"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+            sim_participate(&client, port, "2", ("SomeData", "50"));
+
+            // 3 + 2 points with three submissions
+            sim_login_groupcode(&client, port, &groupcode, ("Bert", "Beta", "6", "1"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "67"));
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+            sim_participate(&client, port, "2", ("SomeData", "50"));
+
+            // 0 points
+            sim_login_groupcode(&client, port, &groupcode, ("Carl", "Gamma", "5", "1"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "0"));
+
+            // Students cannot see the ranking
+            let resp = client.pget(port, "contest/1/ranking/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // The public scoreboard is anonymized and highlights the own row
+            let mut resp = client.pget(port, "contest/1/scoreboard/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(!content.contains("Alpha"));
+            assert!(!content.contains("Beta"));
+            assert!(!content.contains("class=\"own\""));
+            assert!(content.contains("<td>1</td>\n        <td>5</td>"));
+            assert!(content.contains("<td>2</td>\n        <td>5</td>"));
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "contest/1/ranking/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            let pos_anna = content.find("Anna Alpha").expect("Anna not found");
+            let pos_bert = content.find("Bert Beta").expect("Bert not found");
+            let pos_carl = content.find("Carl Gamma").expect("Carl not found");
+            assert!(pos_anna < pos_bert);
+            assert!(pos_bert < pos_carl);
+            assert!(content.contains("<td>Abgaben</td>"));
+
+            let mut resp = client.pget(port, "contest/1/ranking/?grade=5&group=&sex=").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Anna Alpha"));
+            assert!(!content.contains("Bert Beta"));
+            assert!(content.contains("Carl Gamma"));
+
+            let mut resp = client.pget(port, "contest/1/ranking/?sex=1").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(!content.contains("Anna Alpha"));
+            let pos_bert = content.find("Bert Beta").expect("Bert not found");
+            assert!(content[..pos_bert].contains("<td>1</td>"));
+
+            // Other teachers do not see students of foreign groups
+            let resp = login(port, &client, "testusr2", "testpw2");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "contest/1/ranking/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(!content.contains("Anna Alpha"));
+
+            // Scoreboards of other contests are not public
+            let resp = client.pget(port, "contest/2/scoreboard/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        })
+}
+
+fn qualification_test_contest(filename: &str, name: &str, qualification: Option<String>) -> Contest {
+    Contest { id: None,
+              location: "directory".to_string(),
+              filename: filename.to_string(),
+              name: name.to_string(),
+              duration: 60,
+              public: true,
+              start: None,
+              end: None,
+              review_start: None,
+              review_end: None,
+              min_grade: None,
+              max_grade: None,
+              positionalnumber: None,
+              protected: false,
+              requires_login: None,
+              requires_contest: None,
+              secret: None,
+              message: None,
+              image: None,
+              language: None,
+              category: None,
+              standalone_task: None,
+              tie_breaking: None,
+              public_scoreboard: None,
+              qualification,
+              tags: Vec::new(),
+              taskgroups: Vec::new() }
+}
+
+#[test]
+fn check_contest_qualification_rules() {
+    run(|conn| {
+            addsimpleuser(conn, "testadm".to_string(), "testpw1".to_string(), false, true); // ID: 1
+            addsimpleuser(conn, "student1".to_string(), "testpw2".to_string(), false, false); // ID: 2
+            addsimpleuser(conn, "student2".to_string(), "testpw3".to_string(), false, false); // ID: 3
+
+            // ID: 1, saved before the default contests
+            let mut contest = qualification_test_contest("round1.yaml", "RoundOne", None);
+            let mut taskgroup = Taskgroup::new("FirstTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir1".to_string(), None, 3)); // ID: 1
+            contest.taskgroups.push(taskgroup);
+            let mut taskgroup = Taskgroup::new("SecondTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir2".to_string(), None, 4)); // ID: 2
+            contest.taskgroups.push(taskgroup);
+            contest.save(conn);
+
+            // ID: 2
+            let rules = vec![qualification::QualificationRule { contest: "round1.yaml".to_string(),
+                                                                min_percentage: Some(50),
+                                                                top_per_grade: None,
+                                                                annotation: None },
+                             qualification::QualificationRule { contest: "round1.yaml".to_string(),
+                                                                min_percentage: None,
+                                                                top_per_grade: Some(1),
+                                                                annotation: Some("zugelassen".to_string()) }];
+            let mut contest =
+                qualification_test_contest("round2.yaml", "RoundTwo", Some(serde_json::to_string(&rules).unwrap()));
+            let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir1".to_string(), None, 3)); // ID: 3
+            contest.taskgroups.push(taskgroup);
+            contest.save(conn);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "student1", "testpw2");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "contest/2").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Du bist für diesen Wettbewerb nicht qualifiziert."));
+            assert!(content.contains("Du hast nicht am Wettbewerb „RoundOne“ teilgenommen."));
+            assert!(!content.contains("csrf_token"));
+
+            // 3 of 7 stars
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+
+            let mut resp = client.pget(port, "contest/2").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Du bist für diesen Wettbewerb nicht qualifiziert."));
+            assert!(content.contains("Du hast 42 % der Punkte erreicht, benötigt werden aber 50 %."));
+            assert!(content.contains("Du bist in deiner Jahrgangsstufe auf Platz 1, die besten 1 sind qualifiziert."));
+            assert!(content.contains("Für dich ist keine Zulassung vermerkt."));
+
+            let mut resp = client.pget(port, "profile").send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            let params = [("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "contest/2").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // Admit student1 via annotation
+            let resp = login(port, &client, "testadm", "testpw1");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/contest/1/csv").send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = &content[pos + 39..pos + 49];
+            let params = [("csrf_token", csrf), ("admission_data", r#"[["2","zugelassen"]]"#)];
+            let resp = client.ppost(port, "admin/contest/1/csv").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let resp = login(port, &client, "student1", "testpw2");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "contest/2").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Du bist für diesen Wettbewerb qualifiziert:"));
+            assert!(content.contains("Deine Zulassung ist vermerkt."));
+
+            sim_start_contest(&client, port, "2");
+
+            // 4 of 7 stars
+            let resp = login(port, &client, "student2", "testpw3");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "2", ("SomeData", "100"));
+
+            let mut resp = client.pget(port, "contest/2").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Du bist für diesen Wettbewerb qualifiziert:"));
+            assert!(content.contains("Du hast 57 % der Punkte erreicht, benötigt werden 50 %."));
+
+            sim_start_contest(&client, port, "2");
+        })
+}
+
+#[test]
+fn check_submission_validation() {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    db_apply_migrations::test(&mut conn);
+
+    let mut contest = Contest { id: None,
+                                location: "./".to_string(),
+                                filename: "checked.yaml".to_string(),
+                                name: "CheckedContestName".to_string(),
+                                duration: 0,
+                                public: true,
+                                start: None,
+                                end: None,
+                                review_start: None,
+                                review_end: None,
+                                min_grade: None,
+                                max_grade: None,
+                                positionalnumber: None,
+                                protected: false,
+                                requires_login: None,
+                                requires_contest: None,
+                                secret: None,
+                                message: None,
+                                image: None,
+                                language: None,
+                                category: None,
+                                standalone_task: None,
+                                tie_breaking: None,
+                                public_scoreboard: None,
+                                qualification: None,
+                                tags: Vec::new(),
+                                taskgroups: Vec::new() };
+    let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
+    let mut task = Task::new(".".to_string(), None, 4); // ID: 1
+    task.checker = Some("cat".to_string()); // Echoes the submitted value as percentage
+    taskgroup.tasks.push(task);
+    contest.taskgroups.push(taskgroup);
+    contest.save(&conn);
+
+    let session = conn.new_session("validationtoken");
+    let submit = |value: &str, grade: i32| {
+        conn.submit_submission(db_objects::Submission { id: None,
+                                                        user: session.id,
+                                                        task: 1,
+                                                        grade,
+                                                        validated: false,
+                                                        nonvalidated_grade: grade,
+                                                        needs_validation: true,
+                                                        subtask_identifier: None,
+                                                        value: value.to_string(),
+                                                        date: time::get_time() })
+    };
+    let timeout = std::time::Duration::from_secs(10);
+
+    // Claimed 4 stars, but the checker only awards 50%. The claimed grade is not used before the validation.
+    submit("50", 4);
+    let grade = conn.get_taskgroup_user_grade("validationtoken", 1);
+    assert_eq!(grade.grade, None);
+    assert!(!grade.validated);
+
+    assert!(validation::validate_next_submission(&conn, timeout));
+    assert!(!validation::validate_next_submission(&conn, timeout));
+
+    let grade = conn.get_taskgroup_user_grade("validationtoken", 1);
+    assert_eq!(grade.grade, Some(2));
+    assert!(grade.validated);
+
+    // Invalid checker output scores the submission with 0 points. A submission still waiting for validation does not
+    // count either.
+    submit("not a number", 4);
+    submit("no number either", 4);
+    let grade = conn.get_taskgroup_user_grade("validationtoken", 1);
+    assert_eq!(grade.grade, Some(2));
+    assert!(grade.validated);
+
+    assert!(validation::validate_next_submission(&conn, timeout));
+    let grade = conn.get_taskgroup_user_grade("validationtoken", 1);
+    assert_eq!(grade.grade, Some(2));
+    assert!(grade.validated);
+
+    assert!(validation::validate_next_submission(&conn, timeout));
+    assert!(!validation::validate_next_submission(&conn, timeout));
+
+    let grade = conn.get_taskgroup_user_grade("validationtoken", 1);
+    assert_eq!(grade.grade, Some(2));
+    assert!(grade.validated);
+    let mut grades: Vec<i32> = conn.get_all_submissions(session.id, 1, None).iter().map(|s| s.grade).collect();
+    grades.sort();
+    assert_eq!(grades, vec![0, 0, 2]);
+}
+
+#[test]
+#[cfg(unix)]
+fn check_checker_timeout_kills_started_processes() {
+    // The background `sleep` keeps standard output open as long as it runs
+    let started = std::time::Instant::now();
+    let result = validation::run_checker("sleep 30 & sleep 30", ".", "", std::time::Duration::from_millis(200));
+    assert!(matches!(result, Err(validation::CheckerError::Timeout)));
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
+
+/// Let statements fail on `event`, e.g. `INSERT ON grade`, as if the database failed in the middle of an operation
+fn inject_failure(conn: &rusqlite::Connection, event: &str) {
+    let query =
+        format!("CREATE TRIGGER injected_failure BEFORE {} BEGIN SELECT RAISE(ABORT, 'injected failure'); END;", event);
+    conn.execute_batch(&query).unwrap();
+}
+
+fn remove_injected_failure(conn: &rusqlite::Connection) {
+    conn.execute_batch("DROP TRIGGER injected_failure").unwrap();
+}
+
+fn count_rows(conn: &rusqlite::Connection, table: &str) -> i64 {
+    conn.query_row(&format!("SELECT count(*) FROM {}", table), &[], |row| row.get(0)).unwrap()
+}
+
+fn panics<F: FnOnce()>(f: F) -> bool { std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err() }
+
+#[test]
+fn check_transactions_are_rolled_back_on_failure() {
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    db_apply_migrations::test(&mut conn);
+
+    let mut admin = conn.new_session("admintoken");
+    admin.username = Some("admin".to_string());
+    admin.is_admin = Some(true);
+    admin.set_password("adminpw").unwrap();
+    let (admin_id, csrf_token) = (admin.id, admin.csrf_token.clone());
+    conn.save_session(admin);
+
+    // Contest::save fails at the taskgroup after the contest has been written
+    let new_contest = || {
+        let mut contest = Contest { id: None,
+                                    location: "./".to_string(),
+                                    filename: "transaction.yaml".to_string(),
+                                    name: "TransactionContest".to_string(),
+                                    duration: 0,
+                                    public: true,
+                                    start: None,
+                                    end: None,
+                                    review_start: None,
+                                    review_end: None,
+                                    min_grade: None,
+                                    max_grade: None,
+                                    positionalnumber: None,
+                                    protected: false,
+                                    requires_login: None,
+                                    requires_contest: None,
+                                    secret: None,
+                                    message: None,
+                                    image: None,
+                                    language: None,
+                                    category: None,
+                                    standalone_task: None,
+                                    tie_breaking: None,
+                                    public_scoreboard: None,
+                                    qualification: None,
+                                    tags: vec!["tag".to_string()],
+                                    taskgroups: Vec::new() };
+        let mut taskgroup = Taskgroup::new("TaskgroupName".to_string(), None);
+        taskgroup.tasks.push(Task::new("taskdir1".to_string(), None, 3)); // ID: 1
+        contest.taskgroups.push(taskgroup);
+        contest
+    };
+    inject_failure(&conn, "INSERT ON taskgroup");
+    assert!(panics(|| new_contest().save(&conn)));
+    assert_eq!(count_rows(&conn, "contest"), 0);
+    assert_eq!(count_rows(&conn, "contest_tags"), 0);
+    remove_injected_failure(&conn);
+    new_contest().save(&conn);
+    assert_eq!(count_rows(&conn, "contest"), 1);
+
+    // submit_submission fails at the grade after the submission has been written
+    let submission = || db_objects::Submission { id: None,
+                                                 user: admin_id,
+                                                 task: 1,
+                                                 grade: 2,
+                                                 validated: false,
+                                                 nonvalidated_grade: 2,
+                                                 needs_validation: false,
+                                                 subtask_identifier: None,
+                                                 value: "SomeData".to_string(),
+                                                 date: time::get_time() };
+    inject_failure(&conn, "INSERT ON grade");
+    assert!(panics(|| conn.submit_submission(submission())));
+    assert_eq!(count_rows(&conn, "submission"), 0);
+    remove_injected_failure(&conn);
+    conn.submit_submission(submission());
+    assert_eq!(count_rows(&conn, "submission"), 1);
+    assert_eq!(count_rows(&conn, "grade"), 1);
+
+    // create_group_with_users fails at the second student after the group and the first student have been written
+    let new_group = || {
+        let mut group = Group { id: None,
+                                name: "TransactionGroup".to_string(),
+                                groupcode: "gtransaction".to_string(),
+                                tag: String::new(),
+                                admin: admin_id,
+                                members: Vec::new() };
+        for name in &["Anna", "Bert"] {
+            let mut student = db_objects::SessionUser::group_user_stub();
+            student.firstname = Some(name.to_string());
+            student.lastname = Some("Student".to_string());
+            group.members.push(student);
+        }
+        group
+    };
+    inject_failure(&conn, "INSERT ON session WHEN NEW.firstname = 'Bert'");
+    assert!(panics(|| {
+                conn.create_group_with_users(new_group());
+            }));
+    assert_eq!(count_rows(&conn, "usergroup"), 0);
+    assert_eq!(count_rows(&conn, "session"), 1);
+    remove_injected_failure(&conn);
+    let group_id = conn.create_group_with_users(new_group());
+    assert_eq!(count_rows(&conn, "session"), 3);
+
+    // Deleting the group fails after the students have been deleted
+    let audit_log_entries = count_rows(&conn, "audit_log");
+    inject_failure(&conn, "DELETE ON usergroup");
+    assert!(panics(|| {
+                let _ = core::admin_delete_group(&conn, group_id, "admintoken", &csrf_token);
+            }));
+    assert_eq!(count_rows(&conn, "session"), 3);
+    assert_eq!(count_rows(&conn, "audit_log"), audit_log_entries);
+
+    // Removing old users fails at their empty group after the students have been deleted
+    let tomorrow = time::get_time() + time::Duration::days(1);
+    assert!(panics(|| {
+                let _ = conn.remove_old_users_and_groups(tomorrow, None, None);
+            }));
+    assert_eq!(count_rows(&conn, "session"), 3);
+    assert_eq!(count_rows(&conn, "usergroup"), 1);
+    remove_injected_failure(&conn);
+    assert_eq!(conn.remove_old_users_and_groups(tomorrow, None, None), Ok((2, 1, 0, 0)));
+    assert_eq!(count_rows(&conn, "session"), 1);
+
+    // The COMMIT itself fails on a deferred foreign key violation, which leaves the transaction open
+    conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+    assert!(panics(|| {
+                conn.transaction(|| {
+                        conn.execute_batch("PRAGMA defer_foreign_keys = ON;
+                                            INSERT INTO usergroup (name, groupcode, tag, admin)
+                                            VALUES ('Orphans', 'gorphans', '', 1234);")
+                            .unwrap()
+                    })
+            }));
+    assert_eq!(count_rows(&conn, "usergroup"), 0);
+    conn.transaction(|| conn.new_session("aftercommitfailure"));
+    assert_eq!(count_rows(&conn, "session"), 2);
+}
+
+#[test]
+fn check_api() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+        },
+        |port| {
+            let client = reqwest::Client::new();
+
+            // Not logged in
+            let resp = client.pget(port, "api/v1/profile").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let mut resp = client.ppost(port, "api/v1/login")
+                                 .body("{\"username\": \"testusr\", \"password\": \"wrongpw\"}")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(resp.headers().get(reqwest::header::WWW_AUTHENTICATE).unwrap(), "Bearer");
+            let data: serde_json::Value = serde_json::from_str(&resp.text().unwrap()).unwrap();
+            assert_eq!(data["error"], "login_failed");
+
+            let mut resp = client.ppost(port, "api/v1/login")
+                                 .body("{\"username\": \"testusr\", \"password\": \"testpw\"}")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let data: serde_json::Value = serde_json::from_str(&resp.text().unwrap()).unwrap();
+            let session_token = data["session_token"].as_str().unwrap().to_string();
+
+            let mut resp = client.pget(port, "api/v1/profile").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let data: serde_json::Value = serde_json::from_str(&resp.text().unwrap()).unwrap();
+            assert_eq!(data["username"], "testusr");
+            let csrf_token = data["csrf_token"].as_str().unwrap().to_string();
+
+            // Missing and wrong CSRF tokens
+            let resp = client.ppost(port, "api/v1/group/")
+                             .bearer_auth(&session_token)
+                             .body("{\"name\": \"APIGroup\"}")
+                             .send()
+                             .unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let resp = client.ppost(port, "api/v1/group/")
+                             .bearer_auth(&session_token)
+                             .header("X-CSRF-Token", "wrongtoken")
+                             .body("{\"name\": \"APIGroup\"}")
+                             .send()
+                             .unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let resp = client.ppost(port, "api/v1/group/")
+                             .bearer_auth(&session_token)
+                             .header("X-CSRF-Token", csrf_token.as_str())
+                             .body("no json")
+                             .send()
+                             .unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+            let mut resp = client.ppost(port, "api/v1/group/")
+                                 .bearer_auth(&session_token)
+                                 .header("X-CSRF-Token", csrf_token.as_str())
+                                 .body("{\"name\": \"APIGroup\", \"tag\": \"tag\"}")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            let data: serde_json::Value = serde_json::from_str(&resp.text().unwrap()).unwrap();
+            assert_eq!(data["group"]["name"], "APIGroup");
+            let group_id = data["group"]["id"].as_i64().unwrap();
+
+            let mut resp = client.pget(port, "api/v1/group/").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("APIGroup"));
+
+            let resp =
+                client.pget(port, &format!("api/v1/group/{}", group_id)).bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let resp = client.pget(port, "api/v1/group/9999").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+            // Start a contest
+            let mut resp = client.pget(port, "api/v1/contest/").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("PublicContestName"));
+
+            let mut resp =
+                client.pget(port, "api/v1/contest/?visibility=all").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("PublicContestName"));
+
+            let resp =
+                client.pget(port, "api/v1/contest/?visibility=reopened").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+            let mut resp = client.ppost(port, "api/v1/contest/1")
+                                 .bearer_auth(&session_token)
+                                 .header("X-CSRF-Token", csrf_token.as_str())
+                                 .body("{}")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            let data: serde_json::Value = serde_json::from_str(&resp.text().unwrap()).unwrap();
+            assert_eq!(data["contest"]["name"], "PublicContestName");
+
+            let resp = client.ppost(port, "api/v1/logout").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let resp = client.ppost(port, "api/v1/logout")
+                             .bearer_auth(&session_token)
+                             .header("X-CSRF-Token", "wrongtoken")
+                             .send()
+                             .unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let resp = client.pget(port, "api/v1/profile").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let resp = client.ppost(port, "api/v1/logout")
+                             .bearer_auth(&session_token)
+                             .header("X-CSRF-Token", csrf_token.as_str())
+                             .send()
+                             .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let resp = client.pget(port, "api/v1/profile").bearer_auth(&session_token).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        })
+}
+
+fn start_mock_idp() -> iron::Listening {
+    use iron::modifiers::RedirectRaw;
+    use iron::prelude::*;
+    use iron::status;
+    use std::sync::{Arc, Mutex};
+    use urlencoded::{UrlEncodedBody, UrlEncodedQuery};
+
+    fn param(map: &std::collections::HashMap<String, Vec<String>>, key: &str) -> String {
+        map.get(key).map(|v| v[0].clone()).unwrap_or_default()
+    }
+
+    let code_challenge = Arc::new(Mutex::new(String::new()));
+    let code_challenge_token = code_challenge.clone();
+
+    let mut router = router::Router::new();
+    router.get("/.well-known/openid-configuration",
+               |req: &mut Request| {
+                   let base = format!("http://localhost:{}", req.url.port());
+                   let discovery = format!("{{\"authorization_endpoint\": \"{0}/authorize\", \
+                                             \"token_endpoint\": \"{0}/token\", \
+                                             \"userinfo_endpoint\": \"{0}/userinfo\"}}",
+                                           base);
+                   Ok(Response::with((status::Ok, discovery)))
+               },
+               "discovery");
+    router.get("/authorize",
+               move |req: &mut Request| {
+                   let query = req.get_ref::<UrlEncodedQuery>().unwrap();
+                   assert_eq!(param(query, "response_type"), "code");
+                   assert_eq!(param(query, "client_id"), "medalclient");
+                   assert_eq!(param(query, "code_challenge_method"), "S256");
+                   *code_challenge.lock().unwrap() = param(query, "code_challenge");
+
+                   let redirect =
+                       format!("{}?code=testcode&state={}", param(query, "redirect_uri"), param(query, "state"));
+                   Ok(Response::with((status::Found, RedirectRaw(redirect))))
+               },
+               "authorize");
+    router.post("/token",
+                move |req: &mut Request| {
+                    let form = req.get_ref::<UrlEncodedBody>().unwrap().clone();
+                    let verifier_matches = webfw_iron::pkce_challenge(&param(&form, "code_verifier"))
+                                           == *code_challenge_token.lock().unwrap();
+                    if param(&form, "code") == "testcode" && verifier_matches {
+                        Ok(Response::with((status::Ok, "{\"access_token\": \"testaccesstoken\"}")))
+                    } else {
+                        Ok(Response::with((status::BadRequest, "{\"error\": \"invalid_grant\"}")))
+                    }
+                },
+                "token");
+    router.get("/userinfo",
+               |req: &mut Request| {
+                   let authorized = req.headers
+                                       .get_raw("Authorization")
+                                       .map(|v| v[0] == b"Bearer testaccesstoken".to_vec())
+                                       .unwrap_or(false);
+                   if authorized {
+                       Ok(Response::with((status::Ok,
+                                          "{\"sub\": \"idp-user-1\", \"given_name\": \"Olivia\", \
+                                           \"family_name\": \"Oidc\", \"gender\": \"female\", \
+                                           \"roles\": [\"staff\", \"lehrkraft\"]}")))
+                   } else {
+                       Ok(Response::with(status::Unauthorized))
+                   }
+               },
+               "userinfo");
+
+    Iron::new(router).http("localhost:0").unwrap()
+}
+
+#[test]
+fn check_oidc_login() {
+    assert_eq!(webfw_iron::pkce_challenge("medaltestverifier0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJ"),
+               "7aJTNgTZmTVKGjKvEcvQolYOxz7c4zPDHGgcHmScf3s");
+
+    let mut idp = start_mock_idp();
+    let idp_port = idp.socket.port();
+
+    run_with_config(|_| {},
+                    move |config| {
+                        let mut role_mapping = std::collections::BTreeMap::new();
+                        role_mapping.insert("lehrkraft".to_string(), "teacher".to_string());
+
+                        config.self_url = Some(format!("http://localhost:{}", config.port.unwrap()));
+                        config.oauth_providers =
+                            Some(vec![config::OauthProvider { provider_id: "testidp".to_string(),
+                                                              medal_oauth_type: "oidc".to_string(),
+                                                              client_id: "medalclient".to_string(),
+                                                              client_secret: "medalsecret".to_string(),
+                                                              login_link_text: "Test IdP".to_string(),
+                                                              discovery_url:
+                                                                  Some(format!("http://localhost:{}/.well-known/openid-configuration",
+                                                                               idp_port)),
+                                                              claim_mapping:
+                                                                  Some(config::OauthClaimMapping { role:
+                                                                                                       Some("roles".to_string()),
+                                                                                                   ..Default::default() }),
+                                                              role_mapping: Some(role_mapping),
+                                                              ..Default::default() }]);
+                    },
+                    |port| {
+                        let client = reqwest::Client::builder().cookie_store(true)
+                                                               .redirect(reqwest::RedirectPolicy::none())
+                                                               .build()
+                                                               .unwrap();
+
+                        // The login link points to medal, which redirects to the identity provider
+                        let mut resp = client.pget(port, "login").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains(&format!("href=\"http://localhost:{}/oauth/testidp/\"", port)));
+
+                        let resp = client.pget(port, "oauth/testidp/").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let location = resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap();
+                        assert!(location.starts_with(&format!("http://localhost:{}/authorize?", idp_port)));
+                        assert!(location.contains("code_challenge="));
+
+                        // The identity provider redirects back to medal
+                        let resp = client.get(location).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let callback =
+                            resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap().to_string();
+                        assert!(callback.starts_with(&format!("http://localhost:{}/oauth/testidp/?code=", port)));
+
+                        // A forged state is rejected
+                        let forged = format!("http://localhost:{}/oauth/testidp/?code=testcode&state=forged", port);
+                        let resp = client.get(&forged).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+                        // The state has been used up by the forged request, so start over
+                        let resp = client.pget(port, "oauth/testidp/").send().unwrap();
+                        let location = resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap();
+                        let resp = client.get(location).send().unwrap();
+                        let callback =
+                            resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap().to_string();
+
+                        let resp = client.get(&callback).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        // Teachers can access the group management
+                        let mut resp = client.pget(port, "group/").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Gruppe anlegen"));
+
+                        let mut resp = client.pget(port, "profile").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Olivia"));
+                        assert!(content.contains("Oidc"));
+
+                        // Replaying the callback does not work
+                        let resp = client.get(&callback).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+                    });
+
+    idp.close().unwrap();
+}
+
+#[test]
+fn check_contest_rescan() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true);
+
+            let task_dir = std::env::temp_dir().join(format!("medal-rescan-{}", std::process::id()));
+            let contest_dir = task_dir.join("wettbewerb");
+            std::fs::create_dir_all(contest_dir.join("aufgabe")).unwrap();
+            let task_dir_name = format!("{}/", task_dir.display());
+
+            let contest_file = contest_dir.join("runde1.yaml");
+            let contest_yaml =
+                "name: Runde 1\nduration_minutes: 60\npublic_listing: true\ntasks:\n  Aufgabe: aufgabe\n";
+            std::fs::write(&contest_file, contest_yaml).unwrap();
+
+            let report = core::rescan_contests(conn, &task_dir_name, false);
+            assert_eq!(report.added.len(), 1);
+            assert!(report.added[0].starts_with("Runde 1 ("));
+            assert!(report.changed.is_empty() && report.hidden.is_empty() && report.errors.is_empty());
+
+            // Nothing changed
+            let report = core::rescan_contests(conn, &task_dir_name, false);
+            assert!(report.is_empty());
+            assert_eq!(report.unchanged, 1);
+
+            std::fs::write(&contest_file, contest_yaml.replace("Runde 1", "Erste Runde")).unwrap();
+            let report = core::rescan_contests(conn, &task_dir_name, false);
+            assert_eq!(report.changed.len(), 1);
+            assert!(report.changed[0].starts_with("Erste Runde ("));
+            assert!(report.added.is_empty() && report.hidden.is_empty());
+
+            // Files with errors are not loaded and leave the current version in place
+            std::fs::write(&contest_file, contest_yaml.replace("duration_minutes: 60\n", "")).unwrap();
+            let report = core::rescan_contests(conn, &task_dir_name, false);
+            assert_eq!(report.errors.len(), 1);
+            assert!(report.errors[0].ends_with("error: 'duration_minutes' missing"));
+            assert!(report.changed.is_empty() && report.hidden.is_empty());
+            let contests = conn.get_contest_list();
+            assert_eq!(contests.len(), 1);
+            assert_eq!(contests[0].name, "Erste Runde");
+            assert!(contests[0].public);
+
+            // Also in the full scan at startup
+            let report = core::rescan_contests(conn, &task_dir_name, true);
+            assert_eq!(report.errors.len(), 1);
+            assert!(report.hidden.is_empty());
+            assert!(conn.get_contest_list()[0].public);
+
+            std::fs::remove_file(&contest_file).unwrap();
+            let report = core::rescan_contests(conn, &task_dir_name, false);
+            assert_eq!(report.hidden.len(), 1);
+            assert!(report.hidden[0].starts_with("Erste Runde ("));
+            assert!(!conn.get_contest_list()[0].public);
+
+            std::fs::remove_dir_all(&task_dir).unwrap();
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            // Teachers can not rescan the contests
+            let resp = client.pget(port, "admin/contest/scan").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testadm", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/contest/scan").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            let params = [("csrf_token", "wrong")];
+            let resp = client.ppost(port, "admin/contest/scan").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let params = [("csrf_token", &csrf)];
+            let mut resp = client.ppost(port, "admin/contest/scan").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("0 hinzugefügt, 0 geändert, 0 ausgeblendet, 0 Fehler"));
+
+            let mut resp = client.pget(port, "admin/audit/").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>rescan_contests</td>"));
+            assert!(content.contains("<td>Hinzugefügt: 0<br>Geändert: 0<br>"));
+        });
+}
+
+#[test]
+fn check_email_confirmation_and_password_reset() {
+    let maildir = std::env::temp_dir().join(format!("medal-maildir-{}", std::process::id()));
+    let maildir_name = maildir.display().to_string();
+
+    // Returns the link of the form `{self_url}/{prefix}/{code}` from the only mail in the maildir and removes the mail
+    let take_mail_link = |prefix: &str| {
+        let mut mails = std::fs::read_dir(maildir.join("new")).unwrap().map(|e| e.unwrap().path()).collect::<Vec<_>>();
+        assert_eq!(mails.len(), 1);
+        let mail = mails.pop().unwrap();
+        let content = std::fs::read_to_string(&mail).unwrap();
+        std::fs::remove_file(&mail).unwrap();
+
+        let start = content.find(&format!("http://localhost:8080/{}/", prefix)).expect("Link not found") + 22;
+        let end = start + content[start..].find(|c: char| c.is_whitespace()).unwrap();
+        content[start..end].to_string()
+    };
+
+    run_with_config(|conn| {
+                        addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+
+                        // Someone else entered the address first, but never confirmed it
+                        let mut other = conn.new_session("");
+                        other.username = Some("otherusr".to_string());
+                        other.email_unconfirmed = Some("test@example.com".to_string());
+                        conn.save_session(other);
+                    },
+                    move |config| {
+                        config.mail_from = Some("medal@example.com".to_string());
+                        config.mail_maildir = Some(maildir_name.clone());
+                        config.self_url = Some("http://localhost:8080".to_string());
+                    },
+                    |port| {
+                        let client = reqwest::Client::builder().cookie_store(true)
+                                                               .redirect(reqwest::RedirectPolicy::none())
+                                                               .build()
+                                                               .unwrap();
+
+                        let resp = login(port, &client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        let mut resp = client.pget(port, "profile").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("action=\"/profile/email\""));
+                        let pos =
+                            content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+                        let csrf = content[pos + 39..pos + 49].to_string();
+
+                        let params = [("csrf_token", csrf.as_str()), ("email", "test@example.com")];
+                        let resp = client.ppost(port, "profile/email").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let location = resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap();
+                        assert!(location.ends_with("?status=EmailConfirmationSent"));
+
+                        // Password resets are only sent to confirmed addresses
+                        let params = [("name", "testusr")];
+                        let resp = client.ppost(port, "password/reset").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+
+                        let link = take_mail_link("email/confirm");
+                        let mut resp = client.pget(port, &link).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("test@example.com wurde erfolgreich bestätigt"));
+
+                        // Confirmation links can only be used once
+                        let mut resp = client.pget(port, &link).send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("ungültig oder wurde bereits verwendet"));
+
+                        // Unknown accounts do not get a mail, but the answer looks the same
+                        let params = [("name", "unknown@example.com")];
+                        let mut resp = client.ppost(port, "password/reset").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("haben wir eine E-Mail"));
+                        assert_eq!(std::fs::read_dir(maildir.join("new")).unwrap().count(), 0);
+
+                        let params = [("name", "test@example.com")];
+                        let resp = client.ppost(port, "password/reset").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let link = take_mail_link("password/reset");
+
+                        let mut resp = client.pget(port, &link).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("name=\"password_repeat\""));
+
+                        let params = [("password", "newpw"), ("password_repeat", "otherpw")];
+                        let mut resp = client.ppost(port, &link).form(&params).send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("stimmen nicht überein"));
+
+                        let params = [("password", "newpw"), ("password_repeat", "newpw")];
+                        let mut resp = client.ppost(port, &link).form(&params).send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("erfolgreich geändert"));
+
+                        // The reset logs the user out
+                        let resp = client.pget(port, "profile").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        // Reset links can only be used once
+                        let mut resp = client.pget(port, &link).send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("ungültig oder abgelaufen"));
+
+                        let resp = login(port, &client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let resp = login(port, &client, "testusr", "newpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                    });
+
+    std::fs::remove_dir_all(&maildir).unwrap();
+}
+
+#[test]
+fn check_staff_roles() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false); // ID: 1
+            addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true); // ID: 2
+            addsimpleuser(conn, "testsup".to_string(), "testpw".to_string(), false, false); // ID: 3
+
+            let mut group = Group { id: None,
+                                    name: "Gruppe".to_string(),
+                                    groupcode: "gcode".to_string(),
+                                    tag: "".to_string(),
+                                    admin: 1,
+                                    members: Vec::new() };
+            conn.add_group(&mut group);
+
+            let mut student = conn.new_session(""); // ID: 4
+            student.managed_by = group.id;
+            student.firstname = Some("Max".to_string());
+            student.lastname = Some("Mustermann".to_string());
+            student.logincode = Some("ustudent".to_string());
+            conn.save_session(student);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            // Without a role, the account can not look up users
+            let resp = login(port, &client, "testsup", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "admin").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let resp = client.pget(port, "admin/user/4").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testadm", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/user/3").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("<input type=\"checkbox\" name=\"role\" value=\"support\" >"));
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            let params = [("csrf_token", csrf.as_str()), ("role", "support"), ("role", "unknown")];
+            let resp = client.ppost(port, "admin/user/3/roles").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin/user/3").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<input type=\"checkbox\" name=\"role\" value=\"support\" checked>"));
+
+            let mut resp = client.pget(port, "admin/audit/").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>set_roles</td>"));
+
+            // Support staff can look up users and groups, but not use the other admin functions
+            let resp = login(port, &client, "testsup", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Admin-Suche"));
+            assert!(!content.contains("Datenbank-Cleanup"));
+
+            let resp = client.pget(port, "admin/cleanup").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let resp = client.pget(port, "admin/audit/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let resp = client.pget(port, "admin/group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let resp = client.pget(port, "admin/group/99").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+            let mut resp = client.pget(port, "admin/user/4").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Mustermann"));
+            assert!(!content.contains("name=\"role\""));
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            // Only admins can assign roles
+            let params = [("csrf_token", csrf.as_str()), ("role", "auditor")];
+            let resp = client.ppost(port, "admin/user/3/roles").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // The teacher still has access to the students of their own group only
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "admin/user/4").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let resp = client.pget(port, "admin/user/3").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let resp = client.pget(port, "admin").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        });
+}
+
+#[test]
+fn check_group_co_teachers() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false); // ID: 1
+            addsimpleuser(conn, "testcot".to_string(), "testpw".to_string(), true, false); // ID: 2
+
+            let mut co_teacher = conn.get_user_by_id(2).unwrap();
+            co_teacher.logincode = Some("lcoteacher".to_string());
+            co_teacher.email_unconfirmed = Some("cot@example.com".to_string());
+            conn.save_session(co_teacher);
+
+            let mut group = Group { id: None,
+                                    name: "Gruppe".to_string(),
+                                    groupcode: "gcode".to_string(),
+                                    tag: "".to_string(),
+                                    admin: 1,
+                                    members: Vec::new() };
+            conn.add_group(&mut group);
+
+            let mut student = conn.new_session(""); // ID: 3
+            student.managed_by = group.id;
+            student.firstname = Some("Max".to_string());
+            student.lastname = Some("Mustermann".to_string());
+            student.logincode = Some("ustudent".to_string());
+            conn.save_session(student);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let csrf_of = |content: &str| {
+                let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+                content[pos + 39..pos + 49].to_string()
+            };
+            let location_of = |resp: &reqwest::Response| {
+                resp.headers().get(reqwest::header::LOCATION).unwrap().to_str().unwrap().to_string()
+            };
+
+            let resp = login(port, &client, "testcot", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // The owner invites the co-teacher by logincode
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Lehrkraft einladen"));
+            let csrf = csrf_of(&content);
+
+            let params = [("csrf_token", csrf.as_str()), ("action", "invite"), ("invitee", "unknown")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            assert!(location_of(&resp).ends_with("/group/1?status=InviteeNotFound"));
+
+            // Teachers cannot be found by an email address they have not confirmed
+            let params = [("csrf_token", csrf.as_str()), ("action", "invite"), ("invitee", "cot@example.com")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            assert!(location_of(&resp).ends_with("/group/1?status=InviteeNotFound"));
+
+            let params = [("csrf_token", csrf.as_str()), ("action", "invite"), ("invitee", "lcoteacher")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            assert!(location_of(&resp).ends_with("/group/1?status=Invited"));
+
+            // The invitation has to be accepted before the group can be accessed
+            let resp = login(port, &client, "testcot", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let mut resp = client.pget(port, "group/").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Einladungen zur Mitverwaltung"));
+            let csrf = csrf_of(&content);
+
+            let params = [("csrf_token", csrf.as_str()), ("action", "accept")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Mustermann"));
+            assert!(content.contains("Mitverwaltung beenden"));
+            assert!(!content.contains("Lehrkraft einladen"));
+            let resp = client.pget(port, "user/3").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            // Co-teachers can neither invite other teachers nor delete the group
+            let params = [("csrf_token", csrf.as_str()), ("action", "invite"), ("invitee", "lcoteacher")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let params = [("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "group/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // The owner hands the group over and stays co-teacher
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            let csrf = csrf_of(&resp.text().unwrap());
+
+            let params = [("csrf_token", csrf.as_str()), ("action", "transfer"), ("teacher", "2")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(!resp.text().unwrap().contains("Lehrkraft einladen"));
+
+            // The new owner removes the previous one
+            let resp = login(port, &client, "testcot", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Lehrkraft einladen"));
+            let csrf = csrf_of(&content);
+
+            let params = [("csrf_token", csrf.as_str()), ("action", "remove"), ("teacher", "1")];
+            let resp = client.ppost(port, "group/1/teachers").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "group/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        });
+}
+
+#[test]
+fn check_participation_time_adjustments() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false); // ID: 1
+            addsimpleuser(conn, "teststu".to_string(), "testpw".to_string(), false, false); // ID: 2
+            addsimpleuser(conn, "testoth".to_string(), "testpw".to_string(), true, false); // ID: 3
+
+            let mut group = Group { id: None,
+                                    name: "Gruppe".to_string(),
+                                    groupcode: "gcode".to_string(),
+                                    tag: "".to_string(),
+                                    admin: 1,
+                                    members: Vec::new() };
+            conn.add_group(&mut group);
+
+            let mut student = conn.get_user_by_id(2).unwrap();
+            student.managed_by = group.id;
+            conn.save_session(student);
+
+            let mut more_than_one_minute_ago = time::get_time();
+            more_than_one_minute_ago.sec -= 90;
+            conn.execute("INSERT INTO participation (contest, session, start_date)
+                          SELECT $1, id, $2 FROM session WHERE username = 'teststu'",
+                         &[&1, &more_than_one_minute_ago])
+                .unwrap();
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let csrf_of = |content: &str| {
+                let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+                content[pos + 39..pos + 49].to_string()
+            };
+            let teacher_csrf = || {
+                let resp = login(port, &client, "testusr", "testpw");
+                assert_eq!(resp.status(), StatusCode::FOUND);
+                let mut resp = client.pget(port, "admin/user/2/1").send().unwrap();
+                assert_eq!(resp.status(), StatusCode::OK);
+                csrf_of(&resp.text().unwrap())
+            };
+            let student_csrf = || {
+                let resp = login(port, &client, "teststu", "testpw");
+                assert_eq!(resp.status(), StatusCode::FOUND);
+                let mut resp = client.pget(port, "profile").send().unwrap();
+                csrf_of(&resp.text().unwrap())
+            };
+
+            // The time is up
+            let csrf = student_csrf();
+            let params = [("data", "SomeData"), ("grade", "67"), ("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "save/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            // Only the teacher of the group may change the time
+            let resp = login(port, &client, "testoth", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = client.pget(port, "profile").send().unwrap();
+            let csrf = csrf_of(&resp.text().unwrap());
+            let params = [("csrf_token", csrf.as_str()), ("action", "extra_time"), ("minutes", "5")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let csrf = teacher_csrf();
+            let mut resp = client.pget(port, "admin/user/2/1").send().unwrap();
+            assert!(resp.text().unwrap().contains("abgelaufen"));
+            let params = [("csrf_token", csrf.as_str()), ("action", "extra_time"), ("minutes", "5")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let csrf = student_csrf();
+            let mut resp = client.pget(port, "contest/1").send().unwrap();
+            assert!(resp.text().unwrap().contains("(inklusive 5 Minuten Zusatzzeit)"));
+            let params = [("data", "SomeData"), ("grade", "67"), ("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "save/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            // No work is possible while the participation is paused
+            let csrf = teacher_csrf();
+            let params = [("csrf_token", csrf.as_str()), ("action", "pause")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let csrf = student_csrf();
+            let mut resp = client.pget(port, "contest/1").send().unwrap();
+            assert!(resp.text().unwrap().contains("Die Teilnahme ist pausiert."));
+            let resp = client.pget(port, "task/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let params = [("data", "OtherData"), ("grade", "67"), ("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "save/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let csrf = teacher_csrf();
+            let params = [("csrf_token", csrf.as_str()), ("action", "resume")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let csrf = student_csrf();
+            let resp = client.pget(port, "task/1").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let params = [("data", "SomeData"), ("grade", "67"), ("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "save/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            // A restart without extra time gives the full duration again and keeps the submissions
+            let csrf_teacher = teacher_csrf();
+            let params = [("csrf_token", csrf_teacher.as_str()), ("action", "extra_time"), ("minutes", "0")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let params = [("csrf_token", csrf_teacher.as_str()), ("action", "restart")];
+            let resp = client.ppost(port, "admin/user/2/1/time").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let csrf = student_csrf();
+            let mut resp = client.pget(port, "load/1").send().unwrap();
+            assert_eq!(resp.text().unwrap(), "SomeData");
+            let params = [("data", "NewData"), ("grade", "67"), ("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, "save/1").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        });
+}
+
+fn check_logout_everywhere_with_session_backend(session_backend: &'static str) {
+    run_with_config(|conn| {
+                        // IDs 1, 2 and 3
+                        addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true);
+                        addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), false, false);
+                        addsimpleuser(conn, "testtch".to_string(), "testpw".to_string(), true, false);
+                    },
+                    move |config| config.session_backend = Some(session_backend.to_string()),
+                    |port| {
+                        let new_client = || {
+                            reqwest::Client::builder().cookie_store(true)
+                                                      .redirect(reqwest::RedirectPolicy::none())
+                                                      .build()
+                                                      .unwrap()
+                        };
+                        let csrf_of = |content: &str| {
+                            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"")
+                                             .expect("CSRF-Token not found");
+                            content[pos + 39..pos + 49].to_string()
+                        };
+                        let is_logged_in = |client: &reqwest::Client| {
+                            let mut resp = client.pget(port, "").send().unwrap();
+                            resp.text().unwrap().contains("Eingeloggt als <em>testusr</em>")
+                        };
+
+                        // Log out on all devices from the own profile
+                        let client = new_client();
+                        let resp = login(port, &client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let cookie_name = match session_backend {
+                            "database" => "medal_session_id=",
+                            "redis" => "iron_session_id=",
+                            _ => "medal_session=",
+                        };
+                        let set_cookies: Vec<_> = resp.headers().get_all(reqwest::header::SET_COOKIE).iter().collect();
+                        assert!(set_cookies.iter().any(|c| c.to_str().unwrap().starts_with(cookie_name)));
+                        assert!(is_logged_in(&client));
+
+                        let mut resp = client.pget(port, "profile").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Auf allen Geräten abmelden"));
+                        let csrf = csrf_of(&content);
+
+                        let params = [("csrf_token", csrf.as_str())];
+                        let resp = client.ppost(port, "logout/everywhere").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        assert!(!is_logged_in(&client));
+
+                        // An admin logs the user out
+                        let user_client = new_client();
+                        let resp = login(port, &user_client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        assert!(is_logged_in(&user_client));
+
+                        let client = new_client();
+                        let resp = login(port, &client, "testtch", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let mut resp = client.pget(port, "profile").send().unwrap();
+                        let csrf = csrf_of(&resp.text().unwrap());
+                        let params = [("csrf_token", csrf.as_str())];
+                        let resp = client.ppost(port, "admin/user/2/sessions").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+                        assert!(is_logged_in(&user_client));
+
+                        let client = new_client();
+                        let resp = login(port, &client, "testadm", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        let mut resp = client.pget(port, "admin/user/2").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Auf allen Geräten abmelden"));
+                        let csrf = csrf_of(&content);
+
+                        let params = [("csrf_token", csrf.as_str())];
+                        let resp = client.ppost(port, "admin/user/2/sessions").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+                        assert!(!is_logged_in(&user_client));
+
+                        let mut resp = client.pget(port, "admin/user/2").send().unwrap();
+                        assert!(!resp.text().unwrap().contains("Auf allen Geräten abmelden"));
+                    });
+}
+
+#[test]
+fn check_logout_everywhere_signed_cookie() { check_logout_everywhere_with_session_backend("cookie"); }
+
+#[test]
+fn check_logout_everywhere_database() { check_logout_everywhere_with_session_backend("database"); }
+
+// Needs a Redis server on localhost: cargo test --features redis -- --ignored
+#[cfg(feature = "redis")]
+#[test]
+#[ignore]
+fn check_logout_everywhere_redis() { check_logout_everywhere_with_session_backend("redis"); }
+
+#[test]
+fn check_group_statistics() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testusr2".to_string(), "testpw2".to_string(), true, false);
+
+            // ID: 1, saved before the default contests
+            let mut contest = qualification_test_contest("statistics.yaml", "StatisticsContestName", None);
+            let mut taskgroup = Taskgroup::new("FirstTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir1".to_string(), None, 3)); // ID: 1
+            contest.taskgroups.push(taskgroup);
+            let mut taskgroup = Taskgroup::new("SecondTaskgroup".to_string(), None);
+            taskgroup.tasks.push(Task::new("taskdir2".to_string(), None, 4)); // ID: 2
+            contest.taskgroups.push(taskgroup);
+            contest.save(conn);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+
+            sim_login_groupcode(&client, port, &groupcode, ("Anna", "Alpha", "5", "2"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+            sim_participate(&client, port, "2", ("SomeData", "50"));
+
+            sim_login_groupcode(&client, port, &groupcode, ("Bert", "Beta", "6", "1"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+            sim_participate(&client, port, "2", ("SomeData", "50"));
+
+            sim_login_groupcode(&client, port, &groupcode, ("Carl", "Gamma", "5", "1"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "0"));
+
+            // Students cannot see the statistics
+            let resp = client.pget(port, &format!("contest/1/result/{}/statistics", group_id)).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "contest/1/result/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains(&format!("href=\"{}/statistics\"", group_id)));
+
+            let mut resp = client.pget(port, &format!("contest/1/result/{}/statistics", group_id)).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("Teilnehmer mit Ergebnissen: 3"));
+            assert!(content.contains("Schwierigste Aufgabe: SecondTaskgroup"));
+            // 3 + 3 + 0 of 3 stars and 2 + 2 of 4 stars
+            assert!(content.contains("<td>2.0 von 3</td>"));
+            assert!(content.contains("<td>1.3 von 4</td>"));
+            assert!(content.contains("\"distribution\":[1,0,0,2]"));
+
+            // Other teachers can not see the statistics of foreign groups
+            let resp = login(port, &client, "testusr2", "testpw2");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let resp = client.pget(port, &format!("contest/1/result/{}/statistics", group_id)).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        })
+}
+
+#[test]
+fn check_admin_reports() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+            addsimpleuser(conn, "testadm".to_string(), "testpw".to_string(), false, true);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+
+            sim_login_groupcode(&client, port, &groupcode, ("Anna", "Alpha", "5", "2"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "100"));
+
+            sim_login_groupcode(&client, port, &groupcode, ("Bert", "Beta", "6", "1"));
+            sim_start_contest(&client, port, "1");
+            sim_participate(&client, port, "1", ("SomeData", "50"));
+
+            // Teachers can not see the reports
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let resp = client.pget(port, "admin/reports/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+            let resp = client.pget(port, "admin/reports/download").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let resp = login(port, &client, "testadm", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "admin").send().unwrap();
+            assert!(resp.text().unwrap().contains("href=\"/admin/reports/\""));
+
+            // The report covers the current year by default, in UTC
+            let today = time::strftime("%F", &time::now_utc()).unwrap();
+            let mut resp = client.pget(port, "admin/reports/").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains(&format!("name=\"until\" value=\"{}\"", today)));
+            assert!(content.contains("<tr><td>Teilnahmen</td><td>2</td></tr>"));
+            assert!(content.contains("<tr><td>Lehrkräfte mit teilnehmenden Gruppen</td><td>1</td></tr>"));
+            assert!(content.contains("<tr><td>PublicContestName</td><td>2</td></tr>"));
+            assert!(content.contains("<tr><td>Ohne OAuth</td><td>2</td></tr>"));
+            assert!(content.contains("<tr><td>5</td><td>1</td></tr>"));
+            assert!(content.contains("<tr><td>weiblich</td><td>1</td></tr>"));
+            assert!(content.contains(&format!("<tr><td>{}</td>", today)));
+
+            let mut resp = client.pget(port, "admin/reports/?from=2000-01-01&until=2000-12-31").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<tr><td>Teilnahmen</td><td>0</td></tr>"));
+            assert!(!content.contains("PublicContestName"));
+
+            let mut resp = client.pget(port, "admin/reports/download?format=csv").send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.headers().get("Content-Type").unwrap().to_str().unwrap().starts_with("text/csv"));
+            let content = resp.text().unwrap();
+            let mut lines = content.lines();
+            assert_eq!(lines.next(), Some("from,until,section,label,value"));
+            assert!(lines.next().unwrap().ends_with(&format!("{},participations,total,2", today)));
+            assert!(content.contains(",participations_per_contest,PublicContestName (1),2\n"));
+            assert!(content.contains(",participations_by_sex,männlich,1\n"));
+            assert!(content.lines()
+                           .filter(|line| line.contains(",submissions_per_hour,"))
+                           .all(|line| line.contains(&format!(",submissions_per_hour,{} ", today))
+                                       && line.contains(":00,")));
+            let submissions: i64 = content.lines()
+                                          .filter(|line| line.contains(",submissions_per_hour,"))
+                                          .map(|line| line.rsplit(',').next().unwrap().parse::<i64>().unwrap())
+                                          .sum();
+            assert_eq!(submissions, 2);
+
+            let mut resp = client.pget(port, "admin/reports/download?format=jsonl&from=2000-01-01&until=2000-12-31")
+                                 .send()
+                                 .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            let first: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+            assert_eq!(first["from"], "2000-01-01");
+            assert_eq!(first["until"], "2000-12-31");
+            assert_eq!(first["section"], "participations");
+            assert_eq!(first["value"], 0);
+        })
+}
+
+#[test]
+fn check_metrics() {
+    run_with_config(|conn| {
+                        addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+                    },
+                    |config| {
+                        config.dbstatus_secret = Some("metricssecret".to_string());
+                    },
+                    |port| {
+                        let client = reqwest::Client::builder().cookie_store(true)
+                                                               .redirect(reqwest::RedirectPolicy::none())
+                                                               .build()
+                                                               .unwrap();
+
+                        let resp = login(port, &client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        let (_group_id, groupcode) = sim_create_group(&client, port, "Groupname");
+                        sim_login_groupcode(&client, port, &groupcode, ("Anna", "Alpha", "5", "2"));
+                        sim_start_contest(&client, port, "1");
+                        sim_participate(&client, port, "1", ("SomeData", "100"));
+
+                        let resp = client.pget(port, "metrics").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+                        let resp = client.pget(port, "metrics?wrongsecret").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+                        let mut resp = client.pget(port, "metrics?metricssecret").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                        let content_type = resp.headers().get("Content-Type").unwrap().to_str().unwrap().to_string();
+                        assert!(content_type.starts_with("text/plain"));
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("# TYPE medal_http_requests_total counter\n"));
+                        assert!(content.contains("medal_http_requests_total{route=\"login_post\",status=\"302\"} "));
+                        assert!(content.contains("medal_http_request_duration_seconds_bucket{route=\"login_post\",le=\"+Inf\"} "));
+                        assert!(content.contains("medal_db_method_duration_seconds_count{method=\"get_session\"} "));
+                        assert!(content.contains("medal_db_connection_wait_seconds_count "));
+                        // The session of the student is active
+                        assert!(!content.contains("medal_active_sessions 0\n"));
+                        let submissions = content.lines()
+                                                 .find(|line| line.starts_with("medal_submissions_total "))
+                                                 .and_then(|line| line.split(' ').nth(1))
+                                                 .and_then(|value| value.parse::<u64>().ok())
+                                                 .unwrap();
+                        assert!(submissions >= 1);
+
+                        let resp = client.pget(port, "metrics").bearer_auth("metricssecret").send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::OK);
+                    })
+}
+
+#[test]
+fn check_metrics_without_secret() {
+    run(|_| {},
+        |port| {
+            // Without a configured secret, the metrics must not be public
+            let resp = reqwest::get(&format!("http://localhost:{}/metrics", port)).unwrap();
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        })
+}
+
+#[test]
+fn check_language_selection() {
+    run_with_config(|conn| {
+                        addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), false, false);
+                    },
+                    |config| {
+                        config.default_language = Some("en".to_string());
+                    },
+                    |port| {
+                        let client = reqwest::Client::builder().cookie_store(true)
+                                                               .redirect(reqwest::RedirectPolicy::none())
+                                                               .build()
+                                                               .unwrap();
+
+                        let mut resp = client.pget(port, "").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Group code / participation code"));
+
+                        let mut resp =
+                            client.pget(port, "").header("Accept-Language", "fr, de-DE;q=0.8").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Gruppencode / Teilnahmecode"));
+
+                        let mut resp = login(port, &client, "nonexistingusername", "wrongpassword");
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Login failed."));
+
+                        let resp = login(port, &client, "testusr", "testpw");
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        let mut resp = client.pget(port, "profile").header("Accept-Language", "de").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("value=\"Sprache ändern\""));
+                        assert!(content.contains("<option value=\"da\" >Dansk</option>"));
+                        let pos =
+                            content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+                        let csrf = &content[pos + 39..pos + 49];
+
+                        // The language of the profile is preferred to the language of the browser
+                        let params = [("language", "da"), ("csrf_token", csrf)];
+                        let resp = client.ppost(port, "profile/language").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        let mut resp = client.pget(port, "profile").header("Accept-Language", "de").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("value=\"Skift sprog\""));
+                        assert!(content.contains("<option value=\"da\" selected>Dansk</option>"));
+
+                        let params = [("language", "xx"), ("csrf_token", csrf)];
+                        let resp = client.ppost(port, "profile/language").form(&params).send().unwrap();
+                        assert_ne!(resp.status(), StatusCode::FOUND);
+
+                        let params = [("language", ""), ("csrf_token", csrf)];
+                        let resp = client.ppost(port, "profile/language").form(&params).send().unwrap();
+                        assert_eq!(resp.status(), StatusCode::FOUND);
+
+                        let mut resp = client.pget(port, "").send().unwrap();
+                        let content = resp.text().unwrap();
+                        assert!(content.contains("Logged in as <em>testusr</em>"));
+                    })
+}
+
+#[test]
+fn check_group_csv_import() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "group/csv").send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            // Export with an extra column, semicolons, a header and Windows-1252 encoding
+            let file = b"Nr;Klasse;Stufe;Vorname;Nachname;Geschlecht\r\n\
+                         1;7a;7;J\xfcrgen;M\xfcller;m\r\n\
+                         2;7a;7;Erika;Beispiel;w\r\n\
+                         3;Info;12 (G9);Max;Mustermann;m\r\n";
+            let file_data = base64::encode(&file[..]);
+            let params = [("csrf_token", csrf.as_str()),
+                          ("file_data", file_data.as_str()),
+                          ("encoding", "auto"),
+                          ("delimiter", "auto"),
+                          ("header", "auto")];
+            let mut resp = client.ppost(port, "group/csv/preview").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td>Jürgen</td>"));
+            assert!(content.contains("<option value=\"semicolon\" selected>"));
+            assert!(content.contains("<option value=\"1\" selected>Spalte 2: Klasse</option>"));
+            assert!(content.contains("<li>7a: 2 Teilnehmer</li>"));
+            assert!(content.contains("value=\"Gruppen erstellen\""));
+
+            // Nothing is created as long as any row has an error
+            let csv_data = "Nr;Klasse;Stufe;Vorname;Nachname;Geschlecht\n\
+                            1;7a;7;Jürgen;Müller;m\n\
+                            2;7a;17;Erika;Beispiel;x\n\
+                            3;Info;12 (G9);Max\n";
+            let params = [("csrf_token", csrf.as_str()),
+                          ("csv_data", csv_data),
+                          ("delimiter", "semicolon"),
+                          ("header", "yes"),
+                          ("column_group", "1"),
+                          ("column_grade", "2"),
+                          ("column_firstname", "3"),
+                          ("column_lastname", "4"),
+                          ("column_sex", "5")];
+            let mut resp = client.ppost(port, "group/csv/preview").form(&params).send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Unbekannte Jahrgangsstufe „17“<br>Unbekanntes Geschlecht „x“<br>"));
+            assert!(content.contains("Die Datei enthält 2 Fehler."));
+            assert!(!content.contains("value=\"Gruppen erstellen\""));
+
+            let mut resp = client.ppost(port, "group/csv").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("Die Datei enthält 2 Fehler."));
+
+            let mut resp = client.pget(port, "group/").send().unwrap();
+            assert!(!resp.text().unwrap().contains("<td><a href=\"/group/1\">"));
+
+            // Without the sex column and with corrected grade
+            let csv_data = csv_data.replace(";17;", ";7;");
+            let params = [("csrf_token", csrf.as_str()),
+                          ("csv_data", csv_data.as_str()),
+                          ("delimiter", "semicolon"),
+                          ("header", "yes"),
+                          ("column_group", "1"),
+                          ("column_grade", "2"),
+                          ("column_firstname", "3"),
+                          ("column_lastname", "4"),
+                          ("column_sex", "")];
+            let resp = client.ppost(port, "group/csv").form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let mut resp = client.pget(port, "group/").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("<td><a href=\"/group/1\">7a</a></td>"));
+            assert!(content.contains("<td><a href=\"/group/2\">Info</a></td>"));
+
+            let mut resp = client.pget(port, "group/1").send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains("Jürgen"));
+            assert!(content.contains("Beispiel"));
+        })
+}
+
+#[test]
+fn check_group_login_sheets() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+            let student = reqwest::Client::builder().cookie_store(true)
+                                                    .redirect(reqwest::RedirectPolicy::none())
+                                                    .build()
+                                                    .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (group_id, groupcode) = sim_create_group(&client, port, "7a");
+            let logincode = sim_login_groupcode(&student, port, &groupcode, ("Max", "Mustermann", "7", "m"));
+
+            let mut resp = client.pget(port, &format!("group/loginsheets/{}", group_id)).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/pdf");
+            let mut pdf = Vec::new();
+            resp.copy_to(&mut pdf).unwrap();
+            assert!(pdf.starts_with(b"%PDF-"));
+            assert!(pdf.windows(logincode.len() + 2).any(|window| window == format!("({})", logincode).as_bytes()));
+
+            // Only teachers of the group get the login sheets
+            let resp = student.pget(port, &format!("group/loginsheets/{}", group_id)).send().unwrap();
+            assert_ne!(resp.status(), StatusCode::OK);
+
+            // The link of the QR code fills in the login code
+            let mut resp = student.pget(port, &format!("login?code={}", logincode)).send().unwrap();
+            let content = resp.text().unwrap();
+            assert!(content.contains(&format!("name=\"code\" value=\"{}\"", logincode)));
+        })
+}
+
+#[test]
+fn check_login_links() {
+    run(|conn| {
+            addsimpleuser(conn, "testusr".to_string(), "testpw".to_string(), true, false);
+        },
+        |port| {
+            let client = reqwest::Client::builder().cookie_store(true)
+                                                   .redirect(reqwest::RedirectPolicy::none())
+                                                   .build()
+                                                   .unwrap();
+            let student = reqwest::Client::builder().cookie_store(true)
+                                                    .redirect(reqwest::RedirectPolicy::none())
+                                                    .build()
+                                                    .unwrap();
+
+            let resp = login(port, &client, "testusr", "testpw");
+            assert_eq!(resp.status(), StatusCode::FOUND);
+
+            let (group_id, groupcode) = sim_create_group(&client, port, "7a");
+            let logincode = sim_login_groupcode(&student, port, &groupcode, ("Max", "Mustermann", "3", "m"));
+
+            let mut resp = client.pget(port, &format!("group/{}", group_id)).send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("<td><a href=\"/admin/user/").expect("Member not found");
+            let user_id: i32 = content[pos + 25..].split('"').next().unwrap().parse().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let csrf = content[pos + 39..pos + 49].to_string();
+
+            // The test configuration signs with the cookie signing secret
+            let signer = login_links::LoginLinkSigner::new("testtesttesttesttesttesttesttest", 90);
+            let link = |generation| signer.link("", user_id, &logincode, generation, time::get_time());
+            let login_link = |link: &str| {
+                let client = reqwest::Client::builder().cookie_store(true)
+                                                       .redirect(reqwest::RedirectPolicy::none())
+                                                       .build()
+                                                       .unwrap();
+                let resp = client.pget(port, &link[1..]).send().unwrap();
+                if resp.status() != StatusCode::FOUND {
+                    return false;
+                }
+                let mut resp = client.pget(port, "profile").send().unwrap();
+                resp.text().unwrap().contains(&logincode)
+            };
+
+            let first_link = link(0);
+            assert!(login_link(&first_link));
+            assert!(!login_link(&format!("{}0", &first_link[..first_link.len() - 1])));
+
+            // Revoke the link of the student
+            let params = [("csrf_token", csrf.as_str()), ("user", &user_id.to_string())];
+            let resp = client.ppost(port, &format!("group/{}/revokelinks", group_id)).form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = student.pget(port, &first_link[1..]).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert!(resp.text().unwrap().contains("Der Login-Link ist abgelaufen oder wurde gesperrt."));
+            assert!(login_link(&link(1)));
+
+            // Revoke the links of the whole group
+            let params = [("csrf_token", csrf.as_str())];
+            let resp = client.ppost(port, &format!("group/{}/revokelinks", group_id)).form(&params).send().unwrap();
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            assert!(!login_link(&link(1)));
+            assert!(login_link(&link(2)));
+
+            // Students can not revoke login links
+            let resp = login_code(port, &student, &logincode);
+            assert_eq!(resp.status(), StatusCode::FOUND);
+            let mut resp = student.pget(port, "profile").send().unwrap();
+            let content = resp.text().unwrap();
+            let pos = content.find("type=\"hidden\" name=\"csrf_token\" value=\"").expect("CSRF-Token not found");
+            let params = [("csrf_token", &content[pos + 39..pos + 49])];
+            let resp = student.ppost(port, &format!("group/{}/revokelinks", group_id)).form(&params).send().unwrap();
+            assert_ne!(resp.status(), StatusCode::FOUND);
+            assert!(login_link(&link(2)));
+        })
+}
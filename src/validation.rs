@@ -0,0 +1,171 @@
+/*  medal                                                                                                            *\
+ *  Copyright (C) 2022  Bundesweite Informatikwettbewerbe, Robert Czechowski                                                            *
+ *                                                                                                                   *
+ *  This program is free software: you can redistribute it and/or modify it under the terms of the GNU Affero        *
+ *  General Public License as published  by the Free Software Foundation, either version 3 of the License, or (at    *
+ *  your option) any later version.                                                                                  *
+ *                                                                                                                   *
+ *  This program is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the       *
+ *  implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the GNU Affero General Public      *
+ *  License for more details.                                                                                        *
+ *                                                                                                                   *
+ *  You should have received a copy of the GNU Affero General Public License along with this program.  If not, see   *
+\*  <http://www.gnu.org/licenses/>.                                                                                  */
+
+use std::io::{Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use config::Config;
+use db_conn::MedalConnection;
+use db_objects::{Contest, Submission, Task};
+use qualification;
+
+#[derive(Debug)]
+pub enum CheckerError {
+    IoError(std::io::Error),
+    Timeout,
+    Failed(Option<i32>),
+    InvalidOutput(String),
+}
+
+impl From<std::io::Error> for CheckerError {
+    fn from(e: std::io::Error) -> Self { CheckerError::IoError(e) }
+}
+
+/// Run the command `checker` in the directory `taskpath` with `value` on its standard input.
+///
+/// The checker has to print the achieved percentage (an integer between 0 and 100) to its standard output and exit
+/// successfully. It is killed if it does not finish within `timeout`.
+pub fn run_checker(checker: &str, taskpath: &str, value: &str, timeout: Duration) -> Result<i32, CheckerError> {
+    let mut command = Command::new("sh");
+    command.arg("-c")
+           .arg(checker)
+           .current_dir(taskpath)
+           .stdin(Stdio::piped())
+           .stdout(Stdio::piped())
+           .stderr(Stdio::null());
+    // Own process group, so that `kill_checker` also reaches the processes started by the checker
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command.spawn()?;
+
+    // Feed stdin and collect stdout in separate threads, so a checker with a lot of output can not block us
+    let mut stdin = child.stdin.take().unwrap();
+    let value = value.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(value.as_bytes()));
+
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            kill_checker(&mut child)?;
+            child.wait()?;
+            // Standard output is closed now that all processes of the checker are gone
+            let _ = writer.join();
+            let _ = reader.join();
+            return Err(CheckerError::Timeout);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    // The checker might exit without reading its input, so a broken pipe is not an error here
+    let _ = writer.join();
+    let output = reader.join().unwrap()?;
+
+    if !status.success() {
+        return Err(CheckerError::Failed(status.code()));
+    }
+
+    match output.trim().parse::<i32>() {
+        Ok(percentage) if (0..=100).contains(&percentage) => Ok(percentage),
+        _ => Err(CheckerError::InvalidOutput(output)),
+    }
+}
+
+#[cfg(unix)]
+fn kill_checker(child: &mut Child) -> std::io::Result<()> {
+    // A negative pid addresses the process group
+    if unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn kill_checker(child: &mut Child) -> std::io::Result<()> { child.kill() }
+
+/// Strip the legacy language prefix of a task location (see `core::show_task`)
+fn task_directory(task: &Task) -> &str {
+    match (&task.language, task.location.chars().next()) {
+        (None, Some('B')) | (None, Some('P')) => &task.location[1..],
+        _ => &task.location,
+    }
+}
+
+/// Grade of `submission` to `task` according to the checker of the task, or `None` if the checker fails
+fn checker_grade(task: &Task, contest: &Contest, submission: &Submission, timeout: Duration) -> Option<i32> {
+    let checker = task.checker.as_ref()?;
+    let taskpath = format!("{}{}", contest.location, task_directory(task));
+
+    match run_checker(checker, &taskpath, &submission.value, timeout) {
+        // Same rounding as in `core::save_submission`
+        Ok(percentage) => Some(((percentage * task.stars * 10) / 100 + 5) / 10),
+        Err(e) => {
+            eprintln!("Could not validate submission {} with checker '{}': {:?}", submission.id.unwrap(), checker, e);
+            None
+        }
+    }
+}
+
+/// Validate the next submission waiting for validation.
+///
+/// If the checker fails, e.g. because it times out or prints something else than a percentage, the submission is
+/// scored with 0 points. Otherwise a value that breaks the checker would keep the grade sent by the browser.
+///
+/// Returns `true` if a submission has been processed and `false` if there was nothing to do.
+pub fn validate_next_submission<C: MedalConnection>(conn: &C, timeout: Duration) -> bool {
+    let submission = match conn.get_next_submission_to_validate() {
+        Some(submission) => submission,
+        None => return false,
+    };
+    let submission_id = submission.id.unwrap();
+
+    let task = conn.get_task_by_id_complete(submission.task);
+    let grade = task.as_ref().and_then(|(task, _, contest)| checker_grade(task, contest, &submission, timeout));
+
+    conn.validate_submission(submission_id, grade.unwrap_or(0));
+    if let Some((_, _, contest)) = task {
+        qualification::invalidate_grade_ranks(contest.id.unwrap());
+    }
+    true
+}
+
+/// Validate submissions in the background using a separate database connection.
+pub fn run_validation_worker<C: MedalConnection>(config: Config) {
+    let conn = C::reconnect(&config);
+    let interval = Duration::from_secs(config.validation_interval.unwrap_or(5));
+    let timeout = Duration::from_secs(config.validation_timeout.unwrap_or(10));
+
+    loop {
+        // A failed database statement, e.g. because the database is busy, panics. The changes of the submission are
+        // rolled back then, so it is tried again after the interval instead of ending the worker.
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| validate_next_submission(&conn, timeout))) {
+            Ok(true) => continue,
+            Ok(false) => (),
+            Err(_) => eprintln!("Could not save the validation of a submission, retrying in {:?}", interval),
+        }
+        std::thread::sleep(interval);
+    }
+}
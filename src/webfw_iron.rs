@@ -15,6 +15,7 @@
 use std::path::Path;
 
 pub use handlebars_iron::handlebars::to_json;
+use handlebars_iron::handlebars::{html_escape, Handlebars, Helper, JsonRender, RenderContext, RenderError};
 use handlebars_iron::{DirectorySource, HandlebarsEngine, Template};
 use iron;
 use iron::mime::Mime;
@@ -23,24 +24,43 @@ use iron::modifiers::RedirectRaw;
 use iron::prelude::*;
 use iron::{status, AfterMiddleware, AroundMiddleware, Handler};
 use iron_sessionstorage;
+#[cfg(feature = "redis")]
+use iron_sessionstorage::backends::RedisBackend;
 use iron_sessionstorage::backends::SignedCookieBackend;
 use iron_sessionstorage::traits::*;
 use iron_sessionstorage::SessionStorage;
 use mount::Mount;
-use persistent::{Read, Write};
+use persistent::Read;
 use reqwest;
 use router::Router;
+use serde;
+use serde_json;
 use staticfile::Static;
 use urlencoded::{UrlEncodedBody, UrlEncodedQuery};
 
 #[cfg(feature = "debug")]
 use iron::BeforeMiddleware;
 
+use base64;
 use config::{Config, OauthProvider};
 use core;
-use db_conn::MedalConnection;
+use db_conn::{MedalConnection, MedalObject};
+use db_objects::Contest;
+use db_pool::ConnectionPool;
+use export;
+use export::{ExportFormat, ExportOptions};
+use grades;
+use group_import;
+use i18n;
 use iron::typemap::Key;
+use login_links::LoginLinkSigner;
+use mail::Mailer;
+use metrics;
+use ranking::RankingFilter;
+use reporting;
 pub use serde_json::value as json_val;
+use session_backend;
+use session_backend::{DatabaseSessionBackend, SessionStore};
 
 #[cfg(feature = "signup")]
 use db_conn::SignupResult;
@@ -64,13 +84,54 @@ macro_rules! mime {
 macro_rules! with_conn {
     ( $x:expr , $c:ident, $r:expr , $($y:expr),* ) => {
         {
-            let mutex = $r.get::<Write<SharedDatabaseConnection<$c>>>().unwrap();
-            let conn = mutex.lock().unwrap_or_else(|e| e.into_inner());
+            let pool = $r.get::<Read<SharedDatabasePool<$c>>>().unwrap();
+            let conn = pool.get();
             $x(&*conn, $($y),*)
         }
     };
 }
 
+/// Like `with_conn!`, but uses a read-only connection if there is one. Only for core functions that do not write.
+macro_rules! with_reader {
+    ( $x:expr , $c:ident, $r:expr , $($y:expr),* ) => {
+        {
+            let pool = $r.get::<Read<SharedDatabasePool<$c>>>().unwrap();
+            let conn = pool.get_reader();
+            $x(&*conn, $($y),*)
+        }
+    };
+}
+
+/// Id of the route that handles the request, used to label the request metrics
+struct RouteId;
+impl Key for RouteId {
+    type Value = &'static str;
+}
+
+/// Handler that stores the id of its route in the request before calling `handler`
+struct NamedRoute<H> {
+    route_id: &'static str,
+    handler: H,
+}
+
+impl<H: Handler> Handler for NamedRoute<H> {
+    fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        req.extensions.insert::<RouteId>(self.route_id);
+        self.handler.handle(req)
+    }
+}
+
+/// Like `router!`, but the route ids are remembered for the request metrics
+macro_rules! named_router {
+    ($($route_id:ident: $method:ident $glob:expr => $handler:expr),+ $(,)*) => ({
+        let mut router = Router::new();
+        $(router.$method($glob,
+                         NamedRoute { route_id: stringify!($route_id), handler: $handler },
+                         stringify!($route_id));)*
+        router
+    });
+}
+
 macro_rules! template_ok {
     ( $x:expr ) => {{
         let (template, data) = $x;
@@ -162,6 +223,14 @@ impl AroundMiddleware for RequestTimeLogger {
             // End measurement
             let duration = start.elapsed();
 
+            // Requests that are not handled by the router (e.g. static files) are counted together
+            let route = req.extensions.get::<RouteId>().cloned().unwrap_or("none");
+            let status = match res {
+                Ok(ref response) => response.status,
+                Err(ref error) => error.response.status,
+            };
+            metrics::observe_request(route, status.map_or(0, |status| status.to_u16()), duration);
+
             if logtiming {
                 println!("t:\t{:?}\t{}\t{}", duration, req.method, req.url);
             } else if duration > threshold_critical {
@@ -191,6 +260,64 @@ impl AroundMiddleware for CookieDistributor {
     }
 }
 
+/// Chooses the language of the user interface for each request, see `i18n::Catalogs::negotiate`. The handler runs
+/// with this language and it is kept in the response for rendering the template.
+///
+/// Has to be linked inside of the session storage to know the user of the request.
+pub struct LanguageSelector<C> {
+    connection_type: std::marker::PhantomData<fn() -> C>,
+}
+
+impl<C> LanguageSelector<C> {
+    pub fn new() -> Self { LanguageSelector { connection_type: std::marker::PhantomData } }
+}
+
+struct RequestLanguage;
+impl Key for RequestLanguage {
+    type Value = String;
+}
+
+fn request_language<C>(req: &mut Request) -> String
+    where C: MedalConnection + std::marker::Send + 'static {
+    let default_language = req.get::<Read<SharedConfiguration>>()
+                              .unwrap()
+                              .default_language
+                              .clone()
+                              .unwrap_or_else(|| i18n::DEFAULT_LANGUAGE.to_string());
+
+    // Static files are not translated, so there is no need to look up the user
+    let session_token = match req.url.path().first() {
+        Some(&"static") | Some(&"tasks") => None,
+        _ => req.get_session_token(),
+    };
+    let preference = session_token.and_then(|session_token| {
+                                      let pool = req.get::<Read<SharedDatabasePool<C>>>().unwrap();
+                                      let conn = pool.get_reader();
+                                      conn.get_session_language(&session_token)
+                                  });
+    let accept_language = req.headers
+                             .get_raw("Accept-Language")
+                             .and_then(|values| values.first())
+                             .and_then(|value| std::str::from_utf8(value).ok());
+
+    i18n::catalogs().negotiate(preference.as_deref(), accept_language, &default_language)
+}
+
+impl<C> AroundMiddleware for LanguageSelector<C> where C: MedalConnection + std::marker::Send + 'static
+{
+    fn around(self, handler: Box<dyn Handler>) -> Box<dyn Handler> {
+        Box::new(move |req: &mut Request| -> IronResult<Response> {
+            let language = request_language::<C>(req);
+            let mut res = i18n::with_language(&language, || handler.handle(req));
+            match res {
+                Ok(ref mut resp) => resp.extensions.insert::<RequestLanguage>(language),
+                Err(ref mut err) => err.response.extensions.insert::<RequestLanguage>(language),
+            };
+            res
+        })
+    }
+}
+
 #[cfg(feature = "debug")]
 pub struct RequestLogger {}
 
@@ -363,10 +490,17 @@ impl<'c, 'a: 'c, 'b: 'c + 'a, T> RequestAugmentMedalError<'c, 'a, 'b, T> for Res
 
 fn login_info(config: &Config) -> core::LoginInfo {
     core::LoginInfo { password_login: config.enable_password_login == Some(true),
+                      password_reset: Mailer::from_config(config).is_some(),
                       self_url: config.self_url.clone(),
                       oauth_providers: config.oauth_providers.clone() }
 }
 
+/// Signer of login links, if a secret for signing is configured
+fn login_link_signer(config: &Config) -> Option<LoginLinkSigner> {
+    let secret = config.login_link_secret.as_ref().or(config.cookie_signing_secret.as_ref())?;
+    Some(LoginLinkSigner::new(secret, config.login_link_validity_days.unwrap_or(90)))
+}
+
 fn greet_personal<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.get_session_token();
@@ -392,6 +526,20 @@ fn dbstatus<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
+fn metrics<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    // Like for `/status`, the secret can be given as query string. Prometheus can send it as bearer token instead.
+    let given_secret = req.url.query().map(|s| s.to_string()).or_else(|| bearer_token(req));
+
+    let metrics = with_reader![core::metrics, C, req, config.dbstatus_secret.clone(), given_secret].aug(req)?;
+
+    let mime: Mime = "text/plain; version=0.0.4; charset=utf-8".parse().unwrap();
+    let mut resp = Response::new();
+    resp.set_mut(metrics).set_mut(status::Ok).set_mut(mime);
+    Ok(resp)
+}
+
 fn debug<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.get_session_token();
@@ -456,18 +604,19 @@ fn contests<C>(req: &mut Request) -> IronResult<Response>
 
     let config = req.get::<Read<SharedConfiguration>>().unwrap();
 
-    let res = with_conn![core::show_contests, C, req, &session_token, login_info(&config), visibility];
+    let pool = req.get::<Read<SharedDatabasePool<C>>>().unwrap();
+    let mut conn = pool.get();
+    let res = core::show_contests(&*conn, &session_token, login_info(&config), visibility);
 
     if res.is_err() {
         // Database connection failed … Create a new database connection!
         // TODO: This code should be unified with the database creation code in main.rs
         println!("DATABASE CONNECTION LOST! Restarting database connection.");
-        let conn = C::reconnect(&config);
-        let mutex = req.get::<Write<SharedDatabaseConnection<C>>>().unwrap();
-        let mut sharedconn = mutex.lock().unwrap_or_else(|e| e.into_inner());
-        *sharedconn = conn;
+        // Replace the connection that failed before it is returned to the pool
+        *conn = C::reconnect(&config);
         // return ServerError();
     }
+    drop(conn);
 
     let (template, mut data) = res.unwrap();
     data.insert("config".to_string(), to_json(&config.template_params));
@@ -526,7 +675,64 @@ fn contestresults<C>(req: &mut Request) -> IronResult<Response>
     let contest_id = req.expect_int::<i32>("contestid")?;
     let session_token = req.require_session_token()?;
 
-    let (template, data) = with_conn![core::show_contest_results, C, req, contest_id, &session_token].aug(req)?;
+    let (template, data) = with_reader![core::show_contest_results, C, req, contest_id, &session_token].aug(req)?;
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn contestresults_statistics<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let disable_contest_results = config.disable_results_page.unwrap_or(false);
+
+    if disable_contest_results {
+        let mut resp = Response::new();
+        resp.set_mut(Template::new(&"nocontestresults", 2)).set_mut(status::Locked);
+        return Ok(resp);
+    }
+
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.require_session_token()?;
+
+    let (template, data) =
+        with_reader![core::show_contest_group_statistics, C, req, contest_id, group_id, &session_token].aug(req)?;
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn contestranking<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.require_session_token()?;
+
+    let filter = {
+        let query = req.get_ref::<UrlEncodedQuery>().ok();
+        let get = |key| -> Option<String> { query?.get(key)?.first().filter(|x| !x.is_empty()).map(|x| x.to_owned()) };
+        RankingFilter { grade: get("grade").and_then(|x| x.parse().ok()),
+                        group_id: get("group").and_then(|x| x.parse().ok()),
+                        school_id: get("school"),
+                        sex: get("sex").and_then(|x| x.parse().ok()) }
+    };
+
+    let (template, data) =
+        with_reader![core::show_contest_ranking, C, req, contest_id, &session_token, filter].aug(req)?;
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn contestscoreboard<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.get_session_token();
+
+    let (template, data) = with_reader![core::show_contest_scoreboard, C, req, contest_id, session_token].aug(req)?;
 
     let mut resp = Response::new();
     resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
@@ -547,7 +753,7 @@ fn contestresults_download<C>(req: &mut Request) -> IronResult<Response>
     let contest_id = req.expect_int::<i32>("contestid")?;
     let session_token = req.require_session_token()?;
 
-    let (template, data) = with_conn![core::show_contest_results, C, req, contest_id, &session_token].aug(req)?;
+    let (template, data) = with_reader![core::show_contest_results, C, req, contest_id, &session_token].aug(req)?;
 
     use iron::headers::{Charset, ContentDisposition, DispositionParam, DispositionType};
 
@@ -594,6 +800,12 @@ fn login<C>(req: &mut Request) -> IronResult<Response>
         data.insert("forward".to_string(), to_json(&query));
     }
 
+    // Links on the login sheets of groups fill in the login code
+    let code = req.get_ref::<UrlEncodedQuery>().ok().and_then(|query| query.get("code")?.first().cloned());
+    if let Some(code) = code {
+        data.insert("code".to_string(), to_json(&code));
+    }
+
     // Antwort erstellen und zurücksenden
     let mut resp = Response::new();
     resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
@@ -659,6 +871,32 @@ fn login_code_post<C>(req: &mut Request) -> IronResult<Response>
     }
 }
 
+fn login_link<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let user_id = req.expect_int::<i32>("userid")?;
+    let expires = req.expect_int::<i64>("expires")?;
+    let signature = req.expect_str("signature")?;
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let signer = login_link_signer(&config);
+    let loginresult =
+        with_conn![core::login_with_link, C, req, user_id, expires, &signature, signer.as_ref(), login_info(&config)];
+
+    match loginresult {
+        // Login successful
+        Ok(sessionkey) => {
+            req.session().set(SessionToken { token: sessionkey }).unwrap();
+            Ok(Response::with((status::Found, Redirect(url_for!(req, "greet")))))
+        }
+        // Login failed
+        Err((template, data)) => {
+            let mut resp = Response::new();
+            resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+            Ok(resp)
+        }
+    }
+}
+
 fn logout<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.get_session_token();
@@ -671,6 +909,21 @@ fn logout<C>(req: &mut Request) -> IronResult<Response>
     Ok(Response::with((status::Found, Redirect(url_for!(req, "greet")))))
 }
 
+fn logout_everywhere<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+    let csrf_token = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        iexpect!(formdata.get("csrf_token"))[0].to_owned()
+    };
+
+    let revoked_token = with_conn![core::logout_everywhere, C, req, &session_token, &csrf_token].aug(req)?;
+    let session_store = req.get::<Read<SharedSessionStore>>().unwrap();
+    with_conn![session_backend::revoke_sessions, C, req, &*session_store, &revoked_token];
+
+    Ok(Response::with((status::Found, Redirect(url_for!(req, "greet")))))
+}
+
 #[cfg(feature = "signup")]
 #[allow(clippy::extra_unused_type_parameters)]
 fn signup<C>(req: &mut Request) -> IronResult<Response>
@@ -694,7 +947,10 @@ fn signup_post<C>(req: &mut Request) -> IronResult<Response>
          iexpect!(formdata.get("password"))[0].to_owned())
     };
 
-    let signupresult = with_conn![core::signup, C, req, session_token, signupdata].aug(req)?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let mailer = Mailer::from_config(&config);
+
+    let signupresult = with_conn![core::signup, C, req, session_token, signupdata, mailer.as_ref()].aug(req)?;
     match signupresult {
         SignupResult::SignedUp => Ok(Response::with((status::Found,
                                                      Redirect(iron::Url::parse(&format!("{}?status={:?}",
@@ -846,6 +1102,55 @@ fn group_download<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
+fn group_login_sheets<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.require_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let self_url = config.self_url.clone().unwrap_or_default();
+    let sheet_config = config.login_sheets.clone().unwrap_or_default();
+    let signer = login_link_signer(&config);
+    let (groupname, pdf) = with_conn![core::group_login_sheets,
+                                      C,
+                                      req,
+                                      group_id,
+                                      &session_token,
+                                      &self_url,
+                                      &sheet_config,
+                                      signer.as_ref()].aug(req)?;
+
+    use iron::headers::{Charset, ContentDisposition, DispositionParam, DispositionType};
+
+    let filename = format!("{}.pdf", groupname).into_bytes();
+    let cd = ContentDisposition { disposition: DispositionType::Attachment,
+                                  parameters: vec![DispositionParam::Filename(Charset::Ext("Utf-8".to_string()),
+                                                                              None,
+                                                                              filename)] };
+
+    let mime: Mime = "application/pdf".parse().unwrap();
+    let mut resp = Response::with((status::Ok, mime, pdf));
+    resp.headers.set(cd);
+    Ok(resp)
+}
+
+fn group_revoke_login_links<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.expect_session_token()?;
+    let (csrf_token, user_id) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(),
+         formdata.get("user").and_then(|x| x[0].parse::<i32>().ok()))
+    };
+
+    with_conn![core::revoke_login_links, C, req, group_id, user_id, &session_token, &csrf_token].aug(req)?;
+
+    let url = url_for!(req, "group", "groupid" => format!("{}", group_id));
+    Ok(Response::with((status::Found,
+                       Redirect(iron::Url::parse(&format!("{}?status=LoginLinksRevoked", &url)).unwrap()))))
+}
+
 fn new_group<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.require_session_token()?;
@@ -866,32 +1171,107 @@ fn group_csv<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.require_session_token()?;
 
-    let si = {
-        let config = req.get::<Read<SharedConfiguration>>().unwrap();
-        core::SexInformation { require_sex: config.require_sex.unwrap_or(false),
-                               allow_sex_na: config.allow_sex_na.unwrap_or(true),
-                               allow_sex_diverse: config.allow_sex_diverse.unwrap_or(false),
-                               allow_sex_other: config.allow_sex_other.unwrap_or(true) }
-    };
+    template_ok!(with_conn![core::group_csv, C, req, &session_token].aug(req)?)
+}
 
-    template_ok!(with_conn![core::group_csv, C, req, &session_token, si].aug(req)?)
+struct GroupImportForm {
+    csrf_token: String,
+    file: Vec<u8>,
+    encoding: group_import::Encoding,
+    options: group_import::ImportOptions,
 }
 
-fn group_csv_upload<C>(req: &mut Request) -> IronResult<Response>
-    where C: MedalConnection + std::marker::Send + 'static {
-    let session_token = req.require_session_token()?;
+/// Read the forms for uploading groups: Either a file is uploaded (as base64, because the files are decoded on the
+/// server) or the decoded file is sent again from the preview, possibly with different settings.
+fn group_import_form(req: &mut Request) -> Option<GroupImportForm> {
+    use group_import::{Columns, Encoding, ImportOptions};
 
-    let (csrf_token, group_data) = {
-        let formdata = iexpect!(req.get_ref::<UrlEncodedBody>().ok());
-        (iexpect!(formdata.get("csrf_token"))[0].to_owned(), iexpect!(formdata.get("group_data"))[0].to_owned())
+    let formdata = req.get_ref::<UrlEncodedBody>().ok()?;
+    let get = |key: &str| formdata.get(key).map(|values| values[0].clone());
+
+    let csrf_token = get("csrf_token")?;
+    let (file, encoding) = match get("file_data").filter(|data| !data.is_empty()) {
+        Some(data) => (base64::decode(&data).ok()?, Encoding::from_name(&get("encoding").unwrap_or_default())?),
+        None => (get("csv_data")?.into_bytes(), Encoding::Utf8),
     };
 
-    #[cfg(feature = "debug")]
-    println!("{}", group_data);
+    let delimiter = match get("delimiter").as_deref() {
+        Some("comma") => Some(b','),
+        Some("semicolon") => Some(b';'),
+        Some("tab") => Some(b'\t'),
+        _ => None,
+    };
+    let header = match get("header").as_deref() {
+        Some("yes") => Some(true),
+        Some("no") => Some(false),
+        _ => None,
+    };
+    // The columns are only chosen in the preview
+    let column = |field: &str| get(&format!("column_{}", field)).map(|column| column.parse().ok());
+    let columns = column("group").map(|group| Columns { group,
+                                                        grade: column("grade").flatten(),
+                                                        firstname: column("firstname").flatten(),
+                                                        lastname: column("lastname").flatten(),
+                                                        sex: column("sex").flatten() });
+
+    Some(GroupImportForm { csrf_token, file, encoding, options: ImportOptions { delimiter, header, columns } })
+}
+
+fn sex_information(req: &mut Request) -> core::SexInformation {
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    core::SexInformation { require_sex: config.require_sex.unwrap_or(false),
+                           allow_sex_na: config.allow_sex_na.unwrap_or(true),
+                           allow_sex_diverse: config.allow_sex_diverse.unwrap_or(false),
+                           allow_sex_other: config.allow_sex_other.unwrap_or(true) }
+}
 
-    with_conn![core::upload_groups, C, req, &session_token, &csrf_token, &group_data].aug(req)?;
+fn group_csv_preview<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.require_session_token()?;
+    let form = iexpect!(group_import_form(req));
+    let si = sex_information(req);
+
+    template_ok!(with_conn![core::preview_groups,
+                            C,
+                            req,
+                            &session_token,
+                            &form.csrf_token,
+                            &form.file,
+                            form.encoding,
+                            form.options,
+                            si].aug(req)?)
+}
 
-    Ok(Response::with((status::Found, Redirect(url_for!(req, "groups")))))
+fn group_csv_upload<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.require_session_token()?;
+    let form = iexpect!(group_import_form(req));
+    let si = sex_information(req);
+
+    let created = with_conn![core::upload_groups,
+                             C,
+                             req,
+                             &session_token,
+                             &form.csrf_token,
+                             &form.file,
+                             form.encoding,
+                             form.options.clone(),
+                             si.clone()].aug(req)?;
+
+    if created {
+        Ok(Response::with((status::Found, Redirect(url_for!(req, "groups")))))
+    } else {
+        // Show the errors again instead of creating some of the groups
+        template_ok!(with_conn![core::preview_groups,
+                                C,
+                                req,
+                                &session_token,
+                                &form.csrf_token,
+                                &form.file,
+                                form.encoding,
+                                form.options,
+                                si].aug(req)?)
+    }
 }
 
 fn contest_admissioncsv<C>(req: &mut Request) -> IronResult<Response>
@@ -933,6 +1313,7 @@ fn profile<C>(req: &mut Request) -> IronResult<Response>
 
     let (template, mut data) = with_conn![core::show_profile, C, req, &session_token, None, query_string, si].aug(req)?;
     data.insert("config".to_string(), to_json(&config.template_params));
+    data.insert("mail_enabled".to_string(), to_json(&Mailer::from_config(&config).is_some()));
 
     let mut resp = Response::new();
     resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
@@ -971,6 +1352,111 @@ fn profile_post<C>(req: &mut Request) -> IronResult<Response>
                                                           profilechangeresult)).unwrap()))))
 }
 
+fn profile_email_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+    let (csrf_token, email) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(), iexpect!(formdata.get("email"))[0].to_owned())
+    };
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let mailer = Mailer::from_config(&config);
+
+    let result = with_conn![core::change_email, C, req, &session_token, &csrf_token, &email, mailer.as_ref()].aug(req)?;
+
+    Ok(Response::with((status::Found,
+                       Redirect(iron::Url::parse(&format!("{}?status={:?}", &url_for!(req, "myprofile"), result))
+                                .unwrap()))))
+}
+
+fn profile_language_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+    let (csrf_token, language) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(), iexpect!(formdata.get("language"))[0].to_owned())
+    };
+
+    with_conn![core::set_language, C, req, &session_token, &csrf_token, &language].aug(req)?;
+
+    Ok(Response::with((status::Found, Redirect(url_for!(req, "myprofile")))))
+}
+
+fn email_confirm<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let code = req.expect_str("code")?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (template, mut data) = with_conn![core::confirm_email, C, req, &code].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+#[allow(clippy::extra_unused_type_parameters)]
+fn password_reset<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let mailer = Mailer::from_config(&config);
+
+    let (template, mut data) = core::show_password_reset_request(mailer.as_ref()).aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn password_reset_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let name = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        iexpect!(formdata.get("name"))[0].to_owned()
+    };
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let mailer = Mailer::from_config(&config);
+
+    let (template, mut data) = with_conn![core::request_password_reset, C, req, &name, mailer.as_ref()].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn password_reset_token<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let token = req.expect_str("token")?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (template, mut data) = with_conn![core::show_password_reset, C, req, &token].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn password_reset_token_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let token = req.expect_str("token")?;
+    let (password, password_repeat) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("password"))[0].to_owned(), iexpect!(formdata.get("password_repeat"))[0].to_owned())
+    };
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (template, mut data) = with_conn![core::reset_password, C, req, &token, &password, &password_repeat].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
 fn user<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let user_id = req.expect_int::<i32>("userid")?;
@@ -1112,22 +1598,56 @@ fn admin_user<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
-fn admin_group<C>(req: &mut Request) -> IronResult<Response>
+fn admin_user_roles_post<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
-    let group_id = req.expect_int::<i32>("groupid")?;
+    let user_id = req.expect_int::<i32>("userid")?;
     let session_token = req.expect_session_token()?;
-    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let (csrf_token, roles) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(), formdata.get("role").cloned().unwrap_or_default())
+    };
 
-    let csrf_token = if let Ok(formdata) = req.get_ref::<UrlEncodedBody>() {
-        formdata.get("csrf_token").map(|x| x[0].to_owned())
-    } else {
+    with_conn![core::admin_set_user_roles, C, req, user_id, &roles, &session_token, &csrf_token].aug(req)?;
+
+    Ok(Response::with((status::Found, Redirect(url_for!(req, "admin_user", "userid" => format!("{}",user_id))))))
+}
+
+fn admin_user_sessions_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let user_id = req.expect_int::<i32>("userid")?;
+    let session_token = req.expect_session_token()?;
+    let csrf_token = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        iexpect!(formdata.get("csrf_token"))[0].to_owned()
+    };
+
+    let revoked_token =
+        with_conn![core::admin_revoke_user_sessions, C, req, user_id, &session_token, &csrf_token].aug(req)?;
+    if let Some(revoked_token) = revoked_token {
+        let session_store = req.get::<Read<SharedSessionStore>>().unwrap();
+        with_conn![session_backend::revoke_sessions, C, req, &*session_store, &revoked_token];
+    }
+
+    Ok(Response::with((status::Found, Redirect(url_for!(req, "admin_user", "userid" => format!("{}",user_id))))))
+}
+
+fn admin_group<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let query_string = req.url.query().map(|s| s.to_string());
+
+    let csrf_token = if let Ok(formdata) = req.get_ref::<UrlEncodedBody>() {
+        formdata.get("csrf_token").map(|x| x[0].to_owned())
+    } else {
         None
     };
 
     let (template, mut data) = if let Some(csrf_token) = csrf_token {
         with_conn![core::admin_delete_group, C, req, group_id, &session_token, &csrf_token].aug(req)?
     } else {
-        with_conn![core::admin_show_group, C, req, group_id, &session_token].aug(req)?
+        with_conn![core::admin_show_group, C, req, group_id, &session_token, query_string].aug(req)?
     };
     data.insert("config".to_string(), to_json(&config.template_params));
 
@@ -1136,6 +1656,86 @@ fn admin_group<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
+fn group_teachers_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.expect_session_token()?;
+    let (csrf_token, action, invitee, teacher_id) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(),
+         iexpect!(formdata.get("action"))[0].to_owned(),
+         formdata.get("invitee").map(|x| x[0].to_owned()).unwrap_or_default(),
+         formdata.get("teacher").and_then(|x| x[0].parse::<i32>().ok()))
+    };
+
+    let result = match (action.as_str(), teacher_id) {
+        ("invite", _) => {
+            with_conn![core::invite_group_admin, C, req, group_id, &invitee, &session_token, &csrf_token].aug(req)?
+        }
+        ("remove", Some(teacher_id)) => {
+            with_conn![core::remove_group_admin, C, req, group_id, teacher_id, &session_token, &csrf_token].aug(req)?
+        }
+        ("transfer", Some(teacher_id)) => with_conn![core::transfer_group_ownership,
+                                                     C,
+                                                     req,
+                                                     group_id,
+                                                     teacher_id,
+                                                     &session_token,
+                                                     &csrf_token].aug(req)?,
+        ("accept", _) => {
+            with_conn![core::answer_group_invitation, C, req, group_id, true, &session_token, &csrf_token].aug(req)?
+        }
+        ("decline", _) => {
+            with_conn![core::answer_group_invitation, C, req, group_id, false, &session_token, &csrf_token].aug(req)?
+        }
+        _ => Err(core::MedalError::UnknownId).aug(req)?,
+    };
+
+    // Teachers that declined an invitation or left the group can no longer see it
+    let url = match result {
+        core::GroupAdminStatus::Declined | core::GroupAdminStatus::Left => url_for!(req, "groups"),
+        _ => url_for!(req, "group", "groupid" => format!("{}", group_id)),
+    };
+
+    Ok(Response::with((status::Found, Redirect(iron::Url::parse(&format!("{}?status={:?}", &url, result)).unwrap()))))
+}
+
+fn admin_participation_time_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let user_id = req.expect_int::<i32>("userid")?;
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.expect_session_token()?;
+    let (csrf_token, action, minutes) = {
+        let formdata = itry!(req.get_ref::<UrlEncodedBody>());
+        (iexpect!(formdata.get("csrf_token"))[0].to_owned(),
+         iexpect!(formdata.get("action"))[0].to_owned(),
+         formdata.get("minutes").and_then(|x| x[0].parse::<i32>().ok()))
+    };
+
+    let adjustment = match (action.as_str(), minutes) {
+        ("extra_time", Some(minutes)) => core::TimeAdjustment::ExtraTime(minutes),
+        ("pause", _) => core::TimeAdjustment::Pause,
+        ("resume", _) => core::TimeAdjustment::Resume,
+        ("restart", _) => core::TimeAdjustment::Restart,
+        _ => Err(core::MedalError::UnknownId).aug(req)?,
+    };
+
+    with_conn![core::admin_adjust_participation_time,
+               C,
+               req,
+               user_id,
+               contest_id,
+               adjustment,
+               &session_token,
+               &csrf_token].aug(req)?;
+
+    Ok(Response::with((status::Found,
+                       Redirect(url_for!(req,
+                                         "admin_participation",
+                                         "userid" => format!("{}", user_id),
+                                         "contestid" => format!("{}", contest_id))))))
+}
+
 fn admin_participation<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let user_id = req.expect_int::<i32>("userid")?;
@@ -1161,6 +1761,29 @@ fn admin_participation<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
+fn submission_history<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let user_id = req.expect_int::<i32>("userid")?;
+    let task_id = req.expect_int::<i32>("taskid")?;
+    let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let compare = if let Ok(query) = req.get_ref::<UrlEncodedQuery>() {
+        let get = |key: &str| query.get(key).and_then(|x| x[0].parse::<i32>().ok());
+        get("left").and_then(|left| Some((left, get("right")?)))
+    } else {
+        None
+    };
+
+    let (template, mut data) =
+        with_conn![core::show_submission_history, C, req, user_id, task_id, &session_token, compare].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
 fn admin_contests<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let session_token = req.expect_session_token()?;
@@ -1178,10 +1801,149 @@ fn admin_export_contest<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let contest_id = req.expect_int::<i32>("contestid")?;
     let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
 
-    let filename = with_conn![core::admin_contest_export, C, req, contest_id, &session_token].aug(req)?;
+    let (template, mut data) =
+        with_conn![core::admin_show_contest_export, C, req, contest_id, &session_token].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
 
-    Ok(Response::with((status::Found, RedirectRaw(format!("/export/{}", filename)))))
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+/// Response body that writes the contest export while the response is being sent, so the export never has to be
+/// stored in memory or on disk completely. A connection is only checked out while a batch of participations is read,
+/// so a slow download does not keep it from other requests.
+struct ContestExportBody<C>
+    where C: MedalConnection
+{
+    pool: std::sync::Arc<ConnectionPool<C>>,
+    contest: Contest,
+    options: ExportOptions,
+}
+
+impl<C> iron::response::WriteBody for ContestExportBody<C> where C: MedalConnection + std::marker::Send + 'static
+{
+    fn write_body(&mut self, res: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let pool = &self.pool;
+        export::write_contest_results(|| pool.get_reader(), &self.contest, &self.options, res)
+    }
+}
+
+fn admin_export_contest_download<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.expect_session_token()?;
+
+    let options = {
+        let query = req.get_ref::<UrlEncodedQuery>().ok();
+        let get = |key: &str| query.and_then(|q| q.get(key)).map(|x| x[0].as_str());
+        let all_columns = || export::EXPORT_COLUMNS.iter().map(|c| c.to_string()).collect();
+        ExportOptions { format: get("format").and_then(ExportFormat::from_name).unwrap_or(ExportFormat::Csv),
+                        columns: query.and_then(|q| q.get("column")).cloned().unwrap_or_else(all_columns),
+                        subtasks: get("subtasks").is_some(),
+                        submission_counts: get("submissions").is_some() }
+    };
+
+    let (contest, filename) =
+        with_conn![core::admin_contest_export, C, req, contest_id, &session_token, options.format].aug(req)?;
+
+    use iron::headers::{Charset, ContentDisposition, DispositionParam, DispositionType};
+
+    let cd = ContentDisposition { disposition: DispositionType::Attachment,
+                                  parameters: vec![DispositionParam::Filename(Charset::Ext("Utf-8".to_string()),
+                                                                              None,
+                                                                              filename.as_bytes().to_vec())] };
+
+    let mime: Mime = options.format.mime_type().parse().unwrap();
+    let body: Box<dyn iron::response::WriteBody> =
+        Box::new(ContestExportBody { pool: req.get::<Read<SharedDatabasePool<C>>>().unwrap(), contest, options });
+
+    let mut resp = Response::new();
+    resp.headers.set(cd);
+    resp.set_mut(body).set_mut(status::Ok).set_mut(mime);
+    Ok(resp)
+}
+
+fn admin_audit<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (user_id, group_id, from, until) = if let Ok(query) = req.get_ref::<UrlEncodedQuery>() {
+        let get = |key: &str| query.get(key).map(|x| x[0].trim().to_owned()).filter(|x| !x.is_empty());
+        (get("user").and_then(|x| x.parse::<i32>().ok()),
+         get("group").and_then(|x| x.parse::<i32>().ok()),
+         get("from"),
+         get("until"))
+    } else {
+        (None, None, None, None)
+    };
+
+    let (template, mut data) =
+        with_conn![core::admin_show_audit_log, C, req, &session_token, user_id, group_id, from, until].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn report_range_query(req: &mut Request) -> (Option<String>, Option<String>) {
+    if let Ok(query) = req.get_ref::<UrlEncodedQuery>() {
+        let get = |key: &str| query.get(key).map(|x| x[0].trim().to_owned()).filter(|x| !x.is_empty());
+        (get("from"), get("until"))
+    } else {
+        (None, None)
+    }
+}
+
+fn admin_reports<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (from, until) = report_range_query(req);
+
+    let (template, mut data) = with_reader![core::admin_show_reports, C, req, &session_token, from, until].aug(req)?;
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
+fn admin_reports_download<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.expect_session_token()?;
+
+    let (from, until) = report_range_query(req);
+    let format = req.get_ref::<UrlEncodedQuery>()
+                    .ok()
+                    .and_then(|query| query.get("format"))
+                    .and_then(|format| ExportFormat::from_name(&format[0]))
+                    .unwrap_or(ExportFormat::Csv);
+
+    let (report, filename) =
+        with_reader![core::admin_report_export, C, req, &session_token, from, until, format].aug(req)?;
+
+    let mut body = Vec::new();
+    itry!(reporting::write_report(&report, format, &mut body));
+
+    use iron::headers::{Charset, ContentDisposition, DispositionParam, DispositionType};
+
+    let cd = ContentDisposition { disposition: DispositionType::Attachment,
+                                  parameters: vec![DispositionParam::Filename(Charset::Ext("Utf-8".to_string()),
+                                                                              None,
+                                                                              filename.as_bytes().to_vec())] };
+
+    let mime: Mime = format.mime_type().parse().unwrap();
+
+    let mut resp = Response::new();
+    resp.headers.set(cd);
+    resp.set_mut(body).set_mut(status::Ok).set_mut(mime);
+    Ok(resp)
 }
 
 fn admin_cleanup<C>(req: &mut Request) -> IronResult<Response>
@@ -1212,6 +1974,32 @@ fn admin_cleanup<C>(req: &mut Request) -> IronResult<Response>
     Ok(resp)
 }
 
+fn admin_contest_scan<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static,
+          Contest: MedalObject<C>
+{
+    let session_token = req.expect_session_token()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let csrf_token = if let Ok(formdata) = req.get_ref::<UrlEncodedBody>() {
+        formdata.get("csrf_token").map(|x| x[0].to_owned())
+    } else {
+        None
+    };
+
+    let (template, mut data) = if let Some(csrf_token) = csrf_token {
+        let task_dir = format!("{}/", TASK_DIR);
+        with_conn![core::admin_do_contest_scan, C, req, &session_token, &csrf_token, &task_dir].aug(req)?
+    } else {
+        with_conn![core::admin_show_contest_scan, C, req, &session_token].aug(req)?
+    };
+    data.insert("config".to_string(), to_json(&config.template_params));
+
+    let mut resp = Response::new();
+    resp.set_mut(Template::new(&template, data)).set_mut(status::Ok);
+    Ok(resp)
+}
+
 fn dbcleanup<C>(req: &mut Request) -> IronResult<Response>
     where C: MedalConnection + std::marker::Send + 'static {
     let (template, data) = with_conn![core::do_session_cleanup, C, req,].aug(req)?;
@@ -1254,6 +2042,7 @@ fn oauth<C>(req: &mut Request) -> IronResult<Response>
 
     let user_data_result = match oauth_provider.medal_oauth_type.as_ref() {
         "pms" => oauth_pms(req, oauth_provider, school_id.as_ref()).aug(req)?,
+        "oidc" => oauth_oidc(req, oauth_provider).aug(req)?,
         _ => return Ok(Response::with(iron::status::NotFound)),
     };
 
@@ -1265,8 +2054,8 @@ fn oauth<C>(req: &mut Request) -> IronResult<Response>
 
     let oauthloginresult = {
         // hier ggf. Daten aus dem Request holen
-        let mutex = req.get::<Write<SharedDatabaseConnection<C>>>().unwrap();
-        let conn = mutex.lock().unwrap_or_else(|e| e.into_inner());
+        let pool = req.get::<Read<SharedDatabasePool<C>>>().unwrap();
+        let conn = pool.get();
 
         // Antwort erstellen und zurücksenden
         core::login_oauth(&*conn, user_data, oauth_id)
@@ -1490,16 +2279,532 @@ fn oauth_pms(req: &mut Request, oauth_provider: OauthProvider, selected_school_i
                                   lastname: user_data.lastName }))
 }
 
-// Share Database connection between workers
+#[derive(Debug)]
+struct OauthState {
+    provider_id: String,
+    state: String,
+    code_verifier: String,
+}
+impl iron_sessionstorage::Value for OauthState {
+    fn get_key() -> &'static str { "medal_oauth_state" }
+    fn into_raw(self) -> String {
+        if self.state.is_empty() {
+            String::new()
+        } else {
+            format!("{} {} {}", self.state, self.code_verifier, self.provider_id)
+        }
+    }
+    fn from_raw(value: String) -> Option<Self> {
+        let mut parts = value.splitn(3, ' ');
+        Some(OauthState { state: parts.next().filter(|s| !s.is_empty())?.to_string(),
+                          code_verifier: parts.next()?.to_string(),
+                          provider_id: parts.next()?.to_string() })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct OidcDiscovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OidcAccess {
+    access_token: String,
+}
+
+/// Compute the PKCE code challenge for the method `S256` (RFC 7636)
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::default();
+    hasher.input(code_verifier.as_bytes());
+
+    base64::encode_config(hasher.result(), base64::URL_SAFE_NO_PAD)
+}
+
+fn oauth_oidc(req: &mut Request, oauth_provider: OauthProvider)
+              -> Result<Result<core::ForeignUserData, Response>, core::MedalError> {
+    use core::{UserSex, UserType};
+    use params::{Params, Value};
+    use rand::{distributions::Alphanumeric, thread_rng, Rng};
+
+    fn er(e: &str) -> core::MedalError { core::MedalError::OauthError(e.to_string()) }
+    fn e<T>(e: &str) -> Result<T, core::MedalError> { Err::<T, _>(er(e)) }
+
+    let client = reqwest::Client::new();
+
+    let (authorization_endpoint, token_endpoint, userinfo_endpoint) = match oauth_provider.discovery_url {
+        Some(ref discovery_url) => {
+            let discovery: OidcDiscovery = client.get(discovery_url).send().or(e("#d0"))?.json().or(e("#d1"))?;
+            (discovery.authorization_endpoint, discovery.token_endpoint, discovery.userinfo_endpoint)
+        }
+        None => {
+            (oauth_provider.url.clone(), oauth_provider.access_token_url.clone(), oauth_provider.user_data_url.clone())
+        }
+    };
+
+    let redirect_uri = {
+        let config = req.get::<Read<SharedConfiguration>>().unwrap();
+        format!("{}/oauth/{}/", config.self_url.clone().unwrap_or_default(), oauth_provider.provider_id)
+    };
+
+    let (has_error, code, state) = {
+        let map = req.get_ref::<Params>().unwrap();
+        let get = |key: &str| match map.find(&[key]) {
+            Some(Value::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+        (get("error").is_some(), get("code"), get("state"))
+    };
+
+    let (code, state) = match (has_error, code, state) {
+        // The identity provider refused the login
+        (true, _, _) => return e("#80"),
+        (false, Some(code), Some(state)) => (code, state),
+        // No answer of the identity provider yet, so this is the start of the login
+        (false, None, None) => {
+            let state: String = thread_rng().sample_iter(&Alphanumeric).take(32).collect();
+            let code_verifier: String = thread_rng().sample_iter(&Alphanumeric).take(64).collect();
+            let code_challenge = pkce_challenge(&code_verifier);
+
+            let url = reqwest::Url::parse_with_params(&authorization_endpoint,
+                                                      &[("response_type", "code"),
+                                                        ("client_id", oauth_provider.client_id.as_str()),
+                                                        ("redirect_uri", redirect_uri.as_str()),
+                                                        ("scope",
+                                                         oauth_provider.scope.as_deref().unwrap_or("openid profile")),
+                                                        ("state", state.as_str()),
+                                                        ("code_challenge", code_challenge.as_str()),
+                                                        ("code_challenge_method", "S256")]).or(e("#81"))?;
+
+            req.session().set(OauthState { provider_id: oauth_provider.provider_id, state, code_verifier }).unwrap();
+            return Ok(Err(Response::with((status::Found, RedirectRaw(url.to_string())))));
+        }
+        _ => return e("#82"),
+    };
+
+    // The state can only be used once
+    let oauth_state = req.session().get::<OauthState>().unwrap().ok_or(er("#83"))?;
+    req.session()
+       .set(OauthState { provider_id: String::new(), state: String::new(), code_verifier: String::new() })
+       .unwrap();
+    if oauth_state.provider_id != oauth_provider.provider_id || oauth_state.state != state {
+        return e("#84");
+    }
+
+    let params = [("grant_type", "authorization_code"),
+                  ("code", code.as_str()),
+                  ("redirect_uri", redirect_uri.as_str()),
+                  ("client_id", oauth_provider.client_id.as_str()),
+                  ("code_verifier", oauth_state.code_verifier.as_str())];
+    let res = client.post(&token_endpoint)
+                    .basic_auth(&oauth_provider.client_id, Some(&oauth_provider.client_secret))
+                    .form(&params)
+                    .send()
+                    .and_then(|r| r.error_for_status());
+    let access: OidcAccess = res.or(e("#00"))?.json().or(e("#01"))?;
+
+    let res = client.get(&userinfo_endpoint).bearer_auth(access.access_token).send().and_then(|r| r.error_for_status());
+    let claims: serde_json::Value = res.or(e("#10"))?.json().or(e("#11"))?;
+
+    let mapping = oauth_provider.claim_mapping.unwrap_or_default();
+    let claim = |name: &Option<String>, default: &str| -> Option<String> {
+        match claims.get(name.as_deref().unwrap_or(default))? {
+            serde_json::Value::String(value) => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            _ => None,
+        }
+    };
+
+    let user_sex = match claim(&mapping.gender, "gender").as_deref() {
+        Some("male") | Some("m") | Some("M") => UserSex::Male,
+        Some("female") | Some("f") | Some("F") | Some("w") | Some("W") => UserSex::Female,
+        _ => UserSex::Unknown,
+    };
+
+    // The role claim can contain a single role or a list of roles. The most privileged role found in the role
+    // mapping wins, users without a mapped role are students.
+    let roles: Vec<String> = match mapping.role.as_ref().and_then(|role| claims.get(role)) {
+        Some(serde_json::Value::String(role)) => vec![role.clone()],
+        Some(serde_json::Value::Array(roles)) => {
+            roles.iter().filter_map(|r| r.as_str()).map(|r| r.to_string()).collect()
+        }
+        _ => Vec::new(),
+    };
+    let role_mapping = oauth_provider.role_mapping.unwrap_or_default();
+    let mapped_roles: Vec<&str> = roles.iter().filter_map(|role| role_mapping.get(role)).map(|r| r.as_str()).collect();
+    let user_type = if mapped_roles.contains(&"admin") {
+        UserType::Admin
+    } else if mapped_roles.contains(&"teacher") {
+        UserType::Teacher
+    } else {
+        UserType::User
+    };
+
+    Ok(Ok(core::ForeignUserData { foreign_id: claim(&mapping.foreign_id, "sub").ok_or(er("#60"))?,
+                                  foreign_type: user_type,
+                                  sex: user_sex,
+                                  firstname: claim(&mapping.firstname, "given_name").unwrap_or_default(),
+                                  lastname: claim(&mapping.lastname, "family_name").unwrap_or_default() }))
+}
+
+/* JSON API
+ *
+ * The handlers below mirror the HTML routes but return the data of the core functions as JSON instead of rendering it
+ * with handlebars. Errors are reported with a matching HTTP status code and a JSON body `{"error": "…"}`.
+ *
+ * The session token is taken from the `Authorization: Bearer <token>` header or, if missing, from the session cookie.
+ * Instead of a `csrf_token` form field, modifying requests have to send the token in the `X-CSRF-Token` header.
+ * Request bodies are JSON and are read before any check, so an early error does not break keep-alive connections.
+ */
+fn api_response<T: serde::Serialize>(status: status::Status, data: &T) -> IronResult<Response> {
+    Ok(Response::with((status, mime!(Application / Json), serde_json::to_string(data).unwrap())))
+}
+
+fn api_error(me: core::MedalError) -> IronError {
+    let (status, error) = match me {
+        core::MedalError::NotLoggedIn => (status::Unauthorized, "not_logged_in".to_string()),
+        core::MedalError::AccessDenied => (status::Forbidden, "access_denied".to_string()),
+        core::MedalError::CsrfCheckFailed => (status::Forbidden, "csrf_check_failed".to_string()),
+        core::MedalError::SessionTimeout => (status::Unauthorized, "session_timeout".to_string()),
+        core::MedalError::DatabaseError => (status::InternalServerError, "database_error".to_string()),
+        core::MedalError::ConfigurationError => (status::InternalServerError, "configuration_error".to_string()),
+        core::MedalError::DatabaseConnectionError => {
+            (status::InternalServerError, "database_connection_error".to_string())
+        }
+        core::MedalError::PasswordHashingError => (status::InternalServerError, "password_hashing_error".to_string()),
+        core::MedalError::UnmatchedPasswords => (status::BadRequest, "unmatched_passwords".to_string()),
+        core::MedalError::NotFound => (status::NotFound, "not_found".to_string()),
+        core::MedalError::AccountIncomplete => (status::Forbidden, "account_incomplete".to_string()),
+        core::MedalError::UnknownId => (status::NotFound, "unknown_id".to_string()),
+        core::MedalError::OauthError(errstr) => (status::Unauthorized, format!("oauth_error {}", errstr)),
+    };
+
+    let mut data = json_val::Map::new();
+    data.insert("error".to_string(), to_json(&error));
+
+    IronError { error: Box::new(SessionError { message: format!("API error: {}", error) }),
+                response: Response::with((status, mime!(Application / Json), serde_json::to_string(&data).unwrap())) }
+}
+
+fn api_bad_request(message: &str) -> IronError {
+    let mut data = json_val::Map::new();
+    data.insert("error".to_string(), to_json(&"bad_request"));
+    data.insert("message".to_string(), to_json(&message));
+
+    IronError { error: Box::new(SessionError { message: format!("API error: {}", message) }),
+                response: Response::with((status::BadRequest,
+                                          mime!(Application / Json),
+                                          serde_json::to_string(&data).unwrap())) }
+}
+
+/// Failed authentication, as opposed to `access_denied` for a logged in user that is not allowed to do something
+fn api_login_failed() -> IronError {
+    let mut data = json_val::Map::new();
+    data.insert("error".to_string(), to_json(&"login_failed"));
+
+    let mut response =
+        Response::with((status::Unauthorized, mime!(Application / Json), serde_json::to_string(&data).unwrap()));
+    response.headers.set_raw("WWW-Authenticate", vec![b"Bearer".to_vec()]);
+
+    IronError { error: Box::new(SessionError { message: "API error: login_failed".to_string() }), response }
+}
+
+/// Token from the `Authorization: Bearer <token>` header
+fn bearer_token(req: &Request) -> Option<String> {
+    req.headers
+       .get_raw("Authorization")
+       .and_then(|values| values.first())
+       .and_then(|value| std::str::from_utf8(value).ok())
+       .and_then(|value| value.trim().strip_prefix("Bearer "))
+       .map(|token| token.trim().to_string())
+}
+
+trait RequestApi {
+    fn api_session_token(&mut self) -> IronResult<String>;
+    fn api_csrf_token(&mut self) -> IronResult<String>;
+    fn api_body<T: serde::de::DeserializeOwned>(&mut self) -> IronResult<T>;
+}
+
+impl<'a, 'b> RequestApi for Request<'a, 'b> {
+    fn api_session_token(&mut self) -> IronResult<String> {
+        match bearer_token(self) {
+            Some(token) => Ok(token),
+            None => self.get_session_token().ok_or_else(|| api_error(core::MedalError::NotLoggedIn)),
+        }
+    }
+
+    fn api_csrf_token(&mut self) -> IronResult<String> {
+        self.headers
+            .get_raw("X-CSRF-Token")
+            .and_then(|values| values.first())
+            .and_then(|value| std::str::from_utf8(value).ok())
+            .map(|token| token.trim().to_string())
+            .ok_or_else(|| api_error(core::MedalError::CsrfCheckFailed))
+    }
+
+    fn api_body<T: serde::de::DeserializeOwned>(&mut self) -> IronResult<T> {
+        use std::io::Read;
+
+        let mut body = String::new();
+        self.body.read_to_string(&mut body).map_err(|_| api_bad_request("Could not read request body"))?;
+        serde_json::from_str(&body).map_err(|e| api_bad_request(&format!("Invalid JSON body: {}", e)))
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiLogin {
+    username: Option<String>,
+    password: Option<String>,
+    code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiContestStart {
+    secret: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiNewGroup {
+    name: String,
+    tag: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiProfile {
+    firstname: String,
+    lastname: String,
+    street: Option<String>,
+    zip: Option<String>,
+    city: Option<String>,
+    password: Option<String>,
+    password_repeat: Option<String>,
+    grade: i32,
+    sex: Option<i32>,
+}
+
+fn api_login<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let login: ApiLogin = req.api_body()?;
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let session_token = match login {
+        ApiLogin { username: Some(username), password: Some(password), .. } => {
+            with_conn![core::login, C, req, (username, password), login_info(&config)].ok()
+        }
+        ApiLogin { code: Some(code), .. } => {
+            with_conn![core::login_with_code, C, req, &code, login_info(&config)].ok().map(|r| r.unwrap_or_else(|e| e))
+        }
+        _ => return Err(api_bad_request("Either username and password or code required")),
+    };
+
+    let session_token = session_token.ok_or_else(api_login_failed)?;
+    req.session().set(SessionToken { token: session_token.clone() }).unwrap();
+
+    let mut data = json_val::Map::new();
+    data.insert("session_token".to_string(), to_json(&session_token));
+    api_response(status::Ok, &data)
+}
+
+fn api_logout<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.api_session_token()?;
+    let csrf_token = req.api_csrf_token()?;
+
+    with_conn![core::logout_with_csrf_check, C, req, &session_token, &csrf_token].map_err(api_error)?;
+
+    api_response(status::Ok, &json_val::Map::new())
+}
+
+fn api_contests<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.api_session_token()?;
+    // Same values as the `filter` of the HTML contest list, e.g. `/contest/?filter=open`
+    let visibility = match req.get_ref::<UrlEncodedQuery>().ok().and_then(|query| query.get("visibility")?.first()) {
+        None => core::ContestVisibility::All,
+        Some(visibility) => match visibility.as_str() {
+            "all" => core::ContestVisibility::All,
+            "open" => core::ContestVisibility::Open,
+            "current" => core::ContestVisibility::Current,
+            "challenge" => core::ContestVisibility::LoginRequired,
+            "standalone_task" => core::ContestVisibility::StandaloneTask,
+            _ => return Err(api_bad_request(&format!("Unknown visibility '{}'", visibility))),
+        },
+    };
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+
+    let (_, data) =
+        with_conn![core::show_contests, C, req, &session_token, login_info(&config), visibility].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_contest<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.api_session_token()?;
+    let query_string = req.url.query().map(|s| s.to_string());
+    let secret: Option<String> = (|| -> Option<String> {
+        req.get_ref::<UrlEncodedQuery>().ok()?.get("secret")?.first().map(|x| x.to_owned())
+    })();
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let res = with_conn![core::show_contest,
+                         C,
+                         req,
+                         contest_id,
+                         &session_token,
+                         query_string,
+                         login_info(&config),
+                         secret].map_err(api_error)?;
+
+    match res {
+        Err(task_id) => {
+            let mut data = json_val::Map::new();
+            data.insert("task_id".to_string(), to_json(&task_id));
+            api_response(status::Ok, &data)
+        }
+        Ok((_, data)) => api_response(status::Ok, &data),
+    }
+}
+
+fn api_contest_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let start: ApiContestStart = req.api_body()?;
+    let session_token = req.api_session_token()?;
+    let csrf_token = req.api_csrf_token()?;
+
+    with_conn![core::start_contest, C, req, contest_id, &session_token, &csrf_token, start.secret.clone()]
+        .map_err(api_error)?;
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let res = with_conn![core::show_contest,
+                         C,
+                         req,
+                         contest_id,
+                         &session_token,
+                         None,
+                         login_info(&config),
+                         start.secret].map_err(api_error)?;
+
+    match res {
+        Err(task_id) => {
+            let mut data = json_val::Map::new();
+            data.insert("task_id".to_string(), to_json(&task_id));
+            api_response(status::Created, &data)
+        }
+        Ok((_, data)) => api_response(status::Created, &data),
+    }
+}
+
+fn api_contest_results<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    if config.disable_results_page.unwrap_or(false) {
+        return Err(api_error(core::MedalError::NotFound));
+    }
+
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.api_session_token()?;
+
+    let (_, data) = with_reader![core::show_contest_results, C, req, contest_id, &session_token].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_groups<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.api_session_token()?;
+
+    let (_, data) = with_conn![core::show_groups, C, req, &session_token].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_groups_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group: ApiNewGroup = req.api_body()?;
+    let session_token = req.api_session_token()?;
+    let csrf_token = req.api_csrf_token()?;
+
+    let group_id = with_conn![core::add_group,
+                              C,
+                              req,
+                              &session_token,
+                              &csrf_token,
+                              group.name,
+                              group.tag.unwrap_or_default()].map_err(api_error)?;
+
+    let (_, data) = with_conn![core::show_group, C, req, group_id, &session_token].map_err(api_error)?;
+
+    api_response(status::Created, &data)
+}
+
+fn api_group<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let group_id = req.expect_int::<i32>("groupid")?;
+    let session_token = req.api_session_token()?;
+
+    let (_, data) = with_conn![core::show_group, C, req, group_id, &session_token].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_participation<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let user_id = req.expect_int::<i32>("userid")?;
+    let contest_id = req.expect_int::<i32>("contestid")?;
+    let session_token = req.api_session_token()?;
+
+    let (_, data) =
+        with_conn![core::admin_show_participation, C, req, user_id, contest_id, &session_token].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_profile<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let session_token = req.api_session_token()?;
+
+    let config = req.get::<Read<SharedConfiguration>>().unwrap();
+    let si = core::SexInformation { require_sex: config.require_sex.unwrap_or(false),
+                                    allow_sex_na: config.allow_sex_na.unwrap_or(true),
+                                    allow_sex_diverse: config.allow_sex_diverse.unwrap_or(false),
+                                    allow_sex_other: config.allow_sex_other.unwrap_or(true) };
+
+    let (_, data) = with_conn![core::show_profile, C, req, &session_token, None, None, si].map_err(api_error)?;
+
+    api_response(status::Ok, &data)
+}
+
+fn api_profile_post<C>(req: &mut Request) -> IronResult<Response>
+    where C: MedalConnection + std::marker::Send + 'static {
+    let p: ApiProfile = req.api_body()?;
+    let session_token = req.api_session_token()?;
+    let csrf_token = req.api_csrf_token()?;
+
+    let profile = (p.firstname, p.lastname, p.street, p.zip, p.city, p.password, p.password_repeat, p.grade, p.sex);
+    let profilechangeresult =
+        with_conn![core::edit_profile, C, req, &session_token, None, &csrf_token, profile].map_err(api_error)?;
+
+    let mut data = json_val::Map::new();
+    data.insert("status".to_string(), to_json(&String::from(profilechangeresult)));
+    api_response(status::Ok, &data)
+}
+
+// Share the database connections between workers
 #[derive(Copy, Clone)]
-pub struct SharedDatabaseConnection<C>
+pub struct SharedDatabasePool<C>
     where C: MedalConnection
 {
     phantom: std::marker::PhantomData<C>,
 }
-impl<C> Key for SharedDatabaseConnection<C> where C: MedalConnection + 'static
+impl<C> Key for SharedDatabasePool<C> where C: MedalConnection + 'static
 {
-    type Value = C;
+    type Value = ConnectionPool<C>;
 }
 
 // Share Configuration between workers
@@ -1509,31 +2814,76 @@ impl Key for SharedConfiguration {
     type Value = Config;
 }
 
-#[cfg(feature = "watch")]
-pub fn get_handlebars_engine(template_name: &str) -> impl AfterMiddleware {
-    // HandlebarsEngine will look up all files with "./examples/templates/**/*.hbs"
-    let mut hbse = HandlebarsEngine::new();
-    hbse.add(Box::new(DirectorySource::new(&format!("./templates/{}/", template_name) as &str, ".hbs")));
+pub struct SharedSessionStore;
+impl Key for SharedSessionStore {
+    type Value = SessionStore;
+}
 
-    // load templates from all registered sources
-    if let Err(r) = hbse.reload() {
-        panic!("{}", r);
+/// Template helper `{{t "message-id" name=value}}`: The message translated into the language of the request
+fn translate_helper(h: &Helper, _: &Handlebars, rc: &mut RenderContext) -> Result<(), RenderError> {
+    let id = h.param(0)
+              .and_then(|param| param.value().as_str())
+              .ok_or_else(|| RenderError::new("Message id expected for helper \"t\""))?;
+    let args: Vec<(String, String)> =
+        h.hash().iter().map(|(name, value)| (name.clone(), value.value().render())).collect();
+    let args: Vec<(&str, &str)> = args.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    rc.writer().write_all(html_escape(&i18n::translate_with(id, &args)).as_bytes())?;
+    Ok(())
+}
+
+/// Template helper `{{grade_name grade}}`: The name of the school grade, e.g. in a selection
+fn grade_name_helper(h: &Helper, _: &Handlebars, rc: &mut RenderContext) -> Result<(), RenderError> {
+    let grade = h.param(0)
+                 .and_then(|param| param.value().as_i64())
+                 .ok_or_else(|| RenderError::new("Grade expected for helper \"grade_name\""))?;
+
+    rc.writer().write_all(html_escape(&grades::scale().name(grade as i32)).as_bytes())?;
+    Ok(())
+}
+
+/// Template helper `{{language}}`: The language of the request, e.g. for `<html lang="…">`
+fn language_helper(_: &Helper, _: &Handlebars, rc: &mut RenderContext) -> Result<(), RenderError> {
+    rc.writer().write_all(i18n::current_language().as_bytes())?;
+    Ok(())
+}
+
+/// Renders the templates in the language chosen by the `LanguageSelector`
+struct LocalizedTemplates<M> {
+    engine: M,
+}
+
+impl<M> LocalizedTemplates<M> {
+    fn language(resp: &Response) -> String {
+        resp.extensions.get::<RequestLanguage>().cloned().unwrap_or_else(|| i18n::DEFAULT_LANGUAGE.to_string())
     }
+}
 
-    use handlebars_iron::Watchable;
-    use std::sync::Arc;
+impl<M> AfterMiddleware for LocalizedTemplates<M> where M: AfterMiddleware
+{
+    fn after(&self, req: &mut Request, resp: Response) -> IronResult<Response> {
+        let language = Self::language(&resp);
+        i18n::with_language(&language, || self.engine.after(req, resp))
+    }
 
-    let hbse_ref = Arc::new(hbse);
-    hbse_ref.watch("./templates/");
-    hbse_ref
+    fn catch(&self, req: &mut Request, err: IronError) -> IronResult<Response> {
+        let language = Self::language(&err.response);
+        i18n::with_language(&language, || self.engine.catch(req, err))
+    }
 }
 
-#[cfg(not(feature = "watch"))]
-pub fn get_handlebars_engine(template_name: &str) -> impl AfterMiddleware {
+fn new_handlebars_engine(template_name: &str) -> HandlebarsEngine {
     // HandlebarsEngine will look up all files with "./templates/<template>/**/*.hbs"
     let mut hbse = HandlebarsEngine::new();
     hbse.add(Box::new(DirectorySource::new(&format!("./templates/{}/", template_name) as &str, ".hbs")));
 
+    {
+        let mut handlebars = hbse.handlebars_mut();
+        handlebars.register_helper("t", Box::new(translate_helper));
+        handlebars.register_helper("grade_name", Box::new(grade_name_helper));
+        handlebars.register_helper("language", Box::new(language_helper));
+    }
+
     // load templates from all registered sources
     if let Err(r) = hbse.reload() {
         panic!("{}", r);
@@ -1542,6 +2892,21 @@ pub fn get_handlebars_engine(template_name: &str) -> impl AfterMiddleware {
     hbse
 }
 
+#[cfg(feature = "watch")]
+pub fn get_handlebars_engine(template_name: &str) -> impl AfterMiddleware {
+    use handlebars_iron::Watchable;
+    use std::sync::Arc;
+
+    let hbse_ref = Arc::new(new_handlebars_engine(template_name));
+    hbse_ref.watch("./templates/");
+    LocalizedTemplates { engine: hbse_ref }
+}
+
+#[cfg(not(feature = "watch"))]
+pub fn get_handlebars_engine(template_name: &str) -> impl AfterMiddleware {
+    LocalizedTemplates { engine: new_handlebars_engine(template_name) }
+}
+
 fn cookie_warning(req: &mut Request) -> IronResult<Response> {
     match req.get_session_token() {
         Some(_session_token) => {
@@ -1558,20 +2923,27 @@ fn cookie_warning(req: &mut Request) -> IronResult<Response> {
 }
 
 pub fn start_server<C>(conn: C, config: Config) -> iron::error::HttpResult<iron::Listening>
-    where C: MedalConnection + std::marker::Send + 'static {
-    let router = router!(
+    where C: MedalConnection + std::marker::Send + 'static,
+          Contest: MedalObject<C>
+{
+    let router = named_router!(
         greet: get "/" => greet_personal::<C>,
         contests: get "/contest/" => contests::<C>,
         contest: get "/contest/:contestid" => contest::<C>,
         contest_secret: get "/contest/:contestid/:secret" => contest::<C>,
         contestresults: get "/contest/:contestid/result/" => contestresults::<C>,
         contestresults_download: get "/contest/:contestid/result/download" => contestresults_download::<C>,
+        contestresults_statistics: get "/contest/:contestid/result/:groupid/statistics" => contestresults_statistics::<C>,
+        contestranking: get "/contest/:contestid/ranking/" => contestranking::<C>,
+        contestscoreboard: get "/contest/:contestid/scoreboard/" => contestscoreboard::<C>,
         contest_post: post "/contest/:contestid" => contest_post::<C>,
         contest_post_secret: post "/contest/:contestid/:secret" => contest_post::<C>, // just ignoring the secret
         login: get "/login" => login::<C>,
         login_post: post "/login" => login_post::<C>,
         login_code_post: post "/clogin" => login_code_post::<C>,
+        login_link: get "/login/link/:userid/:expires/:signature" => login_link::<C>,
         logout: get "/logout" => logout::<C>,
+        logout_everywhere: post "/logout/everywhere" => logout_everywhere::<C>,
         signup: get "/signup" => signup::<C>,
         signup_post: post "/signup" => signup_post::<C>,
         subm_load: get "/load/:taskid" => submission::<C>,
@@ -1581,11 +2953,22 @@ pub fn start_server<C>(conn: C, config: Config) -> iron::error::HttpResult<iron:
         group: get "/group/:groupid" => admin_group::<C>,
         group_post: post "/group/:groupid" => admin_group::<C>,
         group_download: get "/group/download/:groupid" => group_download::<C>,
+        group_login_sheets: get "/group/loginsheets/:groupid" => group_login_sheets::<C>,
+        group_revoke_login_links: post "/group/:groupid/revokelinks" => group_revoke_login_links::<C>,
+        group_teachers_post: post "/group/:groupid/teachers" => group_teachers_post::<C>,
         //group_post: post "/group" => group_post::<C>,
         groupcsv: get "/group/csv" => group_csv::<C>,
         groupcsv_post: post "/group/csv" => group_csv_upload::<C>,
+        groupcsv_preview: post "/group/csv/preview" => group_csv_preview::<C>,
         myprofile: get "/profile" => profile::<C>,
         myprofile_post: post "/profile" => profile_post::<C>,
+        myprofile_email_post: post "/profile/email" => profile_email_post::<C>,
+        myprofile_language_post: post "/profile/language" => profile_language_post::<C>,
+        email_confirm: get "/email/confirm/:code" => email_confirm::<C>,
+        password_reset: get "/password/reset" => password_reset::<C>,
+        password_reset_post: post "/password/reset" => password_reset_post::<C>,
+        password_reset_token: get "/password/reset/:token" => password_reset_token::<C>,
+        password_reset_token_post: post "/password/reset/:token" => password_reset_token_post::<C>,
         user: get "/user/:userid" => admin_user::<C>,
         user_post: post "/user/:userid" => admin_user::<C>,
         profile: get "/profile/:userid" => user::<C>,
@@ -1597,14 +2980,24 @@ pub fn start_server<C>(conn: C, config: Config) -> iron::error::HttpResult<iron:
         admin_users: post "/admin/user/" => admin_users::<C>,
         admin_user: get "/admin/user/:userid" => admin_user::<C>,
         admin_user_post: post "/admin/user/:userid" => admin_user::<C>,
+        admin_user_roles_post: post "/admin/user/:userid/roles" => admin_user_roles_post::<C>,
+        admin_user_sessions_post: post "/admin/user/:userid/sessions" => admin_user_sessions_post::<C>,
         admin_group: get "/admin/group/:groupid" => admin_group::<C>,
         admin_group_post: post "/admin/group/:groupid" => admin_group::<C>,
         admin_participation: get "/admin/user/:userid/:contestid" => admin_participation::<C>,
+        submission_history: get "/admin/user/:userid/task/:taskid" => submission_history::<C>,
         admin_participation_post: post "/admin/user/:userid/:contestid" => admin_participation::<C>,
+        admin_participation_time_post: post "/admin/user/:userid/:contestid/time" => admin_participation_time_post::<C>,
         admin_contests: get "/admin/contest/" => admin_contests::<C>,
+        admin_contest_scan: get "/admin/contest/scan" => admin_contest_scan::<C>,
+        admin_contest_scan_post: post "/admin/contest/scan" => admin_contest_scan::<C>,
         admin_contest_admissioncsv: get "/admin/contest/:contestid/csv" => contest_admissioncsv::<C>,
         admin_contest_admissioncsv_post: post "/admin/contest/:contestid/csv" => contest_admissioncsv_upload::<C>,
         admin_export_contest: get "/admin/contest/:contestid/export" => admin_export_contest::<C>,
+        admin_export_contest_download: get "/admin/contest/:contestid/export/download" => admin_export_contest_download::<C>,
+        admin_audit: get "/admin/audit/" => admin_audit::<C>,
+        admin_reports: get "/admin/reports/" => admin_reports::<C>,
+        admin_reports_download: get "/admin/reports/download" => admin_reports_download::<C>,
         admin_cleanup: get "/admin/cleanup" => admin_cleanup::<C>,
         admin_cleanup_post: post "/admin/cleanup/:type" => admin_cleanup::<C>,
         oauth: get "/oauth/:oauthid/" => oauth::<C>,
@@ -1612,18 +3005,30 @@ pub fn start_server<C>(conn: C, config: Config) -> iron::error::HttpResult<iron:
         check_cookie: get "/cookie" => cookie_warning,
         dbstatus: get "/dbstatus" => dbstatus::<C>,
         status: get "/status" => dbstatus::<C>,
+        metrics: get "/metrics" => metrics::<C>,
         dbcleanup: get "/cleanup" => dbcleanup::<C>,
         debug: get "/debug" => debug::<C>,
         debug_reset: get "/debug/reset" => debug_new_token::<C>,
         debug_logout: get "/debug/logout" => debug_logout::<C>,
         debug_create: get "/debug/create" => debug_create_session::<C>,
+        api_login: post "/api/v1/login" => api_login::<C>,
+        api_logout: post "/api/v1/logout" => api_logout::<C>,
+        api_contests: get "/api/v1/contest/" => api_contests::<C>,
+        api_contest: get "/api/v1/contest/:contestid" => api_contest::<C>,
+        api_contest_post: post "/api/v1/contest/:contestid" => api_contest_post::<C>,
+        api_contest_results: get "/api/v1/contest/:contestid/result/" => api_contest_results::<C>,
+        api_groups: get "/api/v1/group/" => api_groups::<C>,
+        api_groups_post: post "/api/v1/group/" => api_groups_post::<C>,
+        api_group: get "/api/v1/group/:groupid" => api_group::<C>,
+        api_participation: get "/api/v1/participation/:userid/:contestid" => api_participation::<C>,
+        api_profile: get "/api/v1/profile" => api_profile::<C>,
+        api_profile_post: post "/api/v1/profile" => api_profile_post::<C>,
     );
 
     let mut mount = Mount::new();
 
     // Serve the shared JS/CSS at /
     mount.mount("/static/", Static::new(Path::new("static")));
-    mount.mount("/export/", Static::new(Path::new("export")));
     mount.mount("/tasks/", Static::new(Path::new(TASK_DIR)));
     mount.mount("/", router);
 
@@ -1632,12 +3037,36 @@ pub fn start_server<C>(conn: C, config: Config) -> iron::error::HttpResult<iron:
     #[cfg(feature = "debug")]
     ch.link_before(RequestLogger {});
 
-    ch.link(Write::<SharedDatabaseConnection<C>>::both(conn));
+    let session_store = match config.session_backend.as_deref().unwrap_or("cookie") {
+        "cookie" => SessionStore::SignedCookie,
+        "database" => SessionStore::Database,
+        #[cfg(feature = "redis")]
+        "redis" => {
+            let redis_url = config.redis_url.as_deref().unwrap_or("redis://127.0.0.1/");
+            let mut backend = RedisBackend::new(redis_url).expect("Could not connect to Redis");
+            backend.index_key(session_backend::SESSION_TOKEN_KEY);
+            // Forget sessions after 30 days without any request, like temporary sessions in the database
+            backend.set_expiry(30 * 24 * 60 * 60);
+            SessionStore::Redis(backend)
+        }
+        #[cfg(not(feature = "redis"))]
+        "redis" => panic!("Session backend 'redis' requires medal to be built with feature 'redis'"),
+        other => panic!("Unknown session backend '{}' (supported: cookie, database, redis)", other),
+    };
+
+    ch.link(Read::<SharedDatabasePool<C>>::both(ConnectionPool::open(conn, &config)));
     ch.link(Read::<SharedConfiguration>::both(config.clone()));
+    ch.link(Read::<SharedSessionStore>::both(session_store.clone()));
 
     ch.link_around(RequestTimeLogger {});
     ch.link_around(CookieDistributor {});
-    ch.link_around(SessionStorage::new(SignedCookieBackend::new(config.cookie_signing_secret.expect("Cookie signing secret not found in configuration").into_bytes())));
+    ch.link_around(LanguageSelector::<C>::new());
+    match session_store {
+        SessionStore::SignedCookie => ch.link_around(SessionStorage::new(SignedCookieBackend::new(config.cookie_signing_secret.expect("Cookie signing secret not found in configuration").into_bytes()))),
+        SessionStore::Database => ch.link_around(SessionStorage::new(DatabaseSessionBackend::<C>::new())),
+        #[cfg(feature = "redis")]
+        SessionStore::Redis(backend) => ch.link_around(SessionStorage::new(backend)),
+    };
 
     ch.link_after(get_handlebars_engine(&config.template.unwrap_or_else(|| "default".to_string())));
     ch.link_after(ErrorReporter);